@@ -1,12 +1,12 @@
 use crate::AppData;
 use actix_identity::{IdentityPolicy, RequestIdentity};
-use actix_utils::future::{ready, Ready};
 use actix_web::{
   dev::{ServiceRequest, ServiceResponse},
   error::{Error, Result},
   web::Data,
 };
 use rusty_paseto::prelude::*;
+use std::future::{ready, Ready};
 pub struct PasetoCookieIdentityPolicy {}
 
 fn validate_auth_token(request: &mut ServiceRequest) -> Result<Option<String>, Error> {
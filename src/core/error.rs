@@ -50,6 +50,10 @@ pub enum PasetoError {
   ///Occurs when an untrusted token string is unable to be parsed into its constituent parts
   #[error("This string has an incorrect number of parts and cannot be parsed into a token")]
   IncorrectSize,
+  ///Occurs when the payload of an untrusted token string is too short to hold the nonce and tag or the signature
+  ///of its version and purpose
+  #[error("The token payload is too short to be a valid token of its version and purpose")]
+  MalformedPayload,
   ///Occurs when an incorrect header is provided on an untrusted token string
   #[error("The token header is invalid")]
   WrongHeader,
@@ -88,3 +92,22 @@ pub enum PasetoError {
     source: std::string::FromUtf8Error,
   },
 }
+
+impl PasetoError {
+  /// Whether the error means the token failed decryption or signature verification, as opposed to being malformed
+  /// or the key being unusable
+  #[cfg(feature = "generic")]
+  pub(crate) fn is_authentication_failure(&self) -> bool {
+    match self {
+      Self::PasetoCipherError(_)
+      | Self::Cryption
+      | Self::Cipher { .. }
+      | Self::InvalidSignature
+      | Self::FooterInvalid
+      | Self::ChaChaCipherError => true,
+      #[cfg(feature = "ed25519-dalek")]
+      Self::RsaCipher { .. } => true,
+      _ => false,
+    }
+  }
+}
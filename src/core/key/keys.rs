@@ -1,4 +1,4 @@
-use crate::core::PasetoError;
+use crate::core::{constant_time_equals, PasetoError};
 use ring::rand::{SecureRandom, SystemRandom};
 use std::convert::{From, TryFrom};
 use std::fmt::Debug;
@@ -68,6 +68,11 @@ impl<const KEYSIZE: usize> Key<KEYSIZE> {
     rng.fill(&mut buf)?;
     Ok(Self(buf))
   }
+
+  /// Compares the bytes of two keys in constant time, so the comparison leaks nothing of where they first differ
+  pub fn ct_eq(&self, other: &Self) -> bool {
+    constant_time_equals(&self.0, &other.0).is_ok()
+  }
 }
 
 impl<const KEYSIZE: usize> Debug for Key<KEYSIZE> {
@@ -1,4 +1,6 @@
 mod keys;
+#[cfg(any(feature = "v3_local", feature = "v4_local"))]
+mod paserk_pie;
 mod paseto_asymmetric_private_key;
 mod paseto_asymmetric_public_key;
 mod paseto_nonce;
@@ -0,0 +1,407 @@
+//! The PASERK [pie](https://github.com/paseto-standard/paserk/blob/master/operations/Wrap/pie.md) key wrapping
+//! protocol, which encrypts a local or secret key with a symmetric wrapping key of the same version so it can be
+//! stored at rest.
+use super::*;
+use crate::core::*;
+use base64::{decode_config, encode_config, URL_SAFE_NO_PAD};
+use zeroize::Zeroize;
+
+//length of the random nonce prefixed to every wrapped key
+const NONCE_SIZE: usize = 32;
+//domain separation prefixes for the encryption and authentication key derivations
+const ENCRYPTION_KEY_DOMAIN: u8 = 0x80;
+const AUTHENTICATION_KEY_DOMAIN: u8 = 0x81;
+
+//decodes the base64 body following the header, which holds the tag, nonce and wrapped key in that order
+fn decode_wrapped(header: &str, wrapped: &str, tag_size: usize) -> Result<Vec<u8>, PasetoError> {
+  let body = wrapped.strip_prefix(header).ok_or(PasetoError::WrongHeader)?;
+  let decoded = decode_config(body, URL_SAFE_NO_PAD)?;
+  if decoded.len() <= tag_size + NONCE_SIZE {
+    return Err(PasetoError::IncorrectSize);
+  }
+  Ok(decoded)
+}
+
+fn join_wrapped(header: &str, tag: &[u8], nonce: &[u8], wrapped_key: &[u8]) -> String {
+  let mut body = Vec::with_capacity(tag.len() + nonce.len() + wrapped_key.len());
+  body.extend_from_slice(tag);
+  body.extend_from_slice(nonce);
+  body.extend_from_slice(wrapped_key);
+  format!("{}{}", header, encode_config(&body, URL_SAFE_NO_PAD))
+}
+
+#[cfg(feature = "v4_local")]
+mod v4 {
+  use super::*;
+  use blake2::{
+    digest::{Update, VariableOutput},
+    VarBlake2b,
+  };
+  use chacha20::cipher::{NewCipher, StreamCipher};
+  use chacha20::{Key as ChaChaKey, XChaCha20, XNonce};
+
+  const TAG_SIZE: usize = 32;
+
+  fn blake2b(key: &[u8], parts: &[&[u8]], size: usize) -> Vec<u8> {
+    let mut context = VarBlake2b::new_keyed(key, size);
+    for part in parts {
+      context.update(part);
+    }
+    context.finalize_boxed().to_vec()
+  }
+
+  //derives the encryption key and nonce from the wrapping key, then applies the XChaCha20 keystream in place
+  fn apply_keystream(wrapping_key: &[u8], nonce: &[u8], key: &mut [u8]) {
+    let mut derived = blake2b(wrapping_key, &[&[ENCRYPTION_KEY_DOMAIN], nonce], 56);
+    let mut cipher = XChaCha20::new(
+      ChaChaKey::from_slice(&derived[..32]),
+      XNonce::from_slice(&derived[32..]),
+    );
+    cipher.apply_keystream(key);
+    derived.zeroize();
+  }
+
+  fn tag(header: &str, wrapping_key: &[u8], nonce: &[u8], wrapped_key: &[u8]) -> Vec<u8> {
+    let mut authentication_key = blake2b(wrapping_key, &[&[AUTHENTICATION_KEY_DOMAIN], nonce], 32);
+    let tag = blake2b(&authentication_key, &[header.as_bytes(), nonce, wrapped_key], TAG_SIZE);
+    authentication_key.zeroize();
+    tag
+  }
+
+  pub(super) fn wrap(header: &str, wrapping_key: &[u8], key: &[u8], nonce: &[u8; NONCE_SIZE]) -> String {
+    let mut wrapped_key = key.to_vec();
+    apply_keystream(wrapping_key, nonce, &mut wrapped_key);
+    let tag = tag(header, wrapping_key, nonce, &wrapped_key);
+    join_wrapped(header, &tag, nonce, &wrapped_key)
+  }
+
+  pub(super) fn unwrap(header: &str, wrapping_key: &[u8], wrapped: &str) -> Result<Vec<u8>, PasetoError> {
+    let decoded = decode_wrapped(header, wrapped, TAG_SIZE)?;
+    let (expected_tag, rest) = decoded.split_at(TAG_SIZE);
+    let (nonce, wrapped_key) = rest.split_at(NONCE_SIZE);
+    constant_time_equals(expected_tag, &tag(header, wrapping_key, nonce, wrapped_key))?;
+
+    let mut key = wrapped_key.to_vec();
+    apply_keystream(wrapping_key, nonce, &mut key);
+    Ok(key)
+  }
+}
+
+#[cfg(feature = "v3_local")]
+mod v3 {
+  use super::*;
+  use aes::cipher::{generic_array::GenericArray, NewCipher, StreamCipher};
+  use aes::Aes256Ctr;
+  use hmac::{Hmac, Mac};
+  use sha2::Sha384;
+
+  const TAG_SIZE: usize = 48;
+
+  fn hmac_sha384(key: &[u8], parts: &[&[u8]]) -> Vec<u8> {
+    let mut mac = Hmac::<Sha384>::new_from_slice(key).expect("HMAC can take key of any size");
+    for part in parts {
+      mac.update(part);
+    }
+    mac.finalize().into_bytes().to_vec()
+  }
+
+  //derives the encryption key and counter nonce from the wrapping key, then applies the AES-256-CTR keystream in place
+  fn apply_keystream(wrapping_key: &[u8], nonce: &[u8], key: &mut [u8]) {
+    let mut derived = hmac_sha384(wrapping_key, &[&[ENCRYPTION_KEY_DOMAIN], nonce]);
+    let mut cipher = Aes256Ctr::new(
+      GenericArray::from_slice(&derived[..32]),
+      GenericArray::from_slice(&derived[32..]),
+    );
+    cipher.apply_keystream(key);
+    derived.zeroize();
+  }
+
+  fn tag(header: &str, wrapping_key: &[u8], nonce: &[u8], wrapped_key: &[u8]) -> Vec<u8> {
+    let mut authentication_key = hmac_sha384(wrapping_key, &[&[AUTHENTICATION_KEY_DOMAIN], nonce]);
+    let tag = hmac_sha384(&authentication_key[..32], &[header.as_bytes(), nonce, wrapped_key]);
+    authentication_key.zeroize();
+    tag
+  }
+
+  pub(super) fn wrap(header: &str, wrapping_key: &[u8], key: &[u8], nonce: &[u8; NONCE_SIZE]) -> String {
+    let mut wrapped_key = key.to_vec();
+    apply_keystream(wrapping_key, nonce, &mut wrapped_key);
+    let tag = tag(header, wrapping_key, nonce, &wrapped_key);
+    join_wrapped(header, &tag, nonce, &wrapped_key)
+  }
+
+  pub(super) fn unwrap(header: &str, wrapping_key: &[u8], wrapped: &str) -> Result<Vec<u8>, PasetoError> {
+    let decoded = decode_wrapped(header, wrapped, TAG_SIZE)?;
+    let (expected_tag, rest) = decoded.split_at(TAG_SIZE);
+    let (nonce, wrapped_key) = rest.split_at(NONCE_SIZE);
+    constant_time_equals(expected_tag, &tag(header, wrapping_key, nonce, wrapped_key))?;
+
+    let mut key = wrapped_key.to_vec();
+    apply_keystream(wrapping_key, nonce, &mut key);
+    Ok(key)
+  }
+}
+
+//copies unwrapped key material of exactly the expected size into a key, wiping the intermediate buffer
+fn into_key<const KEYSIZE: usize>(mut unwrapped: Vec<u8>) -> Result<Key<KEYSIZE>, PasetoError> {
+  let key = <&[u8; KEYSIZE]>::try_from(unwrapped.as_slice())
+    .map(Key::from)
+    .map_err(|_| PasetoError::InvalidKey);
+  unwrapped.zeroize();
+  key
+}
+
+fn random_nonce() -> Result<[u8; NONCE_SIZE], PasetoError> {
+  Ok(*Key::<NONCE_SIZE>::try_new_random()?)
+}
+
+#[cfg(feature = "v4_local")]
+impl PasetoSymmetricKey<V4, Local> {
+  const LOCAL_WRAP_HEADER: &'static str = "k4.local-wrap.pie.";
+
+  /// Wraps this key with the given wrapping key, returning a `k4.local-wrap.pie.` PASERK string
+  ///
+  /// # Errors
+  ///
+  /// Returns [`PasetoError`] if a random nonce can't be generated
+  ///
+  /// # Example
+  /// ```
+  /// # #[cfg(feature = "v4_local")]
+  /// # {
+  /// use rusty_paseto::core::*;
+  ///
+  /// let master_key = PasetoSymmetricKey::<V4, Local>::from(Key::<32>::try_new_random()?);
+  /// let key = PasetoSymmetricKey::<V4, Local>::from(Key::from(*b"wubbalubbadubdubwubbalubbadubdub"));
+  ///
+  /// let wrapped = key.wrap(&master_key)?;
+  /// assert!(wrapped.starts_with("k4.local-wrap.pie."));
+  ///
+  /// let unwrapped = PasetoSymmetricKey::<V4, Local>::unwrap(&wrapped, &master_key)?;
+  /// assert_eq!(unwrapped.as_ref(), key.as_ref());
+  /// # }
+  /// # Ok::<(),anyhow::Error>(())
+  /// ```
+  pub fn wrap(&self, wrapping_key: &PasetoSymmetricKey<V4, Local>) -> Result<String, PasetoError> {
+    Ok(self.wrap_with_nonce(wrapping_key, &random_nonce()?))
+  }
+
+  fn wrap_with_nonce(&self, wrapping_key: &PasetoSymmetricKey<V4, Local>, nonce: &[u8; NONCE_SIZE]) -> String {
+    v4::wrap(Self::LOCAL_WRAP_HEADER, wrapping_key.as_ref(), self.as_ref(), nonce)
+  }
+
+  /// Recovers a key from a `k4.local-wrap.pie.` PASERK string created with the same wrapping key
+  ///
+  /// # Errors
+  ///
+  /// Returns [`PasetoError`] if the header doesn't match, the string is malformed or it fails authentication with
+  /// the wrapping key
+  pub fn unwrap(wrapped: &str, wrapping_key: &PasetoSymmetricKey<V4, Local>) -> Result<Self, PasetoError> {
+    let unwrapped = v4::unwrap(Self::LOCAL_WRAP_HEADER, wrapping_key.as_ref(), wrapped)?;
+    Ok(Self::from(into_key::<32>(unwrapped)?))
+  }
+}
+
+#[cfg(all(feature = "v4_local", feature = "v4_public"))]
+impl PasetoAsymmetricPrivateKey<'_, V4, Public> {
+  const SECRET_WRAP_HEADER: &'static str = "k4.secret-wrap.pie.";
+
+  /// Wraps this secret key with the given wrapping key, returning a `k4.secret-wrap.pie.` PASERK string
+  ///
+  /// # Errors
+  ///
+  /// Returns [`PasetoError`] if a random nonce can't be generated
+  pub fn wrap(&self, wrapping_key: &PasetoSymmetricKey<V4, Local>) -> Result<String, PasetoError> {
+    Ok(self.wrap_with_nonce(wrapping_key, &random_nonce()?))
+  }
+
+  fn wrap_with_nonce(&self, wrapping_key: &PasetoSymmetricKey<V4, Local>, nonce: &[u8; NONCE_SIZE]) -> String {
+    v4::wrap(Self::SECRET_WRAP_HEADER, wrapping_key.as_ref(), self.as_ref(), nonce)
+  }
+
+  /// Recovers the bytes of a secret key from a `k4.secret-wrap.pie.` PASERK string created with the same wrapping
+  /// key
+  ///
+  /// Since a [PasetoAsymmetricPrivateKey] borrows its bytes, the owned [Key] is returned to create one from.
+  ///
+  /// # Errors
+  ///
+  /// Returns [`PasetoError`] if the header doesn't match, the string is malformed or it fails authentication with
+  /// the wrapping key
+  pub fn unwrap(wrapped: &str, wrapping_key: &PasetoSymmetricKey<V4, Local>) -> Result<Key<64>, PasetoError> {
+    into_key(v4::unwrap(Self::SECRET_WRAP_HEADER, wrapping_key.as_ref(), wrapped)?)
+  }
+}
+
+#[cfg(feature = "v3_local")]
+impl PasetoSymmetricKey<V3, Local> {
+  const LOCAL_WRAP_HEADER: &'static str = "k3.local-wrap.pie.";
+
+  /// Wraps this key with the given wrapping key, returning a `k3.local-wrap.pie.` PASERK string
+  ///
+  /// # Errors
+  ///
+  /// Returns [`PasetoError`] if a random nonce can't be generated
+  pub fn wrap(&self, wrapping_key: &PasetoSymmetricKey<V3, Local>) -> Result<String, PasetoError> {
+    Ok(self.wrap_with_nonce(wrapping_key, &random_nonce()?))
+  }
+
+  fn wrap_with_nonce(&self, wrapping_key: &PasetoSymmetricKey<V3, Local>, nonce: &[u8; NONCE_SIZE]) -> String {
+    v3::wrap(Self::LOCAL_WRAP_HEADER, wrapping_key.as_ref(), self.as_ref(), nonce)
+  }
+
+  /// Recovers a key from a `k3.local-wrap.pie.` PASERK string created with the same wrapping key
+  ///
+  /// # Errors
+  ///
+  /// Returns [`PasetoError`] if the header doesn't match, the string is malformed or it fails authentication with
+  /// the wrapping key
+  pub fn unwrap(wrapped: &str, wrapping_key: &PasetoSymmetricKey<V3, Local>) -> Result<Self, PasetoError> {
+    let unwrapped = v3::unwrap(Self::LOCAL_WRAP_HEADER, wrapping_key.as_ref(), wrapped)?;
+    Ok(Self::from(into_key::<32>(unwrapped)?))
+  }
+}
+
+#[cfg(all(feature = "v3_local", feature = "v3_public"))]
+impl PasetoAsymmetricPrivateKey<'_, V3, Public> {
+  const SECRET_WRAP_HEADER: &'static str = "k3.secret-wrap.pie.";
+
+  /// Wraps this secret key with the given wrapping key, returning a `k3.secret-wrap.pie.` PASERK string
+  ///
+  /// # Errors
+  ///
+  /// Returns [`PasetoError`] if a random nonce can't be generated
+  pub fn wrap(&self, wrapping_key: &PasetoSymmetricKey<V3, Local>) -> Result<String, PasetoError> {
+    Ok(self.wrap_with_nonce(wrapping_key, &random_nonce()?))
+  }
+
+  fn wrap_with_nonce(&self, wrapping_key: &PasetoSymmetricKey<V3, Local>, nonce: &[u8; NONCE_SIZE]) -> String {
+    v3::wrap(Self::SECRET_WRAP_HEADER, wrapping_key.as_ref(), self.as_ref(), nonce)
+  }
+
+  /// Recovers the bytes of a secret key from a `k3.secret-wrap.pie.` PASERK string created with the same wrapping
+  /// key
+  ///
+  /// Since a [PasetoAsymmetricPrivateKey] borrows its bytes, the owned [Key] is returned to create one from.
+  ///
+  /// # Errors
+  ///
+  /// Returns [`PasetoError`] if the header doesn't match, the string is malformed or it fails authentication with
+  /// the wrapping key
+  pub fn unwrap(wrapped: &str, wrapping_key: &PasetoSymmetricKey<V3, Local>) -> Result<Key<48>, PasetoError> {
+    into_key(v3::unwrap(Self::SECRET_WRAP_HEADER, wrapping_key.as_ref(), wrapped)?)
+  }
+}
+
+#[cfg(test)]
+mod unit_tests {
+  use super::*;
+  use anyhow::Result;
+
+  //expected values were produced by an independent implementation of the pie protocol with a fixed nonce
+  const WRAPPING_KEY: &str = "707172737475767778797a7b7c7d7e7f808182838485868788898a8b8c8d8e8f";
+  const NONCE: &str = "df654812bac492663825520ba2f6e67cf5ca5bdc13d4e7507a98cc4c2fcc3ad8";
+  #[cfg(feature = "v4_public")]
+  const V4_SECRET_KEY: &str = "b4cbfb43df4ce210727d953e4a713307fa19bb7d9f85041438d9e11b942a37741eb9dbbbbc047c03fd70604e0071f0987e16b28b757225c11f00415d0e20b1a2";
+  #[cfg(feature = "v3_public")]
+  const V3_SECRET_KEY: &str =
+    "20347609607477aca8fbfbc5e6218455f3199669792ef8b466faa87bdc67798144c848dd03661eed5ac62461340cea96";
+
+  fn nonce() -> Result<[u8; NONCE_SIZE]> {
+    Ok(*Key::<NONCE_SIZE>::try_from(NONCE)?)
+  }
+
+  #[cfg(feature = "v4_local")]
+  #[test]
+  fn test_k4_local_wrap() -> Result<()> {
+    let wrapping_key = PasetoSymmetricKey::<V4, Local>::from(Key::<32>::try_from(WRAPPING_KEY)?);
+    let key = PasetoSymmetricKey::<V4, Local>::from(*b"wubbalubbadubdubwubbalubbadubdub");
+    let wrapped = key.wrap_with_nonce(&wrapping_key, &nonce()?);
+    assert_eq!(wrapped, "k4.local-wrap.pie.93cySkj0Ynm_THq1IrK6HKbFEoywNwJJ6GcuX1iXG7nfZUgSusSSZjglUgui9uZ89cpb3BPU51B6mMxML8w62AUX2rGBC9cNgkN77eAdpzNZnVYePw72QWaM6FHodnHa");
+
+    let unwrapped = PasetoSymmetricKey::<V4, Local>::unwrap(&wrapped, &wrapping_key)?;
+    assert_eq!(unwrapped.as_ref(), key.as_ref());
+
+    //a fresh wrap uses a random nonce but still round trips
+    let wrapped = key.wrap(&wrapping_key)?;
+    assert_eq!(
+      PasetoSymmetricKey::<V4, Local>::unwrap(&wrapped, &wrapping_key)?.as_ref(),
+      key.as_ref()
+    );
+    Ok(())
+  }
+
+  #[cfg(feature = "v4_local")]
+  #[test]
+  fn test_k4_local_unwrap_failures() -> Result<()> {
+    let wrapping_key = PasetoSymmetricKey::<V4, Local>::from(Key::<32>::try_from(WRAPPING_KEY)?);
+    let key = PasetoSymmetricKey::<V4, Local>::from(*b"wubbalubbadubdubwubbalubbadubdub");
+    let wrapped = key.wrap(&wrapping_key)?;
+
+    let other_key = PasetoSymmetricKey::<V4, Local>::from([0u8; 32]);
+    assert!(PasetoSymmetricKey::<V4, Local>::unwrap(&wrapped, &other_key).is_err());
+
+    let mut tampered = wrapped.clone();
+    let last = if tampered.ends_with('A') { "B" } else { "A" };
+    tampered.replace_range(tampered.len() - 1.., last);
+    assert!(PasetoSymmetricKey::<V4, Local>::unwrap(&tampered, &wrapping_key).is_err());
+
+    assert!(matches!(
+      PasetoSymmetricKey::<V4, Local>::unwrap(&wrapped.replace("k4.local-wrap", "k3.local-wrap"), &wrapping_key),
+      Err(PasetoError::WrongHeader)
+    ));
+    assert!(matches!(
+      PasetoSymmetricKey::<V4, Local>::unwrap("k4.local-wrap.pie.AAAA", &wrapping_key),
+      Err(PasetoError::IncorrectSize)
+    ));
+    Ok(())
+  }
+
+  #[cfg(all(feature = "v4_local", feature = "v4_public"))]
+  #[test]
+  fn test_k4_secret_wrap() -> Result<()> {
+    let wrapping_key = PasetoSymmetricKey::<V4, Local>::from(Key::<32>::try_from(WRAPPING_KEY)?);
+    let secret_key = Key::<64>::try_from(V4_SECRET_KEY)?;
+    let secret_key = PasetoAsymmetricPrivateKey::<V4, Public>::from(&secret_key);
+    let wrapped = secret_key.wrap_with_nonce(&wrapping_key, &nonce()?);
+    assert_eq!(wrapped, "k4.secret-wrap.pie.yLvr290SF-6V_NCUuJqUciGtYWIQisYevSL9I8rwPQ3fZUgSusSSZjglUgui9uZ89cpb3BPU51B6mMxML8w62MapQ5A_K0B_kl-KpsgI4VbU8Y8BweeHNzw0bT8eODPMGAgW84nQKX1Fofx7eOVPjwVEhpWFk76XhofkQGP-h78");
+
+    let unwrapped = PasetoAsymmetricPrivateKey::<V4, Public>::unwrap(&wrapped, &wrapping_key)?;
+    assert_eq!(unwrapped.as_ref(), secret_key.as_ref());
+
+    //a wrapped local key is not a wrapped secret key
+    let local_key = PasetoSymmetricKey::<V4, Local>::from([0u8; 32]).wrap(&wrapping_key)?;
+    assert!(matches!(
+      PasetoAsymmetricPrivateKey::<V4, Public>::unwrap(&local_key, &wrapping_key),
+      Err(PasetoError::WrongHeader)
+    ));
+    Ok(())
+  }
+
+  #[cfg(feature = "v3_local")]
+  #[test]
+  fn test_k3_local_wrap() -> Result<()> {
+    let wrapping_key = PasetoSymmetricKey::<V3, Local>::from(Key::<32>::try_from(WRAPPING_KEY)?);
+    let key = PasetoSymmetricKey::<V3, Local>::from(*b"wubbalubbadubdubwubbalubbadubdub");
+    let wrapped = key.wrap_with_nonce(&wrapping_key, &nonce()?);
+    assert_eq!(wrapped, "k3.local-wrap.pie.63ewsFY0qOkUKDUCw80V3xwKBMH3pUGFGtNEKRyBlVKvp-tt2wSMQaWLelI66W8832VIErrEkmY4JVILovbmfPXKW9wT1OdQepjMTC_MOtgmQ5pVzC_XYDmaR6viVkDgXHps8GDGkjJ31mUhdggihQ");
+
+    let unwrapped = PasetoSymmetricKey::<V3, Local>::unwrap(&wrapped, &wrapping_key)?;
+    assert_eq!(unwrapped.as_ref(), key.as_ref());
+    Ok(())
+  }
+
+  #[cfg(all(feature = "v3_local", feature = "v3_public"))]
+  #[test]
+  fn test_k3_secret_wrap() -> Result<()> {
+    let wrapping_key = PasetoSymmetricKey::<V3, Local>::from(Key::<32>::try_from(WRAPPING_KEY)?);
+    let secret_key = Key::<48>::try_from(V3_SECRET_KEY)?;
+    let secret_key = PasetoAsymmetricPrivateKey::<V3, Public>::from(&secret_key);
+    let wrapped = secret_key.wrap_with_nonce(&wrapping_key, &nonce()?);
+    assert_eq!(wrapped, "k3.secret-wrap.pie.Z-lkqnl1NBSJjOTZlsgyPSWIoMv0FJAA9EFgS-Ka1Ll569ddSufKaGbdMVqBmKG_32VIErrEkmY4JVILovbmfPXKW9wT1OdQepjMTC_MOthxAo4-zTfVrvMA2BtmE7HX2BaY-3iEH-RzTakvyAsuZi3-0i7S25DDrH-1dBI-w9E");
+
+    let unwrapped = PasetoAsymmetricPrivateKey::<V3, Public>::unwrap(&wrapped, &wrapping_key)?;
+    assert_eq!(unwrapped.as_ref(), secret_key.as_ref());
+    Ok(())
+  }
+}
@@ -1,22 +1,51 @@
 use super::*;
 use crate::core::*;
-use std::convert::{AsRef, From};
+use std::convert::{AsRef, From, TryFrom};
 use std::marker::PhantomData;
 
 /// A wrapper for the private half of an asymmetric key pair
 ///
-/// [V2] and [V4] keys are created from [Key] of size 64, [V1] keys are of an arbitrary size
-pub struct PasetoAsymmetricPrivateKey<'a, Version, Purpose> {
+/// [V2] and [V4] keys are created from [Key] of size 64, [V3] keys from [Key] of size 48 and [V1] keys are of an
+/// arbitrary size. Slices are checked to be exactly the expected length for their version.
+///
+/// Asymmetric keys only exist for the [Public] purpose, so a private key sealing local tokens can't even be named
+///
+/// ```compile_fail,E0277
+/// use rusty_paseto::core::*;
+///
+/// fn seal<Version>(key: &PasetoAsymmetricPrivateKey<'_, Version, Local>) {}
+/// ```
+pub struct PasetoAsymmetricPrivateKey<'a, Version, Purpose>
+where
+  Purpose: AsymmetricPurpose,
+{
   version: PhantomData<Version>,
   purpose: PhantomData<Purpose>,
   key: &'a [u8],
 }
 
-impl<'a, Version> From<&'a [u8]> for PasetoAsymmetricPrivateKey<'a, Version, Public>
+impl<'a, Version> TryFrom<&'a [u8]> for PasetoAsymmetricPrivateKey<'a, Version, Public>
 where
   Version: V2orV4,
 {
-  fn from(key: &'a [u8]) -> Self {
+  type Error = PasetoError;
+  fn try_from(key: &'a [u8]) -> Result<Self, Self::Error> {
+    if key.len() != 64 {
+      return Err(PasetoError::InvalidKey);
+    }
+    Ok(Self {
+      version: PhantomData,
+      purpose: PhantomData,
+      key,
+    })
+  }
+}
+
+impl<'a, Version> From<&'a [u8; 64]> for PasetoAsymmetricPrivateKey<'a, Version, Public>
+where
+  Version: V2orV4,
+{
+  fn from(key: &'a [u8; 64]) -> Self {
     Self {
       version: PhantomData,
       purpose: PhantomData,
@@ -25,12 +54,27 @@ where
   }
 }
 
-impl<'a, Version, Purpose> AsRef<[u8]> for PasetoAsymmetricPrivateKey<'a, Version, Purpose> {
+impl<'a, Version, Purpose> AsRef<[u8]> for PasetoAsymmetricPrivateKey<'a, Version, Purpose>
+where
+  Purpose: AsymmetricPurpose,
+{
   fn as_ref(&self) -> &[u8] {
     self.key
   }
 }
 
+impl<'a, Version, Purpose> PasetoAsymmetricPrivateKey<'a, Version, Purpose>
+where
+  Purpose: AsymmetricPurpose,
+{
+  /// Compares the bytes of two keys in constant time, see [Key::ct_eq]
+  ///
+  /// V1 keys of different lengths compare unequal, which their lengths alone reveal.
+  pub fn ct_eq(&self, other: &Self) -> bool {
+    constant_time_equals(self.key, other.key).is_ok()
+  }
+}
+
 #[cfg(feature = "v1_public")]
 impl<'a> From<&'a [u8]> for PasetoAsymmetricPrivateKey<'a, V1, Public> {
   fn from(key: &'a [u8]) -> Self {
@@ -66,4 +110,142 @@ impl<'a> From<&'a Key<48>> for PasetoAsymmetricPrivateKey<'a, V3, Public> {
   }
 }
 
+#[cfg(feature = "v3_public")]
+impl<'a> From<&'a [u8; 48]> for PasetoAsymmetricPrivateKey<'a, V3, Public> {
+  fn from(key: &'a [u8; 48]) -> Self {
+    Self {
+      version: PhantomData,
+      purpose: PhantomData,
+      key,
+    }
+  }
+}
 
+#[cfg(feature = "v3_public")]
+impl<'a> TryFrom<&'a [u8]> for PasetoAsymmetricPrivateKey<'a, V3, Public> {
+  type Error = PasetoError;
+  fn try_from(key: &'a [u8]) -> Result<Self, Self::Error> {
+    if key.len() != 48 {
+      return Err(PasetoError::InvalidKey);
+    }
+    Ok(Self {
+      version: PhantomData,
+      purpose: PhantomData,
+      key,
+    })
+  }
+}
+
+#[cfg(any(feature = "v2_public", feature = "v4_public"))]
+impl<'a, Version> PasetoAsymmetricPrivateKey<'a, Version, Public>
+where
+  Version: V2orV4,
+{
+  /// Derives the Ed25519 public key of this secret key, for publication to verifiers
+  ///
+  /// # Errors
+  ///
+  /// Returns [`PasetoError`] if the key isn't a valid Ed25519 key pair, including when its public half doesn't
+  /// belong to its secret half
+  ///
+  /// # Example
+  /// ```
+  /// # #[cfg(feature = "v4_public")]
+  /// # {
+  /// use rusty_paseto::core::*;
+  ///
+  /// let private_key = Key::<64>::try_from("b4cbfb43df4ce210727d953e4a713307fa19bb7d9f85041438d9e11b942a37741eb9dbbbbc047c03fd70604e0071f0987e16b28b757225c11f00415d0e20b1a2")?;
+  /// let private_key = PasetoAsymmetricPrivateKey::<V4, Public>::from(&private_key);
+  ///
+  /// let public_key = private_key.public_key()?;
+  /// let public_key = PasetoAsymmetricPublicKey::<V4, Public>::from(&public_key);
+  /// # }
+  /// # Ok::<(),anyhow::Error>(())
+  /// ```
+  pub fn public_key(&self) -> Result<Key<32>, PasetoError> {
+    let signing_key = ed25519_dalek::SigningKey::from_keypair_bytes(<&[u8; 64]>::try_from(self.key)?)?;
+    Ok(Key::from(signing_key.verifying_key().to_bytes()))
+  }
+}
+
+#[cfg(feature = "v3_public")]
+impl PasetoAsymmetricPrivateKey<'_, V3, Public> {
+  /// Derives the compressed P-384 public key of this secret key, for publication to verifiers
+  ///
+  /// # Errors
+  ///
+  /// Returns [`PasetoError`] if the key isn't a valid P-384 secret scalar
+  pub fn public_key(&self) -> Result<Key<49>, PasetoError> {
+    let signing_key = p384::ecdsa::SigningKey::from_bytes(self.key).map_err(|_| PasetoError::InvalidKey)?;
+    let public_key = p384::ecdsa::VerifyingKey::from(&signing_key).to_encoded_point(true);
+    Ok(Key::from(public_key.as_bytes()))
+  }
+}
+
+#[cfg(all(test, feature = "v4_public"))]
+mod unit_tests {
+  use super::*;
+
+  #[test]
+  fn test_v4_private_key_from_array() {
+    let bytes = [1u8; 64];
+    let key = PasetoAsymmetricPrivateKey::<V4, Public>::from(&bytes);
+    assert_eq!(key.as_ref(), &bytes);
+  }
+
+  #[test]
+  fn test_v4_private_key_try_from_slice() {
+    let bytes = [1u8; 65];
+    assert!(PasetoAsymmetricPrivateKey::<V4, Public>::try_from(&bytes[..64]).is_ok());
+    assert!(matches!(
+      PasetoAsymmetricPrivateKey::<V4, Public>::try_from(&bytes[..32]),
+      Err(PasetoError::InvalidKey)
+    ));
+    assert!(matches!(
+      PasetoAsymmetricPrivateKey::<V4, Public>::try_from(&bytes[..]),
+      Err(PasetoError::InvalidKey)
+    ));
+  }
+
+  #[test]
+  fn test_v4_public_key_derivation() -> anyhow::Result<()> {
+    let private_key = Key::<64>::try_from("b4cbfb43df4ce210727d953e4a713307fa19bb7d9f85041438d9e11b942a37741eb9dbbbbc047c03fd70604e0071f0987e16b28b757225c11f00415d0e20b1a2")?;
+    let private_key = PasetoAsymmetricPrivateKey::<V4, Public>::from(&private_key);
+    assert_eq!(
+      hex::encode(*private_key.public_key()?),
+      "1eb9dbbbbc047c03fd70604e0071f0987e16b28b757225c11f00415d0e20b1a2"
+    );
+
+    //a public half which doesn't belong to the secret half is rejected
+    let mut mismatched = [0u8; 64];
+    mismatched[..32].copy_from_slice(&private_key.as_ref()[..32]);
+    assert!(PasetoAsymmetricPrivateKey::<V4, Public>::from(&mismatched).public_key().is_err());
+    Ok(())
+  }
+
+  #[cfg(feature = "v3_public")]
+  #[test]
+  fn test_v3_public_key_derivation() -> anyhow::Result<()> {
+    let private_key = Key::<48>::try_from(
+      "20347609607477aca8fbfbc5e6218455f3199669792ef8b466faa87bdc67798144c848dd03661eed5ac62461340cea96",
+    )?;
+    let private_key = PasetoAsymmetricPrivateKey::<V3, Public>::from(&private_key);
+    assert_eq!(
+      hex::encode(*private_key.public_key()?),
+      "02fbcb7c69ee1c60579be7a334134878d9c5c5bf35d552dab63c0140397ed14cef637d7720925c44699ea30e72874c72fb"
+    );
+    assert!(PasetoAsymmetricPrivateKey::<V3, Public>::from(&[0u8; 48]).public_key().is_err());
+    Ok(())
+  }
+
+  #[cfg(feature = "v3_public")]
+  #[test]
+  fn test_v3_private_key_try_from_slice() {
+    let bytes = [1u8; 49];
+    assert!(PasetoAsymmetricPrivateKey::<V3, Public>::try_from(&bytes[..48]).is_ok());
+    assert!(matches!(
+      PasetoAsymmetricPrivateKey::<V3, Public>::try_from(&bytes[..]),
+      Err(PasetoError::InvalidKey)
+    ));
+  }
+}
@@ -1,22 +1,49 @@
 use super::Key;
 use crate::core::*;
-use std::convert::{AsRef, From};
+use std::convert::{AsRef, From, TryFrom};
 use std::marker::PhantomData;
 /// A wrapper for the public half of an asymmetric key pair
 ///
-/// [V2] and [V4] keys are created from [Key] of size 32, [V1] keys are of an arbitrary size
-pub struct PasetoAsymmetricPublicKey<'a, Version, Purpose> {
+/// [V2] and [V4] keys are created from [Key] of size 32, [V3] keys from a compressed point [Key] of size 49 and [V1]
+/// keys are of an arbitrary size. Slices are checked to be exactly the expected length for their version.
+///
+/// Asymmetric keys only exist for the [Public] purpose, so a public key handed to a local parse can't even be named
+///
+/// ```compile_fail,E0277
+/// use rusty_paseto::core::*;
+///
+/// fn open<Version>(key: &PasetoAsymmetricPublicKey<'_, Version, Local>) {}
+/// ```
+pub struct PasetoAsymmetricPublicKey<'a, Version, Purpose>
+where
+  Purpose: AsymmetricPurpose,
+{
   version: PhantomData<Version>,
   purpose: PhantomData<Purpose>,
   key: &'a [u8],
 }
 
-impl<'a, Version, Purpose> AsRef<[u8]> for PasetoAsymmetricPublicKey<'a, Version, Purpose> {
+impl<'a, Version, Purpose> AsRef<[u8]> for PasetoAsymmetricPublicKey<'a, Version, Purpose>
+where
+  Purpose: AsymmetricPurpose,
+{
   fn as_ref(&self) -> &[u8] {
     self.key
   }
 }
 
+impl<'a, Version, Purpose> PasetoAsymmetricPublicKey<'a, Version, Purpose>
+where
+  Purpose: AsymmetricPurpose,
+{
+  /// Compares the bytes of two keys in constant time, see [Key::ct_eq]
+  ///
+  /// V1 keys of different lengths compare unequal, which their lengths alone reveal.
+  pub fn ct_eq(&self, other: &Self) -> bool {
+    constant_time_equals(self.key, other.key).is_ok()
+  }
+}
+
 #[cfg(feature = "v1_public")]
 impl<'a> From<&'a [u8]> for PasetoAsymmetricPublicKey<'a, V1, Public> {
   fn from(key: &'a [u8]) -> Self {
@@ -32,14 +59,32 @@ impl<'a> From<&'a [u8]> for PasetoAsymmetricPublicKey<'a, V1, Public> {
 impl<'a> TryFrom<&'a Key<49>> for PasetoAsymmetricPublicKey<'a, V3, Public> {
   type Error = PasetoError;
   fn try_from(key: &'a Key<49>) -> Result<Self, Self::Error> {
-    if key[0] != 2 && key[0] != 3 {
+    Self::try_from(key.as_ref())
+  }
+}
+
+/// Even with the exact array size, a [V3] public key must be a compressed point, so construction
+/// remains fallible
+#[cfg(feature = "v3_public")]
+impl<'a> TryFrom<&'a [u8; 49]> for PasetoAsymmetricPublicKey<'a, V3, Public> {
+  type Error = PasetoError;
+  fn try_from(key: &'a [u8; 49]) -> Result<Self, Self::Error> {
+    Self::try_from(&key[..])
+  }
+}
+
+#[cfg(feature = "v3_public")]
+impl<'a> TryFrom<&'a [u8]> for PasetoAsymmetricPublicKey<'a, V3, Public> {
+  type Error = PasetoError;
+  fn try_from(key: &'a [u8]) -> Result<Self, Self::Error> {
+    if key.len() != 49 || (key[0] != 2 && key[0] != 3) {
       return Err(PasetoError::InvalidKey);
     }
     //if this is successful, we can be sure our key is in a valid format
     Ok(Self {
       version: PhantomData,
       purpose: PhantomData,
-      key: key.as_ref(),
+      key,
     })
   }
 }
@@ -56,3 +101,74 @@ where
     }
   }
 }
+
+impl<'a, Version> From<&'a [u8; 32]> for PasetoAsymmetricPublicKey<'a, Version, Public>
+where
+  Version: V2orV4,
+{
+  fn from(key: &'a [u8; 32]) -> Self {
+    Self {
+      version: PhantomData,
+      purpose: PhantomData,
+      key,
+    }
+  }
+}
+
+impl<'a, Version> TryFrom<&'a [u8]> for PasetoAsymmetricPublicKey<'a, Version, Public>
+where
+  Version: V2orV4,
+{
+  type Error = PasetoError;
+  fn try_from(key: &'a [u8]) -> Result<Self, Self::Error> {
+    if key.len() != 32 {
+      return Err(PasetoError::InvalidKey);
+    }
+    Ok(Self {
+      version: PhantomData,
+      purpose: PhantomData,
+      key,
+    })
+  }
+}
+
+#[cfg(all(test, feature = "v4_public"))]
+mod unit_tests {
+  use super::*;
+
+  #[test]
+  fn test_v4_public_key_try_from_slice() {
+    let bytes = [1u8; 33];
+    let array = [1u8; 32];
+    assert_eq!(PasetoAsymmetricPublicKey::<V4, Public>::from(&array).as_ref(), &array);
+    assert!(PasetoAsymmetricPublicKey::<V4, Public>::try_from(&bytes[..32]).is_ok());
+    assert!(matches!(
+      PasetoAsymmetricPublicKey::<V4, Public>::try_from(&bytes[..]),
+      Err(PasetoError::InvalidKey)
+    ));
+  }
+
+  #[test]
+  fn test_public_key_ct_eq() {
+    let array = [1u8; 32];
+    let key = PasetoAsymmetricPublicKey::<V4, Public>::from(&array);
+    assert!(key.ct_eq(&PasetoAsymmetricPublicKey::from(&[1u8; 32])));
+    assert!(!key.ct_eq(&PasetoAsymmetricPublicKey::from(&[2u8; 32])));
+  }
+
+  #[cfg(feature = "v3_public")]
+  #[test]
+  fn test_v3_public_key_try_from_slice() {
+    let mut bytes = [2u8; 50];
+    assert!(PasetoAsymmetricPublicKey::<V3, Public>::try_from(&bytes[..49]).is_ok());
+    assert!(matches!(
+      PasetoAsymmetricPublicKey::<V3, Public>::try_from(&bytes[..]),
+      Err(PasetoError::InvalidKey)
+    ));
+    bytes[0] = 4;
+    assert!(matches!(
+      PasetoAsymmetricPublicKey::<V3, Public>::try_from(&bytes[..49]),
+      Err(PasetoError::InvalidKey)
+    ));
+  }
+}
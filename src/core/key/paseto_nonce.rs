@@ -37,7 +37,6 @@ use std::ops::Deref;
 ///
 /// # Ok::<(),anyhow::Error>(())
 /// ```
-
 pub struct PasetoNonce<'a, Version, Purpose> {
   version: PhantomData<Version>,
   purpose: PhantomData<Purpose>,
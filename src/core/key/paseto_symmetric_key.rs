@@ -1,12 +1,25 @@
 use super::Key;
-use crate::core::Local;
-use std::convert::{AsRef, From};
+use crate::core::{constant_time_equals, Local, PasetoError, SymmetricPurpose};
+use std::convert::{AsRef, From, TryFrom};
 use std::marker::PhantomData;
 
 /// A wrapper for a symmetric key
 ///
-/// Keys are created from [Key] of size 32
-pub struct PasetoSymmetricKey<Version, Purpose> {
+/// Keys are created from [Key] of size 32, an array of 32 bytes or a slice which is checked to be
+/// exactly 32 bytes long
+///
+/// Symmetric keys only exist for the [Local] purpose, so a key able to open or seal public tokens can't even be
+/// named
+///
+/// ```compile_fail,E0277
+/// use rusty_paseto::core::*;
+///
+/// fn sign<Version>(key: &PasetoSymmetricKey<Version, Public>) {}
+/// ```
+pub struct PasetoSymmetricKey<Version, Purpose>
+where
+  Purpose: SymmetricPurpose,
+{
   version: PhantomData<Version>,
   purpose: PhantomData<Purpose>,
   key: Key<32>,
@@ -22,8 +35,73 @@ impl<Version> From<Key<32>> for PasetoSymmetricKey<Version, Local> {
   }
 }
 
-impl<Version, Purpose> AsRef<[u8]> for PasetoSymmetricKey<Version, Purpose> {
+impl<Version> From<[u8; 32]> for PasetoSymmetricKey<Version, Local> {
+  fn from(key: [u8; 32]) -> Self {
+    Self::from(Key::from(key))
+  }
+}
+
+impl<Version> TryFrom<&[u8]> for PasetoSymmetricKey<Version, Local> {
+  type Error = PasetoError;
+  fn try_from(key: &[u8]) -> Result<Self, Self::Error> {
+    let key = <[u8; 32]>::try_from(key).map_err(|_| PasetoError::InvalidKey)?;
+    Ok(Self::from(key))
+  }
+}
+
+impl<Version, Purpose> AsRef<[u8]> for PasetoSymmetricKey<Version, Purpose>
+where
+  Purpose: SymmetricPurpose,
+{
   fn as_ref(&self) -> &[u8] {
     self.key.as_ref()
   }
 }
+
+impl<Version, Purpose> PasetoSymmetricKey<Version, Purpose>
+where
+  Purpose: SymmetricPurpose,
+{
+  /// Compares the bytes of two keys in constant time, see [Key::ct_eq]
+  pub fn ct_eq(&self, other: &Self) -> bool {
+    constant_time_equals(self.as_ref(), other.as_ref()).is_ok()
+  }
+}
+
+#[cfg(all(test, feature = "v4_local"))]
+mod unit_tests {
+  use super::*;
+  use crate::core::V4;
+
+  #[test]
+  fn test_symmetric_key_from_array() {
+    let key = PasetoSymmetricKey::<V4, Local>::from([7u8; 32]);
+    assert_eq!(key.as_ref(), &[7u8; 32]);
+  }
+
+  #[test]
+  fn test_symmetric_key_ct_eq() {
+    let key = PasetoSymmetricKey::<V4, Local>::from([7u8; 32]);
+    assert!(key.ct_eq(&PasetoSymmetricKey::from([7u8; 32])));
+    let mut other = [7u8; 32];
+    other[31] = 8;
+    assert!(!key.ct_eq(&PasetoSymmetricKey::from(other)));
+
+    assert!(Key::from([7u8; 32]).ct_eq(&Key::from([7u8; 32])));
+    assert!(!Key::from([7u8; 32]).ct_eq(&Key::from(other)));
+  }
+
+  #[test]
+  fn test_symmetric_key_try_from_slice() {
+    let bytes = [7u8; 33];
+    assert!(PasetoSymmetricKey::<V4, Local>::try_from(&bytes[..32]).is_ok());
+    assert!(matches!(
+      PasetoSymmetricKey::<V4, Local>::try_from(&bytes[..31]),
+      Err(PasetoError::InvalidKey)
+    ));
+    assert!(matches!(
+      PasetoSymmetricKey::<V4, Local>::try_from(&bytes[..]),
+      Err(PasetoError::InvalidKey)
+    ));
+  }
+}
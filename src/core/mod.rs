@@ -49,7 +49,11 @@ mod key;
 mod paseto;
 mod payload;
 mod purpose;
+#[cfg(feature = "v2_local")]
+mod raw_components;
+mod supported_tokens;
 mod traits;
+mod untrusted_token;
 mod version;
 
 pub use error::PasetoError;
@@ -59,7 +63,11 @@ pub use implicit_assertion::ImplicitAssertion;
 pub use key::{Key, PasetoAsymmetricPrivateKey, PasetoAsymmetricPublicKey, PasetoNonce, PasetoSymmetricKey};
 pub use paseto::Paseto;
 pub use payload::Payload;
-pub use purpose::{Local, Public};
-pub(crate) use traits::{Base64Encodable, V1orV3, V2orV4};
-pub use traits::{ImplicitAssertionCapable, PurposeTrait, VersionTrait};
+pub use purpose::{Local, PasetoPurpose, Public};
+#[cfg(feature = "v2_local")]
+pub use raw_components::V2LocalComponents;
+pub use supported_tokens::supported_tokens;
+pub(crate) use traits::{constant_time_equals, Base64Encodable, V1orV3, V2orV4};
+pub use traits::{AsymmetricPurpose, ImplicitAssertionCapable, PurposeTrait, SymmetricPurpose, VersionTrait};
+pub use untrusted_token::{max_decoded_len, overhead_bytes, token_len, UntrustedToken};
 pub use version::*;
@@ -4,7 +4,7 @@ use std::{
     fmt::Display,
     marker::PhantomData,
     ops::{Add, Deref},
-    str, usize,
+    str,
 };
 
 #[cfg(feature = "aes")]
@@ -40,14 +40,8 @@ use ring::{
     rand::SystemRandom,
     signature::{RSA_PSS_2048_8192_SHA384, RSA_PSS_SHA384, RsaKeyPair, UnparsedPublicKey},
 };
-use ring::constant_time::verify_slices_are_equal as ConstantTimeEquals;
+#[cfg(any(feature = "v1_local", feature = "v3_local"))]
 use ring::hkdf;
-// #[cfg(any(feature = "v1_local", feature = "v3_local", feature = "v4_local"))]
-#[cfg(all(
-any(feature = "v1_local", feature = "v3_local", feature = "v4_local"),
-not(any(feature = "v1_public", feature = "v2_public", feature = "v3_public", feature = "v4_public")),
-))]
-use ring::hmac::SigningKey;
 #[cfg(all(feature = "p384", feature = "v3_public"))]
 use sha2::Digest;
 #[cfg(feature = "sha2")]
@@ -56,7 +50,6 @@ use sha2::Sha384;
 use super::*;
 
 /// Used to build and encrypt / decrypt core PASETO tokens
-
 ///
 /// Given a [Payload], optional [Footer] and optional [ImplicitAssertion] ([V3] or [V4] only)
 /// returns an encrypted token when [Local] is specified as the purpose or a signed token when
@@ -184,16 +177,46 @@ impl<'a, Version: VersionTrait, Purpose: PurposeTrait> Paseto<'a, Version, Purpo
         }
     }
 
+    #[cfg(any(
+        feature = "v1_local",
+        feature = "v2_local",
+        feature = "v3_local",
+        feature = "v1_public",
+        feature = "v2_public",
+        feature = "v3_public",
+        feature = "v4_public"
+    ))]
     fn parse_raw_token(
         raw_token: &'a str,
-        footer: (impl Into<Option<Footer<'a>>> + Copy),
+        footer: impl Into<Option<Footer<'a>>> + Copy,
         v: &Version,
         p: &Purpose,
     ) -> Result<Vec<u8>, PasetoError> {
-        //split the raw token into parts
-        let potential_parts = raw_token.split('.').collect::<Vec<_>>();
+        let mut decoded_payload = Vec::new();
+        Self::parse_raw_token_into(raw_token, footer, v, p, &mut decoded_payload)?;
+        Ok(decoded_payload)
+    }
+
+    //parses like parse_raw_token but decodes the payload into the given buffer, reusing its capacity
+    fn parse_raw_token_into(
+        raw_token: &'a str,
+        footer: impl Into<Option<Footer<'a>>> + Copy,
+        v: &Version,
+        p: &Purpose,
+        decoded_payload: &mut Vec<u8>,
+    ) -> Result<(), PasetoError> {
+        //split the raw token into parts, without allocating since this runs for every token parsed
+        let mut potential_parts = [""; 4];
+        let mut length = 0;
+        for part in raw_token.split('.') {
+            if length == potential_parts.len() {
+                return Err(PasetoError::IncorrectSize);
+            }
+            potential_parts[length] = part;
+            length += 1;
+        }
         //inspect the parts
-        match potential_parts.len() {
+        match length {
             length if !(3..=4).contains(&length) => {
                 return Err(PasetoError::IncorrectSize);
             }
@@ -208,18 +231,21 @@ impl<'a, Version: VersionTrait, Purpose: PurposeTrait> Paseto<'a, Version, Purpo
             _ => {}
         }
 
-        //grab the header
-        let potential_header = format!("{}.{}.", potential_parts[0], potential_parts[1]);
         //we should be able to verify the header using the passed in Version and Purpose
-        let expected_header = format!("{}.{}.", v, p);
-
-        //verify the header
-        if potential_header.ne(&expected_header) {
+        if potential_parts[0] != v.as_ref() || potential_parts[1] != p.as_ref() {
             return Err(PasetoError::WrongHeader);
         };
 
-        let encrypted_payload = Payload::from(potential_parts[2]);
-        Ok(encrypted_payload.decode()?)
+        decoded_payload.clear();
+        Payload::from(potential_parts[2]).decode_into(decoded_payload)?;
+
+        //reject payloads too short for the nonce, tag or signature before any cryptography.  This is checked on the
+        //decoded bytes since padded base64 decodes to fewer bytes than its length suggests
+        let overhead = overhead_bytes(PasetoVersion::try_from(potential_parts[0])?, PasetoPurpose::try_from(potential_parts[1])?);
+        if decoded_payload.len() < overhead {
+            return Err(PasetoError::MalformedPayload);
+        }
+        Ok(())
     }
     /* END PRIVATE FUNCTIONS */
 }
@@ -271,15 +297,52 @@ impl<'a> Paseto<'a, V1, Public> {
     pub fn try_verify(
         signature: &'a str,
         public_key: &PasetoAsymmetricPublicKey<V1, Public>,
-        footer: (impl Into<Option<Footer<'a>>> + Copy),
+        footer: impl Into<Option<Footer<'a>>> + Copy,
     ) -> Result<String, PasetoError> {
         let decoded_payload = Self::parse_raw_token(signature, footer, &V1::default(), &Public::default())?;
 
-        let ciphertext =
-            CipherText::<V1, Public>::try_verify(&decoded_payload, public_key, &footer.into().unwrap_or_default())?
-                .ciphertext;
+        let msg = decoded_payload[..(decoded_payload.len() - 256)].as_ref();
+        let sig = decoded_payload[msg.len()..].as_ref();
+
+        Self::verify_detached(public_key, msg, sig, footer)?;
+
+        Ok(String::from_utf8(Vec::from(msg))?)
+    }
+
+    /// Verifies a signature stored apart from the message it signs, as if they were the payload of a
+    /// (V1, Public) token
+    ///
+    /// **Advanced.** The inputs are the raw bytes a token string carries once its payload is base64 decoded, not
+    /// anything base64 encoded:
+    ///
+    /// * `message` is the signed message, the JSON claims for a token built by this crate
+    /// * `signature` is the 256 byte RSA-PSS signature which follows the message in the decoded payload
+    /// * `footer` is the footer in the clear, exactly as it was signed
+    ///
+    /// The `v1.public.` header is authenticated implicitly.  Nothing is checked but the signature, so the message
+    /// still has to be parsed and its claims validated by the caller.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`PasetoError`] if the signature isn't 256 bytes or doesn't verify for the message and footer
+    pub fn verify_detached(
+        public_key: &PasetoAsymmetricPublicKey<V1, Public>,
+        message: &[u8],
+        signature: &[u8],
+        footer: impl Into<Option<Footer<'a>>> + Copy,
+    ) -> Result<(), PasetoError> {
+        if signature.len() != 256 {
+            return Err(PasetoError::InvalidSignature);
+        }
+        let public_key = UnparsedPublicKey::new(&RSA_PSS_2048_8192_SHA384, public_key);
+        let pae = PreAuthenticationEncoding::parse(&[
+            &Header::<V1, Public>::default(),
+            message,
+            &footer.into().unwrap_or_default(),
+        ]);
 
-        Ok(String::from_utf8(ciphertext)?)
+        public_key.verify(&pae, signature)?;
+        Ok(())
     }
 
     /// Attempts to sign a V1 Public Paseto
@@ -313,20 +376,46 @@ impl<'a> Paseto<'a, V2, Public> {
     pub fn try_verify(
         signature: &'a str,
         public_key: &PasetoAsymmetricPublicKey<V2, Public>,
-        footer: (impl Into<Option<Footer<'a>>> + Copy),
+        footer: impl Into<Option<Footer<'a>>> + Copy,
     ) -> Result<String, PasetoError> {
         let decoded_payload = Self::parse_raw_token(signature, footer, &V2::default(), &Public::default())?;
 
-        let verifying_key: VerifyingKey = VerifyingKey::from_bytes(<&[u8; 32]>::try_from(public_key.as_ref())?)?;
-
         // let public_key = PublicKey::from_bytes(public_key.as_ref()).map_err(|_| PasetoError::InvalidSignature)?;
         let msg = decoded_payload[..(decoded_payload.len() - ed25519_dalek::SIGNATURE_LENGTH)].as_ref();
         let sig = decoded_payload[msg.len()..msg.len() + ed25519_dalek::SIGNATURE_LENGTH].as_ref();
 
-        let signature = Signature::try_from(sig).map_err(|_| PasetoError::InvalidSignature)?;
+        Self::verify_detached(public_key, msg, sig, footer)?;
+
+        Ok(String::from_utf8(Vec::from(msg))?)
+    }
+
+    /// Verifies a signature stored apart from the message it signs, as if they were the payload of a
+    /// (V2, Public) token
+    ///
+    /// **Advanced.** The inputs are the raw bytes a token string carries once its payload is base64 decoded, not
+    /// anything base64 encoded:
+    ///
+    /// * `message` is the signed message, the JSON claims for a token built by this crate
+    /// * `signature` is the 64 byte Ed25519 signature which follows the message in the decoded payload
+    /// * `footer` is the footer in the clear, exactly as it was signed
+    ///
+    /// The `v2.public.` header is authenticated implicitly.  Nothing is checked but the signature, so the message
+    /// still has to be parsed and its claims validated by the caller.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`PasetoError`] if the signature isn't 64 bytes or doesn't verify for the message and footer
+    pub fn verify_detached(
+        public_key: &PasetoAsymmetricPublicKey<V2, Public>,
+        message: &[u8],
+        signature: &[u8],
+        footer: impl Into<Option<Footer<'a>>> + Copy,
+    ) -> Result<(), PasetoError> {
+        let verifying_key: VerifyingKey = VerifyingKey::from_bytes(<&[u8; 32]>::try_from(public_key.as_ref())?)?;
+        let signature = Signature::try_from(signature).map_err(|_| PasetoError::InvalidSignature)?;
         let pae = PreAuthenticationEncoding::parse(&[
             &Header::<V2, Public>::default(),
-            msg,
+            message,
             &footer.into().unwrap_or_default(),
         ]);
 
@@ -334,8 +423,7 @@ impl<'a> Paseto<'a, V2, Public> {
         // public_key
         //     .verify(&pae, &signature)
         //     .map_err(|_| PasetoError::InvalidSignature)?;
-
-        Ok(String::from_utf8(Vec::from(msg))?)
+        Ok(())
     }
 
     /// Attempts to sign a V2 Public Paseto
@@ -377,7 +465,7 @@ impl<'a> Paseto<'a, V1, Local> {
     pub fn try_decrypt(
         token: &'a str,
         key: &PasetoSymmetricKey<V1, Local>,
-        footer: (impl Into<Option<Footer<'a>>> + Copy),
+        footer: impl Into<Option<Footer<'a>>> + Copy,
     ) -> Result<String, PasetoError> {
         let decoded_payload = Self::parse_raw_token(token, footer, &V1::default(), &Local::default())?;
         let nonce = Key::from(&decoded_payload[..32]);
@@ -402,7 +490,7 @@ impl<'a> Paseto<'a, V1, Local> {
         let tag = &decoded_payload[(nonce.len() + ciphertext.len())..];
         let tag2 = &Tag::<V1, Local>::from(authentication_key, &pae);
         //compare tags
-        ConstantTimeEquals(tag, tag2)?;
+        constant_time_equals(tag, tag2)?;
 
         //decrypt payload
         let ciphertext = CipherText::<V1, Local>::from(ciphertext, &encryption_key);
@@ -489,7 +577,7 @@ impl<'a> Paseto<'a, V2, Local> {
     pub fn try_decrypt(
         token: &'a str,
         key: &PasetoSymmetricKey<V2, Local>,
-        footer: (impl Into<Option<Footer<'a>>> + Copy),
+        footer: impl Into<Option<Footer<'a>>> + Copy,
     ) -> Result<String, PasetoError> {
         //get footer
 
@@ -537,11 +625,31 @@ impl<'a> Paseto<'a, V2, Local> {
         key: &PasetoSymmetricKey<V2, Local>,
         nonce: &PasetoNonce<V2, Local>,
     ) -> Result<String, PasetoError> {
+        let components = self.try_encrypt_raw(key, nonce)?;
+
+        //generate appended and base64 encoded payload
+        let raw_payload = RawPayload::<V2, Local>::from(&components.nonce, &[&components.ciphertext[..], &components.tag].concat());
+
+        //format as paseto with header and optional footer
+        Ok(self.format_token(&raw_payload))
+    }
+
+    /// Encrypts the payload like [try_encrypt](Self::try_encrypt) but returns the raw nonce, ciphertext and tag
+    /// instead of the assembled token string
+    ///
+    /// **Advanced.** This is an escape hatch for custom framing such as binary protocols, see
+    /// [V2LocalComponents] for what has to be preserved to decrypt the parts again.  Prefer
+    /// [try_encrypt](Self::try_encrypt) whenever a token string will do.
+    pub fn try_encrypt_raw(
+        &self,
+        key: &PasetoSymmetricKey<V2, Local>,
+        nonce: &PasetoNonce<V2, Local>,
+    ) -> Result<V2LocalComponents, PasetoError> {
         //setup
         let footer = self.footer.unwrap_or_default();
 
         //create the blake2 context to generate the nonce
-        let mut blake2 = VarBlake2b::new_keyed(nonce.as_ref(), 24);
+        let mut blake2 = VarBlake2b::new_keyed(nonce.as_ref(), V2LocalComponents::NONCE_LEN);
         blake2.update(&*self.payload);
         let context = blake2.finalize_boxed();
 
@@ -551,14 +659,38 @@ impl<'a> Paseto<'a, V2, Local> {
         //pack preauth
         let pae = PreAuthenticationEncoding::parse(&[&self.header, nonce, &footer]);
 
-        //encrypt payload
+        //encrypt payload, the tag is appended to the ciphertext
         let ciphertext = CipherText::<V2, Local>::try_from(key, nonce, &self.payload, &pae)?;
 
-        //generate appended and base64 encoded payload
-        let raw_payload = RawPayload::<V2, Local>::from(&context, &ciphertext);
+        V2LocalComponents::try_from([&context[..], &ciphertext[..]].concat().as_slice())
+    }
 
-        //format as paseto with header and optional footer
-        Ok(self.format_token(&raw_payload))
+    /// Decrypts the raw parts of a (V2, Local) token produced by [try_encrypt_raw](Self::try_encrypt_raw), given the
+    /// same footer the parts were encrypted with
+    ///
+    /// **Advanced.** See [V2LocalComponents].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`PasetoError`] if the parts, key or footer don't authenticate
+    pub fn try_decrypt_raw(
+        components: &V2LocalComponents,
+        key: &PasetoSymmetricKey<V2, Local>,
+        footer: impl Into<Option<Footer<'a>>> + Copy,
+    ) -> Result<String, PasetoError> {
+        //pack preauth
+        let pae = &PreAuthenticationEncoding::parse(&[
+            &Header::<V2, Local>::default(),
+            &components.nonce,
+            &footer.into().unwrap_or_default(),
+        ]);
+
+        //decrypt the ciphertext with its tag appended
+        let ciphertext = [&components.ciphertext[..], &components.tag].concat();
+        let nonce = XNonce::from_slice(&components.nonce);
+        let ciphertext = CipherText::<V2, Local>::try_decrypt_from(key, nonce, &ciphertext, pae)?;
+
+        Ok(str::from_utf8(&ciphertext)?.to_owned())
     }
 }
 
@@ -583,8 +715,8 @@ impl<'a> Paseto<'a, V3, Local> {
     pub fn try_decrypt(
         token: &'a str,
         key: &PasetoSymmetricKey<V3, Local>,
-        footer: (impl Into<Option<Footer<'a>>> + Copy),
-        implicit_assertion: (impl Into<Option<ImplicitAssertion<'a>>> + Copy),
+        footer: impl Into<Option<Footer<'a>>> + Copy,
+        implicit_assertion: impl Into<Option<ImplicitAssertion<'a>>> + Copy,
     ) -> Result<String, PasetoError> {
         //get footer
 
@@ -611,7 +743,7 @@ impl<'a> Paseto<'a, V3, Local> {
         let tag = &decoded_payload[(nonce.len() + ciphertext.len())..];
         let tag2 = &Tag::<V3, Local>::from(authentication_key, &pae);
         //compare tags
-        ConstantTimeEquals(tag, tag2)?;
+        constant_time_equals(tag, tag2)?;
 
         //decrypt payload
         let ciphertext = CipherText::<V3, Local>::from(ciphertext, &encryption_key);
@@ -676,11 +808,45 @@ impl<'a> Paseto<'a, V3, Public> {
     pub fn try_verify(
         signature: &'a str,
         public_key: &PasetoAsymmetricPublicKey<V3, Public>,
-        footer: (impl Into<Option<Footer<'a>>> + Copy),
-        implicit_assertion: (impl Into<Option<ImplicitAssertion<'a>>> + Copy),
+        footer: impl Into<Option<Footer<'a>>> + Copy,
+        implicit_assertion: impl Into<Option<ImplicitAssertion<'a>>> + Copy,
     ) -> Result<String, PasetoError> {
         let decoded_payload = Self::parse_raw_token(signature, footer, &V3::default(), &Public::default())?;
 
+        let msg = decoded_payload[..(decoded_payload.len() - 96)].as_ref();
+        let sig = decoded_payload[msg.len()..msg.len() + 96].as_ref();
+
+        Self::verify_detached(public_key, msg, sig, footer, implicit_assertion)?;
+
+        Ok(String::from_utf8(Vec::from(msg))?)
+    }
+
+    /// Verifies a signature stored apart from the message it signs, as if they were the payload of a
+    /// (V3, Public) token
+    ///
+    /// **Advanced.** The inputs are the raw bytes a token string carries once its payload is base64 decoded, not
+    /// anything base64 encoded:
+    ///
+    /// * `message` is the signed message, the JSON claims for a token built by this crate
+    /// * `signature` is the 96 byte P-384 ECDSA signature, `r` and `s` of 48 bytes each, which follows the message in
+    ///   the decoded payload
+    /// * `footer` is the footer in the clear, exactly as it was signed
+    /// * `implicit_assertion` is the implicit assertion the token was signed with, which is never part of a token
+    ///
+    /// The `v3.public.` header and the compressed public key are authenticated implicitly.  Nothing is checked but
+    /// the signature, so the message still has to be parsed and its claims validated by the caller.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`PasetoError`] if the public key isn't a P-384 point, the signature isn't 96 bytes or it doesn't
+    /// verify for the message, footer and implicit assertion
+    pub fn verify_detached(
+        public_key: &PasetoAsymmetricPublicKey<V3, Public>,
+        message: &[u8],
+        signature: &[u8],
+        footer: impl Into<Option<Footer<'a>>> + Copy,
+        implicit_assertion: impl Into<Option<ImplicitAssertion<'a>>> + Copy,
+    ) -> Result<(), PasetoError> {
         //compress the key
         let compressed_public_key = PublicCompressedKey::from_sec1_bytes(public_key.as_ref())
             .map_err(|_| PasetoError::InvalidKey)?
@@ -688,14 +854,12 @@ impl<'a> Paseto<'a, V3, Public> {
 
         let verifying_key =
             P384VerifyingKey::from_sec1_bytes(compressed_public_key.as_ref()).map_err(|_| PasetoError::InvalidKey)?;
-        let msg = decoded_payload[..(decoded_payload.len() - 96)].as_ref();
-        let sig = decoded_payload[msg.len()..msg.len() + 96].as_ref();
 
-        let signature = P384Signature::try_from(sig).map_err(|_| PasetoError::Signature)?;
+        let signature = P384Signature::try_from(signature).map_err(|_| PasetoError::Signature)?;
         let m2 = PreAuthenticationEncoding::parse(&[
             compressed_public_key.as_ref(),
             &Header::<V3, Public>::default(),
-            msg,
+            message,
             &footer.into().unwrap_or_default(),
             &implicit_assertion.into().unwrap_or_default(),
         ]);
@@ -705,8 +869,7 @@ impl<'a> Paseto<'a, V3, Public> {
         verifying_key
             .verify_digest(msg_digest, &signature)
             .map_err(|_| PasetoError::InvalidSignature)?;
-
-        Ok(String::from_utf8(Vec::from(msg))?)
+        Ok(())
     }
 
     /// Attempts to sign a V3 Public Paseto
@@ -757,12 +920,60 @@ impl<'a> Paseto<'a, V4, Local> {
     pub fn try_decrypt(
         token: &'a str,
         key: &PasetoSymmetricKey<V4, Local>,
-        footer: (impl Into<Option<Footer<'a>>> + Copy),
-        implicit_assertion: (impl Into<Option<ImplicitAssertion<'a>>> + Copy),
+        footer: impl Into<Option<Footer<'a>>> + Copy,
+        implicit_assertion: impl Into<Option<ImplicitAssertion<'a>>> + Copy,
     ) -> Result<String, PasetoError> {
-        //get footer
+        let mut plaintext = Vec::new();
+        Self::try_decrypt_into(token, key, footer, implicit_assertion, &mut plaintext)?;
+
+        //return decrypted payload
+        Ok(String::from_utf8(plaintext)?)
+    }
+
+    /// Attempts to decrypt a PASETO token like [try_decrypt](Self::try_decrypt), writing the plaintext into the
+    /// given buffer instead of returning a new string
+    ///
+    /// The buffer is cleared first and decrypted in place, so reusing one buffer across calls saves the allocations
+    /// of the decoded payload, the ciphertext and the returned string once it has grown to the size of the largest
+    /// token.  What remains are a few small allocations of a fixed size for the derived keys and the tag, whatever
+    /// the size of the payload.  On success it holds the UTF-8 plaintext, on failure it is left empty.
+    /// ```
+    /// # use serde_json::json;
+    /// # use rusty_paseto::core::*;
+    /// # let key = PasetoSymmetricKey::<V4, Local>::from(Key::<32>::try_from("707172737475767778797a7b7c7d7e7f808182838485868788898a8b8c8d8e8f")?);
+    /// # let nonce = Key::<32>::try_from("0000000000000000000000000000000000000000000000000000000000000000")?;
+    /// # let nonce = PasetoNonce::<V4, Local>::from(&nonce);
+    /// # let payload = json!({"data": "this is a secret message", "exp":"2022-01-01T00:00:00+00:00"}).to_string();
+    /// # let payload = payload.as_str();
+    /// # let token = Paseto::<V4, Local>::builder().set_payload(Payload::from(payload)).try_encrypt(&key, &nonce)?;
+    /// let mut plaintext = Vec::with_capacity(1024);
+    /// Paseto::<V4, Local>::try_decrypt_into(&token, &key, None, None, &mut plaintext)?;
+    /// # assert_eq!(plaintext, payload.as_bytes());
+    /// # Ok::<(),anyhow::Error>(())
+    /// ```
+    pub fn try_decrypt_into(
+        token: &'a str,
+        key: &PasetoSymmetricKey<V4, Local>,
+        footer: impl Into<Option<Footer<'a>>> + Copy,
+        implicit_assertion: impl Into<Option<ImplicitAssertion<'a>>> + Copy,
+        plaintext: &mut Vec<u8>,
+    ) -> Result<(), PasetoError> {
+        let result = Self::decrypt_in_place(token, key, footer, implicit_assertion, plaintext);
+        if result.is_err() {
+            //never hand out a partially processed payload
+            plaintext.clear();
+        }
+        result
+    }
 
-        let decoded_payload = Self::parse_raw_token(token, footer, &V4::default(), &Local::default())?;
+    fn decrypt_in_place(
+        token: &'a str,
+        key: &PasetoSymmetricKey<V4, Local>,
+        footer: impl Into<Option<Footer<'a>>> + Copy,
+        implicit_assertion: impl Into<Option<ImplicitAssertion<'a>>> + Copy,
+        decoded_payload: &mut Vec<u8>,
+    ) -> Result<(), PasetoError> {
+        Self::parse_raw_token_into(token, footer, &V4::default(), &Local::default(), decoded_payload)?;
         let nonce = Key::from(&decoded_payload[..32]);
         let nonce = PasetoNonce::<V4, Local>::from(&nonce);
 
@@ -770,30 +981,31 @@ impl<'a> Paseto<'a, V4, Local> {
             AuthenticationKey::<V4, Local>::from(&(AuthenticationKeySeparator::default() + &nonce), key);
         let encryption_key = EncryptionKey::<V4, Local>::from(&(EncryptionKeySeparator::default() + &nonce), key);
 
-        let ciphertext = &decoded_payload[32..(decoded_payload.len() - 32)];
-
-        //pack preauth
-        let pae = PreAuthenticationEncoding::parse(&[
-            &Header::<V4, Local>::default(),
-            nonce.as_ref(),
-            ciphertext,
-            &footer.into().unwrap_or_default(),
-            &implicit_assertion.into().unwrap_or_default(),
-        ]);
-
-        //generate tags
-        let tag = &decoded_payload[(nonce.len() + ciphertext.len())..];
-        let tag2 = &Tag::<V4, Local>::from(authentication_key, &pae);
+        let tag_start = decoded_payload.len() - 32;
+        let (message, tag) = decoded_payload.split_at(tag_start);
+
+        //generate tags, streaming the preauth rather than packing a copy of the ciphertext
+        let tag2 = &Tag::<V4, Local>::from_pieces(
+            authentication_key,
+            &[
+                &Header::<V4, Local>::default(),
+                nonce.as_ref(),
+                &message[32..],
+                &footer.into().unwrap_or_default(),
+                &implicit_assertion.into().unwrap_or_default(),
+            ],
+        );
         //compare tags
-        ConstantTimeEquals(tag, tag2)?;
+        constant_time_equals(tag, tag2)?;
 
-        //decrypt payload
-        let ciphertext = CipherText::<V4, Local>::from(ciphertext, &encryption_key);
-
-        let decoded_str = str::from_utf8(&ciphertext)?;
+        //decrypt payload in place, then drop the nonce and tag around it
+        let mut cipher = chacha20::XChaCha20::new(encryption_key.as_ref(), encryption_key.counter_nonce());
+        cipher.apply_keystream(&mut decoded_payload[32..tag_start]);
+        decoded_payload.truncate(tag_start);
+        decoded_payload.drain(..32);
 
-        //return decrypted payload
-        Ok(decoded_str.to_owned())
+        str::from_utf8(decoded_payload)?;
+        Ok(())
     }
 
     /// Attempts to encrypt a PASETO token
@@ -851,28 +1063,79 @@ impl<'a> Paseto<'a, V4, Public> {
     pub fn try_verify(
         signature: &'a str,
         public_key: &PasetoAsymmetricPublicKey<V4, Public>,
-        footer: (impl Into<Option<Footer<'a>>> + Copy),
-        implicit_assertion: (impl Into<Option<ImplicitAssertion<'a>>> + Copy),
+        footer: impl Into<Option<Footer<'a>>> + Copy,
+        implicit_assertion: impl Into<Option<ImplicitAssertion<'a>>> + Copy,
     ) -> Result<String, PasetoError> {
         let decoded_payload = Self::parse_raw_token(signature, footer, &V4::default(), &Public::default())?;
 
-        let verifying_key: VerifyingKey = VerifyingKey::from_bytes(<&[u8; 32]>::try_from(public_key.as_ref())?)?;
-
         let msg = decoded_payload[..(decoded_payload.len() - ed25519_dalek::SIGNATURE_LENGTH)].as_ref();
         let sig = decoded_payload[msg.len()..msg.len() + ed25519_dalek::SIGNATURE_LENGTH].as_ref();
 
-        let signature = Signature::try_from(sig)?;
+        Self::verify_detached(public_key, msg, sig, footer, implicit_assertion)?;
+
+        Ok(String::from_utf8(Vec::from(msg))?)
+    }
+
+    /// Verifies a signature stored apart from the message it signs, as if they were the payload of a
+    /// (V4, Public) token
+    ///
+    /// **Advanced.** The inputs are the raw bytes a token string carries once its payload is base64 decoded, not
+    /// anything base64 encoded:
+    ///
+    /// * `message` is the signed message, the JSON claims for a token built by this crate
+    /// * `signature` is the 64 byte Ed25519 signature which follows the message in the decoded payload
+    /// * `footer` is the footer in the clear, exactly as it was signed
+    /// * `implicit_assertion` is the implicit assertion the token was signed with, which is never part of a token
+    ///
+    /// The `v4.public.` header is authenticated implicitly.  Nothing is checked but the signature, so the message
+    /// still has to be parsed and its claims validated by the caller.
+    ///
+    /// # Usage
+    /// ```
+    /// # #[cfg(feature = "v4_public")]
+    /// # {
+    /// # use rusty_paseto::core::*;
+    /// # let private_key = Key::<64>::try_from("b4cbfb43df4ce210727d953e4a713307fa19bb7d9f85041438d9e11b942a37741eb9dbbbbc047c03fd70604e0071f0987e16b28b757225c11f00415d0e20b1a2")?;
+    /// # let private_key = PasetoAsymmetricPrivateKey::<V4, Public>::from(&private_key);
+    /// # let public_key = Key::<32>::try_from("1eb9dbbbbc047c03fd70604e0071f0987e16b28b757225c11f00415d0e20b1a2")?;
+    /// # let public_key = PasetoAsymmetricPublicKey::<V4, Public>::from(&public_key);
+    /// let token = Paseto::<V4, Public>::builder()
+    ///   .set_payload(Payload::from("{\"data\":\"this is a signed message\"}"))
+    ///   .try_sign(&private_key)?;
+    ///
+    /// //store the message and its signature apart
+    /// let payload = base64::decode_config(token.trim_start_matches("v4.public."), base64::URL_SAFE_NO_PAD)?;
+    /// let (message, signature) = payload.split_at(payload.len() - 64);
+    ///
+    /// Paseto::<V4, Public>::verify_detached(&public_key, message, signature, None, None)?;
+    /// assert!(Paseto::<V4, Public>::verify_detached(&public_key, b"{}", signature, None, None).is_err());
+    /// # }
+    /// # Ok::<(),anyhow::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns [`PasetoError`] if the signature isn't 64 bytes or doesn't verify for the message, footer and
+    /// implicit assertion
+    pub fn verify_detached(
+        public_key: &PasetoAsymmetricPublicKey<V4, Public>,
+        message: &[u8],
+        signature: &[u8],
+        footer: impl Into<Option<Footer<'a>>> + Copy,
+        implicit_assertion: impl Into<Option<ImplicitAssertion<'a>>> + Copy,
+    ) -> Result<(), PasetoError> {
+        let verifying_key: VerifyingKey = VerifyingKey::from_bytes(<&[u8; 32]>::try_from(public_key.as_ref())?)?;
+        let signature = Signature::try_from(signature)?;
         let pae = PreAuthenticationEncoding::parse(&[
             &Header::<V4, Public>::default(),
-            msg,
+            message,
             &footer.into().unwrap_or_default(),
             &implicit_assertion.into().unwrap_or_default(),
         ]);
 
         verifying_key.verify(&pae, &signature)?;
         // public_key.verify(&pae, &signature)?;
-
-        Ok(String::from_utf8(Vec::from(msg))?)
+        Ok(())
     }
 
     pub fn try_sign(&mut self, key: &PasetoAsymmetricPrivateKey<V4, Public>) -> Result<String, PasetoError> {
@@ -900,27 +1163,6 @@ struct CipherText<Version, Purpose> {
     purpose: PhantomData<Purpose>,
 }
 
-#[cfg(feature = "v1_public")]
-impl CipherText<V1, Public> {
-    fn try_verify(decoded_payload: &[u8], public_key: &impl AsRef<[u8]>, footer: &Footer) -> Result<Self, PasetoError> {
-        let signature = decoded_payload[(decoded_payload.len() - 256)..].as_ref();
-        let public_key = UnparsedPublicKey::new(&RSA_PSS_2048_8192_SHA384, public_key);
-        let msg = decoded_payload[..(decoded_payload.len() - 256)].as_ref();
-
-        let pae = PreAuthenticationEncoding::parse(&[&Header::<V1, Public>::default(), msg, footer]);
-
-        public_key.verify(&pae, signature)?;
-
-        let ciphertext = Vec::from(msg);
-
-        Ok(CipherText {
-            ciphertext,
-            version: PhantomData,
-            purpose: PhantomData,
-        })
-    }
-}
-
 #[cfg(feature = "v1_local")]
 impl CipherText<V1, Local> {
     fn from(payload: &[u8], encryption_key: &EncryptionKey<V1, Local>) -> Self {
@@ -1047,16 +1289,6 @@ impl<Version, Purpose> std::ops::Deref for CipherText<Version, Purpose> {
     }
 }
 
-#[cfg(feature = "chacha20poly1305")]
-struct EncryptionNonce(XNonce);
-
-#[cfg(feature = "chacha20poly1305")]
-impl AsRef<XNonce> for EncryptionNonce {
-    fn as_ref(&self) -> &XNonce {
-        &self.0
-    }
-}
-
 struct Tag<Version, Purpose> {
     version: PhantomData<Version>,
     purpose: PhantomData<Purpose>,
@@ -1068,8 +1300,21 @@ impl Tag<V4, Local> {
     fn from(authentication_key: impl AsRef<[u8]>, pae: &PreAuthenticationEncoding) -> Self {
         let mut tag_context = VarBlake2b::new_keyed(authentication_key.as_ref(), 32);
         tag_context.update(pae.as_ref());
+        Self::finalize(tag_context)
+    }
+
+    //tags the pre-authentication encoding of the pieces as it is produced, without packing it into a buffer first
+    fn from_pieces(authentication_key: impl AsRef<[u8]>, pieces: &[&[u8]]) -> Self {
+        let mut tag_context = VarBlake2b::new_keyed(authentication_key.as_ref(), 32);
+        PreAuthenticationEncoding::encode(pieces, |bytes| tag_context.update(bytes));
+        Self::finalize(tag_context)
+    }
+
+    fn finalize(tag_context: VarBlake2b) -> Self {
+        let mut tag = Vec::new();
+        tag_context.finalize_variable(|out| tag.extend_from_slice(out));
         Self {
-            tag: tag_context.finalize_boxed().as_ref().to_vec(),
+            tag,
             version: PhantomData,
             purpose: PhantomData,
         }
@@ -1245,9 +1490,12 @@ impl EncryptionKey<V4, Local> {
         //let mut context = Blake2b::new_keyed(key.as_ref(), 56);
         let mut context = VarBlake2b::new_keyed(key.as_ref(), 56);
         context.update(message.as_ref());
-        let context = context.finalize_boxed();
-        let key = context.as_ref()[..32].to_vec();
-        let nonce = context.as_ref()[32..].to_vec();
+        let mut key = Vec::new();
+        let mut nonce = Vec::new();
+        context.finalize_variable(|out| {
+            key.extend_from_slice(&out[..32]);
+            nonce.extend_from_slice(&out[32..]);
+        });
         assert_eq!(key.len(), 32);
         assert_eq!(nonce.len(), 24);
         Self {
@@ -1298,15 +1546,18 @@ impl Deref for EncryptionKey<V4, Local> {
     }
 }
 
+#[cfg(any(feature = "v1_local", feature = "v3_local"))]
 #[derive(Debug, PartialEq)]
 struct HkdfKey<T: core::fmt::Debug + PartialEq>(T);
 
+#[cfg(any(feature = "v1_local", feature = "v3_local"))]
 impl hkdf::KeyType for HkdfKey<usize> {
     fn len(&self) -> usize {
         self.0
     }
 }
 
+#[cfg(any(feature = "v1_local", feature = "v3_local"))]
 impl TryFrom<hkdf::Okm<'_, HkdfKey<usize>>> for HkdfKey<Vec<u8>> {
     type Error = PasetoError;
     fn try_from(okm: hkdf::Okm<HkdfKey<usize>>) -> Result<Self, Self::Error> {
@@ -1361,10 +1612,12 @@ impl AuthenticationKey<V4, Local> {
     fn from(message: &Key<56>, key: &PasetoSymmetricKey<V4, Local>) -> Self {
         let mut context = VarBlake2b::new_keyed(key.as_ref(), 32);
         context.update(message.as_ref());
+        let mut authentication_key = Vec::new();
+        context.finalize_variable(|out| authentication_key.extend_from_slice(out));
         Self {
             version: PhantomData,
             purpose: PhantomData,
-            key: context.finalize_boxed().as_ref().to_vec(),
+            key: authentication_key,
         }
     }
 }
@@ -1470,30 +1723,44 @@ pub struct PreAuthenticationEncoding(Vec<u8>);
 ///
 impl PreAuthenticationEncoding {
     /// * `pieces` - The Pieces to concatenate, and encode together.
+    ///
     /// Refactored from original code found at
     /// <https://github.com/instructure/paseto/blob/trunk/src/pae.rs>
     pub fn parse<'a>(pieces: &'a [&'a [u8]]) -> Self {
-        let the_vec = PreAuthenticationEncoding::le64(pieces.len() as u64);
+        let mut the_vec = Vec::with_capacity(8 + pieces.iter().map(|piece| 8 + piece.len()).sum::<usize>());
+        Self::encode(pieces, |bytes| the_vec.extend_from_slice(bytes));
+        Self(the_vec)
+    }
 
-        Self(pieces.iter().fold(the_vec, |mut acc, piece| {
-            acc.extend(PreAuthenticationEncoding::le64(piece.len() as u64));
-            acc.extend(piece.iter());
-            acc
-        }))
+    //hands the encoding to the sink chunk by chunk, so it can be hashed or copied without intermediate buffers
+    pub(crate) fn encode(pieces: &[&[u8]], mut sink: impl FnMut(&[u8])) {
+        sink(&Self::le64(pieces.len() as u64));
+        for piece in pieces {
+            sink(&Self::le64(piece.len() as u64));
+            sink(piece);
+        }
     }
     /// Encodes a u64-bit unsigned integer into a little-endian binary string.
     ///
     /// * `to_encode` - The u8 to encode.
+    ///
+    /// As required by the specification, the most significant bit is cleared for interoperability
+    /// with languages which lack unsigned 64-bit integers.
+    ///
     /// Copied and gently refactored from <https://github.com/instructure/paseto/blob/trunk/src/pae.rs>
-    pub(crate) fn le64(mut to_encode: u64) -> Vec<u8> {
-        let mut the_vec = Vec::with_capacity(8);
+    pub(crate) fn le64(mut to_encode: u64) -> [u8; 8] {
+        let mut the_bytes = [0u8; 8];
 
-        for _idx in 0..8 {
-            the_vec.push((to_encode & 255) as u8);
+        for (idx, byte) in the_bytes.iter_mut().enumerate() {
+            if idx == 7 {
+                //clear the most significant bit
+                to_encode &= 127;
+            }
+            *byte = (to_encode & 255) as u8;
             to_encode >>= 8;
         }
 
-        the_vec
+        the_bytes
     }
 }
 
@@ -1510,3 +1777,242 @@ impl AsRef<Vec<u8>> for PreAuthenticationEncoding {
         &self.0
     }
 }
+
+#[cfg(test)]
+mod unit_tests {
+    use super::*;
+    use anyhow::Result;
+
+    #[test]
+    fn test_pae_no_pieces() {
+        let pae = PreAuthenticationEncoding::parse(&[]);
+        assert_eq!(pae.as_ref(), &[0u8; 8]);
+    }
+
+    #[test]
+    fn test_pae_empty_piece() {
+        let pae = PreAuthenticationEncoding::parse(&[b""]);
+        assert_eq!(pae.as_ref(), &[1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]);
+
+        let pae = PreAuthenticationEncoding::parse(&[b"", b""]);
+        let mut expected = vec![2, 0, 0, 0, 0, 0, 0, 0];
+        expected.extend([0u8; 16]);
+        assert_eq!(pae.as_ref(), &expected);
+    }
+
+    #[test]
+    fn test_pae_pieces() {
+        let pae = PreAuthenticationEncoding::parse(&[b"test"]);
+        assert_eq!(
+            pae.as_ref(),
+            b"\x01\x00\x00\x00\x00\x00\x00\x00\x04\x00\x00\x00\x00\x00\x00\x00test"
+        );
+
+        let pae = PreAuthenticationEncoding::parse(&[b"v4.local.", b"ab", b""]);
+        let mut expected = b"\x03\x00\x00\x00\x00\x00\x00\x00".to_vec();
+        expected.extend(b"\x09\x00\x00\x00\x00\x00\x00\x00v4.local.");
+        expected.extend(b"\x02\x00\x00\x00\x00\x00\x00\x00ab");
+        expected.extend(b"\x00\x00\x00\x00\x00\x00\x00\x00");
+        assert_eq!(pae.as_ref(), &expected);
+
+        //lengths past a single byte are little-endian
+        let piece = [b'a'; 258];
+        let pae = PreAuthenticationEncoding::parse(&[&piece]);
+        assert_eq!(&pae[8..16], &[2, 1, 0, 0, 0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn test_le64() {
+        assert_eq!(PreAuthenticationEncoding::le64(0), [0; 8]);
+        assert_eq!(PreAuthenticationEncoding::le64(1), [1, 0, 0, 0, 0, 0, 0, 0]);
+        assert_eq!(
+            PreAuthenticationEncoding::le64(0x0102_0304_0506_0708),
+            [8, 7, 6, 5, 4, 3, 2, 1]
+        );
+        //the most significant bit is always cleared
+        assert_eq!(
+            PreAuthenticationEncoding::le64(u64::MAX),
+            [255, 255, 255, 255, 255, 255, 255, 127]
+        );
+        assert_eq!(PreAuthenticationEncoding::le64(1 << 63), [0; 8]);
+    }
+
+    #[cfg(feature = "v4_local")]
+    #[test]
+    fn test_truncated_payload() -> Result<()> {
+        let key = PasetoSymmetricKey::<V4, Local>::from(Key::<32>::try_from(KEY)?);
+        assert!(matches!(
+            Paseto::<V4, Local>::try_decrypt("v4.local.", &key, None, None),
+            Err(PasetoError::MalformedPayload)
+        ));
+        //63 of the 64 bytes of nonce and tag
+        let truncated = format!("v4.local.{}", encode_config([0u8; 63], URL_SAFE_NO_PAD));
+        assert!(matches!(
+            Paseto::<V4, Local>::try_decrypt(&truncated, &key, None, None),
+            Err(PasetoError::MalformedPayload)
+        ));
+        //room for an empty message gets as far as authentication
+        let empty = format!("v4.local.{}", encode_config([0u8; 64], URL_SAFE_NO_PAD));
+        assert!(Paseto::<V4, Local>::try_decrypt(&empty, &key, None, None)
+            .unwrap_err()
+            .is_authentication_failure());
+        Ok(())
+    }
+
+    #[cfg(feature = "v2_public")]
+    #[test]
+    fn test_truncated_signature() -> Result<()> {
+        let key = Key::<32>::from([0u8; 32]);
+        let key = PasetoAsymmetricPublicKey::<V2, Public>::from(&key);
+        let truncated = format!("v2.public.{}", encode_config([0u8; 63], URL_SAFE_NO_PAD));
+        assert!(matches!(
+            Paseto::<V2, Public>::try_verify(&truncated, &key, None),
+            Err(PasetoError::MalformedPayload)
+        ));
+        Ok(())
+    }
+
+    #[cfg(feature = "v2_public")]
+    #[test]
+    fn test_verify_detached() -> Result<()> {
+        let private_key = Key::<64>::try_from("b4cbfb43df4ce210727d953e4a713307fa19bb7d9f85041438d9e11b942a37741eb9dbbbbc047c03fd70604e0071f0987e16b28b757225c11f00415d0e20b1a2")?;
+        let private_key = PasetoAsymmetricPrivateKey::<V2, Public>::from(&private_key);
+        let public_key = Key::<32>::try_from("1eb9dbbbbc047c03fd70604e0071f0987e16b28b757225c11f00415d0e20b1a2")?;
+        let public_key = PasetoAsymmetricPublicKey::<V2, Public>::from(&public_key);
+        let footer = Footer::from("some footer");
+        let token = Paseto::<V2, Public>::builder()
+            .set_payload(Payload::from("{\"data\":\"this is a signed message\"}"))
+            .set_footer(footer)
+            .try_sign(&private_key)?;
+
+        let payload = token.split('.').nth(2).unwrap_or_default();
+        let payload = base64::decode_config(payload, URL_SAFE_NO_PAD)?;
+        let (message, signature) = payload.split_at(payload.len() - 64);
+
+        Paseto::<V2, Public>::verify_detached(&public_key, message, signature, footer)?;
+        assert!(Paseto::<V2, Public>::verify_detached(&public_key, message, signature, None).is_err());
+        assert!(Paseto::<V2, Public>::verify_detached(&public_key, b"{}", signature, footer).is_err());
+        assert!(matches!(
+            Paseto::<V2, Public>::verify_detached(&public_key, message, &signature[1..], footer),
+            Err(PasetoError::InvalidSignature)
+        ));
+        Ok(())
+    }
+
+    #[cfg(feature = "v1_public")]
+    #[test]
+    fn test_v1_verify_detached() -> Result<()> {
+        let private_key: &[u8] = include_bytes!("../../tests/v1_public_test_vectors_private_key.pk8");
+        let private_key = PasetoAsymmetricPrivateKey::<V1, Public>::from(private_key);
+        let public_key: &[u8] = include_bytes!("../../tests/v1_public_test_vectors_public_key.der");
+        let public_key = PasetoAsymmetricPublicKey::<V1, Public>::from(public_key);
+        let footer = Footer::from("some footer");
+        let token = Paseto::<V1, Public>::builder()
+            .set_payload(Payload::from("{\"data\":\"this is a signed message\"}"))
+            .set_footer(footer)
+            .try_sign(&private_key)?;
+
+        let payload = token.split('.').nth(2).unwrap_or_default();
+        let payload = base64::decode_config(payload, URL_SAFE_NO_PAD)?;
+        let (message, signature) = payload.split_at(payload.len() - 256);
+
+        Paseto::<V1, Public>::verify_detached(&public_key, message, signature, footer)?;
+        assert!(Paseto::<V1, Public>::verify_detached(&public_key, message, signature, None).is_err());
+        assert!(Paseto::<V1, Public>::verify_detached(&public_key, b"{}", signature, footer).is_err());
+        assert!(matches!(
+            Paseto::<V1, Public>::verify_detached(&public_key, message, &signature[1..], footer),
+            Err(PasetoError::InvalidSignature)
+        ));
+        Ok(())
+    }
+
+    #[cfg(feature = "v3_public")]
+    #[test]
+    fn test_v3_verify_detached() -> Result<()> {
+        let private_key = Key::<48>::try_from(
+            "20347609607477aca8fbfbc5e6218455f3199669792ef8b466faa87bdc67798144c848dd03661eed5ac62461340cea96",
+        )?;
+        let private_key = PasetoAsymmetricPrivateKey::<V3, Public>::from(&private_key);
+        let public_key = Key::<49>::try_from(
+            "02fbcb7c69ee1c60579be7a334134878d9c5c5bf35d552dab63c0140397ed14cef637d7720925c44699ea30e72874c72fb",
+        )?;
+        let public_key = PasetoAsymmetricPublicKey::<V3, Public>::try_from(&public_key)?;
+        let footer = Footer::from("some footer");
+        let implicit_assertion = ImplicitAssertion::from("some assertion");
+        let token = Paseto::<V3, Public>::builder()
+            .set_payload(Payload::from("{\"data\":\"this is a signed message\"}"))
+            .set_footer(footer)
+            .set_implicit_assertion(implicit_assertion)
+            .try_sign(&private_key)?;
+
+        let payload = token.split('.').nth(2).unwrap_or_default();
+        let payload = base64::decode_config(payload, URL_SAFE_NO_PAD)?;
+        let (message, signature) = payload.split_at(payload.len() - 96);
+
+        Paseto::<V3, Public>::verify_detached(&public_key, message, signature, footer, implicit_assertion)?;
+        assert!(Paseto::<V3, Public>::verify_detached(&public_key, message, signature, footer, None).is_err());
+        assert!(
+            Paseto::<V3, Public>::verify_detached(&public_key, b"{}", signature, footer, implicit_assertion).is_err()
+        );
+        assert!(matches!(
+            Paseto::<V3, Public>::verify_detached(&public_key, message, &signature[1..], footer, implicit_assertion),
+            Err(PasetoError::Signature)
+        ));
+        Ok(())
+    }
+
+    //the key of the official v3/v4 local test vectors, the expected subkeys were computed independently of this crate
+    //with the HKDF-SHA384 and keyed BLAKE2b steps described in the specification
+    const KEY: &str = "707172737475767778797a7b7c7d7e7f808182838485868788898a8b8c8d8e8f";
+
+    #[cfg(feature = "v3_local")]
+    #[test]
+    fn test_v3_local_key_derivation() -> Result<()> {
+        //nonce of test vector 3-E-3
+        let nonce = Key::<32>::try_from("26f7553354482a1d91d4784627854b8da6b8042a7966523c2b404e8dbbe7f7f2")?;
+        let nonce = PasetoNonce::<V3, Local>::from(&nonce);
+        let key = PasetoSymmetricKey::<V3, Local>::from(Key::<32>::try_from(KEY)?);
+
+        let encryption_key = EncryptionKey::<V3, Local>::try_from(&(EncryptionKeySeparator::default() + &nonce), &key)?;
+        assert_eq!(
+            hex::encode(&*encryption_key),
+            "c17c4f34ac4c7e8d410e613d01f275592b5d136180847d1bb8784ce4be859704"
+        );
+        assert_eq!(hex::encode(encryption_key.counter_nonce()), "53323f4ddc4e373789a7625e896a0c60");
+
+        let authentication_key =
+            AuthenticationKey::<V3, Local>::try_from(&(AuthenticationKeySeparator::default() + &nonce), &key)?;
+        assert_eq!(
+            hex::encode(&*authentication_key),
+            "4c16e7b17756b2d8cd42101e8f7138904d322203f37335b260a8c3575856c7668830d3f96df46472d3383638385ac088"
+        );
+        Ok(())
+    }
+
+    #[cfg(feature = "v4_local")]
+    #[test]
+    fn test_v4_local_key_derivation() -> Result<()> {
+        //nonce of test vector 4-E-3
+        let nonce = Key::<32>::try_from("df654812bac492663825520ba2f6e67cf5ca5bdc13d4e7507a98cc4c2fcc3ad8")?;
+        let nonce = PasetoNonce::<V4, Local>::from(&nonce);
+        let key = PasetoSymmetricKey::<V4, Local>::from(Key::<32>::try_from(KEY)?);
+
+        let encryption_key = EncryptionKey::<V4, Local>::from(&(EncryptionKeySeparator::default() + &nonce), &key);
+        assert_eq!(
+            hex::encode(&*encryption_key),
+            "15ff8fdad29bd0546a34fe4378cef6af1758916cd6fd9ced06ba60026e21f912"
+        );
+        assert_eq!(
+            hex::encode(encryption_key.counter_nonce()),
+            "b7dbd2c6aae19c31d048a8dfdd0c7d3a74549f8411563e56"
+        );
+
+        let authentication_key =
+            AuthenticationKey::<V4, Local>::from(&(AuthenticationKeySeparator::default() + &nonce), &key);
+        assert_eq!(
+            hex::encode(&*authentication_key),
+            "47269073e673702ba62012865fd52b7e008b0badf8ba6d43c8ee0935b9d4aba9"
+        );
+        Ok(())
+    }
+}
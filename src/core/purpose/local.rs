@@ -6,6 +6,7 @@ use std::fmt::Display;
 #[derive(Debug, Clone, Copy)]
 pub struct Local(&'static str);
 impl PurposeTrait for Local {}
+impl SymmetricPurpose for Local {}
 impl Default for Local {
   fn default() -> Self {
     Self("local")
@@ -1,4 +1,6 @@
 mod local;
+mod paseto_purpose;
 mod public;
 pub use local::Local;
+pub use paseto_purpose::PasetoPurpose;
 pub use public::Public;
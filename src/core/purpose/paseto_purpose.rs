@@ -0,0 +1,60 @@
+use crate::core::PasetoError;
+use std::convert::TryFrom;
+use std::fmt;
+use std::fmt::Display;
+use std::str::FromStr;
+
+/// A PASETO purpose determined at runtime
+///
+/// The [Local](crate::core::Local) and [Public](crate::core::Public) types select a purpose at compile time.
+/// A [PasetoPurpose] is what you get when inspecting an untrusted token string with an
+/// [UntrustedToken](crate::core::UntrustedToken).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum PasetoPurpose {
+  /// Symmetric encryption
+  Local,
+  /// Asymmetric signatures
+  Public,
+}
+
+impl PasetoPurpose {
+  /// The purpose as it appears in a PASETO header, e.g. `local`
+  pub fn as_str(&self) -> &'static str {
+    match self {
+      Self::Local => "local",
+      Self::Public => "public",
+    }
+  }
+}
+
+impl AsRef<str> for PasetoPurpose {
+  fn as_ref(&self) -> &str {
+    self.as_str()
+  }
+}
+
+impl Display for PasetoPurpose {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "{}", self.as_str())
+  }
+}
+
+impl TryFrom<&str> for PasetoPurpose {
+  type Error = PasetoError;
+
+  fn try_from(value: &str) -> Result<Self, Self::Error> {
+    match value {
+      "local" => Ok(Self::Local),
+      "public" => Ok(Self::Public),
+      _ => Err(PasetoError::WrongHeader),
+    }
+  }
+}
+
+impl FromStr for PasetoPurpose {
+  type Err = PasetoError;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    Self::try_from(s)
+  }
+}
@@ -7,6 +7,7 @@ use std::fmt::Display;
 pub struct Public(&'static str);
 
 impl PurposeTrait for Public {}
+impl AsymmetricPurpose for Public {}
 impl AsRef<str> for Public {
   fn as_ref(&self) -> &str {
     self.0
@@ -0,0 +1,119 @@
+use std::convert::TryFrom;
+
+use super::PasetoError;
+
+/// The raw parts of an encrypted (V2, Local) token, for callers framing them in their own transport
+///
+/// **Advanced, easy to get wrong.** These are the bytes the standard token string base64 encodes after its
+/// `v2.local.` header: the 24 byte nonce, then the ciphertext, then the 16 byte Poly1305 tag.  Any other layout
+/// isn't a PASETO token and won't interoperate with other implementations.  The header and footer are
+/// authenticated but not carried here, so the same footer has to travel alongside for
+/// [try_decrypt_raw](crate::core::Paseto::try_decrypt_raw) to succeed.
+///
+/// # Usage
+/// ```
+/// # #[cfg(feature = "v2_local")]
+/// # {
+/// # use rusty_paseto::core::*;
+/// # let key = PasetoSymmetricKey::<V2, Local>::from(Key::<32>::from(*b"wubbalubbadubdubwubbalubbadubdub"));
+/// # let nonce = Key::<32>::try_new_random()?;
+/// # let nonce = PasetoNonce::<V2, Local>::from(&nonce);
+/// let components = Paseto::<V2, Local>::builder()
+///   .set_payload(Payload::from("{\"data\":\"this is a secret message\"}"))
+///   .try_encrypt_raw(&key, &nonce)?;
+///
+/// //frame the parts however the transport needs, then put them back together on the other side
+/// let framed = components.to_bytes();
+/// let components = V2LocalComponents::try_from(framed.as_slice())?;
+///
+/// let payload = Paseto::<V2, Local>::try_decrypt_raw(&components, &key, None)?;
+/// assert_eq!(payload, "{\"data\":\"this is a secret message\"}");
+/// # }
+/// # Ok::<(),anyhow::Error>(())
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct V2LocalComponents {
+  /// The nonce derived from the payload and the random nonce given when encrypting
+  pub nonce: [u8; 24],
+  /// The encrypted payload, as long as the plaintext
+  pub ciphertext: Vec<u8>,
+  /// The Poly1305 authentication tag
+  pub tag: [u8; 16],
+}
+
+impl V2LocalComponents {
+  /// The length of the nonce in bytes
+  pub const NONCE_LEN: usize = 24;
+  /// The length of the authentication tag in bytes
+  pub const TAG_LEN: usize = 16;
+
+  /// The components laid out as nonce, ciphertext and tag, the layout of the decoded payload of a token
+  pub fn to_bytes(&self) -> Vec<u8> {
+    [&self.nonce[..], &self.ciphertext, &self.tag].concat()
+  }
+}
+
+impl TryFrom<&[u8]> for V2LocalComponents {
+  type Error = PasetoError;
+
+  /// Splits bytes laid out as nonce, ciphertext and tag
+  ///
+  /// # Errors
+  ///
+  /// Returns [MalformedPayload](PasetoError::MalformedPayload) if there aren't enough bytes for the nonce and tag
+  fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
+    if bytes.len() < Self::NONCE_LEN + Self::TAG_LEN {
+      return Err(PasetoError::MalformedPayload);
+    }
+    let (nonce, rest) = bytes.split_at(Self::NONCE_LEN);
+    let (ciphertext, tag) = rest.split_at(rest.len() - Self::TAG_LEN);
+    Ok(Self {
+      nonce: <[u8; 24]>::try_from(nonce).map_err(|_| PasetoError::MalformedPayload)?,
+      ciphertext: ciphertext.to_vec(),
+      tag: <[u8; 16]>::try_from(tag).map_err(|_| PasetoError::MalformedPayload)?,
+    })
+  }
+}
+
+#[cfg(test)]
+mod unit_tests {
+  use super::*;
+  use crate::core::*;
+  use anyhow::Result;
+
+  #[test]
+  fn test_raw_components_match_token() -> Result<()> {
+    let key = PasetoSymmetricKey::<V2, Local>::from(Key::from(*b"wubbalubbadubdubwubbalubbadubdub"));
+    let nonce = Key::<32>::from([0u8; 32]);
+    let nonce = PasetoNonce::<V2, Local>::from(&nonce);
+    let payload = Payload::from("{\"data\":\"this is a secret message\"}");
+    let footer = Footer::from("some footer");
+
+    let mut builder = Paseto::<V2, Local>::builder();
+    builder.set_payload(payload).set_footer(footer);
+    let token = builder.try_encrypt(&key, &nonce)?;
+    let components = builder.try_encrypt_raw(&key, &nonce)?;
+
+    //the components are exactly the decoded payload of the token
+    let untrusted = token.split('.').nth(2).unwrap_or_default();
+    assert_eq!(
+      base64::decode_config(untrusted, base64::URL_SAFE_NO_PAD)?,
+      components.to_bytes()
+    );
+    assert_eq!(
+      Paseto::<V2, Local>::try_decrypt_raw(&components, &key, footer)?,
+      "{\"data\":\"this is a secret message\"}"
+    );
+    assert!(Paseto::<V2, Local>::try_decrypt_raw(&components, &key, None).is_err());
+
+    let mut tampered = components.clone();
+    tampered.tag[0] ^= 1;
+    assert!(Paseto::<V2, Local>::try_decrypt_raw(&tampered, &key, footer).is_err());
+
+    assert!(matches!(
+      V2LocalComponents::try_from(&[0u8; 39][..]),
+      Err(PasetoError::MalformedPayload)
+    ));
+    Ok(())
+  }
+}
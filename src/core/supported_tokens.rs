@@ -0,0 +1,71 @@
+use super::{PasetoPurpose, PasetoVersion};
+
+//one entry per version and purpose feature, in the order of the versions
+const SUPPORTED_TOKENS: &[(PasetoVersion, PasetoPurpose)] = &[
+  #[cfg(feature = "v1_local")]
+  (PasetoVersion::V1, PasetoPurpose::Local),
+  #[cfg(feature = "v1_public")]
+  (PasetoVersion::V1, PasetoPurpose::Public),
+  #[cfg(feature = "v2_local")]
+  (PasetoVersion::V2, PasetoPurpose::Local),
+  #[cfg(feature = "v2_public")]
+  (PasetoVersion::V2, PasetoPurpose::Public),
+  #[cfg(feature = "v3_local")]
+  (PasetoVersion::V3, PasetoPurpose::Local),
+  #[cfg(feature = "v3_public")]
+  (PasetoVersion::V3, PasetoPurpose::Public),
+  #[cfg(feature = "v4_local")]
+  (PasetoVersion::V4, PasetoPurpose::Local),
+  #[cfg(feature = "v4_public")]
+  (PasetoVersion::V4, PasetoPurpose::Public),
+];
+
+/// The versions and purposes of the tokens this build of the crate can create and open, one for each enabled
+/// version and purpose feature such as `v4_local`
+///
+/// For reporting the capabilities of a deployment, or checking a header before dispatching on it.  The pairs are
+/// ordered by version and then purpose.
+///
+/// # Example
+/// ```
+/// # #[cfg(all(feature = "core", feature = "v4_local"))]
+/// # {
+/// use rusty_paseto::core::*;
+///
+/// assert!(supported_tokens().contains(&(PasetoVersion::V4, PasetoPurpose::Local)));
+/// for (version, purpose) in supported_tokens() {
+///   println!("{}.{}", version, purpose);
+/// }
+/// # }
+/// ```
+pub fn supported_tokens() -> &'static [(PasetoVersion, PasetoPurpose)] {
+  SUPPORTED_TOKENS
+}
+
+#[cfg(test)]
+mod unit_tests {
+  use super::*;
+
+  #[test]
+  fn test_supported_tokens_follow_features() {
+    let enabled = [
+      cfg!(feature = "v1_local"),
+      cfg!(feature = "v1_public"),
+      cfg!(feature = "v2_local"),
+      cfg!(feature = "v2_public"),
+      cfg!(feature = "v3_local"),
+      cfg!(feature = "v3_public"),
+      cfg!(feature = "v4_local"),
+      cfg!(feature = "v4_public"),
+    ];
+    assert_eq!(
+      supported_tokens().len(),
+      enabled.iter().filter(|enabled| **enabled).count()
+    );
+    assert!(supported_tokens().windows(2).all(|pair| pair[0] < pair[1]));
+    assert_eq!(
+      supported_tokens().contains(&(PasetoVersion::V4, PasetoPurpose::Local)),
+      cfg!(feature = "v4_local")
+    );
+  }
+}
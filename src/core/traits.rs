@@ -1,5 +1,4 @@
-use base64::{decode_config, encode_config, DecodeError, URL_SAFE_NO_PAD};
-use ring::constant_time::verify_slices_are_equal as ConstantTimeEquals;
+use base64::{decode_config_buf, encode_config, DecodeError, URL_SAFE_NO_PAD};
 use std::fmt::Display;
 
 //marker traits
@@ -7,6 +6,12 @@ use std::fmt::Display;
 pub trait VersionTrait: Display + Default + AsRef<str> {}
 /// Used by marker traits to determine at compile time which PASETO purpose the user is attempting to use
 pub trait PurposeTrait: Display + Default + AsRef<str> {}
+/// A marker trait for the purposes a [PasetoSymmetricKey](crate::core::PasetoSymmetricKey) can have, which is only
+/// [Local](crate::core::Local)
+pub trait SymmetricPurpose: PurposeTrait {}
+/// A marker trait for the purposes the halves of an asymmetric key pair can have, which is only
+/// [Public](crate::core::Public)
+pub trait AsymmetricPurpose: PurposeTrait {}
 pub trait V1orV3: VersionTrait {}
 /// A marker trait used to determine if the PASETO token version is capable of using an implicit
 /// assertion. Currently this applies only to V3/V4 PASETO tokens
@@ -19,13 +24,20 @@ pub(crate) trait Base64Encodable<T: ?Sized + AsRef<[u8]>>: Display + AsRef<T> {
   fn encode(&self) -> String {
     encode_config(self.as_ref(), URL_SAFE_NO_PAD)
   }
-  fn decode(&self) -> Result<Vec<u8>, DecodeError> {
-    decode_config(self.as_ref(), URL_SAFE_NO_PAD)
+  //decodes into the given buffer, appending to it and reusing its capacity
+  fn decode_into(&self, buffer: &mut Vec<u8>) -> Result<(), DecodeError> {
+    decode_config_buf(self.as_ref(), URL_SAFE_NO_PAD, buffer)
   }
   fn constant_time_equals<B>(&self, other: B) -> bool
   where
     B: AsRef<str>,
   {
-    ConstantTimeEquals(self.encode().as_ref(), other.as_ref().as_bytes()).is_ok()
+    constant_time_equals(self.encode().as_ref(), other.as_ref().as_bytes()).is_ok()
   }
 }
+
+/// Compares two byte slices in constant time, failing if they differ in length or content
+#[allow(deprecated)]
+pub(crate) fn constant_time_equals(a: &[u8], b: &[u8]) -> Result<(), ring::error::Unspecified> {
+  ring::constant_time::verify_slices_are_equal(a, b)
+}
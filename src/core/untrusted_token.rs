@@ -0,0 +1,317 @@
+use super::*;
+#[cfg(feature = "generic")]
+use base64::encode_config;
+use base64::{decode_config, URL_SAFE_NO_PAD};
+use std::convert::TryFrom;
+
+/// An untrusted token string split into its constituent parts *without* any decryption or signature verification
+///
+/// Nothing exposed by an [UntrustedToken] has been authenticated. It exists so that policy
+/// decisions which only depend on the token structure, like which [PasetoVersion]s a service is willing to
+/// honor, can be made before attempting to decrypt or verify the token.
+///
+/// # Usage
+/// ```
+/// # #[cfg(feature = "core")]
+/// # {
+/// use rusty_paseto::core::*;
+///
+/// let token = UntrustedToken::try_from("v2.local.97TTOvgwIxNGvV80XKiGZg_kD3tsXM_-qB4dZGHOeN1cTkgQ4PnW8888l802W8d9AvEGnoNBY3BnqHORy8a5cC8aKpbA0En8XELw2yDk2f1sVODyfnDbi6rEGMY3pSfCbLWMM2oHJxvlEl2XbQ")?;
+/// assert_eq!(token.version(), PasetoVersion::V2);
+/// assert_eq!(token.purpose(), PasetoPurpose::Local);
+/// # }
+/// # Ok::<(),anyhow::Error>(())
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UntrustedToken<'a> {
+  version: PasetoVersion,
+  purpose: PasetoPurpose,
+  payload: &'a str,
+  footer: Option<&'a str>,
+}
+
+impl<'a> UntrustedToken<'a> {
+  /// The [PasetoVersion] found in the token header
+  pub fn version(&self) -> PasetoVersion {
+    self.version
+  }
+
+  /// The [PasetoPurpose] found in the token header
+  pub fn purpose(&self) -> PasetoPurpose {
+    self.purpose
+  }
+
+  /// The decoded footer of the token, if it has one
+  ///
+  /// # Errors
+  ///
+  /// Returns [`PasetoError`] if the footer isn't valid base64 encoded UTF-8
+  pub fn footer(&self) -> Result<Option<String>, PasetoError> {
+    self
+      .footer
+      .map(|footer| Ok(String::from_utf8(decode_config(footer, URL_SAFE_NO_PAD)?)?))
+      .transpose()
+  }
+
+  /// The upper bound on the length of the plaintext message carried by this token, computed from the length of
+  /// the base64 encoded payload segment without decoding it
+  ///
+  /// The nonce, authentication tag or signature of the token's version and purpose are excluded so a buffer of this
+  /// size is always large enough for the decrypted or verified message. A payload too short to hold that overhead
+  /// yields zero, such a token is rejected when it is actually parsed.
+  ///
+  /// # Example
+  /// ```
+  /// # #[cfg(feature = "core")]
+  /// # {
+  /// use rusty_paseto::core::*;
+  ///
+  /// let token = UntrustedToken::try_from("v2.local.97TTOvgwIxNGvV80XKiGZg_kD3tsXM_-qB4dZGHOeN1cTkgQ4PnW8888l802W8d9AvEGnoNBY3BnqHORy8a5cC8aKpbA0En8XELw2yDk2f1sVODyfnDbi6rEGMY3pSfCbLWMM2oHJxvlEl2XbQ")?;
+  /// let buffer = Vec::<u8>::with_capacity(token.max_message_len());
+  /// assert!(buffer.capacity() >= token.max_message_len());
+  /// # }
+  /// # Ok::<(),anyhow::Error>(())
+  /// ```
+  pub fn max_message_len(&self) -> usize {
+    max_decoded_len(self.payload.len()).saturating_sub(overhead_bytes(self.version, self.purpose))
+  }
+
+  //the token re-encoded with strict unpadded base64url segments, carrying exactly the same bytes.  Nothing is
+  //verified here, callers verify the result before handing it out.
+  #[cfg(feature = "generic")]
+  pub(crate) fn canonical_string(&self) -> Result<String, PasetoError> {
+    let payload = encode_config(decode_config(self.payload, URL_SAFE_NO_PAD)?, URL_SAFE_NO_PAD);
+    let mut token = format!("{}.{}.{}", self.version, self.purpose, payload);
+    if let Some(footer) = self.footer {
+      token.push('.');
+      token.push_str(&encode_config(decode_config(footer, URL_SAFE_NO_PAD)?, URL_SAFE_NO_PAD));
+    }
+    Ok(token)
+  }
+}
+
+/// The upper bound on the number of bytes decoded from an unpadded base64 string of the given length
+pub fn max_decoded_len(encoded_len: usize) -> usize {
+  encoded_len / 4 * 3 + encoded_len % 4 * 3 / 4
+}
+
+/// The fixed number of bytes a token of the given version and purpose seals around its message before base64
+/// encoding: the nonce and authentication tag of local tokens, the signature of public tokens
+///
+/// See [token_len] for the length of the whole token string.
+///
+/// # Example
+/// ```
+/// # #[cfg(feature = "core")]
+/// # {
+/// use rusty_paseto::core::*;
+///
+/// //a 24 byte nonce and a 16 byte Poly1305 tag
+/// assert_eq!(overhead_bytes(PasetoVersion::V2, PasetoPurpose::Local), 40);
+/// //an Ed25519 signature
+/// assert_eq!(overhead_bytes(PasetoVersion::V4, PasetoPurpose::Public), 64);
+/// # }
+/// ```
+pub const fn overhead_bytes(version: PasetoVersion, purpose: PasetoPurpose) -> usize {
+  match (version, purpose) {
+    //32 byte nonce and a 48 byte HMAC-SHA384 tag
+    (PasetoVersion::V1, PasetoPurpose::Local) => 80,
+    //24 byte nonce and a 16 byte Poly1305 tag
+    (PasetoVersion::V2, PasetoPurpose::Local) => 40,
+    //32 byte nonce and a 48 byte HMAC-SHA384 tag
+    (PasetoVersion::V3, PasetoPurpose::Local) => 80,
+    //32 byte nonce and a 32 byte BLAKE2b tag
+    (PasetoVersion::V4, PasetoPurpose::Local) => 64,
+    //RSA-PSS 2048 signature
+    (PasetoVersion::V1, PasetoPurpose::Public) => 256,
+    //Ed25519 signature
+    (PasetoVersion::V2, PasetoPurpose::Public) => 64,
+    //ECDSA P-384 signature
+    (PasetoVersion::V3, PasetoPurpose::Public) => 96,
+    //Ed25519 signature
+    (PasetoVersion::V4, PasetoPurpose::Public) => 64,
+  }
+}
+
+/// The exact length of the string of a token of the given version and purpose sealing a message of `message_len`
+/// bytes, with a footer of `footer_len` bytes or none when zero, for checking a payload fits a cookie or header budget
+///
+/// The length is the header, such as `v4.local.`, followed by the unpadded base64url encoding of the message plus its
+/// [overhead](overhead_bytes), and the dot separated encoding of the footer.  The implicit assertion of V3 and V4
+/// tokens isn't part of the token and adds nothing.
+///
+/// # Example
+/// ```
+/// # #[cfg(feature = "core")]
+/// # {
+/// use rusty_paseto::core::*;
+///
+/// //a 1000 byte payload of claims in a 4096 byte cookie, with room to spare for the cookie's name and attributes
+/// assert_eq!(token_len(PasetoVersion::V4, PasetoPurpose::Local, 1000, 0), 1428);
+/// assert!(token_len(PasetoVersion::V4, PasetoPurpose::Local, 1000, 0) <= 4000);
+/// # }
+/// ```
+pub fn token_len(version: PasetoVersion, purpose: PasetoPurpose, message_len: usize, footer_len: usize) -> usize {
+  //the version, the purpose and a dot after each
+  let header_len = version.as_str().len() + purpose.as_str().len() + 2;
+  let footer_len = match footer_len {
+    0 => 0,
+    footer_len => 1 + encoded_len(footer_len),
+  };
+  header_len + encoded_len(message_len + overhead_bytes(version, purpose)) + footer_len
+}
+
+//the length of the unpadded base64 encoding of the given number of bytes
+fn encoded_len(decoded_len: usize) -> usize {
+  (decoded_len * 4).div_ceil(3)
+}
+
+impl<'a> TryFrom<&'a str> for UntrustedToken<'a> {
+  type Error = PasetoError;
+
+  fn try_from(value: &'a str) -> Result<Self, Self::Error> {
+    let parts = value.split('.').collect::<Vec<_>>();
+    if !(3..=4).contains(&parts.len()) {
+      return Err(PasetoError::IncorrectSize);
+    }
+
+    Ok(Self {
+      version: PasetoVersion::try_from(parts[0])?,
+      purpose: PasetoPurpose::try_from(parts[1])?,
+      payload: parts[2],
+      footer: parts.get(3).copied(),
+    })
+  }
+}
+
+#[cfg(test)]
+mod unit_tests {
+
+  use super::*;
+
+  #[test]
+  fn test_untrusted_token_header() {
+    let token = UntrustedToken::try_from("v4.public.cGF5bG9hZA").unwrap();
+    assert_eq!(token.version(), PasetoVersion::V4);
+    assert_eq!(token.purpose(), PasetoPurpose::Public);
+    assert_eq!(token.payload, "cGF5bG9hZA");
+    assert_eq!(token.footer, None);
+  }
+
+  #[test]
+  fn test_untrusted_token_with_footer() {
+    let token = UntrustedToken::try_from("v2.local.cGF5bG9hZA.Zm9vdGVy").unwrap();
+    assert_eq!(token.version(), PasetoVersion::V2);
+    assert_eq!(token.purpose(), PasetoPurpose::Local);
+    assert_eq!(token.footer, Some("Zm9vdGVy"));
+    assert_eq!(token.footer().unwrap().as_deref(), Some("footer"));
+
+    let token = UntrustedToken::try_from("v2.local.cGF5bG9hZA").unwrap();
+    assert_eq!(token.footer().unwrap(), None);
+    let token = UntrustedToken::try_from("v2.local.cGF5bG9hZA.!!").unwrap();
+    assert!(matches!(token.footer(), Err(PasetoError::PayloadBase64Decode { .. })));
+  }
+
+  #[test]
+  fn test_untrusted_token_unknown_header() {
+    assert!(matches!(
+      UntrustedToken::try_from("v5.local.cGF5bG9hZA"),
+      Err(PasetoError::WrongHeader)
+    ));
+    assert!(matches!(
+      UntrustedToken::try_from("v4.secret.cGF5bG9hZA"),
+      Err(PasetoError::WrongHeader)
+    ));
+  }
+
+  #[test]
+  #[cfg(feature = "generic")]
+  fn test_untrusted_token_canonical_string() {
+    let token = UntrustedToken::try_from("v2.local.cGF5bG9hZA==.Zm9vdGVy").unwrap();
+    assert_eq!(token.canonical_string().unwrap(), "v2.local.cGF5bG9hZA.Zm9vdGVy");
+    let token = UntrustedToken::try_from("v4.public.cGF5bG9hZA.Zm9vdGVyMQ==").unwrap();
+    assert_eq!(token.canonical_string().unwrap(), "v4.public.cGF5bG9hZA.Zm9vdGVyMQ");
+    let token = UntrustedToken::try_from("v4.public.cGF5bG9hZA").unwrap();
+    assert_eq!(token.canonical_string().unwrap(), "v4.public.cGF5bG9hZA");
+
+    let token = UntrustedToken::try_from("v4.public.cGF5b!9hZA").unwrap();
+    assert!(matches!(
+      token.canonical_string(),
+      Err(PasetoError::PayloadBase64Decode { .. })
+    ));
+  }
+
+  #[test]
+  fn test_max_decoded_len() {
+    assert_eq!(max_decoded_len(0), 0);
+    assert_eq!(max_decoded_len(1), 0);
+    assert_eq!(max_decoded_len(2), 1);
+    assert_eq!(max_decoded_len(3), 2);
+    assert_eq!(max_decoded_len(4), 3);
+    assert_eq!(max_decoded_len(67), 50);
+  }
+
+  #[test]
+  fn test_encoded_len() {
+    for decoded_len in 0..64 {
+      let encoded = base64::encode_config(vec![0u8; decoded_len], URL_SAFE_NO_PAD);
+      assert_eq!(encoded_len(decoded_len), encoded.len());
+      assert_eq!(max_decoded_len(encoded.len()), decoded_len);
+    }
+  }
+
+  #[test]
+  #[cfg(all(feature = "v4_local", feature = "v2_public"))]
+  fn test_token_len() -> anyhow::Result<()> {
+    let message = "{\"data\":\"this is a secret message\"}";
+    let key = PasetoSymmetricKey::<V4, Local>::from(Key::from(*b"wubbalubbadubdubwubbalubbadubdub"));
+    let nonce = Key::<32>::try_new_random()?;
+    let token = Paseto::<V4, Local>::builder()
+      .set_payload(Payload::from(message))
+      .set_footer(Footer::from("key-id:1"))
+      .try_encrypt(&key, &PasetoNonce::<V4, Local>::from(&nonce))?;
+    assert_eq!(
+      token_len(PasetoVersion::V4, PasetoPurpose::Local, message.len(), 8),
+      token.len()
+    );
+
+    let private_key = Key::<64>::try_from("b4cbfb43df4ce210727d953e4a713307fa19bb7d9f85041438d9e11b942a37741eb9dbbbbc047c03fd70604e0071f0987e16b28b757225c11f00415d0e20b1a2")?;
+    let token = Paseto::<V2, Public>::builder()
+      .set_payload(Payload::from(message))
+      .try_sign(&PasetoAsymmetricPrivateKey::<V2, Public>::from(&private_key))?;
+    assert_eq!(
+      token_len(PasetoVersion::V2, PasetoPurpose::Public, message.len(), 0),
+      token.len()
+    );
+    Ok(())
+  }
+
+  #[test]
+  fn test_untrusted_token_max_message_len() {
+    //50 bytes of payload
+    let payload = "AAECAwQFBgcICQoLDA0ODxAREhMUFRYXGBkaGxwdHh8gISIjJCUmJygpKissLS4vMDE";
+    let message_len =
+      |header: &str| UntrustedToken::try_from(format!("{}{}", header, payload).as_str()).map(|t| t.max_message_len());
+    assert_eq!(message_len("v2.local.").unwrap(), 10);
+    assert_eq!(message_len("v4.public.").unwrap(), 0);
+
+    //70 bytes of payload
+    let token = UntrustedToken::try_from(
+      "v4.local._____________________________________________________________________________________________w",
+    )
+    .unwrap();
+    assert_eq!(token.max_message_len(), 6);
+  }
+
+  #[test]
+  fn test_untrusted_token_incorrect_size() {
+    assert!(matches!(
+      UntrustedToken::try_from("v4.local"),
+      Err(PasetoError::IncorrectSize)
+    ));
+    assert!(matches!(
+      UntrustedToken::try_from("v4.local.a.b.c"),
+      Err(PasetoError::IncorrectSize)
+    ));
+  }
+}
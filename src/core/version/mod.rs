@@ -1,3 +1,4 @@
+mod paseto_version;
 #[cfg(any(feature = "v1", doc))]
 mod v1;
 #[cfg(any(feature = "v2", doc))]
@@ -7,6 +8,7 @@ mod v3;
 #[cfg(any(feature = "v4", doc))]
 mod v4;
 
+pub use paseto_version::PasetoVersion;
 #[cfg(any(feature = "v1", doc))]
 pub use v1::V1;
 #[cfg(any(feature = "v2", doc))]
@@ -0,0 +1,82 @@
+use crate::core::PasetoError;
+use std::convert::TryFrom;
+use std::fmt;
+use std::fmt::Display;
+use std::str::FromStr;
+
+/// A PASETO protocol version determined at runtime
+///
+/// The [V1](crate::core::V1), [V2](crate::core::V2), [V3](crate::core::V3) and [V4](crate::core::V4) types select a version at compile time. A [PasetoVersion] is
+/// what you get when inspecting an untrusted token string with an [UntrustedToken](crate::core::UntrustedToken),
+/// which is useful for making policy decisions about a token before a key is ever involved.
+///
+/// # Example
+///
+/// ```
+/// # #[cfg(feature = "core")]
+/// # {
+/// use rusty_paseto::core::*;
+///
+/// let version = PasetoVersion::try_from("v4")?;
+/// assert_eq!(version, PasetoVersion::V4);
+/// assert_eq!(version.to_string(), "v4");
+/// # }
+/// # Ok::<(),anyhow::Error>(())
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum PasetoVersion {
+  /// Version 1: NIST Compatibility
+  V1,
+  /// Version 2: Sodium Original
+  V2,
+  /// Version 3: NIST Modern
+  V3,
+  /// Version 4: Sodium Modern
+  V4,
+}
+
+impl PasetoVersion {
+  /// The version as it appears in a PASETO header, e.g. `v4`
+  pub fn as_str(&self) -> &'static str {
+    match self {
+      Self::V1 => "v1",
+      Self::V2 => "v2",
+      Self::V3 => "v3",
+      Self::V4 => "v4",
+    }
+  }
+}
+
+impl AsRef<str> for PasetoVersion {
+  fn as_ref(&self) -> &str {
+    self.as_str()
+  }
+}
+
+impl Display for PasetoVersion {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "{}", self.as_str())
+  }
+}
+
+impl TryFrom<&str> for PasetoVersion {
+  type Error = PasetoError;
+
+  fn try_from(value: &str) -> Result<Self, Self::Error> {
+    match value {
+      "v1" => Ok(Self::V1),
+      "v2" => Ok(Self::V2),
+      "v3" => Ok(Self::V3),
+      "v4" => Ok(Self::V4),
+      _ => Err(PasetoError::WrongHeader),
+    }
+  }
+}
+
+impl FromStr for PasetoVersion {
+  type Err = PasetoError;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    Self::try_from(s)
+  }
+}
@@ -0,0 +1,27 @@
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+/// A serializable record of what a builder was asked to issue, for audit trails
+///
+/// Captured by [GenericBuilder::config_snapshot](crate::generic::GenericBuilder::config_snapshot) and
+/// [PasetoBuilder::config_snapshot](crate::prelude::PasetoBuilder::config_snapshot) without consuming the builder.
+/// It holds the staged claims exactly as they would be put in the payload, the footer, the implicit assertion and
+/// any relative expiration policy.  Keys and nonces are never part of it.
+///
+/// Both the footer and the claims are recorded in the clear, so log the snapshot only where the payload itself may
+/// be logged.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct BuilderConfig {
+  /// The staged claims
+  pub claims: Map<String, Value>,
+  /// The footer, left out when there is none
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub footer: Option<String>,
+  /// The implicit assertion of V3 and V4 tokens, left out when there is none
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub implicit_assertion: Option<String>,
+  /// The number of seconds from the time of building until the token expires, when the expiration is
+  /// [relative](crate::prelude::PasetoBuilder::set_expiration_relative) rather than a staged `exp` claim
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub ttl_seconds: Option<i64>,
+}
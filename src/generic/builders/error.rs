@@ -21,6 +21,32 @@ pub enum GenericBuilderError {
     #[from]
     source: crate::core::PasetoError,
   },
+  ///An error indicating a raw payload was set along with individual claims
+  #[error("A raw payload cannot be combined with individual claims")]
+  RawPayloadWithClaims,
+  ///An error indicating a raw payload is valid JSON but not a JSON object
+  #[error("The raw payload must be a JSON object")]
+  RawPayloadNotObject,
+  ///An error indicating a relative expiration resolves to a date which can't be represented
+  #[error("The relative expiration falls outside the range of representable dates")]
+  ExpirationOutOfRange,
+  ///An error indicating the footer appears to carry secret key material, naming where it was found
+  #[error("The footer appears to carry secret key material at '{0}' and footers are never encrypted")]
+  SecretInFooter(String),
+  ///An error indicating a claim holds a floating point number while floats are forbidden, naming the claim
+  #[error("The claim '{0}' holds a number which isn't an integer")]
+  FloatClaim(String),
+  ///An error indicating the serialized payload is longer than the cap set on the builder
+  #[error("The payload of {size} bytes exceeds the limit of {max} bytes")]
+  PayloadTooLarge {
+    ///The length of the serialized payload in bytes
+    size: usize,
+    ///The cap set on the builder
+    max: usize,
+  },
+  ///An error indicating the deferred implicit assertion computed bytes which aren't UTF-8
+  #[error("The implicit assertion computed when building isn't valid UTF-8")]
+  ImplicitAssertionNotUtf8,
   ///A JSON serialization error with the token payload
   #[error("The payload was unable to be serialized into json")]
   PayloadJsonError {
@@ -0,0 +1,82 @@
+use serde_json::Value;
+
+//field names, and parts of field names, which suggest secret key material, compared case insensitively
+const SECRET_NAMES: [&str; 1] = ["sk"];
+const SECRET_NAME_PARTS: [&str; 3] = ["secret", "private", "password"];
+
+//PASERK types which hold a plaintext symmetric or secret key, as opposed to wrapped keys, ids or public keys
+const SECRET_PASERK_TYPES: [&str; 2] = ["local", "secret"];
+
+/// Scans a footer for what looks like secret key material, returning where it was found
+///
+/// This is a heuristic, it catches the common mistakes of a JSON footer field named like `sk` or `secret` and of a
+/// plaintext PASERK secret or local key anywhere in the footer, but it can't recognize every encoding of a key and
+/// may flag an innocent field name.
+pub(crate) fn find_secret(footer: &str) -> Option<String> {
+  match serde_json::from_str::<Value>(footer) {
+    Ok(json) => find_secret_in_json(&json, "$"),
+    Err(_) => contains_secret_paserk(footer).then(|| "footer".to_string()),
+  }
+}
+
+fn find_secret_in_json(json: &Value, path: &str) -> Option<String> {
+  match json {
+    Value::Object(fields) => fields.iter().find_map(|(name, value)| {
+      let path = format!("{}.{}", path, name);
+      let name = name.to_lowercase();
+      if SECRET_NAMES.contains(&name.as_str()) || SECRET_NAME_PARTS.iter().any(|part| name.contains(part)) {
+        Some(path)
+      } else {
+        find_secret_in_json(value, &path)
+      }
+    }),
+    Value::Array(values) => values
+      .iter()
+      .enumerate()
+      .find_map(|(index, value)| find_secret_in_json(value, &format!("{}[{}]", path, index))),
+    Value::String(value) if contains_secret_paserk(value) => Some(path.to_string()),
+    _ => None,
+  }
+}
+
+fn contains_secret_paserk(value: &str) -> bool {
+  value.match_indices('k').any(|(start, _)| {
+    let mut parts = value[start + 1..].splitn(3, '.');
+    matches!(parts.next(), Some("1" | "2" | "3" | "4"))
+      && parts
+        .next()
+        .is_some_and(|paserk_type| SECRET_PASERK_TYPES.contains(&paserk_type))
+      && parts.next().is_some_and(|key| !key.is_empty())
+  })
+}
+
+#[cfg(test)]
+mod unit_tests {
+  use super::*;
+
+  #[test]
+  fn test_find_secret() {
+    assert_eq!(find_secret(r#"{"kid":"k4.lid.abc"}"#), None);
+    assert_eq!(
+      find_secret(r#"{"kid":"k4.pid.abc","wpk":"k4.local-wrap.pie.abc"}"#),
+      None
+    );
+    assert_eq!(find_secret("some footer"), None);
+
+    assert_eq!(find_secret(r#"{"kid":"a","sk":"abc"}"#).as_deref(), Some("$.sk"));
+    assert_eq!(find_secret(r#"{"SK":"abc"}"#).as_deref(), Some("$.SK"));
+    assert_eq!(
+      find_secret(r#"{"client_secret":"abc"}"#).as_deref(),
+      Some("$.client_secret")
+    );
+    assert_eq!(
+      find_secret(r#"{"keys":[{"kid":"a"},{"k":"k4.secret.abc"}]}"#).as_deref(),
+      Some("$.keys[1].k")
+    );
+    assert_eq!(find_secret(r#"{"k":"k2.local.abc"}"#).as_deref(), Some("$.k"));
+    assert_eq!(find_secret("rotated to k3.secret.abc").as_deref(), Some("footer"));
+
+    //the key itself has to be there, a bare type isn't flagged
+    assert_eq!(find_secret(r#"{"type":"k4.local."}"#), None);
+  }
+}
@@ -1,7 +1,8 @@
+use super::footer_guard;
 use crate::generic::*;
 use core::marker::PhantomData;
+use std::collections::HashMap;
 use std::fmt::Write;
-use std::{collections::HashMap, mem::take};
 
 ///The GenericBuilder is created at compile time by specifying a PASETO version and purpose and
 ///providing a key of the same version and purpose. This structure allows setting [PASETO claims](https://github.com/paseto-standard/paseto-spec/blob/master/docs/02-Implementation-Guide/04-Claims.md),
@@ -19,11 +20,11 @@ use std::{collections::HashMap, mem::take};
 ///# #[cfg(all(feature = "generic", feature="v2_local"))]
 ///# {
 ///   use rusty_paseto::generic::*;
-
+///
 ///     let key = PasetoSymmetricKey::<V2, Local>::from(Key::<32>::from(*b"wubbalubbadubdubwubbalubbadubdub"));
-
+///
 ///     let footer = Footer::from("some footer");
-
+///
 ///     //create a builder, add some claims and then build the token with the key
 ///     let token = GenericBuilder::<V2, Local>::default()
 ///       .set_claim(AudienceClaim::from("customers"))
@@ -38,12 +39,12 @@ use std::{collections::HashMap, mem::take};
 ///       .set_claim(CustomClaim::try_from(("pi to 6 digits", 3.141526))?)
 ///       .set_footer(footer)
 ///       .try_encrypt(&key)?;
-
+///
 ///     //now let's decrypt the token and verify the values
 ///     let json = GenericParser::<V2, Local>::default()
 ///       .set_footer(footer)
 ///       .parse(&token, &key)?;
-
+///
 ///     assert_eq!(json["aud"], "customers");
 ///     assert_eq!(json["jti"], "me");
 ///     assert_eq!(json["iss"], "me");
@@ -57,13 +58,20 @@ use std::{collections::HashMap, mem::take};
 ///  # }
 /// # Ok::<(),anyhow::Error>(())
 ///   ```
-
 pub struct GenericBuilder<'a, 'b, Version, Purpose> {
   version: PhantomData<Version>,
   purpose: PhantomData<Purpose>,
   claims: HashMap<String, Box<dyn erased_serde::Serialize + 'b>>,
+  extra_claims: serde_json::Map<String, serde_json::Value>,
   footer: Option<Footer<'a>>,
   implicit_assertion: Option<ImplicitAssertion<'a>>,
+  //computes the implicit assertion when the token is built, replacing a fixed one
+  implicit_assertion_with: Option<&'a dyn Fn() -> Vec<u8>>,
+  raw_payload: Option<String>,
+  forbid_footer_secrets: bool,
+  forbid_floats: bool,
+  max_payload_bytes: Option<usize>,
+  claim_spec: Option<ClaimSpec>,
 }
 
 impl<'a, 'b, Version, Purpose> GenericBuilder<'a, 'b, Version, Purpose> {
@@ -72,8 +80,15 @@ impl<'a, 'b, Version, Purpose> GenericBuilder<'a, 'b, Version, Purpose> {
       version: PhantomData::<Version>,
       purpose: PhantomData::<Purpose>,
       claims: HashMap::with_capacity(10),
+      extra_claims: serde_json::Map::new(),
       footer: None,
       implicit_assertion: None,
+      implicit_assertion_with: None,
+      raw_payload: None,
+      forbid_footer_secrets: false,
+      forbid_floats: false,
+      max_payload_bytes: None,
+      claim_spec: None,
     }
   }
 
@@ -98,20 +113,280 @@ impl<'a, 'b, Version, Purpose> GenericBuilder<'a, 'b, Version, Purpose> {
     self
   }
 
+  ///Adds a [claim](PasetoClaim) to the token builder only when `condition` is true
+  pub fn set_claim_if<T: 'b + PasetoClaim + erased_serde::Serialize>(&mut self, condition: bool, value: T) -> &mut Self
+  where
+    'b: 'a,
+  {
+    if condition {
+      self.set_claim(value);
+    }
+    self
+  }
+
+  ///Merges a bag of dynamic claims into the payload alongside the typed [claims](PasetoClaim)
+  ///
+  ///The collision policy, checked when building:
+  ///
+  ///* a claim set with [set_claim](Self::set_claim) takes precedence over an extra claim of the same name, which is
+  ///  left out of the payload
+  ///* an extra claim named like a reserved PASETO claim which wasn't set fails the build with
+  ///  [Reserved](PasetoClaimError::Reserved), as for any [CustomClaim], since those claims have typed setters
+  ///  checking their values
+  ///* extra claims set by several calls are merged, a later value replacing an earlier one
+  pub fn set_extra(&mut self, extra_claims: serde_json::Map<String, serde_json::Value>) -> &mut Self {
+    self.extra_claims.extend(extra_claims);
+    self
+  }
+
+  //whether an extra claim of the given name was set, for wrappers generating claims of their own
+  #[cfg(feature = "batteries_included")]
+  pub(crate) fn has_extra(&self, claim_key: &str) -> bool {
+    self.extra_claims.contains_key(claim_key)
+  }
+
+  ///Scopes the setting of several [claims](PasetoClaim) within a closure which receives this builder
+  pub fn claims<F>(&mut self, scope: F) -> &mut Self
+  where
+    F: FnOnce(&mut Self),
+  {
+    scope(self);
+    self
+  }
+
   ///Adds an optional [footer](Footer) to the token builder
+  ///
+  ///The footer is authenticated but never encrypted, even in Local tokens.  Prefer
+  ///[set_public_footer](Self::set_public_footer) which says so at the call site.
   pub fn set_footer(&mut self, footer: Footer<'a>) -> &mut Self {
     self.footer = Some(footer);
     self
   }
 
-  fn build_payload_from_claims(&mut self) -> Result<String, GenericBuilderError> {
+  ///Adds an optional [footer](Footer) to the token builder, which anyone holding the token can read
+  ///
+  ///The footer is base64 encoded into the token in the clear, whatever the purpose of the token, so it must never
+  ///carry confidential data.  Secrets belong in the claims of a Local token.
+  pub fn set_public_footer(&mut self, footer: Footer<'a>) -> &mut Self {
+    self.set_footer(footer)
+  }
+
+  ///Refuses to build tokens whose footer appears to carry secret key material, failing with
+  ///[SecretInFooter](GenericBuilderError::SecretInFooter) instead
+  ///
+  ///The footer is never encrypted so a key placed in it leaks to anyone holding the token.  The check
+  ///is a heuristic: it flags JSON fields named like `sk`, `secret`, `private` or `password` and
+  ///plaintext PASERK `local` or `secret` keys anywhere in the footer, but other encodings of a key go
+  ///unnoticed and an innocent field can be flagged.
+  pub fn forbid_secrets_in_footer(&mut self) -> &mut Self {
+    self.forbid_footer_secrets = true;
+    self
+  }
+
+  ///Refuses to build tokens with a claim holding a number which isn't an integer, anywhere in its value,
+  ///failing with [FloatClaim](GenericBuilderError::FloatClaim) naming the claim instead
+  ///
+  ///Floats lose precision and serialize differently across languages, so systems which need exact numbers
+  ///should carry them as integers, such as cents rather than dollars, or as strings.
+  pub fn forbid_float_claims(&mut self) -> &mut Self {
+    self.forbid_floats = true;
+    self
+  }
+
+  ///Refuses to build tokens whose serialized payload is longer than the given number of bytes, failing with
+  ///[PayloadTooLarge](GenericBuilderError::PayloadTooLarge) reporting both sizes instead
+  ///
+  ///The plaintext JSON is measured, before encryption or signing, so the cap holds the same for every version and
+  ///purpose.  The token itself is longer: see [token_len](crate::core::token_len) for the size of the token a
+  ///payload makes.
+  ///
+  ///# Example
+  ///```
+  ///# #[cfg(all(feature = "generic", feature="v4_local"))]
+  ///# {
+  ///   use rusty_paseto::generic::*;
+  ///
+  ///   let key = PasetoSymmetricKey::<V4, Local>::from(Key::<32>::from(*b"wubbalubbadubdubwubbalubbadubdub"));
+  ///   let result = GenericBuilder::<V4, Local>::default()
+  ///     .set_claim(SubjectClaim::from("loyal subjects"))
+  ///     .max_payload_bytes(16)
+  ///     .try_encrypt(&key);
+  ///
+  ///   //{"sub":"loyal subjects"}
+  ///   assert!(matches!(result, Err(GenericBuilderError::PayloadTooLarge { size: 24, max: 16 })));
+  ///# }
+  ///```
+  pub fn max_payload_bytes(&mut self, max: usize) -> &mut Self {
+    self.max_payload_bytes = Some(max);
+    self
+  }
+
+  ///Checks the JSON type of the claims the [ClaimSpec] names when the payload is assembled, failing with
+  ///[WrongType](PasetoClaimError::WrongType) for a claim of the wrong type instead of issuing the token
+  ///
+  ///The assembled payload is checked, so claims from every path are covered: typed claims, extra claims and a
+  ///[raw payload](Self::set_raw_payload).
+  ///
+  ///# Example
+  ///```
+  ///# #[cfg(all(feature = "generic", feature="v4_local"))]
+  ///# {
+  ///   use rusty_paseto::generic::*;
+  ///
+  ///   let key = PasetoSymmetricKey::<V4, Local>::from(Key::<32>::from(*b"wubbalubbadubdubwubbalubbadubdub"));
+  ///   let result = GenericBuilder::<V4, Local>::default()
+  ///     .set_raw_payload(r#"{"sub":"loyal subjects","exp":true}"#.to_string())
+  ///     .set_claim_spec(ClaimSpec::registered())
+  ///     .try_encrypt(&key);
+  ///
+  ///   assert!(matches!(
+  ///     result,
+  ///     Err(GenericBuilderError::ClaimError {
+  ///       source: PasetoClaimError::WrongType(claim, ClaimType::String, "a boolean")
+  ///     }) if claim == "exp"
+  ///   ));
+  ///# }
+  ///```
+  pub fn set_claim_spec(&mut self, spec: ClaimSpec) -> &mut Self {
+    self.claim_spec = Some(spec);
+    self
+  }
+
+  ///Sets a complete, pre-serialized JSON payload which is encrypted or signed verbatim instead of
+  ///a payload assembled from [claims](PasetoClaim)
+  ///
+  ///Useful when the payload has already been assembled or canonicalized elsewhere. The payload must be
+  ///a JSON object, otherwise building fails with [RawPayloadNotObject](GenericBuilderError::RawPayloadNotObject).
+  ///Since the payload is used as is, building also fails with
+  ///[RawPayloadWithClaims](GenericBuilderError::RawPayloadWithClaims) if any claims were set.
+  ///
+  ///# Example
+  ///```
+  ///# #[cfg(all(feature = "generic", feature="v4_local"))]
+  ///# {
+  ///   use rusty_paseto::generic::*;
+  ///
+  ///   let key = PasetoSymmetricKey::<V4, Local>::from(Key::<32>::from(*b"wubbalubbadubdubwubbalubbadubdub"));
+  ///   let token = GenericBuilder::<V4, Local>::default()
+  ///     .set_raw_payload(r#"{"sub":"canonical","seats":4}"#.to_string())
+  ///     .try_encrypt(&key)?;
+  ///
+  ///   let json = GenericParser::<V4, Local>::default().parse(&token, &key)?;
+  ///   assert_eq!(json["sub"], "canonical");
+  ///# }
+  ///# Ok::<(),anyhow::Error>(())
+  ///```
+  pub fn set_raw_payload(&mut self, json: String) -> &mut Self {
+    self.raw_payload = Some(json);
+    self
+  }
+
+  ///Captures the staged claims, footer and implicit assertion as a serializable [BuilderConfig] without consuming
+  ///the builder, for logging what was requested alongside the token it produced
+  ///
+  ///The claims are serialized exactly as they will be in the payload, applying the same checks as building.
+  ///
+  /// # Errors
+  ///
+  /// Returns [`GenericBuilderError`] if the staged claims or raw payload wouldn't build
+  ///
+  /// # Example
+  ///```
+  ///# #[cfg(all(feature = "generic", feature="v4_local"))]
+  ///# {
+  ///   use rusty_paseto::generic::*;
+  ///
+  ///   let key = PasetoSymmetricKey::<V4, Local>::from(Key::<32>::from(*b"wubbalubbadubdubwubbalubbadubdub"));
+  ///   let mut builder = GenericBuilder::<V4, Local>::default();
+  ///   builder
+  ///     .set_claim(SubjectClaim::from("loyal subjects"))
+  ///     .set_footer(Footer::from("key-id:1"));
+  ///
+  ///   let snapshot = builder.config_snapshot()?;
+  ///   let token = builder.try_encrypt(&key)?;
+  ///
+  ///   assert_eq!(
+  ///     serde_json::to_string(&snapshot)?,
+  ///     r#"{"claims":{"sub":"loyal subjects"},"footer":"key-id:1"}"#
+  ///   );
+  ///# }
+  ///# Ok::<(),anyhow::Error>(())
+  ///```
+  pub fn config_snapshot(&self) -> Result<BuilderConfig, GenericBuilderError> {
+    Ok(BuilderConfig {
+      claims: serde_json::from_str(&self.build_payload_from_claims(false)?)?,
+      footer: self.footer.map(|footer| footer.to_string()),
+      implicit_assertion: self
+        .deferred_implicit_assertion()?
+        .or_else(|| self.implicit_assertion.map(|assertion| assertion.to_string())),
+      ttl_seconds: None,
+    })
+  }
+
+  //runs the function of set_implicit_assertion_with, if there is one, for the token being built
+  fn deferred_implicit_assertion(&self) -> Result<Option<String>, GenericBuilderError> {
+    self
+      .implicit_assertion_with
+      .map(|compute| String::from_utf8(compute()).map_err(|_| GenericBuilderError::ImplicitAssertionNotUtf8))
+      .transpose()
+  }
+
+  //the claims are only borrowed here so that a failure part way through leaves the builder
+  //intact and a retry produces the same payload rather than a silently emptied one.  Public tokens
+  //always sort their claims by key, see try_sign, local tokens skip the sort
+  fn build_payload_from_claims(&self, sorted: bool) -> Result<String, GenericBuilderError> {
+    //every build passes through here, so the footer guard runs before anything is encrypted or signed
+    if let Some(footer) = self.footer.filter(|_| self.forbid_footer_secrets) {
+      if let Some(location) = footer_guard::find_secret(footer.as_ref()) {
+        return Err(GenericBuilderError::SecretInFooter(location));
+      }
+    }
+    if let Some(raw_payload) = &self.raw_payload {
+      if !self.claims.is_empty() || !self.extra_claims.is_empty() {
+        return Err(GenericBuilderError::RawPayloadWithClaims);
+      }
+      let json = serde_json::from_str::<serde_json::Value>(raw_payload)?;
+      let claims = json.as_object().ok_or(GenericBuilderError::RawPayloadNotObject)?;
+      if let Some(spec) = &self.claim_spec {
+        spec.check(&json)?;
+      }
+      if self.forbid_floats {
+        if let Some((key, _)) = claims.iter().find(|(_, value)| holds_float(value)) {
+          return Err(GenericBuilderError::FloatClaim(key.clone()));
+        }
+      }
+      return self.within_payload_cap(raw_payload.clone());
+    }
+
+    //the typed claims win any collision with the extras, the rest are checked like custom claims
+    let extra_claims = self
+      .extra_claims
+      .iter()
+      .filter(|(key, _)| !self.claims.contains_key(*key))
+      .map(|(key, value)| Ok((key, CustomClaim::try_from((key.as_str(), value))?)))
+      .collect::<Result<Vec<_>, PasetoClaimError>>()?;
+    let mut claims = self
+      .claims
+      .iter()
+      .map(|(key, claim)| (key, claim.as_ref() as &dyn erased_serde::Serialize))
+      .chain(
+        extra_claims
+          .iter()
+          .map(|(key, claim)| (*key, claim as &dyn erased_serde::Serialize)),
+      )
+      .collect::<Vec<_>>();
+    if sorted {
+      claims.sort_unstable_by_key(|(key, _)| *key);
+    }
+
     //here we need to go through all the claims and serialize them to build a payload
     let mut payload = String::from('{');
 
-    let claims = take(&mut self.claims);
-
-    for claim in claims.into_values() {
-      let raw = serde_json::to_string(&claim)?;
+    for (key, claim) in claims {
+      let raw = serde_json::to_string(claim)?;
+      if self.forbid_floats && holds_float(&serde_json::from_str(&raw)?) {
+        return Err(GenericBuilderError::FloatClaim(key.clone()));
+      }
       let trimmed = raw.trim_start_matches('{').trim_end_matches('}');
       let _ = write!(payload, "{},", trimmed);
     }
@@ -120,7 +395,29 @@ impl<'a, 'b, Version, Purpose> GenericBuilder<'a, 'b, Version, Purpose> {
     //way to do this)
     payload = payload.trim_end_matches(',').to_string();
     payload.push('}');
-    Ok(payload)
+    if let Some(spec) = &self.claim_spec {
+      spec.check(&serde_json::from_str(&payload)?)?;
+    }
+    self.within_payload_cap(payload)
+  }
+
+  fn within_payload_cap(&self, payload: String) -> Result<String, GenericBuilderError> {
+    match self.max_payload_bytes {
+      Some(max) if payload.len() > max => Err(GenericBuilderError::PayloadTooLarge {
+        size: payload.len(),
+        max,
+      }),
+      _ => Ok(payload),
+    }
+  }
+}
+
+fn holds_float(value: &serde_json::Value) -> bool {
+  match value {
+    serde_json::Value::Number(number) => number.is_f64(),
+    serde_json::Value::Array(values) => values.iter().any(holds_float),
+    serde_json::Value::Object(fields) => fields.values().any(holds_float),
+    _ => false,
   }
 }
 
@@ -132,6 +429,46 @@ where
   ///tokens only
   pub fn set_implicit_assertion(&mut self, implicit_assertion: ImplicitAssertion<'a>) -> &mut Self {
     self.implicit_assertion = Some(implicit_assertion);
+    self.implicit_assertion_with = None;
+    self
+  }
+
+  ///Defers the [implicit assertion](ImplicitAssertion) of V3/V4 tokens to a function run each time a token is built,
+  ///replacing any assertion set before
+  ///
+  ///Meant for binding tokens to context known only at the time of issuing, such as a digest of the client's user
+  ///agent and address.  The verifier computes the same bytes and passes them to the parser's
+  ///`set_implicit_assertion`, which takes a string, so the bytes have to be UTF-8: hex or base64 encode a binary
+  ///digest first.  Building fails with [ImplicitAssertionNotUtf8](GenericBuilderError::ImplicitAssertionNotUtf8)
+  ///otherwise.
+  ///
+  /// # Example
+  ///```
+  ///# #[cfg(all(feature = "generic", feature="v4_local"))]
+  ///# {
+  ///   use rusty_paseto::generic::*;
+  ///
+  ///   let key = PasetoSymmetricKey::<V4, Local>::from(Key::<32>::from(*b"wubbalubbadubdubwubbalubbadubdub"));
+  ///   let (user_agent, address) = ("curl/8.0", "192.0.2.1");
+  ///   let context = || format!("{}|{}", user_agent, address);
+  ///
+  ///   let token = GenericBuilder::<V4, Local>::default()
+  ///     .set_claim(SubjectClaim::from("loyal subjects"))
+  ///     .set_implicit_assertion_with(&|| context().into_bytes())
+  ///     .try_encrypt(&key)?;
+  ///
+  ///   //the verifier derives the same context from the request it serves
+  ///   let context = context();
+  ///   let json = GenericParser::<V4, Local>::default()
+  ///     .set_implicit_assertion(ImplicitAssertion::from(context.as_str()))
+  ///     .parse(&token, &key)?;
+  ///   assert_eq!(json["sub"], "loyal subjects");
+  ///# }
+  ///# Ok::<(),anyhow::Error>(())
+  ///```
+  pub fn set_implicit_assertion_with(&mut self, compute: &'a dyn Fn() -> Vec<u8>) -> &mut Self {
+    self.implicit_assertion_with = Some(compute);
+    self.implicit_assertion = None;
     self
   }
 }
@@ -161,11 +498,11 @@ impl GenericBuilder<'_, '_, V1, Local> {
   ///# #[cfg(all(feature = "generic", feature="v1_local"))]
   ///# {
   ///   use rusty_paseto::generic::*;
-
+  ///
   ///     let key = PasetoSymmetricKey::<V1, Local>::from(Key::<32>::from(*b"wubbalubbadubdubwubbalubbadubdub"));
-
+  ///
   ///     let footer = Footer::from("some footer");
-
+  ///
   ///     //create a builder, add some claims and then build the token with the key
   ///     let token = GenericBuilder::<V1, Local>::default()
   ///       .set_claim(AudienceClaim::from("customers"))
@@ -180,12 +517,12 @@ impl GenericBuilder<'_, '_, V1, Local> {
   ///       .set_claim(CustomClaim::try_from(("pi to 6 digits", 3.141526))?)
   ///       .set_footer(footer)
   ///       .try_encrypt(&key)?;
-
+  ///
   ///     //now let's decrypt the token and verify the values
   ///     let json = GenericParser::<V1, Local>::default()
   ///       .set_footer(footer)
   ///       .parse(&token, &key)?;
-
+  ///
   ///     assert_eq!(json["aud"], "customers");
   ///     assert_eq!(json["jti"], "me");
   ///     assert_eq!(json["iss"], "me");
@@ -199,11 +536,10 @@ impl GenericBuilder<'_, '_, V1, Local> {
   ///  # }
   /// # Ok::<(),anyhow::Error>(())
   ///   ```
-
   pub fn try_encrypt(&mut self, key: &PasetoSymmetricKey<V1, Local>) -> Result<String, GenericBuilderError> {
     let mut token_builder = Paseto::<V1, Local>::builder();
 
-    let payload = self.build_payload_from_claims()?;
+    let payload = self.build_payload_from_claims(false)?;
     token_builder.set_payload(Payload::from(payload.as_str()));
     if let Some(footer) = &self.footer {
       token_builder.set_footer(*footer);
@@ -232,11 +568,11 @@ impl GenericBuilder<'_, '_, V2, Local> {
   ///# #[cfg(all(feature = "generic", feature="v2_local"))]
   ///# {
   ///   use rusty_paseto::generic::*;
-
+  ///
   ///     let key = PasetoSymmetricKey::<V2, Local>::from(Key::<32>::from(*b"wubbalubbadubdubwubbalubbadubdub"));
-
+  ///
   ///     let footer = Footer::from("some footer");
-
+  ///
   ///     //create a builder, add some claims and then build the token with the key
   ///     let token = GenericBuilder::<V2, Local>::default()
   ///       .set_claim(AudienceClaim::from("customers"))
@@ -251,12 +587,12 @@ impl GenericBuilder<'_, '_, V2, Local> {
   ///       .set_claim(CustomClaim::try_from(("pi to 6 digits", 3.141526))?)
   ///       .set_footer(footer)
   ///       .try_encrypt(&key)?;
-
+  ///
   ///     //now let's decrypt the token and verify the values
   ///     let json = GenericParser::<V2, Local>::default()
   ///       .set_footer(footer)
   ///       .parse(&token, &key)?;
-
+  ///
   ///     assert_eq!(json["aud"], "customers");
   ///     assert_eq!(json["jti"], "me");
   ///     assert_eq!(json["iss"], "me");
@@ -270,11 +606,10 @@ impl GenericBuilder<'_, '_, V2, Local> {
   ///  # }
   /// # Ok::<(),anyhow::Error>(())
   ///   ```
-
   pub fn try_encrypt(&mut self, key: &PasetoSymmetricKey<V2, Local>) -> Result<String, GenericBuilderError> {
     let mut token_builder = Paseto::<V2, Local>::builder();
 
-    let payload = self.build_payload_from_claims()?;
+    let payload = self.build_payload_from_claims(false)?;
     token_builder.set_payload(Payload::from(payload.as_str()));
     if let Some(footer) = &self.footer {
       token_builder.set_footer(*footer);
@@ -303,12 +638,12 @@ impl GenericBuilder<'_, '_, V3, Local> {
   ///# #[cfg(all(feature = "generic", feature="v3_local"))]
   ///# {
   ///   use rusty_paseto::generic::*;
-
+  ///
   ///     let key = PasetoSymmetricKey::<V3, Local>::from(Key::<32>::from(*b"wubbalubbadubdubwubbalubbadubdub"));
-
+  ///
   ///     let footer = Footer::from("some footer");
   ///     let implicit_assertion = ImplicitAssertion::from("some assertion");
-
+  ///
   ///     //create a builder, add some claims and then build the token with the key
   ///     let token = GenericBuilder::<V3, Local>::default()
   ///       .set_claim(AudienceClaim::from("customers"))
@@ -324,13 +659,13 @@ impl GenericBuilder<'_, '_, V3, Local> {
   ///       .set_footer(footer)
   ///       .set_implicit_assertion(implicit_assertion)
   ///       .try_encrypt(&key)?;
-
+  ///
   ///     //now let's decrypt the token and verify the values
   ///     let json = GenericParser::<V3, Local>::default()
   ///       .set_footer(footer)
   ///       .set_implicit_assertion(implicit_assertion)
   ///       .parse(&token, &key)?;
-
+  ///
   ///     assert_eq!(json["aud"], "customers");
   ///     assert_eq!(json["jti"], "me");
   ///     assert_eq!(json["iss"], "me");
@@ -344,17 +679,21 @@ impl GenericBuilder<'_, '_, V3, Local> {
   ///  # }
   /// # Ok::<(),anyhow::Error>(())
   ///   ```
-
   pub fn try_encrypt(&mut self, key: &PasetoSymmetricKey<V3, Local>) -> Result<String, GenericBuilderError> {
     let mut token_builder = Paseto::<V3, Local>::builder();
 
-    let payload = self.build_payload_from_claims()?;
+    let payload = self.build_payload_from_claims(false)?;
     token_builder.set_payload(Payload::from(payload.as_str()));
     if let Some(footer) = &self.footer {
       token_builder.set_footer(*footer);
     }
-    if let Some(implicit_assertion) = &self.implicit_assertion {
-      token_builder.set_implicit_assertion(*implicit_assertion);
+    let deferred = self.deferred_implicit_assertion()?;
+    if let Some(implicit_assertion) = deferred
+      .as_deref()
+      .map(ImplicitAssertion::from)
+      .or(self.implicit_assertion)
+    {
+      token_builder.set_implicit_assertion(implicit_assertion);
     }
     let nonce = Key::<32>::try_new_random()?;
     let nonce = PasetoNonce::<V3, Local>::from(&nonce);
@@ -381,12 +720,12 @@ impl GenericBuilder<'_, '_, V4, Local> {
   ///# #[cfg(all(feature = "generic", feature="v4_local"))]
   ///# {
   ///   use rusty_paseto::generic::*;
-
+  ///
   ///     let key = PasetoSymmetricKey::<V4, Local>::from(Key::<32>::from(*b"wubbalubbadubdubwubbalubbadubdub"));
-
+  ///
   ///     let footer = Footer::from("some footer");
   ///     let implicit_assertion = ImplicitAssertion::from("some assertion");
-
+  ///
   ///     //create a builder, add some claims and then build the token with the key
   ///     let token = GenericBuilder::<V4, Local>::default()
   ///       .set_claim(AudienceClaim::from("customers"))
@@ -402,13 +741,13 @@ impl GenericBuilder<'_, '_, V4, Local> {
   ///       .set_footer(footer)
   ///       .set_implicit_assertion(implicit_assertion)
   ///       .try_encrypt(&key)?;
-
+  ///
   ///     //now let's decrypt the token and verify the values
   ///     let json = GenericParser::<V4, Local>::default()
   ///       .set_footer(footer)
   ///       .set_implicit_assertion(implicit_assertion)
   ///       .parse(&token, &key)?;
-
+  ///
   ///     assert_eq!(json["aud"], "customers");
   ///     assert_eq!(json["jti"], "me");
   ///     assert_eq!(json["iss"], "me");
@@ -422,18 +761,22 @@ impl GenericBuilder<'_, '_, V4, Local> {
   ///  # }
   /// # Ok::<(),anyhow::Error>(())
   ///   ```
-
   pub fn try_encrypt(&mut self, key: &PasetoSymmetricKey<V4, Local>) -> Result<String, GenericBuilderError> {
     let mut token_builder = Paseto::<V4, Local>::builder();
 
-    let payload = self.build_payload_from_claims()?;
+    let payload = self.build_payload_from_claims(false)?;
     token_builder.set_payload(Payload::from(payload.as_str()));
 
     if let Some(footer) = &self.footer {
       token_builder.set_footer(*footer);
     }
-    if let Some(implicit_assertion) = &self.implicit_assertion {
-      token_builder.set_implicit_assertion(*implicit_assertion);
+    let deferred = self.deferred_implicit_assertion()?;
+    if let Some(implicit_assertion) = deferred
+      .as_deref()
+      .map(ImplicitAssertion::from)
+      .or(self.implicit_assertion)
+    {
+      token_builder.set_implicit_assertion(implicit_assertion);
     }
     let nonce = Key::<32>::try_new_random()?;
     let nonce = PasetoNonce::<V4, Local>::from(&nonce);
@@ -446,6 +789,8 @@ impl GenericBuilder<'_, '_, V1, Public> {
   /// Given a [PasetoAsymmetricPrivateKey], attempts to sign a ([V1], [Public]) PASETO token from the data and
   /// claims provided to the GenericBuilder with an optional [Footer].
   ///
+  /// The claims are always signed sorted by key, so the same claims reproduce the same signing input.
+  ///
   /// Returns `Ok(String)` on success, where the String is the signed PASETO token, otherwise returns an error.
   ///
   /// # Errors
@@ -459,14 +804,14 @@ impl GenericBuilder<'_, '_, V1, Public> {
   ///# #[cfg(all(feature = "generic", feature="v1_public"))]
   ///# {
   ///   # use rusty_paseto::generic::*;
-
+  ///
   ///    //obtain a private key (pk)
   ///   # let private_key = include_bytes!("../../../tests/v1_public_test_vectors_private_key.pk8");
   ///   # let pk: &[u8] = private_key;
   ///    let private_key = PasetoAsymmetricPrivateKey::<V1, Public>::from(pk);
-
+  ///
   ///     let footer = Footer::from("some footer");
-
+  ///
   ///     //sign a public V1 token
   ///     let token = GenericBuilder::<V1, Public>::default()
   ///       .set_claim(AudienceClaim::from("customers"))
@@ -481,7 +826,7 @@ impl GenericBuilder<'_, '_, V1, Public> {
   ///       .set_claim(CustomClaim::try_from(("pi to 6 digits", 3.141526))?)
   ///       .set_footer(footer)
   ///       .try_sign(&private_key)?;
-
+  ///
   /// //obtain a public key (pubk)
   ///   #  let public_key = include_bytes!("../../../tests/v1_public_test_vectors_public_key.der");
   ///   #  let pubk: &[u8] = public_key;
@@ -517,7 +862,7 @@ impl GenericBuilder<'_, '_, V1, Public> {
   pub fn try_sign(&mut self, key: &PasetoAsymmetricPrivateKey<V1, Public>) -> Result<String, GenericBuilderError> {
     let mut token_builder = Paseto::<V1, Public>::builder();
 
-    let payload = self.build_payload_from_claims()?;
+    let payload = self.build_payload_from_claims(true)?;
     token_builder.set_payload(Payload::from(payload.as_str()));
     if let Some(footer) = &self.footer {
       token_builder.set_footer(*footer);
@@ -531,6 +876,8 @@ impl GenericBuilder<'_, '_, V2, Public> {
   /// Given a [PasetoAsymmetricPrivateKey], attempts to sign a ([V2], [Public]) PASETO token from the data and
   /// claims provided to the GenericBuilder with an optional [Footer].
   ///
+  /// The claims are always signed sorted by key, so the same claims reproduce the same signing input.
+  ///
   /// Returns `Ok(String)` on success, where the String is the signed PASETO token, otherwise returns an error.
   ///
   /// # Errors
@@ -544,16 +891,16 @@ impl GenericBuilder<'_, '_, V2, Public> {
   ///# #[cfg(all(feature = "generic", feature="v1_public"))]
   ///# {
   ///   # use rusty_paseto::generic::*;
-
+  ///
   ///    //obtain a key
   /// let private_key = Key::<64>::try_from("b4cbfb43df4ce210727d953e4a713307fa19bb7d9f85041438d9e11b942a37741eb9dbbbbc047c03fd70604e0071f0987e16b28b757225c11f00415d0e20b1a2")?;
   /// let private_key = PasetoAsymmetricPrivateKey::<V2, Public>::from(&private_key);
-
+  ///
   /// let public_key = Key::<32>::try_from("1eb9dbbbbc047c03fd70604e0071f0987e16b28b757225c11f00415d0e20b1a2")?;
   /// let public_key = PasetoAsymmetricPublicKey::<V2, Public>::from(&public_key);
-
+  ///
   /// let footer = Footer::from("some footer");
-
+  ///
   /// //sign a public V2 token
   /// let token = GenericBuilder::<V2, Public>::default()
   ///   .set_claim(AudienceClaim::from("customers"))
@@ -568,7 +915,7 @@ impl GenericBuilder<'_, '_, V2, Public> {
   ///   .set_claim(CustomClaim::try_from(("pi to 6 digits", 3.141526))?)
   ///   .set_footer(footer)
   ///   .try_sign(&private_key)?;
-
+  ///
   /// //now let's try to verify it
   /// let json = GenericParser::<V2, Public>::default()
   ///   .set_footer(footer)
@@ -600,7 +947,7 @@ impl GenericBuilder<'_, '_, V2, Public> {
   pub fn try_sign(&mut self, key: &PasetoAsymmetricPrivateKey<V2, Public>) -> Result<String, GenericBuilderError> {
     let mut token_builder = Paseto::<V2, Public>::builder();
 
-    let payload = self.build_payload_from_claims()?;
+    let payload = self.build_payload_from_claims(true)?;
     token_builder.set_payload(Payload::from(payload.as_str()));
     if let Some(footer) = &self.footer {
       token_builder.set_footer(*footer);
@@ -615,6 +962,8 @@ impl GenericBuilder<'_, '_, V3, Public> {
   /// claims provided to the GenericBuilder with an optional [Footer] and an optional
   /// [ImplicitAssertion].
   ///
+  /// The claims are always signed sorted by key, so the same claims reproduce the same signing input.
+  ///
   /// Returns `Ok(String)` on success, where the String is the signed PASETO token, otherwise returns an error.
   ///
   /// # Errors
@@ -628,20 +977,20 @@ impl GenericBuilder<'_, '_, V3, Public> {
   ///# #[cfg(all(feature = "generic", feature="v3_public"))]
   ///# {
   ///   # use rusty_paseto::generic::*;
-
+  ///
   ///    //obtain a key
   /// let private_key = Key::<48>::try_from(
   ///   "20347609607477aca8fbfbc5e6218455f3199669792ef8b466faa87bdc67798144c848dd03661eed5ac62461340cea96",
   /// )?;
   /// let private_key = PasetoAsymmetricPrivateKey::<V3, Public>::from(&private_key);
-
+  ///
   /// let public_key = Key::<49>::try_from(
   ///   "02fbcb7c69ee1c60579be7a334134878d9c5c5bf35d552dab63c0140397ed14cef637d7720925c44699ea30e72874c72fb",
   /// )?;
   /// let public_key = PasetoAsymmetricPublicKey::<V3, Public>::try_from(&public_key)?;
-
+  ///
   /// let footer = Footer::from("some footer");
-
+  ///
   /// let implicit_assertion = ImplicitAssertion::from("some assertion");
   /// //sign a public V3 token
   /// let token = GenericBuilder::<V3, Public>::default()
@@ -658,7 +1007,7 @@ impl GenericBuilder<'_, '_, V3, Public> {
   ///   .set_footer(footer)
   ///   .set_implicit_assertion(implicit_assertion)
   ///   .try_sign(&private_key)?;
-
+  ///
   /// //now let's try to verify it
   /// let json = GenericParser::<V3, Public>::default()
   ///   .set_footer(footer)
@@ -691,14 +1040,19 @@ impl GenericBuilder<'_, '_, V3, Public> {
   pub fn try_sign(&mut self, key: &PasetoAsymmetricPrivateKey<V3, Public>) -> Result<String, GenericBuilderError> {
     let mut token_builder = Paseto::<V3, Public>::builder();
 
-    let payload = self.build_payload_from_claims()?;
+    let payload = self.build_payload_from_claims(true)?;
     token_builder.set_payload(Payload::from(payload.as_str()));
 
     if let Some(footer) = &self.footer {
       token_builder.set_footer(*footer);
     }
-    if let Some(implicit_assertion) = &self.implicit_assertion {
-      token_builder.set_implicit_assertion(*implicit_assertion);
+    let deferred = self.deferred_implicit_assertion()?;
+    if let Some(implicit_assertion) = deferred
+      .as_deref()
+      .map(ImplicitAssertion::from)
+      .or(self.implicit_assertion)
+    {
+      token_builder.set_implicit_assertion(implicit_assertion);
     }
     Ok(token_builder.try_sign(key)?)
   }
@@ -710,6 +1064,8 @@ impl GenericBuilder<'_, '_, V4, Public> {
   /// claims provided to the GenericBuilder with an optional [Footer] and an optional
   /// [ImplicitAssertion].
   ///
+  /// The claims are always signed sorted by key, so the same claims reproduce the same signing input.
+  ///
   /// Returns `Ok(String)` on success, where the String is the signed PASETO token, otherwise returns an error.
   ///
   /// # Errors
@@ -723,18 +1079,18 @@ impl GenericBuilder<'_, '_, V4, Public> {
   ///# #[cfg(all(feature = "generic", feature="v4_public"))]
   ///# {
   ///   # use rusty_paseto::generic::*;
-
+  ///
   /// //create a key
   /// let private_key = Key::<64>::try_from("b4cbfb43df4ce210727d953e4a713307fa19bb7d9f85041438d9e11b942a37741eb9dbbbbc047c03fd70604e0071f0987e16b28b757225c11f00415d0e20b1a2")?;
   /// let pk: &[u8] = private_key.as_slice();
-  /// let private_key = PasetoAsymmetricPrivateKey::<V4, Public>::from(pk);
-
+  /// let private_key = PasetoAsymmetricPrivateKey::<V4, Public>::try_from(pk)?;
+  ///
   /// let public_key = Key::<32>::try_from("1eb9dbbbbc047c03fd70604e0071f0987e16b28b757225c11f00415d0e20b1a2")?;
   /// let public_key = PasetoAsymmetricPublicKey::<V4, Public>::from(&public_key);
-
+  ///
   /// let footer = Footer::from("some footer");
   /// let implicit_assertion = ImplicitAssertion::from("some assertion");
-
+  ///
   /// //sign a public V4 token
   /// let token = GenericBuilder::<V4, Public>::default()
   ///   .set_claim(AudienceClaim::from("customers"))
@@ -750,7 +1106,7 @@ impl GenericBuilder<'_, '_, V4, Public> {
   ///   .set_footer(footer)
   ///   .set_implicit_assertion(implicit_assertion)
   ///   .try_sign(&private_key)?;
-
+  ///
   /// //now let's try to verify it
   /// let json = GenericParser::<V4, Public>::default()
   ///   .set_footer(footer)
@@ -777,22 +1133,26 @@ impl GenericBuilder<'_, '_, V4, Public> {
   /// assert_eq!(json["sub"], "loyal subjects");
   /// assert_eq!(json["pi to 6 digits"], 3.141526);
   /// assert_eq!(json["seats"], 4);
-
+  ///
   ///  # }
   /// # Ok::<(),anyhow::Error>(())
   ///```
-
   pub fn try_sign(&mut self, key: &PasetoAsymmetricPrivateKey<V4, Public>) -> Result<String, GenericBuilderError> {
     let mut token_builder = Paseto::<V4, Public>::builder();
 
-    let payload = self.build_payload_from_claims()?;
+    let payload = self.build_payload_from_claims(true)?;
     token_builder.set_payload(Payload::from(payload.as_str()));
 
     if let Some(footer) = &self.footer {
       token_builder.set_footer(*footer);
     }
-    if let Some(implicit_assertion) = &self.implicit_assertion {
-      token_builder.set_implicit_assertion(*implicit_assertion);
+    let deferred = self.deferred_implicit_assertion()?;
+    if let Some(implicit_assertion) = deferred
+      .as_deref()
+      .map(ImplicitAssertion::from)
+      .or(self.implicit_assertion)
+    {
+      token_builder.set_implicit_assertion(implicit_assertion);
     }
     Ok(token_builder.try_sign(key)?)
   }
@@ -810,7 +1170,7 @@ mod generic_v4_public_builders {
     //create a key
     let private_key = Key::<64>::try_from("b4cbfb43df4ce210727d953e4a713307fa19bb7d9f85041438d9e11b942a37741eb9dbbbbc047c03fd70604e0071f0987e16b28b757225c11f00415d0e20b1a2")?;
     let pk: &[u8] = private_key.as_slice();
-    let private_key = PasetoAsymmetricPrivateKey::<V4, Public>::from(pk);
+    let private_key = PasetoAsymmetricPrivateKey::<V4, Public>::try_from(pk)?;
 
     let public_key = Key::<32>::try_from("1eb9dbbbbc047c03fd70604e0071f0987e16b28b757225c11f00415d0e20b1a2")?;
     let public_key = PasetoAsymmetricPublicKey::<V4, Public>::from(&public_key);
@@ -862,6 +1222,36 @@ mod generic_v4_public_builders {
     assert_eq!(json["seats"], 4);
     Ok(())
   }
+
+  #[test]
+  fn sorted_public_claims_test() -> Result<()> {
+    let private_key = Key::<64>::try_from("b4cbfb43df4ce210727d953e4a713307fa19bb7d9f85041438d9e11b942a37741eb9dbbbbc047c03fd70604e0071f0987e16b28b757225c11f00415d0e20b1a2")?;
+    let private_key = PasetoAsymmetricPrivateKey::<V4, Public>::from(&private_key);
+    let public_key = Key::<32>::try_from("1eb9dbbbbc047c03fd70604e0071f0987e16b28b757225c11f00415d0e20b1a2")?;
+    let public_key = PasetoAsymmetricPublicKey::<V4, Public>::from(&public_key);
+
+    let sign = || {
+      GenericBuilder::<V4, Public>::default()
+        .set_claim(SubjectClaim::from("loyal subjects"))
+        .set_claim(IssuerClaim::from("me"))
+        .set_claim(AudienceClaim::from("customers"))
+        .set_claim(CustomClaim::try_from(("seats", 4))?)
+        .set_claim(CustomClaim::try_from(("data", "this is a secret message"))?)
+        .set_extra(serde_json::Map::from_iter([("tenant".to_string(), "acme".into())]))
+        .try_sign(&private_key)
+        .map_err(anyhow::Error::from)
+    };
+    let token = sign()?;
+    //Ed25519 signatures are deterministic, so separately built tokens only match if their payloads do
+    for _ in 0..8 {
+      assert_eq!(sign()?, token);
+    }
+    assert_eq!(
+      crate::core::Paseto::<V4, Public>::try_verify(&token, &public_key, None, None)?,
+      r#"{"aud":"customers","data":"this is a secret message","iss":"me","seats":4,"sub":"loyal subjects","tenant":"acme"}"#
+    );
+    Ok(())
+  }
 }
 
 #[cfg(all(test, feature = "v3_public"))]
@@ -1108,6 +1498,48 @@ mod generic_v3_local_builders {
     assert_eq!(json["seats"], 4);
     Ok(())
   }
+
+  #[test]
+  fn deferred_implicit_assertion_test() -> Result<()> {
+    let key = PasetoSymmetricKey::<V3, Local>::from(Key::<32>::from(*b"wubbalubbadubdubwubbalubbadubdub"));
+    let calls = std::cell::Cell::new(0);
+    let context = std::cell::RefCell::new("curl/8.0|192.0.2.1".to_string());
+
+    let compute = || {
+      calls.set(calls.get() + 1);
+      context.borrow().clone().into_bytes()
+    };
+    let mut builder = GenericBuilder::<V3, Local>::default();
+    builder.set_implicit_assertion_with(&compute);
+    //nothing is computed until a token is built
+    assert_eq!(calls.get(), 0);
+    let token = builder.try_encrypt(&key)?;
+    *context.borrow_mut() = "curl/8.0|198.51.100.7".to_string();
+    let rebound = builder.try_encrypt(&key)?;
+    assert_eq!(calls.get(), 2);
+
+    let parse = |token: &str, assertion: &str| {
+      GenericParser::<V3, Local>::default()
+        .set_implicit_assertion(ImplicitAssertion::from(assertion))
+        .parse(token, &key)
+    };
+    assert!(parse(&token, "curl/8.0|192.0.2.1").is_ok());
+    assert!(parse(&token, "curl/8.0|198.51.100.7").is_err());
+    assert!(parse(&rebound, "curl/8.0|198.51.100.7").is_ok());
+
+    //a fixed assertion replaces the deferred one
+    let token = GenericBuilder::<V3, Local>::default()
+      .set_implicit_assertion_with(&|| b"deferred".to_vec())
+      .set_implicit_assertion(ImplicitAssertion::from("fixed"))
+      .try_encrypt(&key)?;
+    assert!(parse(&token, "fixed").is_ok());
+
+    let result = GenericBuilder::<V3, Local>::default()
+      .set_implicit_assertion_with(&|| vec![0xff, 0xfe])
+      .try_encrypt(&key);
+    assert!(matches!(result, Err(GenericBuilderError::ImplicitAssertionNotUtf8)));
+    Ok(())
+  }
 }
 
 #[cfg(all(test, feature = "v2_local"))]
@@ -1186,6 +1618,186 @@ mod builders {
     Ok(())
   }
 
+  #[test]
+  fn failed_build_keeps_claims_test() -> Result<()> {
+    use std::collections::HashMap;
+
+    let key = PasetoSymmetricKey::<V2, Local>::from(Key::<32>::from(*b"wubbalubbadubdubwubbalubbadubdub"));
+    //json object keys must be strings, so this claim fails to serialize
+    let mut unserializable = HashMap::new();
+    unserializable.insert((1, 2), "non-string key");
+
+    let mut builder = GenericBuilder::<V2, Local>::default();
+    builder
+      .set_claim(AudienceClaim::from("customers"))
+      .set_claim(CustomClaim::try_from(("seats", 4))?)
+      .set_claim(CustomClaim::try_from(("broken", unserializable))?);
+
+    let error = builder.try_encrypt(&key).unwrap_err();
+    assert!(matches!(error, GenericBuilderError::PayloadJsonError { .. }));
+
+    //the remaining claims survived the failed attempt and a retry includes all of them
+    let token = builder.remove_claim("broken").try_encrypt(&key)?;
+    let json = GenericParser::<V2, Local>::default().parse(&token, &key)?;
+    assert_eq!(json["aud"], "customers");
+    assert_eq!(json["seats"], 4);
+    Ok(())
+  }
+
+  #[test]
+  fn raw_payload_test() -> Result<()> {
+    let key = PasetoSymmetricKey::<V2, Local>::from(Key::<32>::from(*b"wubbalubbadubdubwubbalubbadubdub"));
+    let raw_payload = r#"{"aud":"customers","nested":{"b":1,"a":2}}"#;
+
+    let token = GenericBuilder::<V2, Local>::default()
+      .set_raw_payload(raw_payload.to_string())
+      .try_encrypt(&key)?;
+    let payload = Paseto::<V2, Local>::try_decrypt(&token, &key, None)?;
+    assert_eq!(payload, raw_payload);
+
+    let error = GenericBuilder::<V2, Local>::default()
+      .set_raw_payload(raw_payload.to_string())
+      .set_claim(SubjectClaim::from("loyal subjects"))
+      .try_encrypt(&key)
+      .unwrap_err();
+    assert!(matches!(error, GenericBuilderError::RawPayloadWithClaims));
+
+    let error = GenericBuilder::<V2, Local>::default()
+      .set_raw_payload("[1, 2, 3]".to_string())
+      .try_encrypt(&key)
+      .unwrap_err();
+    assert!(matches!(error, GenericBuilderError::RawPayloadNotObject));
+
+    let error = GenericBuilder::<V2, Local>::default()
+      .set_raw_payload("{not json".to_string())
+      .try_encrypt(&key)
+      .unwrap_err();
+    assert!(matches!(error, GenericBuilderError::PayloadJsonError { .. }));
+    Ok(())
+  }
+
+  #[test]
+  fn forbid_secrets_in_footer_test() -> Result<()> {
+    let key = PasetoSymmetricKey::<V2, Local>::from(Key::<32>::from(*b"wubbalubbadubdubwubbalubbadubdub"));
+    let footer = Footer::from(r#"{"kid":"k2.lid.abc","sk":"wubbalubbadubdub"}"#);
+
+    //the guard is opt in
+    assert!(GenericBuilder::<V2, Local>::default()
+      .set_footer(footer)
+      .try_encrypt(&key)
+      .is_ok());
+
+    let error = GenericBuilder::<V2, Local>::default()
+      .set_footer(footer)
+      .forbid_secrets_in_footer()
+      .try_encrypt(&key)
+      .unwrap_err();
+    assert!(matches!(&error, GenericBuilderError::SecretInFooter(location) if location == "$.sk"));
+    //the secret itself is never echoed
+    assert!(!error.to_string().contains("wubbalubbadubdub"));
+
+    assert!(GenericBuilder::<V2, Local>::default()
+      .set_footer(Footer::from(r#"{"kid":"k2.lid.abc"}"#))
+      .forbid_secrets_in_footer()
+      .try_encrypt(&key)
+      .is_ok());
+    Ok(())
+  }
+
+  #[test]
+  fn extra_claims_test() -> Result<()> {
+    let key = PasetoSymmetricKey::<V2, Local>::from(Key::<32>::from(*b"wubbalubbadubdubwubbalubbadubdub"));
+    let extras = serde_json::json!({"tenant": "acme", "seats": 2, "tags": ["a"]});
+    let extras = extras.as_object().cloned().unwrap_or_default();
+
+    let token = GenericBuilder::<V2, Local>::default()
+      .set_claim(IssuerClaim::from("me"))
+      .set_claim(CustomClaim::try_from(("seats", 4))?)
+      .set_extra(extras)
+      .set_extra(serde_json::Map::from_iter([("tenant".to_string(), "globex".into())]))
+      .try_encrypt(&key)?;
+    let json = GenericParser::<V2, Local>::default().parse(&token, &key)?;
+    //the typed claim wins over the extra, and a later extra over an earlier one
+    assert_eq!(
+      json,
+      serde_json::json!({"iss": "me", "seats": 4, "tenant": "globex", "tags": ["a"]})
+    );
+
+    let reserved = serde_json::Map::from_iter([("exp".to_string(), "tomorrow".into())]);
+    let error = GenericBuilder::<V2, Local>::default()
+      .set_extra(reserved.clone())
+      .try_encrypt(&key)
+      .unwrap_err();
+    assert!(matches!(
+      error,
+      GenericBuilderError::ClaimError {
+        source: PasetoClaimError::Reserved(claim)
+      } if claim == "exp"
+    ));
+    //unless the typed claim is there to take precedence
+    assert!(GenericBuilder::<V2, Local>::default()
+      .set_claim(ExpirationClaim::try_from("2030-01-01T00:00:00Z")?)
+      .set_extra(reserved)
+      .try_encrypt(&key)
+      .is_ok());
+    Ok(())
+  }
+
+  #[test]
+  fn forbid_float_claims_test() -> Result<()> {
+    let key = PasetoSymmetricKey::<V2, Local>::from(Key::<32>::from(*b"wubbalubbadubdubwubbalubbadubdub"));
+
+    //floats are allowed unless forbidden
+    assert!(GenericBuilder::<V2, Local>::default()
+      .set_claim(CustomClaim::try_from(("ratio", 0.5))?)
+      .try_encrypt(&key)
+      .is_ok());
+
+    let error = GenericBuilder::<V2, Local>::default()
+      .forbid_float_claims()
+      .set_claim(CustomClaim::try_from(("seats", 4))?)
+      .set_claim(CustomClaim::try_from(("dims", serde_json::json!({"sizes": [1, 2.5]})))?)
+      .try_encrypt(&key)
+      .unwrap_err();
+    assert!(matches!(error, GenericBuilderError::FloatClaim(claim) if claim == "dims"));
+
+    //integers of any sign and size pass, and so does a float written as a string
+    assert!(GenericBuilder::<V2, Local>::default()
+      .forbid_float_claims()
+      .set_claim(CustomClaim::try_from(("offset", -2))?)
+      .set_claim(CustomClaim::try_from(("big", u64::MAX))?)
+      .set_claim(CustomClaim::try_from(("price", "2.50"))?)
+      .try_encrypt(&key)
+      .is_ok());
+
+    let error = GenericBuilder::<V2, Local>::default()
+      .forbid_float_claims()
+      .set_raw_payload(r#"{"sub":"me","price":2.5}"#.to_string())
+      .try_encrypt(&key)
+      .unwrap_err();
+    assert!(matches!(error, GenericBuilderError::FloatClaim(claim) if claim == "price"));
+    Ok(())
+  }
+
+  #[test]
+  fn public_footer_test() -> Result<()> {
+    let key = PasetoSymmetricKey::<V2, Local>::from(Key::<32>::from(*b"wubbalubbadubdubwubbalubbadubdub"));
+    let token = GenericBuilder::<V2, Local>::default()
+      .set_claim(CustomClaim::try_from(("data", "this is a secret message"))?)
+      .set_public_footer(Footer::from("not a secret"))
+      .try_encrypt(&key)?;
+
+    //the footer can be read without the key, unlike the claims
+    let untrusted_token = UntrustedToken::try_from(token.as_str())?;
+    assert_eq!(untrusted_token.footer()?.as_deref(), Some("not a secret"));
+
+    let json = GenericParser::<V2, Local>::default()
+      .set_footer(Footer::from("not a secret"))
+      .parse(&token, &key)?;
+    assert_eq!(json["data"], "this is a secret message");
+    Ok(())
+  }
+
   #[test]
   fn test_no_claims() -> Result<()> {
     let key = PasetoSymmetricKey::<V2, Local>::from(Key::<32>::from(*b"wubbalubbadubdubwubbalubbadubdub"));
@@ -1197,4 +1809,72 @@ mod builders {
     assert_eq!(decrypted.to_string(), "{}");
     Ok(())
   }
+
+  #[test]
+  fn max_payload_bytes_test() -> Result<()> {
+    let key = PasetoSymmetricKey::<V2, Local>::from(Key::<32>::from(*b"wubbalubbadubdubwubbalubbadubdub"));
+    //{"data":"this is a secret message"} is 35 bytes
+    let mut builder = GenericBuilder::<V2, Local>::default();
+    builder.set_claim(CustomClaim::try_from(("data", "this is a secret message"))?);
+
+    //a payload of exactly the cap is accepted
+    let token = builder.max_payload_bytes(35).try_encrypt(&key)?;
+    assert_eq!(
+      GenericParser::<V2, Local>::default().parse(&token, &key)?["data"],
+      "this is a secret message"
+    );
+    assert!(matches!(
+      builder.max_payload_bytes(34).try_encrypt(&key),
+      Err(GenericBuilderError::PayloadTooLarge { size: 35, max: 34 })
+    ));
+
+    //a raw payload is measured as given
+    let result = GenericBuilder::<V2, Local>::default()
+      .set_raw_payload(r#"{ "data": "this is a secret message" }"#.to_string())
+      .max_payload_bytes(35)
+      .try_encrypt(&key);
+    assert!(matches!(
+      result,
+      Err(GenericBuilderError::PayloadTooLarge { size: 38, max: 35 })
+    ));
+    Ok(())
+  }
+
+  #[test]
+  fn claim_spec_test() -> Result<()> {
+    let key = PasetoSymmetricKey::<V2, Local>::from(Key::<32>::from(*b"wubbalubbadubdubwubbalubbadubdub"));
+    let spec = ClaimSpec::registered().expect("seats", ClaimType::Integer);
+
+    let token = GenericBuilder::<V2, Local>::default()
+      .set_claim(ExpirationClaim::try_from("2030-01-01T00:00:00Z")?)
+      .set_claim(CustomClaim::try_from(("seats", 4))?)
+      .set_claim_spec(spec.clone())
+      .try_encrypt(&key)?;
+    assert_eq!(GenericParser::<V2, Local>::default().parse(&token, &key)?["seats"], 4);
+
+    //claims from the dynamic paths are checked too
+    let mut extra_claims = serde_json::Map::new();
+    extra_claims.insert("seats".to_string(), serde_json::json!("four"));
+    let result = GenericBuilder::<V2, Local>::default()
+      .set_extra(extra_claims)
+      .set_claim_spec(spec.clone())
+      .try_encrypt(&key);
+    assert!(matches!(
+      result,
+      Err(GenericBuilderError::ClaimError {
+        source: PasetoClaimError::WrongType(claim, ClaimType::Integer, "a string")
+      }) if claim == "seats"
+    ));
+    let result = GenericBuilder::<V2, Local>::default()
+      .set_raw_payload(r#"{"exp":1893456000}"#.to_string())
+      .set_claim_spec(spec)
+      .try_encrypt(&key);
+    assert!(matches!(
+      result,
+      Err(GenericBuilderError::ClaimError {
+        source: PasetoClaimError::WrongType(claim, ClaimType::String, "an integer")
+      }) if claim == "exp"
+    ));
+    Ok(())
+  }
 }
@@ -1,6 +1,11 @@
+mod builder_config;
 mod error;
+mod footer_guard;
 mod generic_builder;
+mod no_footer_builder;
 mod traits;
+pub use builder_config::BuilderConfig;
 pub use error::GenericBuilderError;
 pub use generic_builder::GenericBuilder;
-//pub use traits::*;
+pub use no_footer_builder::NoFooterBuilder;
+pub use traits::PasetoSealingKey;
@@ -0,0 +1,209 @@
+use crate::generic::*;
+use std::collections::HashMap;
+
+/// A [GenericBuilder] for tokens which must never carry a footer, such as those of services which can't leak
+/// routing metadata
+///
+/// The builder offers everything the [GenericBuilder] does except for setting a footer, so a footer can't be added
+/// by mistake and every token it builds has none.  There is no way to turn a [GenericBuilder] into one, as it may
+/// already have a footer.
+///
+/// # Usage
+/// ```
+/// # #[cfg(all(feature = "generic", feature = "v4_local"))]
+/// # {
+/// use rusty_paseto::generic::*;
+///
+/// let key = PasetoSymmetricKey::<V4, Local>::from(Key::<32>::from(*b"wubbalubbadubdubwubbalubbadubdub"));
+/// let token = NoFooterBuilder::<V4, Local>::default()
+///   .set_claim(SubjectClaim::from("loyal subjects"))
+///   .try_encrypt(&key)?;
+///
+/// assert_eq!(UntrustedToken::try_from(token.as_str())?.footer()?, None);
+/// # }
+/// # Ok::<(),anyhow::Error>(())
+/// ```
+///
+/// Setting a footer doesn't compile
+///
+/// ```compile_fail
+/// # use rusty_paseto::generic::*;
+/// NoFooterBuilder::<V4, Local>::default().set_footer(Footer::from("kid:1"));
+/// ```
+pub struct NoFooterBuilder<'a, 'b, Version, Purpose> {
+  builder: GenericBuilder<'a, 'b, Version, Purpose>,
+}
+
+impl<'a, 'b, Version, Purpose> NoFooterBuilder<'a, 'b, Version, Purpose> {
+  ///Removes a [claim](PasetoClaim) by key, see [GenericBuilder::remove_claim]
+  pub fn remove_claim(&mut self, claim_key: &str) -> &mut Self {
+    self.builder.remove_claim(claim_key);
+    self
+  }
+
+  ///Adds multiple [claims](PasetoClaim) at once, see [GenericBuilder::extend_claims]
+  pub fn extend_claims(&mut self, value: HashMap<String, Box<dyn erased_serde::Serialize>>) -> &mut Self {
+    self.builder.extend_claims(value);
+    self
+  }
+
+  ///Adds a [claim](PasetoClaim) to the token builder
+  pub fn set_claim<T: 'b + PasetoClaim + erased_serde::Serialize>(&mut self, value: T) -> &mut Self
+  where
+    'b: 'a,
+  {
+    self.builder.set_claim(value);
+    self
+  }
+
+  ///Adds a [claim](PasetoClaim) to the token builder only when `condition` is true
+  pub fn set_claim_if<T: 'b + PasetoClaim + erased_serde::Serialize>(&mut self, condition: bool, value: T) -> &mut Self
+  where
+    'b: 'a,
+  {
+    self.builder.set_claim_if(condition, value);
+    self
+  }
+
+  ///Merges a bag of dynamic claims into the payload, see [GenericBuilder::set_extra]
+  pub fn set_extra(&mut self, extra_claims: serde_json::Map<String, serde_json::Value>) -> &mut Self {
+    self.builder.set_extra(extra_claims);
+    self
+  }
+
+  ///Scopes the setting of several [claims](PasetoClaim) within a closure which receives this builder
+  pub fn claims<F>(&mut self, scope: F) -> &mut Self
+  where
+    F: FnOnce(&mut Self),
+  {
+    scope(self);
+    self
+  }
+
+  ///Refuses to build tokens with a claim holding a float, see [GenericBuilder::forbid_float_claims]
+  pub fn forbid_float_claims(&mut self) -> &mut Self {
+    self.builder.forbid_float_claims();
+    self
+  }
+
+  ///Caps the length of the serialized payload, see [GenericBuilder::max_payload_bytes]
+  pub fn max_payload_bytes(&mut self, max: usize) -> &mut Self {
+    self.builder.max_payload_bytes(max);
+    self
+  }
+
+  ///Checks the JSON type of the claims the [ClaimSpec] names, see [GenericBuilder::set_claim_spec]
+  pub fn set_claim_spec(&mut self, spec: ClaimSpec) -> &mut Self {
+    self.builder.set_claim_spec(spec);
+    self
+  }
+
+  ///Sets a complete, pre-serialized JSON payload, see [GenericBuilder::set_raw_payload]
+  pub fn set_raw_payload(&mut self, json: String) -> &mut Self {
+    self.builder.set_raw_payload(json);
+    self
+  }
+
+  ///Captures the staged claims and implicit assertion, see [GenericBuilder::config_snapshot]
+  ///
+  /// # Errors
+  ///
+  /// Returns [`GenericBuilderError`] if the staged claims can't be serialized
+  pub fn config_snapshot(&self) -> Result<BuilderConfig, GenericBuilderError> {
+    self.builder.config_snapshot()
+  }
+}
+
+impl<'a, 'b, Version, Purpose> NoFooterBuilder<'a, 'b, Version, Purpose>
+where
+  Version: ImplicitAssertionCapable,
+{
+  ///Adds an optional [implicit assertion](ImplicitAssertion) for V3/V4 tokens only, which is never part of the token
+  pub fn set_implicit_assertion(&mut self, implicit_assertion: ImplicitAssertion<'a>) -> &mut Self {
+    self.builder.set_implicit_assertion(implicit_assertion);
+    self
+  }
+
+  ///Defers the [implicit assertion](ImplicitAssertion) to a function run each time a token is built, see
+  ///[GenericBuilder::set_implicit_assertion_with]
+  pub fn set_implicit_assertion_with(&mut self, compute: &'a dyn Fn() -> Vec<u8>) -> &mut Self {
+    self.builder.set_implicit_assertion_with(compute);
+    self
+  }
+}
+
+impl<'a, 'b, Version> NoFooterBuilder<'a, 'b, Version, Local> {
+  ///Encrypts the claims into a token without a footer
+  ///
+  /// # Errors
+  ///
+  /// Returns [`GenericBuilderError`] for any errors when building the payload or encrypting it
+  pub fn try_encrypt<K>(&mut self, key: &K) -> Result<String, GenericBuilderError>
+  where
+    K: PasetoSealingKey<Version, Local>,
+  {
+    key.try_seal(&mut self.builder)
+  }
+}
+
+impl<'a, 'b, Version> NoFooterBuilder<'a, 'b, Version, Public> {
+  ///Signs the claims into a token without a footer
+  ///
+  /// # Errors
+  ///
+  /// Returns [`GenericBuilderError`] for any errors when building the payload or signing it
+  pub fn try_sign<K>(&mut self, key: &K) -> Result<String, GenericBuilderError>
+  where
+    K: PasetoSealingKey<Version, Public>,
+  {
+    key.try_seal(&mut self.builder)
+  }
+}
+
+impl<Version, Purpose> Default for NoFooterBuilder<'_, '_, Version, Purpose> {
+  fn default() -> Self {
+    Self {
+      builder: GenericBuilder::default(),
+    }
+  }
+}
+
+#[cfg(all(test, feature = "v4_local", feature = "v4_public"))]
+mod unit_tests {
+  use super::*;
+  use anyhow::Result;
+  use std::convert::TryFrom;
+
+  #[test]
+  fn test_no_footer_builder() -> Result<()> {
+    let key = PasetoSymmetricKey::<V4, Local>::from(Key::from(*b"wubbalubbadubdubwubbalubbadubdub"));
+    let token = NoFooterBuilder::<V4, Local>::default()
+      .set_claim(SubjectClaim::from("loyal subjects"))
+      .set_implicit_assertion(ImplicitAssertion::from("tenant:acme"))
+      .try_encrypt(&key)?;
+    let json = GenericParser::<V4, Local>::default()
+      .set_implicit_assertion(ImplicitAssertion::from("tenant:acme"))
+      .parse(&token, &key)?;
+    assert_eq!(json["sub"], "loyal subjects");
+    assert_eq!(token.matches('.').count(), 2);
+
+    let private_key = Key::<64>::try_from("b4cbfb43df4ce210727d953e4a713307fa19bb7d9f85041438d9e11b942a37741eb9dbbbbc047c03fd70604e0071f0987e16b28b757225c11f00415d0e20b1a2")?;
+    let private_key = PasetoAsymmetricPrivateKey::<V4, Public>::from(&private_key);
+    let public_key = Key::<32>::try_from("1eb9dbbbbc047c03fd70604e0071f0987e16b28b757225c11f00415d0e20b1a2")?;
+    let public_key = PasetoAsymmetricPublicKey::<V4, Public>::from(&public_key);
+    let token = NoFooterBuilder::<V4, Public>::default()
+      .set_claim(SubjectClaim::from("loyal subjects"))
+      .try_sign(&private_key)?;
+    assert_eq!(UntrustedToken::try_from(token.as_str())?.footer()?, None);
+    assert_eq!(
+      GenericParser::<V4, Public>::default().parse(&token, &public_key)?["sub"],
+      "loyal subjects"
+    );
+
+    let config = NoFooterBuilder::<V4, Local>::default()
+      .set_claim(SubjectClaim::from("loyal subjects"))
+      .config_snapshot()?;
+    assert_eq!(config.footer, None);
+    Ok(())
+  }
+}
@@ -0,0 +1,70 @@
+use crate::generic::*;
+
+/// A key which is able to seal (encrypt or sign) PASETO tokens of a specific version and purpose
+///
+/// Implemented for [PasetoSymmetricKey] (Local) and [PasetoAsymmetricPrivateKey] (Public) for every enabled version so
+/// that builder functionality can be written once for all versions and purposes, the counterpart of
+/// [PasetoOpeningKey].
+///
+/// The key types only exist for their own purpose, so no key of the wrong purpose can be handed to a builder, see
+/// [PasetoSymmetricKey] and [PasetoAsymmetricPrivateKey].
+pub trait PasetoSealingKey<Version, Purpose> {
+  /// Encrypts or signs the claims, footer and implicit assertion of the builder with this key
+  fn try_seal(&self, builder: &mut GenericBuilder<'_, '_, Version, Purpose>) -> Result<String, GenericBuilderError>;
+}
+
+#[cfg(feature = "v1_local")]
+impl PasetoSealingKey<V1, Local> for PasetoSymmetricKey<V1, Local> {
+  fn try_seal(&self, builder: &mut GenericBuilder<'_, '_, V1, Local>) -> Result<String, GenericBuilderError> {
+    builder.try_encrypt(self)
+  }
+}
+
+#[cfg(feature = "v2_local")]
+impl PasetoSealingKey<V2, Local> for PasetoSymmetricKey<V2, Local> {
+  fn try_seal(&self, builder: &mut GenericBuilder<'_, '_, V2, Local>) -> Result<String, GenericBuilderError> {
+    builder.try_encrypt(self)
+  }
+}
+
+#[cfg(feature = "v3_local")]
+impl PasetoSealingKey<V3, Local> for PasetoSymmetricKey<V3, Local> {
+  fn try_seal(&self, builder: &mut GenericBuilder<'_, '_, V3, Local>) -> Result<String, GenericBuilderError> {
+    builder.try_encrypt(self)
+  }
+}
+
+#[cfg(feature = "v4_local")]
+impl PasetoSealingKey<V4, Local> for PasetoSymmetricKey<V4, Local> {
+  fn try_seal(&self, builder: &mut GenericBuilder<'_, '_, V4, Local>) -> Result<String, GenericBuilderError> {
+    builder.try_encrypt(self)
+  }
+}
+
+#[cfg(feature = "v1_public")]
+impl PasetoSealingKey<V1, Public> for PasetoAsymmetricPrivateKey<'_, V1, Public> {
+  fn try_seal(&self, builder: &mut GenericBuilder<'_, '_, V1, Public>) -> Result<String, GenericBuilderError> {
+    builder.try_sign(self)
+  }
+}
+
+#[cfg(feature = "v2_public")]
+impl PasetoSealingKey<V2, Public> for PasetoAsymmetricPrivateKey<'_, V2, Public> {
+  fn try_seal(&self, builder: &mut GenericBuilder<'_, '_, V2, Public>) -> Result<String, GenericBuilderError> {
+    builder.try_sign(self)
+  }
+}
+
+#[cfg(feature = "v3_public")]
+impl PasetoSealingKey<V3, Public> for PasetoAsymmetricPrivateKey<'_, V3, Public> {
+  fn try_seal(&self, builder: &mut GenericBuilder<'_, '_, V3, Public>) -> Result<String, GenericBuilderError> {
+    builder.try_sign(self)
+  }
+}
+
+#[cfg(feature = "v4_public")]
+impl PasetoSealingKey<V4, Public> for PasetoAsymmetricPrivateKey<'_, V4, Public> {
+  fn try_seal(&self, builder: &mut GenericBuilder<'_, '_, V4, Public>) -> Result<String, GenericBuilderError> {
+    builder.try_sign(self)
+  }
+}
@@ -1,32 +1,92 @@
 use super::PasetoClaim;
 #[cfg(feature = "serde")]
 use serde::ser::SerializeMap;
+use std::borrow::Cow;
 
 ///The reserved ['aud'](https://github.com/paseto-standard/paseto-spec/blob/master/docs/02-Implementation-Guide/04-Claims.md) PASETO claim
+///
+///With the `url` feature the claim is also created from a `url::Url`, borrowed or owned, holding its string form.
 #[derive(Clone)]
-pub struct AudienceClaim<'a>((&'a str, &'a str));
+pub struct AudienceClaim<'a>((&'static str, Cow<'a, str>), bool);
 impl<'a> PasetoClaim for AudienceClaim<'a> {
   fn get_key(&self) -> &str {
     self.0 .0
   }
+
+  fn is_case_insensitive(&self) -> bool {
+    self.1
+  }
+}
+
+impl<'a> AudienceClaim<'a> {
+  /// Whether a parser [checking](crate::generic::GenericParser::check_claim) the audience accepts it in any ASCII
+  /// case, for issuers emitting hostnames with inconsistent casing.  Exact matching is the default.
+  ///
+  /// **Loosening the comparison is a security decision.**  Every audience differing only in case becomes the same
+  /// audience, so only enable it where the audiences are case insensitive by nature, such as hostnames, and no
+  /// other service is told apart from this one by case alone.  Characters outside of ASCII are still compared
+  /// exactly.
+  ///
+  /// # Example
+  ///```
+  ///# #[cfg(feature = "default")]
+  ///# {
+  ///   use rusty_paseto::prelude::*;
+  ///   let key = PasetoSymmetricKey::<V4, Local>::from(Key::<32>::from(*b"wubbalubbadubdubwubbalubbadubdub"));
+  ///   let token = PasetoBuilder::<V4, Local>::default()
+  ///     .set_claim(AudienceClaim::from("API.Example.com"))
+  ///     .build(&key)?;
+  ///
+  ///   let json = PasetoParser::<V4, Local>::default()
+  ///     .check_claim(AudienceClaim::from("api.example.com").case_insensitive(true))
+  ///     .parse(&token, &key)?;
+  ///   assert_eq!(json["aud"], "API.Example.com");
+  ///# }
+  ///# Ok::<(),anyhow::Error>(())
+  ///```
+  pub fn case_insensitive(mut self, case_insensitive: bool) -> Self {
+    self.1 = case_insensitive;
+    self
+  }
 }
 
 impl<'a> Default for AudienceClaim<'a> {
   fn default() -> Self {
-    Self(("aud", ""))
+    Self(("aud", Cow::Borrowed("")), false)
   }
 }
 
 //created using the From trait
 impl<'a> From<&'a str> for AudienceClaim<'a> {
   fn from(s: &'a str) -> Self {
-    Self(("aud", s))
+    Self(("aud", Cow::Borrowed(s)), false)
+  }
+}
+
+impl<'a> From<String> for AudienceClaim<'a> {
+  fn from(s: String) -> Self {
+    Self(("aud", Cow::Owned(s)), false)
+  }
+}
+
+//a URL is put in the claim in its serialized string form
+#[cfg(feature = "url")]
+impl<'a> From<&'a url::Url> for AudienceClaim<'a> {
+  fn from(url: &'a url::Url) -> Self {
+    Self::from(url.as_str())
+  }
+}
+
+#[cfg(feature = "url")]
+impl<'a> From<url::Url> for AudienceClaim<'a> {
+  fn from(url: url::Url) -> Self {
+    Self::from(String::from(url))
   }
 }
 
 //want to receive a reference as a tuple
-impl<'a> AsRef<(&'a str, &'a str)> for AudienceClaim<'a> {
-  fn as_ref(&self) -> &(&'a str, &'a str) {
+impl<'a> AsRef<(&'static str, Cow<'a, str>)> for AudienceClaim<'a> {
+  fn as_ref(&self) -> &(&'static str, Cow<'a, str>) {
     &self.0
   }
 }
@@ -38,7 +98,7 @@ impl<'a> serde::Serialize for AudienceClaim<'a> {
     S: serde::Serializer,
   {
     let mut map = serializer.serialize_map(Some(2))?;
-    map.serialize_key(&self.0 .0)?;
+    map.serialize_key(self.0 .0)?;
     map.serialize_value(&self.0 .1)?;
     //map.serialize_entry(self.0 .0, self.0 .1)?;
     map.end()
@@ -0,0 +1,182 @@
+use super::PasetoClaimError;
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// The JSON type a claim of a [ClaimSpec] is expected to hold
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ClaimType {
+  /// A JSON string, which the registered claims all are, dates included
+  String,
+  /// A JSON number without a fractional part
+  Integer,
+  /// Any JSON number
+  Number,
+  /// `true` or `false`
+  Boolean,
+  /// A JSON array
+  Array,
+  /// A JSON object
+  Object,
+}
+
+impl ClaimType {
+  fn matches(self, value: &Value) -> bool {
+    match self {
+      Self::String => value.is_string(),
+      Self::Integer => value.is_i64() || value.is_u64(),
+      Self::Number => value.is_number(),
+      Self::Boolean => value.is_boolean(),
+      Self::Array => value.is_array(),
+      Self::Object => value.is_object(),
+    }
+  }
+}
+
+impl fmt::Display for ClaimType {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.write_str(match self {
+      Self::String => "a string",
+      Self::Integer => "an integer",
+      Self::Number => "a number",
+      Self::Boolean => "a boolean",
+      Self::Array => "an array",
+      Self::Object => "an object",
+    })
+  }
+}
+
+/// The JSON types expected of known claim keys, checked against a whole payload
+///
+/// Only the claims present in the payload are checked, a claim of the spec which is absent is not an error, and
+/// claims the spec doesn't name may hold anything.  Set on a builder with
+/// [set_claim_spec](crate::generic::GenericBuilder::set_claim_spec) it catches claims of the wrong type which got in
+/// through a dynamic path, such as a raw payload, before the token is issued.
+///
+/// # Example
+/// ```
+/// # #[cfg(feature = "generic")]
+/// # {
+/// use rusty_paseto::generic::*;
+/// use serde_json::json;
+///
+/// let spec = ClaimSpec::registered().expect("seats", ClaimType::Integer);
+///
+/// assert!(spec.check(&json!({"sub": "loyal subjects", "seats": 4})).is_ok());
+/// assert!(matches!(
+///   spec.check(&json!({"exp": true})),
+///   Err(PasetoClaimError::WrongType(claim, ClaimType::String, "a boolean")) if claim == "exp"
+/// ));
+/// # }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ClaimSpec {
+  types: BTreeMap<String, ClaimType>,
+}
+
+impl ClaimSpec {
+  /// Creates a spec expecting nothing of any claim
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Creates a spec expecting a string of each of the registered PASETO claims, `iss`, `sub`, `aud`, `exp`, `nbf`,
+  /// `iat` and `jti`
+  pub fn registered() -> Self {
+    ["iss", "sub", "aud", "exp", "nbf", "iat", "jti"]
+      .into_iter()
+      .fold(Self::new(), |spec, key| spec.expect(key, ClaimType::String))
+  }
+
+  /// Expects the claim of the given key to hold the given type, replacing any type expected of it before
+  pub fn expect(mut self, key: impl Into<String>, claim_type: ClaimType) -> Self {
+    self.types.insert(key.into(), claim_type);
+    self
+  }
+
+  /// Checks the type of every claim of the payload the spec names
+  ///
+  /// # Errors
+  ///
+  /// Returns [WrongType](PasetoClaimError::WrongType) for the first claim, by key, holding another type than
+  /// expected.  A payload which isn't an object has no claims and always passes.
+  pub fn check(&self, claims: &Value) -> Result<(), PasetoClaimError> {
+    let claims = match claims.as_object() {
+      Some(claims) => claims,
+      None => return Ok(()),
+    };
+    for (key, claim_type) in &self.types {
+      match claims.get(key) {
+        Some(value) if !claim_type.matches(value) => {
+          return Err(PasetoClaimError::WrongType(key.clone(), *claim_type, json_type(value)))
+        }
+        _ => {}
+      }
+    }
+    Ok(())
+  }
+}
+
+fn json_type(value: &Value) -> &'static str {
+  match value {
+    Value::Null => "null",
+    Value::Bool(_) => "a boolean",
+    Value::Number(number) if number.is_f64() => "a number",
+    Value::Number(_) => "an integer",
+    Value::String(_) => "a string",
+    Value::Array(_) => "an array",
+    Value::Object(_) => "an object",
+  }
+}
+
+#[cfg(test)]
+mod unit_tests {
+  use super::*;
+  use serde_json::json;
+
+  #[test]
+  fn test_claim_spec() {
+    let spec = ClaimSpec::registered()
+      .expect("seats", ClaimType::Integer)
+      .expect("ratio", ClaimType::Number)
+      .expect("admin", ClaimType::Boolean)
+      .expect("roles", ClaimType::Array)
+      .expect("profile", ClaimType::Object);
+    assert!(spec
+      .check(&json!({
+        "exp": "2030-01-01T00:00:00Z",
+        "seats": 4,
+        "ratio": 4,
+        "admin": false,
+        "roles": ["reader"],
+        "profile": {},
+        "other": null
+      }))
+      .is_ok());
+    //absent claims and payloads without claims aren't checked
+    assert!(spec.check(&json!({})).is_ok());
+    assert!(spec.check(&json!([1, 2])).is_ok());
+
+    for (claims, key, expected, found) in [
+      (json!({"exp": true}), "exp", ClaimType::String, "a boolean"),
+      (json!({"iat": 1_893_456_000}), "iat", ClaimType::String, "an integer"),
+      (json!({"seats": 4.5}), "seats", ClaimType::Integer, "a number"),
+      (json!({"ratio": "1/2"}), "ratio", ClaimType::Number, "a string"),
+      (json!({"roles": null}), "roles", ClaimType::Array, "null"),
+    ] {
+      assert!(
+        matches!(
+          spec.check(&claims),
+          Err(PasetoClaimError::WrongType(claim, claim_type, type_found))
+            if claim == key && claim_type == expected && type_found == found
+        ),
+        "{}",
+        claims
+      );
+    }
+    assert_eq!(
+      spec.check(&json!({"exp": true})).map_err(|error| error.to_string()),
+      Err(String::from("The claim 'exp' holds a boolean rather than a string"))
+    );
+  }
+}
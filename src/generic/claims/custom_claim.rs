@@ -1,12 +1,13 @@
 use super::{PasetoClaim, PasetoClaimError};
 #[cfg(feature = "serde")]
 use serde::ser::SerializeMap;
+use std::borrow::Cow;
 
 ///A custom PASETO claim which can be created with a key and a value T
 /// ## Setting your own Custom Claims
 ///
 /// The CustomClaim struct takes a tuple in the form of `(key: String, value: T)` where T is any
-/// serializable type
+/// serializable type.  A `&str` key is borrowed rather than copied, for the lifetime of the builder it is set on.
 /// #### Note: *CustomClaims use the TryFrom trait and return a Result<(), PasetoClaimError> if you attempt to use one of the [reserved PASETO keys](https://github.com/paseto-standard/paseto-spec/blob/master/docs/02-Implementation-Guide/04-Claims.md) in your CustomClaim*
 ///
 /// ```rust
@@ -155,11 +156,10 @@ use serde::ser::SerializeMap;
 /// # }
 /// # Ok::<(),anyhow::Error>(())
 /// ```
-
 #[derive(Clone, Debug)]
-pub struct CustomClaim<T>((String, T));
+pub struct CustomClaim<'a, T>((Cow<'a, str>, T));
 
-impl<T> CustomClaim<T> {
+impl<'a, T> CustomClaim<'a, T> {
   //TODO: this needs to be refactored to be configurable for eventual compressed token
   //implementations
   pub(self) const RESERVED_CLAIMS: [&'static str; 7] = ["iss", "sub", "aud", "exp", "nbf", "iat", "jti"];
@@ -173,48 +173,48 @@ impl<T> CustomClaim<T> {
 }
 
 #[cfg(feature = "serde")]
-impl<T: serde::Serialize> PasetoClaim for CustomClaim<T> {
+impl<'a, T: serde::Serialize> PasetoClaim for CustomClaim<'a, T> {
   fn get_key(&self) -> &str {
     &self.0 .0
   }
 }
 
-impl TryFrom<&str> for CustomClaim<&str> {
+impl<'a> TryFrom<&'a str> for CustomClaim<'a, &'a str> {
   type Error = PasetoClaimError;
 
-  fn try_from(key: &str) -> Result<Self, Self::Error> {
+  fn try_from(key: &'a str) -> Result<Self, Self::Error> {
     Self::check_if_reserved_claim_key(key)?;
-    Ok(Self((String::from(key), "")))
+    Ok(Self((Cow::Borrowed(key), "")))
   }
 }
 
-impl<T> TryFrom<(String, T)> for CustomClaim<T> {
+impl<'a, T> TryFrom<(String, T)> for CustomClaim<'a, T> {
   type Error = PasetoClaimError;
 
   fn try_from(val: (String, T)) -> Result<Self, Self::Error> {
     Self::check_if_reserved_claim_key(val.0.as_str())?;
-    Ok(Self((val.0, val.1)))
+    Ok(Self((Cow::Owned(val.0), val.1)))
   }
 }
 
-impl<T> TryFrom<(&str, T)> for CustomClaim<T> {
+impl<'a, T> TryFrom<(&'a str, T)> for CustomClaim<'a, T> {
   type Error = PasetoClaimError;
 
-  fn try_from(val: (&str, T)) -> Result<Self, Self::Error> {
+  fn try_from(val: (&'a str, T)) -> Result<Self, Self::Error> {
     Self::check_if_reserved_claim_key(val.0)?;
-    Ok(Self((String::from(val.0), val.1)))
+    Ok(Self((Cow::Borrowed(val.0), val.1)))
   }
 }
 
 //we want to receive a reference as a tuple
-impl<T> AsRef<(String, T)> for CustomClaim<T> {
-  fn as_ref(&self) -> &(String, T) {
+impl<'a, T> AsRef<(Cow<'a, str>, T)> for CustomClaim<'a, T> {
+  fn as_ref(&self) -> &(Cow<'a, str>, T) {
     &self.0
   }
 }
 
 #[cfg(feature = "serde")]
-impl<T: serde::Serialize> serde::Serialize for CustomClaim<T> {
+impl<'a, T: serde::Serialize> serde::Serialize for CustomClaim<'a, T> {
   fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
   where
     S: serde::Serializer,
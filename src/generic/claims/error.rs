@@ -1,3 +1,4 @@
+use serde::{Serialize, Serializer};
 use thiserror::Error;
 
 /// Errors from validating claims in a parsed token
@@ -21,6 +22,10 @@ pub enum PasetoClaimError {
   /// Occurs when a custom claim fails validation
   #[error("The claim '{0}' failed custom validation")]
   CustomValidation(String),
+  /// Occurs when a validator spanning several claims rejects the payload, carrying the
+  /// validator's own message
+  #[error("{0}")]
+  CrossClaimValidation(String),
   /// Occurs when a claim fails validation
   #[error("The claim '{0}' failed validation.  Expected '{1}' but received '{2}'")]
   Invalid(String, String, String),
@@ -30,4 +35,149 @@ pub enum PasetoClaimError {
   /// Occurs when a user attempts to use a top level claim more than once in the payload
   #[error("The claim '{0}' appears more than once in the top level payload json")]
   DuplicateTopLevelPayloadClaim(String),
+  /// Occurs when a claim holds another JSON type than its [ClaimSpec](super::ClaimSpec) expects, naming the claim,
+  /// the expected type and the type found
+  #[error("The claim '{0}' holds {2} rather than {1}")]
+  WrongType(String, super::ClaimType, &'static str),
+}
+
+impl PasetoClaimError {
+  //the longest offending value echoed back in an error, so huge or sensitive input doesn't end up in logs wholesale
+  const MAX_ECHOED_VALUE_CHARS: usize = 64;
+
+  /// Creates an [RFC3339Date](Self::RFC3339Date) error for the offending value, truncated if it is too long to be
+  /// logged in full
+  pub(crate) fn rfc3339_date(value: &str) -> Self {
+    Self::RFC3339Date(Self::echoed_value(value))
+  }
+
+  /// A serializable view of this error which leaves out the values carried by it, such as the expected and
+  /// received claim values, keeping only the reason and the name of the claim
+  ///
+  /// The [Serialize] implementation of the error itself includes those values, which may hold data from the token
+  /// or from the parser configuration that shouldn't be returned to the caller of an API
+  ///
+  /// # Example
+  /// ```
+  /// # #[cfg(feature = "generic")]
+  /// # {
+  /// use rusty_paseto::generic::PasetoClaimError;
+  ///
+  /// let error = PasetoClaimError::Invalid("aud".into(), "internal-audience".into(), "other".into());
+  ///
+  /// assert_eq!(
+  ///   serde_json::to_string(&error)?,
+  ///   r#"{"reason":"invalid","claim":"aud","expected":"internal-audience","actual":"other","message":"The claim 'aud' failed validation.  Expected 'internal-audience' but received 'other'"}"#
+  /// );
+  /// assert_eq!(
+  ///   serde_json::to_string(&error.redacted())?,
+  ///   r#"{"reason":"invalid","claim":"aud"}"#
+  /// );
+  /// # }
+  /// # Ok::<(),anyhow::Error>(())
+  /// ```
+  pub fn redacted(&self) -> impl Serialize + '_ {
+    self.report().redacted()
+  }
+
+  pub(crate) fn report(&self) -> ErrorReport<'_> {
+    let report = match self {
+      Self::Expired => ErrorReport::new("expired").claim("exp"),
+      Self::UseBeforeAvailable(not_before) => ErrorReport::new("use_before_available")
+        .claim("nbf")
+        .expected(not_before),
+      Self::RFC3339Date(value) => ErrorReport::new("malformed_date").actual(value),
+      Self::Missing(claim) => ErrorReport::new("missing").claim(claim),
+      Self::Unexpected(claim) => ErrorReport::new("unexpected_type").claim(claim),
+      Self::CustomValidation(claim) => ErrorReport::new("custom_validation").claim(claim),
+      Self::CrossClaimValidation(_) => ErrorReport::new("cross_claim_validation"),
+      Self::Invalid(claim, expected, actual) => ErrorReport::new("invalid")
+        .claim(claim)
+        .expected(expected)
+        .actual(actual),
+      Self::Reserved(claim) => ErrorReport::new("reserved").claim(claim),
+      Self::DuplicateTopLevelPayloadClaim(claim) => ErrorReport::new("duplicate_claim").claim(claim),
+      Self::WrongType(claim, expected, found) => ErrorReport::new("wrong_type")
+        .claim(claim)
+        .expected(expected)
+        .actual(found),
+    };
+    report.message(self.to_string())
+  }
+
+  fn echoed_value(value: &str) -> String {
+    match value.char_indices().nth(Self::MAX_ECHOED_VALUE_CHARS) {
+      Some((end, _)) => format!("{}... ({} bytes total)", &value[..end], value.len()),
+      None => value.to_string(),
+    }
+  }
+}
+
+impl Serialize for PasetoClaimError {
+  fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    self.report().serialize(serializer)
+  }
+}
+
+/// The machine readable form of a failed check, shared by the serializable error types
+///
+/// `reason` is a stable snake case code and `claim` names the claim at fault, both of which are always kept.  The
+/// other fields carry values and are dropped from the redacted form.
+#[derive(Debug, Serialize)]
+pub(crate) struct ErrorReport<'a> {
+  reason: &'static str,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  claim: Option<&'a str>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  expected: Option<String>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  actual: Option<String>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  message: Option<String>,
+}
+
+impl<'a> ErrorReport<'a> {
+  pub(crate) fn new(reason: &'static str) -> Self {
+    Self {
+      reason,
+      claim: None,
+      expected: None,
+      actual: None,
+      message: None,
+    }
+  }
+
+  #[cfg(feature = "metrics")]
+  pub(crate) fn reason(&self) -> &'static str {
+    self.reason
+  }
+
+  pub(crate) fn claim(mut self, claim: &'a str) -> Self {
+    self.claim = Some(claim);
+    self
+  }
+
+  pub(crate) fn expected(mut self, expected: impl ToString) -> Self {
+    self.expected = Some(expected.to_string());
+    self
+  }
+
+  pub(crate) fn actual(mut self, actual: impl ToString) -> Self {
+    self.actual = Some(actual.to_string());
+    self
+  }
+
+  pub(crate) fn message(mut self, message: String) -> Self {
+    self.message = Some(message);
+    self
+  }
+
+  pub(crate) fn redacted(self) -> Self {
+    Self {
+      expected: None,
+      actual: None,
+      message: None,
+      ..self
+    }
+  }
 }
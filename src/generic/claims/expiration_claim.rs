@@ -1,59 +1,63 @@
 use super::{PasetoClaim, PasetoClaimError};
 #[cfg(feature = "serde")]
 use serde::ser::SerializeMap;
+use std::borrow::Cow;
 
 ///The reserved ['exp'](https://github.com/paseto-standard/paseto-spec/blob/master/docs/02-Implementation-Guide/04-Claims.md) PASETO claim
+///
+///The date is borrowed when the claim is created from a `&str`, so formatting it once and setting it on many
+///builders costs no further allocation.
 #[derive(Clone)]
-pub struct ExpirationClaim((String, String));
-impl PasetoClaim for ExpirationClaim {
+pub struct ExpirationClaim<'a>((&'static str, Cow<'a, str>));
+impl<'a> PasetoClaim for ExpirationClaim<'a> {
   fn get_key(&self) -> &str {
-    self.0 .0.as_str()
+    self.0 .0
   }
 }
 
-impl Default for ExpirationClaim {
+impl<'a> Default for ExpirationClaim<'a> {
   fn default() -> Self {
-    Self(("exp".to_string(), "2019-01-01T00:00:00+00:00".to_string()))
+    Self(("exp", Cow::Borrowed("2019-01-01T00:00:00+00:00")))
   }
 }
 
-impl TryFrom<String> for ExpirationClaim {
+impl<'a> TryFrom<String> for ExpirationClaim<'a> {
   type Error = PasetoClaimError;
 
   fn try_from(value: String) -> Result<Self, Self::Error> {
     match iso8601::datetime(&value) {
-      Ok(_) => Ok(Self(("exp".to_string(), value))),
-      Err(_) => Err(PasetoClaimError::RFC3339Date(value.to_string())),
+      Ok(_) => Ok(Self(("exp", Cow::Owned(value)))),
+      Err(_) => Err(PasetoClaimError::rfc3339_date(&value)),
     }
   }
 }
 
-impl TryFrom<&str> for ExpirationClaim {
+impl<'a> TryFrom<&'a str> for ExpirationClaim<'a> {
   type Error = PasetoClaimError;
 
-  fn try_from(value: &str) -> Result<Self, Self::Error> {
+  fn try_from(value: &'a str) -> Result<Self, Self::Error> {
     match iso8601::datetime(value) {
-      Ok(_) => Ok(Self(("exp".to_string(), value.to_string()))),
-      Err(_) => Err(PasetoClaimError::RFC3339Date(value.to_string())),
+      Ok(_) => Ok(Self(("exp", Cow::Borrowed(value)))),
+      Err(_) => Err(PasetoClaimError::rfc3339_date(value)),
     }
   }
 }
 
 //want to receive a reference as a tuple
-impl AsRef<(String, String)> for ExpirationClaim {
-  fn as_ref(&self) -> &(String, String) {
+impl<'a> AsRef<(&'static str, Cow<'a, str>)> for ExpirationClaim<'a> {
+  fn as_ref(&self) -> &(&'static str, Cow<'a, str>) {
     &self.0
   }
 }
 
 #[cfg(feature = "serde")]
-impl serde::Serialize for ExpirationClaim {
+impl<'a> serde::Serialize for ExpirationClaim<'a> {
   fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
   where
     S: serde::Serializer,
   {
     let mut map = serializer.serialize_map(Some(2))?;
-    map.serialize_key(&self.0 .0)?;
+    map.serialize_key(self.0 .0)?;
     map.serialize_value(&self.0 .1)?;
     map.end()
   }
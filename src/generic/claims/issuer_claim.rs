@@ -1,10 +1,13 @@
 use super::PasetoClaim;
 #[cfg(feature = "serde")]
 use serde::ser::SerializeMap;
+use std::borrow::Cow;
 
 ///The reserved ['iss'](https://github.com/paseto-standard/paseto-spec/blob/master/docs/02-Implementation-Guide/04-Claims.md) PASETO claim
+///
+///With the `url` feature the claim is also created from a `url::Url`, borrowed or owned, holding its string form.
 #[derive(Clone)]
-pub struct IssuerClaim<'a>((&'a str, &'a str));
+pub struct IssuerClaim<'a>((&'static str, Cow<'a, str>));
 
 impl<'a> PasetoClaim for IssuerClaim<'a> {
   fn get_key(&self) -> &str {
@@ -14,20 +17,41 @@ impl<'a> PasetoClaim for IssuerClaim<'a> {
 
 impl<'a> Default for IssuerClaim<'a> {
   fn default() -> Self {
-    Self(("iss", ""))
+    Self(("iss", Cow::Borrowed("")))
   }
 }
 
 //created using the From trait
 impl<'a> From<&'a str> for IssuerClaim<'a> {
   fn from(s: &'a str) -> Self {
-    Self(("iss", s))
+    Self(("iss", Cow::Borrowed(s)))
+  }
+}
+
+impl<'a> From<String> for IssuerClaim<'a> {
+  fn from(s: String) -> Self {
+    Self(("iss", Cow::Owned(s)))
+  }
+}
+
+//a URL is put in the claim in its serialized string form
+#[cfg(feature = "url")]
+impl<'a> From<&'a url::Url> for IssuerClaim<'a> {
+  fn from(url: &'a url::Url) -> Self {
+    Self::from(url.as_str())
+  }
+}
+
+#[cfg(feature = "url")]
+impl<'a> From<url::Url> for IssuerClaim<'a> {
+  fn from(url: url::Url) -> Self {
+    Self::from(String::from(url))
   }
 }
 
 //want to receive a reference as a tuple
-impl<'a> AsRef<(&'a str, &'a str)> for IssuerClaim<'a> {
-  fn as_ref(&self) -> &(&'a str, &'a str) {
+impl<'a> AsRef<(&'static str, Cow<'a, str>)> for IssuerClaim<'a> {
+  fn as_ref(&self) -> &(&'static str, Cow<'a, str>) {
     &self.0
   }
 }
@@ -39,7 +63,7 @@ impl<'a> serde::Serialize for IssuerClaim<'a> {
     S: serde::Serializer,
   {
     let mut map = serializer.serialize_map(Some(2))?;
-    map.serialize_entry(self.0 .0, self.0 .1)?;
+    map.serialize_entry(self.0 .0, &self.0 .1)?;
     map.end()
   }
 }
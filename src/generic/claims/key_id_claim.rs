@@ -0,0 +1,56 @@
+use super::PasetoClaim;
+#[cfg(feature = "serde")]
+use serde::ser::SerializeMap;
+
+///A `kid` (key identifier) claim carried in the payload of the token
+///
+///The PASETO specification places the key identifier in the [footer](crate::core::Footer), which is
+///what most consumers expect. Some ecosystems read it from the payload instead, and this claim
+///supports that convention.
+///
+///#### Security difference
+///The footer is never encrypted, so a footer `kid` is readable by anyone holding the token, while a
+///payload `kid` is encrypted along with the other claims for [Local](crate::core::Local) tokens (it is
+///readable, but signed, for [Public](crate::core::Public) tokens). The flip side is that a payload
+///`kid` is only available *after* decryption, so it cannot be used to select the key to decrypt
+///with; it is useful for consumers that confirm which key issued the token once it has been opened.
+#[derive(Clone)]
+pub struct KeyIdClaim<'a>((&'a str, &'a str));
+impl<'a> PasetoClaim for KeyIdClaim<'a> {
+  fn get_key(&self) -> &str {
+    self.0 .0
+  }
+}
+
+impl<'a> Default for KeyIdClaim<'a> {
+  fn default() -> Self {
+    Self(("kid", ""))
+  }
+}
+
+//created using the From trait
+impl<'a> From<&'a str> for KeyIdClaim<'a> {
+  fn from(s: &'a str) -> Self {
+    Self(("kid", s))
+  }
+}
+
+//want to receive a reference as a tuple
+impl<'a> AsRef<(&'a str, &'a str)> for KeyIdClaim<'a> {
+  fn as_ref(&self) -> &(&'a str, &'a str) {
+    &self.0
+  }
+}
+
+#[cfg(feature = "serde")]
+impl<'a> serde::Serialize for KeyIdClaim<'a> {
+  fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+  where
+    S: serde::Serializer,
+  {
+    let mut map = serializer.serialize_map(Some(2))?;
+    map.serialize_key(&self.0 .0)?;
+    map.serialize_value(&self.0 .1)?;
+    map.end()
+  }
+}
@@ -2,30 +2,40 @@ use serde_json::Value;
 use std::collections::HashMap;
 
 mod audience_claim;
+mod claim_spec;
 mod custom_claim;
 mod error;
 mod expiration_claim;
 mod issued_at_claim;
 mod issuer_claim;
+mod key_id_claim;
 mod not_before_claim;
 mod subject_claim;
 mod token_identifier_claim;
+mod token_type_claim;
 mod traits;
 
 pub use audience_claim::AudienceClaim;
+pub use claim_spec::{ClaimSpec, ClaimType};
 pub use custom_claim::CustomClaim;
+pub(crate) use error::ErrorReport;
 pub use error::PasetoClaimError;
 pub use expiration_claim::ExpirationClaim;
 pub use issued_at_claim::IssuedAtClaim;
 pub use issuer_claim::IssuerClaim;
+pub use key_id_claim::KeyIdClaim;
 pub use not_before_claim::NotBeforeClaim;
 pub use subject_claim::SubjectClaim;
 pub use token_identifier_claim::TokenIdentifierClaim;
+pub use token_type_claim::TokenType;
 pub use traits::PasetoClaim;
 ///A type for creating generic claim validation functions
 pub type ValidatorFn = dyn Fn(&str, &Value) -> Result<(), PasetoClaimError>;
 ///A type for tracking claims in a token
 pub type ValidatorMap = HashMap<String, Box<ValidatorFn>>;
+///A type for validation functions which receive the full payload of a token to enforce relationships
+///between several claims
+pub type CrossClaimValidatorFn = dyn Fn(&Value) -> Result<(), String>;
 
 #[cfg(test)]
 mod unit_tests {
@@ -52,6 +62,31 @@ mod unit_tests {
     Ok(())
   }
 
+  #[test]
+  fn test_malformed_date_error_value() {
+    let error = ExpirationClaim::try_from("not-a-date").err().map(|e| e.to_string());
+    assert_eq!(
+      error.as_deref(),
+      Some("The value not-a-date is a malformed RFC3339 date")
+    );
+
+    //long values are capped rather than echoed in full
+    let long_value = "9".repeat(10_000);
+    let error = NotBeforeClaim::try_from(long_value.as_str())
+      .err()
+      .map(|e| e.to_string())
+      .unwrap_or_default();
+    assert!(error.contains(&format!("{}... (10000 bytes total)", "9".repeat(64))));
+    assert!(error.len() < 200);
+
+    //and truncated on a character boundary
+    let error = IssuedAtClaim::try_from("é".repeat(100))
+      .err()
+      .map(|e| e.to_string())
+      .unwrap_or_default();
+    assert!(error.contains(&format!("{}... (200 bytes total)", "é".repeat(64))));
+  }
+
   #[test]
   fn test_not_before_claim() -> Result<()> {
     // setup
@@ -93,6 +128,46 @@ mod unit_tests {
     assert_eq!("jti", claim.get_key());
   }
 
+  #[test]
+  fn test_key_id_claim() {
+    // setup
+    let borrowed_str = String::from("2024-06");
+    let claim = KeyIdClaim::from(borrowed_str.as_str());
+
+    //verify
+    assert_eq!("kid", claim.get_key());
+  }
+
+  #[test]
+  fn test_token_type_claim() -> Result<()> {
+    assert_eq!("typ", TokenType::Access.get_key());
+    assert_eq!(serde_json::to_string(&TokenType::Access)?, r#"{"typ":"access"}"#);
+    assert_eq!(serde_json::to_string(&TokenType::Refresh)?, r#"{"typ":"refresh"}"#);
+    assert_eq!(serde_json::to_string(&TokenType::from("id"))?, r#"{"typ":"id"}"#);
+    assert_eq!(TokenType::from("refresh"), TokenType::Refresh);
+    Ok(())
+  }
+
+  #[test]
+  #[cfg(feature = "url")]
+  fn test_url_claims() -> Result<()> {
+    let url = url::Url::parse("https://Issuer.Example.com/tenants/acme")?;
+    //serialized in the normalized form of the URL
+    assert_eq!(
+      serde_json::to_string(&IssuerClaim::from(&url))?,
+      r#"{"iss":"https://issuer.example.com/tenants/acme"}"#
+    );
+    assert_eq!(
+      serde_json::to_string(&AudienceClaim::from(&url))?,
+      r#"{"aud":"https://issuer.example.com/tenants/acme"}"#
+    );
+    assert_eq!(
+      serde_json::to_string(&SubjectClaim::from(url))?,
+      r#"{"sub":"https://issuer.example.com/tenants/acme"}"#
+    );
+    Ok(())
+  }
+
   #[test]
   fn test_audience_claim() {
     // setup
@@ -131,7 +206,8 @@ mod unit_tests {
     //verify
 
     assert_eq!(claim.get_key(), "universe");
-    let (_, v) = claim.as_ref();
+    let (k, v) = claim.as_ref();
+    assert!(matches!(k, std::borrow::Cow::Borrowed("universe")));
     assert_eq!(v, &137);
     Ok(())
   }
@@ -1,59 +1,63 @@
 use super::{PasetoClaim, PasetoClaimError};
 #[cfg(feature = "serde")]
 use serde::ser::SerializeMap;
+use std::borrow::Cow;
 
 ///The reserved ['nbf'](https://github.com/paseto-standard/paseto-spec/blob/master/docs/02-Implementation-Guide/04-Claims.md) PASETO claim
+///
+///The date is borrowed when the claim is created from a `&str`, so formatting it once and setting it on many
+///builders costs no further allocation.
 #[derive(Clone)]
-pub struct NotBeforeClaim((String, String));
-impl PasetoClaim for NotBeforeClaim {
+pub struct NotBeforeClaim<'a>((&'static str, Cow<'a, str>));
+impl<'a> PasetoClaim for NotBeforeClaim<'a> {
   fn get_key(&self) -> &str {
-    &self.0 .0
+    self.0 .0
   }
 }
 
-impl Default for NotBeforeClaim {
+impl<'a> Default for NotBeforeClaim<'a> {
   fn default() -> Self {
-    Self(("nbf".to_string(), "2019-01-01T00:00:00+00:00".to_string()))
+    Self(("nbf", Cow::Borrowed("2019-01-01T00:00:00+00:00")))
   }
 }
 
-impl TryFrom<String> for NotBeforeClaim {
+impl<'a> TryFrom<String> for NotBeforeClaim<'a> {
   type Error = PasetoClaimError;
 
   fn try_from(value: String) -> Result<Self, Self::Error> {
     match iso8601::datetime(&value) {
-      Ok(_) => Ok(Self(("nbf".to_string(), value))),
-      Err(_) => Err(PasetoClaimError::RFC3339Date(value.to_string())),
+      Ok(_) => Ok(Self(("nbf", Cow::Owned(value)))),
+      Err(_) => Err(PasetoClaimError::rfc3339_date(&value)),
     }
   }
 }
 
-impl TryFrom<&str> for NotBeforeClaim {
+impl<'a> TryFrom<&'a str> for NotBeforeClaim<'a> {
   type Error = PasetoClaimError;
 
-  fn try_from(value: &str) -> Result<Self, Self::Error> {
+  fn try_from(value: &'a str) -> Result<Self, Self::Error> {
     match iso8601::datetime(value) {
-      Ok(_) => Ok(Self(("nbf".to_string(), value.to_string()))),
-      Err(_) => Err(PasetoClaimError::RFC3339Date(value.to_string())),
+      Ok(_) => Ok(Self(("nbf", Cow::Borrowed(value)))),
+      Err(_) => Err(PasetoClaimError::rfc3339_date(value)),
     }
   }
 }
 
 //want to receive a reference as a tuple
-impl AsRef<(String, String)> for NotBeforeClaim {
-  fn as_ref(&self) -> &(String, String) {
+impl<'a> AsRef<(&'static str, Cow<'a, str>)> for NotBeforeClaim<'a> {
+  fn as_ref(&self) -> &(&'static str, Cow<'a, str>) {
     &self.0
   }
 }
 
 #[cfg(feature = "serde")]
-impl serde::Serialize for NotBeforeClaim {
+impl<'a> serde::Serialize for NotBeforeClaim<'a> {
   fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
   where
     S: serde::Serializer,
   {
     let mut map = serializer.serialize_map(Some(2))?;
-    map.serialize_key(&self.0 .0)?;
+    map.serialize_key(self.0 .0)?;
     map.serialize_value(&self.0 .1)?;
     map.end()
   }
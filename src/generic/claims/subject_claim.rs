@@ -1,10 +1,13 @@
 use super::PasetoClaim;
 #[cfg(feature = "serde")]
 use serde::ser::SerializeMap;
+use std::borrow::Cow;
 
 ///The reserved ['sub'](https://github.com/paseto-standard/paseto-spec/blob/master/docs/02-Implementation-Guide/04-Claims.md) PASETO claim
+///
+///With the `url` feature the claim is also created from a `url::Url`, borrowed or owned, holding its string form.
 #[derive(Clone)]
-pub struct SubjectClaim<'a>((&'a str, &'a str));
+pub struct SubjectClaim<'a>((&'static str, Cow<'a, str>));
 
 impl<'a> PasetoClaim for SubjectClaim<'a> {
   fn get_key(&self) -> &str {
@@ -14,20 +17,41 @@ impl<'a> PasetoClaim for SubjectClaim<'a> {
 
 impl<'a> Default for SubjectClaim<'a> {
   fn default() -> Self {
-    Self(("sub", ""))
+    Self(("sub", Cow::Borrowed("")))
   }
 }
 
 //created using the From trait
 impl<'a> From<&'a str> for SubjectClaim<'a> {
   fn from(s: &'a str) -> Self {
-    Self(("sub", s))
+    Self(("sub", Cow::Borrowed(s)))
+  }
+}
+
+impl<'a> From<String> for SubjectClaim<'a> {
+  fn from(s: String) -> Self {
+    Self(("sub", Cow::Owned(s)))
+  }
+}
+
+//a URL is put in the claim in its serialized string form
+#[cfg(feature = "url")]
+impl<'a> From<&'a url::Url> for SubjectClaim<'a> {
+  fn from(url: &'a url::Url) -> Self {
+    Self::from(url.as_str())
+  }
+}
+
+#[cfg(feature = "url")]
+impl<'a> From<url::Url> for SubjectClaim<'a> {
+  fn from(url: url::Url) -> Self {
+    Self::from(String::from(url))
   }
 }
 
 //want to receive a reference as a tuple
-impl<'a> AsRef<(&'a str, &'a str)> for SubjectClaim<'a> {
-  fn as_ref(&self) -> &(&'a str, &'a str) {
+impl<'a> AsRef<(&'static str, Cow<'a, str>)> for SubjectClaim<'a> {
+  fn as_ref(&self) -> &(&'static str, Cow<'a, str>) {
     &self.0
   }
 }
@@ -39,7 +63,7 @@ impl<'a> serde::Serialize for SubjectClaim<'a> {
     S: serde::Serializer,
   {
     let mut map = serializer.serialize_map(Some(2))?;
-    map.serialize_entry(self.0 .0, self.0 .1)?;
+    map.serialize_entry(self.0 .0, &self.0 .1)?;
     map.end()
   }
 }
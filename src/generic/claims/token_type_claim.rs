@@ -0,0 +1,85 @@
+use super::PasetoClaim;
+#[cfg(feature = "serde")]
+use serde::ser::SerializeMap;
+use std::fmt;
+
+///A `typ` claim telling which kind of token this is, so a verifier can reject a token presented where a token of
+///another kind is expected, such as a refresh token sent to a resource server
+///
+///The well known kinds serialize to the short strings `"access"` and `"refresh"`, anything else is carried as a
+///[Custom](Self::Custom) string.
+///
+///# Usage
+///```
+///# #[cfg(feature = "default")]
+///# {
+///   use rusty_paseto::prelude::*;
+///
+///     # let key = PasetoSymmetricKey::<V4, Local>::from(Key::<32>::from(*b"wubbalubbadubdubwubbalubbadubdub"));
+///     let token = PasetoBuilder::<V4, Local>::default()
+///       .set_claim(TokenType::Refresh)
+///       .build(&key)?;
+///
+///     let result = PasetoParser::<V4, Local>::default()
+///       .expect_type(TokenType::Access)
+///       .parse(&token, &key);
+///     assert!(result.is_err());
+///# }
+///# Ok::<(),anyhow::Error>(())
+///```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TokenType {
+  /// A short lived token presented to resource servers, `"access"`
+  Access,
+  /// A long lived token exchanged for new tokens, `"refresh"`
+  Refresh,
+  /// Any other kind of token
+  Custom(String),
+}
+
+impl TokenType {
+  /// The string the token type is serialized as
+  pub fn as_str(&self) -> &str {
+    match self {
+      Self::Access => "access",
+      Self::Refresh => "refresh",
+      Self::Custom(token_type) => token_type,
+    }
+  }
+}
+
+impl PasetoClaim for TokenType {
+  fn get_key(&self) -> &str {
+    "typ"
+  }
+}
+
+//the well known kinds are recognized whichever way they are created
+impl From<&str> for TokenType {
+  fn from(s: &str) -> Self {
+    match s {
+      "access" => Self::Access,
+      "refresh" => Self::Refresh,
+      token_type => Self::Custom(token_type.to_string()),
+    }
+  }
+}
+
+impl fmt::Display for TokenType {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "{}", self.as_str())
+  }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for TokenType {
+  fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+  where
+    S: serde::Serializer,
+  {
+    let mut map = serializer.serialize_map(Some(1))?;
+    map.serialize_key(self.get_key())?;
+    map.serialize_value(self.as_str())?;
+    map.end()
+  }
+}
@@ -1,4 +1,10 @@
 /// a simple marker trait to identify claims
 pub trait PasetoClaim: erased_serde::Serialize {
   fn get_key(&self) -> &str;
+
+  /// Whether a parser checking the claim compares its string value without regard to ASCII case, exact unless the
+  /// claim opts in
+  fn is_case_insensitive(&self) -> bool {
+    false
+  }
 }
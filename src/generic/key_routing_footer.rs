@@ -0,0 +1,143 @@
+use crate::generic::*;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+/// The JSON footer of the [key-id pattern](https://github.com/paseto-standard/paseto-spec/blob/master/docs/01-Protocol-Versions/Common.md#optional-footer)
+/// used to route a token to the key which opens it, `{"kid":"...","wpk":"..."}`
+///
+/// `kid` identifies the key, typically by its PASERK id, and the optional `wpk` carries a wrapped key such as the
+/// output of [PasetoSymmetricKey::wrap].  Unknown fields are ignored when reading a footer back so tokens carrying
+/// additional footer data still route.
+///
+/// # Usage
+/// ```
+/// # #[cfg(feature = "default")]
+/// # {
+/// # use rusty_paseto::prelude::*;
+/// # let key = PasetoSymmetricKey::<V4, Local>::from(Key::<32>::from(b"wubbalubbadubdubwubbalubbadubdub"));
+/// let footer = KeyRoutingFooter::new("k4.lid.XxPub51WIAEmbVTmrs-lFoFodxTSKk8RuYEJk3gl-DYB").to_string();
+/// let token = PasetoBuilder::<V4, Local>::default()
+///   .set_footer(Footer::from(footer.as_str()))
+///   .build(&key)?;
+///
+/// //pick the key to parse with from the footer, before any cryptography
+/// let routing = KeyRoutingFooter::from_untrusted_token(&UntrustedToken::try_from(token.as_str())?)?;
+/// assert_eq!(
+///   routing.map(|routing| routing.kid),
+///   Some("k4.lid.XxPub51WIAEmbVTmrs-lFoFodxTSKk8RuYEJk3gl-DYB".to_string())
+/// );
+/// # }
+/// # Ok::<(),anyhow::Error>(())
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KeyRoutingFooter {
+  /// The id of the key which opens the token
+  pub kid: String,
+  /// A wrapped or public key reference, left out of the footer when absent
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub wpk: Option<String>,
+}
+
+impl KeyRoutingFooter {
+  /// Creates a footer routing to the key with the given id
+  pub fn new(kid: impl Into<String>) -> Self {
+    Self {
+      kid: kid.into(),
+      wpk: None,
+    }
+  }
+
+  /// Adds a wrapped or public key reference to the footer
+  pub fn with_wpk(mut self, wpk: impl Into<String>) -> Self {
+    self.wpk = Some(wpk.into());
+    self
+  }
+
+  /// Reads the routing footer of a token without decrypting or verifying it, `None` if the token has no footer
+  ///
+  /// # Errors
+  ///
+  /// Returns [`GenericParserError`] if the footer can't be decoded or isn't a key routing object
+  pub fn from_untrusted_token(token: &UntrustedToken<'_>) -> Result<Option<Self>, GenericParserError> {
+    token
+      .footer()?
+      .map(|footer| {
+        footer
+          .parse()
+          .map_err(|source| GenericParserError::MalformedFooter { source })
+      })
+      .transpose()
+  }
+}
+
+impl fmt::Display for KeyRoutingFooter {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "{}", serde_json::to_string(self).map_err(|_| fmt::Error)?)
+  }
+}
+
+impl FromStr for KeyRoutingFooter {
+  type Err = serde_json::Error;
+
+  fn from_str(footer: &str) -> Result<Self, Self::Err> {
+    serde_json::from_str(footer)
+  }
+}
+
+#[cfg(all(test, feature = "v4_local"))]
+mod unit_tests {
+  use super::*;
+  use anyhow::Result;
+
+  #[test]
+  fn test_key_routing_footer_json() -> Result<()> {
+    assert_eq!(
+      KeyRoutingFooter::new("k4.lid.abc").to_string(),
+      r#"{"kid":"k4.lid.abc"}"#
+    );
+    let footer = KeyRoutingFooter::new("k4.lid.abc").with_wpk("k4.local-wrap.pie.xyz");
+    assert_eq!(
+      footer.to_string(),
+      r#"{"kid":"k4.lid.abc","wpk":"k4.local-wrap.pie.xyz"}"#
+    );
+    assert_eq!(footer.to_string().parse::<KeyRoutingFooter>()?, footer);
+
+    //other footer data doesn't get in the way of routing
+    let footer = r#"{"kid":"k4.lid.abc","note":"rotated 2022-01-01"}"#.parse::<KeyRoutingFooter>()?;
+    assert_eq!(footer, KeyRoutingFooter::new("k4.lid.abc"));
+
+    assert!(r#"{"wpk":"k4.local-wrap.pie.xyz"}"#.parse::<KeyRoutingFooter>().is_err());
+    assert!("not json".parse::<KeyRoutingFooter>().is_err());
+    Ok(())
+  }
+
+  #[test]
+  fn test_key_routing_footer_from_token() -> Result<()> {
+    let key = PasetoSymmetricKey::<V4, Local>::from(Key::from(*b"wubbalubbadubdubwubbalubbadubdub"));
+    let routing = KeyRoutingFooter::new("k4.lid.abc").with_wpk("k4.local-wrap.pie.xyz");
+    let footer = routing.to_string();
+    let token = GenericBuilder::<V4, Local>::default()
+      .set_footer(Footer::from(footer.as_str()))
+      .try_encrypt(&key)?;
+    assert_eq!(
+      KeyRoutingFooter::from_untrusted_token(&UntrustedToken::try_from(token.as_str())?)?,
+      Some(routing)
+    );
+
+    let token = GenericBuilder::<V4, Local>::default().try_encrypt(&key)?;
+    assert_eq!(
+      KeyRoutingFooter::from_untrusted_token(&UntrustedToken::try_from(token.as_str())?)?,
+      None
+    );
+
+    let token = GenericBuilder::<V4, Local>::default()
+      .set_footer(Footer::from("plain text footer"))
+      .try_encrypt(&key)?;
+    assert!(matches!(
+      KeyRoutingFooter::from_untrusted_token(&UntrustedToken::try_from(token.as_str())?),
+      Err(GenericParserError::MalformedFooter { .. })
+    ));
+    Ok(())
+  }
+}
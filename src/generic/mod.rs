@@ -23,6 +23,7 @@
 //! Refer to the [PASETO specification](https://github.com/paseto-standard/paseto-spec/blob/master/docs/02-Implementation-Guide/04-Claims.md) to review reserved claims for use within PASETO.
 mod builders;
 mod claims;
+mod key_routing_footer;
 mod parsers;
 
 pub use crate::generic::claims::*;
@@ -32,3 +33,5 @@ pub use crate::core::*;
 pub use crate::generic::builders::*;
 
 pub use crate::generic::parsers::*;
+
+pub use crate::generic::key_routing_footer::KeyRoutingFooter;
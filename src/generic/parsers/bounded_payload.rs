@@ -0,0 +1,123 @@
+use serde::de::{self, DeserializeSeed, IgnoredAny, MapAccess, SeqAccess, Visitor};
+use serde::Deserialize;
+use serde_json::{Map, Value};
+use std::fmt;
+
+//a decrypted or verified payload, unless its top level object holds more claims than allowed
+pub(crate) enum BoundedPayload {
+  Parsed(Value),
+  TooManyClaims(usize),
+}
+
+//deserializes the payload while counting the claims of its top level object, skipping the values of every claim
+//past the limit rather than building them so a payload of thousands of claims costs a scan of its text and no more.
+//Payloads which aren't an object are deserialized as they are.
+pub(crate) fn parse_bounded(payload: &str, max_claims: usize) -> Result<BoundedPayload, serde_json::Error> {
+  let mut deserializer = serde_json::Deserializer::from_str(payload);
+  let parsed = BoundedClaims { max_claims }.deserialize(&mut deserializer)?;
+  deserializer.end()?;
+  Ok(parsed)
+}
+
+struct BoundedClaims {
+  max_claims: usize,
+}
+
+impl<'de> DeserializeSeed<'de> for BoundedClaims {
+  type Value = BoundedPayload;
+
+  fn deserialize<D: de::Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+    deserializer.deserialize_any(self)
+  }
+}
+
+impl<'de> Visitor<'de> for BoundedClaims {
+  type Value = BoundedPayload;
+
+  fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.write_str("a JSON payload")
+  }
+
+  fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+    let mut claims = Map::new();
+    let mut found = 0;
+    while let Some(key) = map.next_key::<String>()? {
+      found += 1;
+      if found > self.max_claims {
+        map.next_value::<IgnoredAny>()?;
+      } else {
+        claims.insert(key, map.next_value()?);
+      }
+    }
+    Ok(if found > self.max_claims {
+      BoundedPayload::TooManyClaims(found)
+    } else {
+      BoundedPayload::Parsed(Value::Object(claims))
+    })
+  }
+
+  fn visit_seq<A: SeqAccess<'de>>(self, seq: A) -> Result<Self::Value, A::Error> {
+    Value::deserialize(de::value::SeqAccessDeserializer::new(seq)).map(BoundedPayload::Parsed)
+  }
+
+  fn visit_bool<E: de::Error>(self, value: bool) -> Result<Self::Value, E> {
+    Ok(BoundedPayload::Parsed(Value::from(value)))
+  }
+
+  fn visit_i64<E: de::Error>(self, value: i64) -> Result<Self::Value, E> {
+    Ok(BoundedPayload::Parsed(Value::from(value)))
+  }
+
+  fn visit_u64<E: de::Error>(self, value: u64) -> Result<Self::Value, E> {
+    Ok(BoundedPayload::Parsed(Value::from(value)))
+  }
+
+  fn visit_f64<E: de::Error>(self, value: f64) -> Result<Self::Value, E> {
+    Ok(BoundedPayload::Parsed(Value::from(value)))
+  }
+
+  fn visit_str<E: de::Error>(self, value: &str) -> Result<Self::Value, E> {
+    Ok(BoundedPayload::Parsed(Value::from(value)))
+  }
+
+  fn visit_unit<E: de::Error>(self) -> Result<Self::Value, E> {
+    Ok(BoundedPayload::Parsed(Value::Null))
+  }
+}
+
+#[cfg(test)]
+mod unit_tests {
+  use super::*;
+  use serde_json::json;
+
+  fn parsed(payload: &str, max_claims: usize) -> Option<Value> {
+    match parse_bounded(payload, max_claims).ok()? {
+      BoundedPayload::Parsed(value) => Some(value),
+      BoundedPayload::TooManyClaims(_) => None,
+    }
+  }
+
+  #[test]
+  fn test_parse_bounded() {
+    let payload = r#"{"sub":"loyal subjects","nested":{"a":[1,2.5,null],"b":{"c":true}},"seats":4}"#;
+    assert_eq!(parsed(payload, 3), serde_json::from_str(payload).ok());
+    //nested objects don't count towards the limit
+    assert!(matches!(
+      parse_bounded(payload, 2),
+      Ok(BoundedPayload::TooManyClaims(3))
+    ));
+    assert!(matches!(
+      parse_bounded(payload, 0),
+      Ok(BoundedPayload::TooManyClaims(3))
+    ));
+
+    for payload in ["[1,{\"a\":2}]", "4", "-4", "1.5", "\"exp\"", "true", "null"] {
+      assert_eq!(parsed(payload, 0), serde_json::from_str(payload).ok(), "{}", payload);
+    }
+    assert_eq!(parsed("{}", 0), Some(json!({})));
+
+    //claims past the limit are still checked to be well formed JSON
+    assert!(parse_bounded(r#"{"a":1,"b":[}"#, 1).is_err());
+    assert!(parse_bounded(r#"{"a":1} trailing"#, 1).is_err());
+  }
+}
@@ -0,0 +1,111 @@
+use serde_json::{Number, Value};
+
+/// The SHA-256 hash, in lowercase hex, of claims written as canonical JSON, for deduplication caches and idempotency
+/// keys which go by what a token says rather than by its bytes
+///
+/// The canonical form has no whitespace, the keys of every object sorted and numbers with an integral value written
+/// as integers, so `1.0` and `1` hash the same.  Only the claims are hashed: tokens with the same claims hash the same
+/// whatever their nonce, version, footer or key.
+///
+/// # Example
+/// ```
+/// # #[cfg(feature = "generic")]
+/// # {
+/// use rusty_paseto::generic::*;
+/// use serde_json::json;
+///
+/// assert_eq!(
+///   claims_hash(&json!({"sub": "loyal subjects", "seats": 4})),
+///   claims_hash(&json!({"seats": 4.0, "sub": "loyal subjects"}))
+/// );
+/// # }
+/// ```
+pub fn claims_hash(claims: &Value) -> String {
+  let mut canonical = String::new();
+  write_canonical(claims, &mut canonical);
+  hex::encode(ring::digest::digest(&ring::digest::SHA256, canonical.as_bytes()))
+}
+
+fn write_canonical(value: &Value, out: &mut String) {
+  match value {
+    Value::Object(map) => {
+      let mut entries = map.iter().collect::<Vec<_>>();
+      entries.sort_unstable_by_key(|(key, _)| *key);
+      out.push('{');
+      for (i, (key, value)) in entries.into_iter().enumerate() {
+        if i > 0 {
+          out.push(',');
+        }
+        write_string(key, out);
+        out.push(':');
+        write_canonical(value, out);
+      }
+      out.push('}');
+    }
+    Value::Array(values) => {
+      out.push('[');
+      for (i, value) in values.iter().enumerate() {
+        if i > 0 {
+          out.push(',');
+        }
+        write_canonical(value, out);
+      }
+      out.push(']');
+    }
+    Value::String(value) => write_string(value, out),
+    Value::Number(number) => write_number(number, out),
+    Value::Bool(value) => out.push_str(if *value { "true" } else { "false" }),
+    Value::Null => out.push_str("null"),
+  }
+}
+
+fn write_string(value: &str, out: &mut String) {
+  //serializing a string can't fail and escapes it the same way every time
+  out.push_str(&Value::from(value).to_string());
+}
+
+//integers as they are, floats with an integral value within the exact range of an f64 as integers, which also turns
+//-0.0 into 0, and any other float in the shortest form which reads back as the same f64
+fn write_number(number: &Number, out: &mut String) {
+  const EXACT: f64 = 9_007_199_254_740_992.0;
+  match number.as_f64() {
+    Some(float) if number.is_f64() && float.fract() == 0.0 && float.abs() <= EXACT => {
+      out.push_str(&(float as i64).to_string())
+    }
+    _ => out.push_str(&number.to_string()),
+  }
+}
+
+#[cfg(test)]
+mod unit_tests {
+  use super::*;
+  use serde_json::json;
+
+  fn canonical(value: &Value) -> String {
+    let mut out = String::new();
+    write_canonical(value, &mut out);
+    out
+  }
+
+  #[test]
+  fn test_canonical_claims() {
+    assert_eq!(
+      canonical(&serde_json::from_str(r#"{ "z": [3, {"b": 1.0, "a": -0.0}], "a": "é\n", "m": null }"#).unwrap()),
+      r#"{"a":"é\n","m":null,"z":[3,{"a":0,"b":1}]}"#
+    );
+    assert_eq!(
+      canonical(&json!([1.5, 1e300, u64::MAX, i64::MIN, true])),
+      format!("[1.5,1e+300,{},{},true]", u64::MAX, i64::MIN)
+    );
+
+    //the hash goes by the canonical form only
+    let hash = claims_hash(&json!({"sub": "loyal subjects", "seats": 4}));
+    assert_eq!(hash, claims_hash(&json!({"seats": 4.0, "sub": "loyal subjects"})));
+    assert_ne!(hash, claims_hash(&json!({"seats": 5, "sub": "loyal subjects"})));
+    assert_eq!(hash.len(), 64);
+    assert_eq!(
+      claims_hash(&json!({})),
+      "44136fa355b3678a1146ad16f7e8649e94fb4fc21fe77e8310c060f61caaff8a"
+    );
+  }
+}
@@ -1,4 +1,6 @@
-use crate::generic::claims::PasetoClaimError;
+use crate::core::{PasetoPurpose, PasetoVersion};
+use crate::generic::claims::{ErrorReport, PasetoClaimError};
+use serde::{Serialize, Serializer};
 use thiserror::Error;
 
 /// Errors raised by the generic parser when validating claims or parsing a PASETO token.
@@ -16,6 +18,91 @@ pub enum GenericParserError {
     #[from]
     source: crate::core::PasetoError,
   },
+  /// The token failed decryption or signature verification
+  ///
+  /// Carries the footer of the token for diagnostics, such as telling which key the issuer intended.  The footer is
+  /// unauthenticated here and must not be trusted, it is only ever public data so exposing it leaks nothing.
+  #[error("The token could not be decrypted or verified")]
+  AuthenticationFailed {
+    /// The error from the cryptographic core
+    source: crate::core::PasetoError,
+    /// The unauthenticated footer of the token, if it has one which decodes
+    footer: Option<String>,
+  },
+  /// The version of the token is not one of the versions allowed by the parser
+  #[error("Tokens of version '{0}' are not allowed by this parser")]
+  VersionNotAllowed(PasetoVersion),
+  /// The purpose of the token is not the one the parser expects
+  #[error("Expected a '{expected}' token but found a '{found}' token")]
+  UnexpectedPurpose {
+    /// The purpose the parser expects
+    expected: PasetoPurpose,
+    /// The purpose found in the token header
+    found: PasetoPurpose,
+  },
+  /// The footer of the token doesn't carry the key id the parser expects
+  #[error("The token footer does not carry the expected key id '{0}'")]
+  KeyIdMismatch(String),
+  /// The token has no footer to resolve its key from the key id of
+  #[error("The token has no footer carrying the key id to resolve its key from, and there is no default key")]
+  MissingKeyId,
+  /// The [resolver](crate::generic::PublicKeyResolver) has no key with the key id of the token
+  #[error("No key is known with the key id '{0}', and there is no default key")]
+  UnknownKeyId(String),
+  /// The footer of the token isn't a [key routing](crate::generic::KeyRoutingFooter) JSON object
+  #[error("The token footer is not a key routing footer")]
+  MalformedFooter {
+    /// The JSON error reading the footer
+    source: serde_json::Error,
+  },
+  /// The payload of the token holds more claims than the parser accepts
+  #[error("The token payload holds {found} claims, more than the {max} allowed by this parser")]
+  TooManyClaims {
+    /// The number of claims found in the payload
+    found: usize,
+    /// The maximum number of claims allowed by the parser
+    max: usize,
+  },
+  /// The claims of the token aren't exactly the set of claims the parser requires
+  #[error("The token claims don't match the required set, missing: {missing:?}, unexpected: {unexpected:?}")]
+  ClaimSetMismatch {
+    /// The required claims absent from the payload, sorted by name
+    missing: Vec<String>,
+    /// The claims in the payload which aren't required, sorted by name
+    unexpected: Vec<String>,
+  },
+  /// The current time is past the server supplied [ceiling](crate::generic::GenericParser::not_valid_after) on the
+  /// validity of tokens, whatever the token's own expiration
+  #[error("The token is no longer accepted by this server after {0}")]
+  ExceededServerLimit(String),
+  /// The token was issued longer ago than the parser's [freshness window](crate::generic::GenericParser::require_fresh)
+  #[error("The token was issued {} seconds ago, longer than the {} seconds allowed", .age.whole_seconds(), .max_age.whole_seconds())]
+  TokenTooOld {
+    /// The time since the token was issued
+    age: time::Duration,
+    /// The maximum age allowed by the parser
+    max_age: time::Duration,
+  },
+  /// The token is valid for longer than the [policy](crate::generic::ParserPolicy::max_validity) allows
+  #[error("The token is valid for {} seconds, longer than the {} seconds allowed", .validity.whole_seconds(), .max_validity.whole_seconds())]
+  ValidityTooLong {
+    /// The time from the issue of the token, or the current time without an `iat` claim, until its expiration
+    validity: time::Duration,
+    /// The maximum validity allowed by the policy
+    max_validity: time::Duration,
+  },
+  /// A Unix timestamp given as the current time is outside the range of supported dates
+  #[error("The Unix timestamp {0} is outside the range of supported dates")]
+  TimestampOutOfRange(i64),
+  /// The payload of the token is JSON but not an object of claims
+  #[error("The token payload is not a JSON object")]
+  PayloadNotObject,
+  /// The `Authorization` header is empty or names a scheme without a token
+  #[error("The Authorization header carries no token")]
+  MissingToken,
+  /// The `Authorization` header uses a scheme other than `Bearer`, carrying the scheme
+  #[error("The Authorization header uses the '{0}' scheme rather than Bearer")]
+  UnsupportedScheme(String),
   /// A JSON deserialization error for the token payload
   #[error("The payload was unable to be serialized into json")]
   PayloadJsonError {
@@ -23,3 +110,70 @@ pub enum GenericParserError {
     source: serde_json::Error,
   },
 }
+
+/// Errors reading a claim of a parsed payload with a typed [accessor](crate::generic::ParsedClaims)
+#[derive(Debug, Error, Clone, PartialEq, Eq)]
+pub enum ClaimAccessError {
+  /// The payload has no claim of this name, or it is null
+  #[error("The claim '{0}' is missing")]
+  Missing(String),
+  /// The claim exists but holds another type of value
+  #[error("The claim '{claim}' is {found}, not {expected}")]
+  WrongType {
+    /// The name of the claim
+    claim: String,
+    /// The type of value asked for
+    expected: &'static str,
+    /// The type of value the claim holds
+    found: &'static str,
+  },
+}
+
+impl GenericParserError {
+  /// A serializable view of this error which leaves out the values carried by it, keeping only the reason and, for
+  /// claim errors, the name of the claim
+  ///
+  /// See [PasetoClaimError::redacted] for what is left out and why.
+  pub fn redacted(&self) -> impl Serialize + '_ {
+    self.report().redacted()
+  }
+
+  pub(crate) fn report(&self) -> ErrorReport<'_> {
+    let report = match self {
+      Self::ClaimError { source } => return source.report(),
+      Self::CipherError { .. } => ErrorReport::new("cipher"),
+      Self::AuthenticationFailed { .. } => ErrorReport::new("authentication_failed"),
+      Self::VersionNotAllowed(version) => ErrorReport::new("version_not_allowed").actual(version),
+      Self::UnexpectedPurpose { expected, found } => {
+        ErrorReport::new("unexpected_purpose").expected(expected).actual(found)
+      }
+      Self::KeyIdMismatch(key_id) => ErrorReport::new("key_id_mismatch").claim("kid").expected(key_id),
+      Self::MissingKeyId => ErrorReport::new("missing_key_id").claim("kid"),
+      Self::UnknownKeyId(key_id) => ErrorReport::new("unknown_key_id").claim("kid").actual(key_id),
+      Self::MalformedFooter { .. } => ErrorReport::new("malformed_footer"),
+      Self::TooManyClaims { found, max } => ErrorReport::new("too_many_claims").expected(max).actual(found),
+      Self::ClaimSetMismatch { .. } => ErrorReport::new("claim_set_mismatch"),
+      Self::ExceededServerLimit(not_valid_after) => ErrorReport::new("exceeded_server_limit").expected(not_valid_after),
+      Self::TokenTooOld { age, max_age } => ErrorReport::new("token_too_old")
+        .claim("iat")
+        .expected(max_age.whole_seconds())
+        .actual(age.whole_seconds()),
+      Self::ValidityTooLong { validity, max_validity } => ErrorReport::new("validity_too_long")
+        .claim("exp")
+        .expected(max_validity.whole_seconds())
+        .actual(validity.whole_seconds()),
+      Self::TimestampOutOfRange(timestamp) => ErrorReport::new("timestamp_out_of_range").actual(timestamp),
+      Self::PayloadNotObject => ErrorReport::new("payload_not_object"),
+      Self::MissingToken => ErrorReport::new("missing_token"),
+      Self::UnsupportedScheme(scheme) => ErrorReport::new("unsupported_scheme").actual(scheme),
+      Self::PayloadJsonError { .. } => ErrorReport::new("payload_json"),
+    };
+    report.message(self.to_string())
+  }
+}
+
+impl Serialize for GenericParserError {
+  fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    self.report().serialize(serializer)
+  }
+}
@@ -1,9 +1,19 @@
-use super::GenericParserError;
+use super::{
+  bounded_payload::{parse_bounded, BoundedPayload},
+  metrics,
+  time_claim::time_claim,
+  GenericParserError,
+};
 use crate::generic::*;
 
 use core::marker::PhantomData;
+use serde::de::DeserializeOwned;
 use serde_json::Value;
-use std::collections::HashMap;
+use std::cell::Cell;
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::rc::Rc;
+use time::format_description::well_known::Rfc3339;
+use time::OffsetDateTime;
 
 ///The GenericParser is created at compile time by specifying a PASETO version and purpose and
 ///providing a key of the same version and purpose. This structure allows parsing an untrusted token string
@@ -22,11 +32,11 @@ use std::collections::HashMap;
 ///# #[cfg(all(feature = "generic", feature="v2_local"))]
 ///# {
 ///   use rusty_paseto::generic::*;
-
+///
 ///     let key = PasetoSymmetricKey::<V2, Local>::from(Key::<32>::from(*b"wubbalubbadubdubwubbalubbadubdub"));
-
+///
 ///     let footer = Footer::from("some footer");
-
+///
 ///     //create a builder, add some claims and then build the token with the key
 ///     let token = GenericBuilder::<V2, Local>::default()
 ///       .set_claim(AudienceClaim::from("customers"))
@@ -41,12 +51,12 @@ use std::collections::HashMap;
 ///       .set_claim(CustomClaim::try_from(("pi to 6 digits", 3.141526))?)
 ///       .set_footer(footer)
 ///       .try_encrypt(&key)?;
-
+///
 ///     //now let's decrypt the token and verify the values
 ///     let json = GenericParser::<V2, Local>::default()
 ///       .set_footer(footer)
 ///       .parse(&token, &key)?;
-
+///
 ///     assert_eq!(json["aud"], "customers");
 ///     assert_eq!(json["jti"], "me");
 ///     assert_eq!(json["iss"], "me");
@@ -60,17 +70,43 @@ use std::collections::HashMap;
 ///  # }
 /// # Ok::<(),anyhow::Error>(())
 ///   ```
-
+///
+///# Metrics
+///
+///With the `metrics` feature every parse increments the `paseto_parse_total` counter of the
+///[metrics](https://docs.rs/metrics) facade, labelled with an `outcome` of `"success"` or the category of the failure
+///such as `"expired"`, `"invalid_signature"`, `"footer_mismatch"` or `"malformed_token"`.  Without the feature
+///nothing is recorded and the instrumentation compiles away.
 pub struct GenericParser<'a, 'b, Version, Purpose> {
   version: PhantomData<Version>,
   purpose: PhantomData<Purpose>,
   claims: HashMap<String, Box<dyn erased_serde::Serialize + 'b>>,
   claim_validators: ValidatorMap,
+  cross_claim_validators: Vec<&'static CrossClaimValidatorFn>,
   footer: Footer<'a>,
   implicit_assertion: ImplicitAssertion<'a>,
+  allowed_versions: Option<HashSet<PasetoVersion>>,
+  expected_purpose: Option<PasetoPurpose>,
+  expected_key_id: Option<&'a str>,
+  max_claims: usize,
+  allow_non_object_payload: bool,
+  exact_claims: Option<BTreeSet<String>>,
+  //the checked claims whose string values are compared without regard to ASCII case
+  case_insensitive_claims: HashSet<String>,
+  not_valid_after: Option<OffsetDateTime>,
+  max_age: Option<time::Duration>,
+  policy: Option<ParserPolicy>,
+  leeway: Rc<Cell<time::Duration>>,
+  //the current time shared with the time checks of the prelude parser, the system clock when unset
+  now: Rc<Cell<Option<OffsetDateTime>>>,
+  //the unit of numeric time claims shared with the prelude parser, numeric time claims are rejected when unset
+  numeric_time_unit: Rc<Cell<Option<NumericTimeUnit>>>,
 }
 
 impl<'a, 'b, Version, Purpose> GenericParser<'a, 'b, Version, Purpose> {
+  /// The maximum number of claims accepted in a payload unless [overridden](Self::max_claims)
+  pub const DEFAULT_MAX_CLAIMS: usize = 1024;
+
   ///Creates a new parser for building. Called by [Default]
   pub fn new() -> Self {
     GenericParser::<Version, Purpose> {
@@ -78,8 +114,22 @@ impl<'a, 'b, Version, Purpose> GenericParser<'a, 'b, Version, Purpose> {
       purpose: PhantomData::<Purpose>,
       claims: HashMap::new(),
       claim_validators: HashMap::new(),
+      cross_claim_validators: Vec::new(),
       footer: Default::default(),
       implicit_assertion: Default::default(),
+      allowed_versions: None,
+      expected_purpose: None,
+      expected_key_id: None,
+      max_claims: Self::DEFAULT_MAX_CLAIMS,
+      allow_non_object_payload: false,
+      exact_claims: None,
+      case_insensitive_claims: HashSet::new(),
+      not_valid_after: None,
+      max_age: None,
+      policy: None,
+      leeway: Rc::default(),
+      now: Rc::default(),
+      numeric_time_unit: Rc::default(),
     }
   }
   ///Allows adding multiple [claims](PasetoClaim) at once to be checked during parsing by passing a Hashmap of claim keys and values
@@ -95,18 +145,23 @@ impl<'a, 'b, Version, Purpose> GenericParser<'a, 'b, Version, Purpose> {
   }
 
   #[cfg(feature = "serde")]
-  fn set_validation_claim<T: PasetoClaim + 'b + serde::Serialize>(
+  pub(crate) fn set_validation_claim<T: PasetoClaim + 'b + serde::Serialize>(
     &mut self,
     value: T,
-    validation_closure: Option<&'static ValidatorFn>,
+    validation_closure: Option<Box<ValidatorFn>>,
   ) -> &mut Self {
     let key = value.get_key().to_string();
+    if value.is_case_insensitive() {
+      self.case_insensitive_claims.insert(key.clone());
+    } else {
+      self.case_insensitive_claims.remove(&key);
+    }
     //first store the claim
     self.claims.insert(key.clone(), Box::new(value));
 
     //if there's a closure, then store that
     if let Some(closure) = validation_closure {
-      self.claim_validators.insert(key, Box::new(closure));
+      self.claim_validators.insert(key, closure);
     }
     self
   }
@@ -120,7 +175,16 @@ impl<'a, 'b, Version, Purpose> GenericParser<'a, 'b, Version, Purpose> {
     value: T,
     validation_closure: &'static ValidatorFn,
   ) -> &mut Self {
-    self.set_validation_claim(value, Some(validation_closure))
+    self.set_validation_claim(value, Some(Box::new(validation_closure)))
+  }
+
+  ///Allows user to pass a [custom function](CrossClaimValidatorFn) which receives the full payload of
+  ///the token after decryption or signature verification, to enforce invariants spanning several
+  ///claims. These run after all of the individual claims have been checked and an `Err` fails parsing
+  ///with [CrossClaimValidation](PasetoClaimError::CrossClaimValidation) carrying the returned message.
+  pub fn validate_across(&mut self, validation_closure: &'static CrossClaimValidatorFn) -> &mut Self {
+    self.cross_claim_validators.push(validation_closure);
+    self
   }
 
   ///Verifies a passed [PasetoClaim] exists
@@ -130,7 +194,7 @@ impl<'a, 'b, Version, Purpose> GenericParser<'a, 'b, Version, Purpose> {
   }
 
   /// Gets an optional [Footer] set during parser building
-  pub fn get_footer(&self) -> Footer {
+  pub fn get_footer(&self) -> Footer<'a> {
     self.footer
   }
   ///Sets an optional [Footer] to use during parsing
@@ -138,613 +202,918 @@ impl<'a, 'b, Version, Purpose> GenericParser<'a, 'b, Version, Purpose> {
     self.footer = footer;
     self
   }
-}
 
-impl<'a, 'b, Version: ImplicitAssertionCapable, Purpose> GenericParser<'a, 'b, Version, Purpose> {
-  ///Sets an optional [ImplicitAssertion] to use during parsing ([V3], [V4] tokens only)
-  pub fn set_implicit_assertion(&mut self, implicit_assertion: ImplicitAssertion<'a>) -> &mut Self {
-    self.implicit_assertion = implicit_assertion;
+  ///Restricts the tokens honored to those whose header declares one of the given [PasetoVersion]s, rejecting any
+  ///other with [VersionNotAllowed](GenericParserError::VersionNotAllowed) before any decryption or signature
+  ///verification is attempted
+  ///
+  ///A parser is typed to a single version, so when parsing the set can only turn away tokens of the parser's own
+  ///version.  During a migration it is a downgrade guard on the header instead: the verifier of the newer version
+  ///runs [check_header](Self::check_header) on every token, which passes tokens of any allowed version, and hands
+  ///those of an older version to a parser of that version.  Dropping the older version from the set ends the
+  ///migration without touching the routing.
+  ///
+  ///By default no restriction beyond the parser's own version is applied.
+  ///
+  ///# Example
+  ///```
+  ///# #[cfg(all(feature = "generic", feature = "v2_local", feature = "v4_local"))]
+  ///# {
+  ///   use rusty_paseto::generic::*;
+  ///
+  ///   let v2_key = PasetoSymmetricKey::<V2, Local>::from(Key::<32>::from(*b"wubbalubbadubdubwubbalubbadubdub"));
+  ///   let v4_key = PasetoSymmetricKey::<V4, Local>::from(Key::<32>::from(*b"wubbalubbadubdubwubbalubbadubdub"));
+  ///   let legacy_token = GenericBuilder::<V2, Local>::default()
+  ///     .set_claim(SubjectClaim::from("loyal subjects"))
+  ///     .try_encrypt(&v2_key)?;
+  ///
+  ///   //a v4 service which still honors v2 tokens during its migration
+  ///   let mut verifier = GenericParser::<V4, Local>::default();
+  ///   verifier.set_allowed_versions(&[PasetoVersion::V2, PasetoVersion::V4]);
+  ///   let json = match verifier.check_header(&legacy_token)?.version() {
+  ///     PasetoVersion::V2 => GenericParser::<V2, Local>::default().parse(&legacy_token, &v2_key)?,
+  ///     _ => verifier.parse(&legacy_token, &v4_key)?,
+  ///   };
+  ///   assert_eq!(json["sub"], "loyal subjects");
+  ///
+  ///   //once the migration is over, v2 tokens are turned away before reaching a v2 parser
+  ///   verifier.set_allowed_versions(&[PasetoVersion::V4]);
+  ///   assert!(matches!(
+  ///     verifier.check_header(&legacy_token),
+  ///     Err(GenericParserError::VersionNotAllowed(PasetoVersion::V2))
+  ///   ));
+  ///# }
+  ///# Ok::<(),anyhow::Error>(())
+  ///```
+  pub fn set_allowed_versions(&mut self, versions: &[PasetoVersion]) -> &mut Self {
+    self.allowed_versions = Some(versions.iter().copied().collect());
     self
   }
 
-  ///Gets an optional [ImplicitAssertion] to use during parsing ([V3], [V4] tokens only)
-  pub fn get_implicit_assertion(&self) -> ImplicitAssertion {
-    self.implicit_assertion
+  /// Gets the set of [PasetoVersion]s allowed during parsing, if one has been configured
+  pub fn get_allowed_versions(&self) -> Option<&HashSet<PasetoVersion>> {
+    self.allowed_versions.as_ref()
   }
-}
-
-impl<'a, 'b, Version, Purpose> GenericParser<'a, 'b, Version, Purpose> {
-  fn verify_claims(&self, token: &str) -> Result<Value, GenericParserError> {
-    let json: Value = serde_json::from_str(token)?;
-
-    // here we want to traverse all of the claims to validate and verify their values
-    for (key, box_val) in &self.claims {
-      //ensure the claim exists
-      //get the raw value of the claim
-      let raw = serde_json::to_value(box_val)?;
 
-      //now let's run any custom validation if there is any
-      if self.claim_validators.contains_key(key) {
-        let box_validator = &self.claim_validators[key];
-        let validator = box_validator.as_ref();
-        validator(key, &json[&key])?;
-      } else {
-        //otherwise, simply verify the claim exists and matches the value passed in
-        if json[&key] == Value::Null {
-          return Err(PasetoClaimError::Missing(key.to_string()).into());
-        }
+  ///Rejects tokens whose header declares any [PasetoPurpose] other than the expected one with
+  ///[UnexpectedPurpose](GenericParserError::UnexpectedPurpose) before any decryption or signature
+  ///verification is attempted
+  ///
+  ///Combined with [check_header](Self::check_header), a misrouted token can be turned away before its key
+  ///is even looked up.
+  pub fn expect_purpose(&mut self, purpose: PasetoPurpose) -> &mut Self {
+    self.expected_purpose = Some(purpose);
+    self
+  }
 
-        if raw[&key] != json[&key] {
-          return Err(
-            PasetoClaimError::Invalid(
-              key.to_string(),
-              json[&key]
-                .as_str()
-                .ok_or_else(|| PasetoClaimError::Unexpected(key.to_string()))?
-                .into(),
-              raw[&key]
-                .as_str()
-                .ok_or_else(|| PasetoClaimError::Unexpected(key.to_string()))?
-                .into(),
-            )
-            .into(),
-          );
-        }
-      }
-    }
+  ///Rejects tokens whose footer isn't a JSON object with a `kid` equal to the expected key id with
+  ///[KeyIdMismatch](GenericParserError::KeyIdMismatch) before any decryption or signature verification
+  ///is attempted
+  ///
+  ///This defends against accepting a token routed to the wrong key. The footer of a token must still
+  ///match the one [set](Self::set_footer) on the parser to be parsed.
+  ///
+  ///# Example
+  ///```
+  ///# #[cfg(all(feature = "generic", feature="v4_local"))]
+  ///# {
+  ///   use rusty_paseto::generic::*;
+  ///
+  ///   let key = PasetoSymmetricKey::<V4, Local>::from(Key::<32>::from(*b"wubbalubbadubdubwubbalubbadubdub"));
+  ///   let footer = Footer::from(r#"{"kid":"2024-06"}"#);
+  ///   let token = GenericBuilder::<V4, Local>::default().set_footer(footer).try_encrypt(&key)?;
+  ///
+  ///   let result = GenericParser::<V4, Local>::default()
+  ///     .set_footer(footer)
+  ///     .expect_key_id("2024-07")
+  ///     .parse(&token, &key);
+  ///   assert!(matches!(result, Err(GenericParserError::KeyIdMismatch(_))));
+  ///# }
+  ///# Ok::<(),anyhow::Error>(())
+  ///```
+  pub fn expect_key_id(&mut self, key_id: &'a str) -> &mut Self {
+    self.expected_key_id = Some(key_id);
+    self
+  }
 
-    Ok(json)
+  ///Requires the token to carry the given [TokenType] as its `typ` claim, rejecting tokens of another
+  ///kind with [Invalid](PasetoClaimError::Invalid) and tokens without one with
+  ///[Missing](PasetoClaimError::Missing)
+  #[cfg(feature = "serde")]
+  pub fn expect_type(&mut self, token_type: TokenType) -> &mut Self {
+    self.check_claim(token_type)
   }
-}
 
-#[cfg(feature = "v1_local")]
-impl<'a, 'b> GenericParser<'a, 'b, V1, Local> {
-  /// Given a [PasetoSymmetricKey], attempts to decrypt a (V1, Local) encrypted PASETO token string and then validate
-  /// claims provided to the GenericParser.
+  ///Sets the maximum number of top level claims accepted in a decrypted or verified payload,
+  ///rejecting payloads with more with [TooManyClaims](GenericParserError::TooManyClaims) before any
+  ///claim is validated
   ///
-  /// Returns a serde_json [Value] with the decrypted [claims](PasetoClaim) and payload on success.
+  ///The claims are counted while the payload is deserialized and those past the limit are skipped rather than
+  ///built, which bounds the work done on behalf of a pathological token carrying thousands of tiny claims.  The
+  ///count comes before the freshness, policy and every other claim check.
+  ///Defaults to [DEFAULT_MAX_CLAIMS](Self::DEFAULT_MAX_CLAIMS).
+  pub fn max_claims(&mut self, max_claims: usize) -> &mut Self {
+    self.max_claims = max_claims;
+    self
+  }
+
+  ///Accepts decrypted or verified payloads which are JSON but not an object of claims, such as an array or a
+  ///number, rather than rejecting them with [PayloadNotObject](GenericParserError::PayloadNotObject)
   ///
-  /// # Errors
+  ///Only for tokens which don't carry claims at all: claim checks and validators see every claim of such a payload
+  ///as missing.  The [core](crate::core) decryption and verification never look at the shape of the payload.
+  pub fn allow_non_object_payload(&mut self) -> &mut Self {
+    self.allow_non_object_payload = true;
+    self
+  }
+
+  ///Requires the decrypted or verified payload to hold exactly the given top level claims, no more
+  ///and no less, rejecting any other payload with [ClaimSetMismatch](GenericParserError::ClaimSetMismatch)
+  ///listing the missing and unexpected claims
   ///
-  /// Returns [`GenericParserError`] for any errors when decrypting the encrypted payload or when validating claims.
+  ///Only the names of the claims are compared, their values are still validated by the claims
+  ///and validators set on the parser.
   ///
   /// # Example
-  ///
-  ///
   ///```
-  ///# #[cfg(all(feature = "generic", feature="v1_local"))]
+  ///# #[cfg(all(feature = "generic", feature="v4_local"))]
   ///# {
   ///   use rusty_paseto::generic::*;
-
-  ///     let key = PasetoSymmetricKey::<V1, Local>::from(Key::<32>::from(*b"wubbalubbadubdubwubbalubbadubdub"));
-
-  ///     let footer = Footer::from("some footer");
-
-  ///     //create a builder, add some claims and then build the token with the key
-  ///     let token = GenericBuilder::<V1, Local>::default()
-  ///       .set_claim(AudienceClaim::from("customers"))
-  ///       .set_claim(SubjectClaim::from("loyal subjects"))
-  ///       .set_claim(IssuerClaim::from("me"))
-  ///       .set_claim(TokenIdentifierClaim::from("me"))
-  ///       .set_claim(IssuedAtClaim::try_from("2019-01-01T00:00:00+00:00")?)
-  ///       .set_claim(NotBeforeClaim::try_from("2019-01-01T00:00:00+00:00")?)
-  ///       .set_claim(ExpirationClaim::try_from("2019-01-01T00:00:00+00:00")?)
-  ///       .set_claim(CustomClaim::try_from(("data", "this is a secret message"))?)
-  ///       .set_claim(CustomClaim::try_from(("seats", 4))?)
-  ///       .set_claim(CustomClaim::try_from(("pi to 6 digits", 3.141526))?)
-  ///       .set_footer(footer)
+  ///
+  ///     # let key = PasetoSymmetricKey::<V4, Local>::from(Key::<32>::from(*b"wubbalubbadubdubwubbalubbadubdub"));
+  ///     let token = GenericBuilder::<V4, Local>::default()
+  ///       .set_claim(IssuerClaim::from("issuer"))
+  ///       .set_claim(SubjectClaim::from("subject"))
+  ///       .set_claim(CustomClaim::try_from(("scope", "admin"))?)
   ///       .try_encrypt(&key)?;
-
-  ///     //now let's decrypt the token and verify the values
-  ///     let json = GenericParser::<V1, Local>::default()
-  ///       .set_footer(footer)
-  ///       .parse(&token, &key)?;
-
-  ///     assert_eq!(json["aud"], "customers");
-  ///     assert_eq!(json["jti"], "me");
-  ///     assert_eq!(json["iss"], "me");
-  ///     assert_eq!(json["data"], "this is a secret message");
-  ///     assert_eq!(json["exp"], "2019-01-01T00:00:00+00:00");
-  ///     assert_eq!(json["iat"], "2019-01-01T00:00:00+00:00");
-  ///     assert_eq!(json["nbf"], "2019-01-01T00:00:00+00:00");
-  ///     assert_eq!(json["sub"], "loyal subjects");
-  ///     assert_eq!(json["pi to 6 digits"], 3.141526);
-  ///     assert_eq!(json["seats"], 4);
-  ///  # }
+  ///
+  ///     let result = GenericParser::<V4, Local>::default()
+  ///       .require_exact_claims(&["iss", "sub", "aud"])
+  ///       .parse(&token, &key);
+  ///
+  ///     assert!(matches!(
+  ///       result,
+  ///       Err(GenericParserError::ClaimSetMismatch { missing, unexpected })
+  ///         if missing == ["aud"] && unexpected == ["scope"]
+  ///     ));
+  /// # }
   /// # Ok::<(),anyhow::Error>(())
-  ///   ```
-
-  pub fn parse(
-    &self,
-    potential_token: &'a str,
-    key: &'a PasetoSymmetricKey<V1, Local>,
-  ) -> Result<Value, GenericParserError> {
-    //decrypt, then validate
-    let token = Paseto::<V1, Local>::try_decrypt(potential_token, key, self.get_footer())?;
-
-    self.verify_claims(&token)
+  ///```
+  pub fn require_exact_claims(&mut self, claims: &[&str]) -> &mut Self {
+    self.exact_claims = Some(claims.iter().map(|claim| claim.to_string()).collect());
+    self
   }
-}
 
-#[cfg(feature = "v2_local")]
-impl<'a, 'b> GenericParser<'a, 'b, V2, Local> {
-  /// Given a [PasetoSymmetricKey], attempts to decrypt a (V2, Local) encrypted PASETO token string and then validate
-  /// claims provided to the GenericParser.
+  ///Sets a ceiling on the validity of every token, rejecting them with
+  ///[ExceededServerLimit](GenericParserError::ExceededServerLimit) once the current time is past it regardless of
+  ///their own expiration.  Useful for hard session caps and revocation windows dictated by the server rather than
+  ///by the issuer.
   ///
-  /// Returns a serde_json [Value] with the decrypted [claims](PasetoClaim) and payload on success.
-  ///
-  /// # Errors
+  /// # Example
+  ///```
+  ///# #[cfg(all(feature = "generic", feature="v4_local"))]
+  ///# {
+  ///   use rusty_paseto::generic::*;
   ///
-  /// Returns [`GenericParserError`] for any errors when decrypting the encrypted payload or when validating claims.
+  ///     # let key = PasetoSymmetricKey::<V4, Local>::from(Key::<32>::from(*b"wubbalubbadubdubwubbalubbadubdub"));
+  ///     let token = GenericBuilder::<V4, Local>::default()
+  ///       .set_claim(ExpirationClaim::try_from("2099-01-01T00:00:00Z")?)
+  ///       .try_encrypt(&key)?;
   ///
-  /// # Example
+  ///     //the session was capped at the start of 2020
+  ///     let session_cap = time::OffsetDateTime::from_unix_timestamp(1_577_836_800)?;
+  ///     let result = GenericParser::<V4, Local>::default()
+  ///       .not_valid_after(session_cap)
+  ///       .parse(&token, &key);
   ///
+  ///     assert!(matches!(result, Err(GenericParserError::ExceededServerLimit(_))));
+  /// # }
+  /// # Ok::<(),anyhow::Error>(())
+  ///```
+  pub fn not_valid_after(&mut self, not_valid_after: OffsetDateTime) -> &mut Self {
+    self.not_valid_after = Some(not_valid_after);
+    self
+  }
+
+  ///Requires the token to have been issued within the given window, checking its `iat` claim against the current
+  ///time and rejecting older tokens with [TokenTooOld](GenericParserError::TokenTooOld).  Meant for sensitive
+  ///operations which need a recent re-authentication rather than merely an unexpired token.  Tokens without an `iat`
+  ///claim fail with [Missing](PasetoClaimError::Missing).
   ///
+  /// # Example
   ///```
-  ///# #[cfg(all(feature = "generic", feature="v2_local"))]
+  ///# #[cfg(all(feature = "generic", feature="v4_local"))]
   ///# {
   ///   use rusty_paseto::generic::*;
-
-  ///     let key = PasetoSymmetricKey::<V2, Local>::from(Key::<32>::from(*b"wubbalubbadubdubwubbalubbadubdub"));
-
-  ///     let footer = Footer::from("some footer");
-
-  ///     //create a builder, add some claims and then build the token with the key
-  ///     let token = GenericBuilder::<V2, Local>::default()
-  ///       .set_claim(AudienceClaim::from("customers"))
-  ///       .set_claim(SubjectClaim::from("loyal subjects"))
-  ///       .set_claim(IssuerClaim::from("me"))
-  ///       .set_claim(TokenIdentifierClaim::from("me"))
-  ///       .set_claim(IssuedAtClaim::try_from("2019-01-01T00:00:00+00:00")?)
-  ///       .set_claim(NotBeforeClaim::try_from("2019-01-01T00:00:00+00:00")?)
-  ///       .set_claim(ExpirationClaim::try_from("2019-01-01T00:00:00+00:00")?)
-  ///       .set_claim(CustomClaim::try_from(("data", "this is a secret message"))?)
-  ///       .set_claim(CustomClaim::try_from(("seats", 4))?)
-  ///       .set_claim(CustomClaim::try_from(("pi to 6 digits", 3.141526))?)
-  ///       .set_footer(footer)
+  ///
+  ///     # let key = PasetoSymmetricKey::<V4, Local>::from(Key::<32>::from(*b"wubbalubbadubdubwubbalubbadubdub"));
+  ///     let token = GenericBuilder::<V4, Local>::default()
+  ///       .set_claim(IssuedAtClaim::try_from("2019-01-01T00:00:00Z")?)
   ///       .try_encrypt(&key)?;
-
-  ///     //now let's decrypt the token and verify the values
-  ///     let json = GenericParser::<V2, Local>::default()
-  ///       .set_footer(footer)
-  ///       .parse(&token, &key)?;
-
-  ///     assert_eq!(json["aud"], "customers");
-  ///     assert_eq!(json["jti"], "me");
-  ///     assert_eq!(json["iss"], "me");
-  ///     assert_eq!(json["data"], "this is a secret message");
-  ///     assert_eq!(json["exp"], "2019-01-01T00:00:00+00:00");
-  ///     assert_eq!(json["iat"], "2019-01-01T00:00:00+00:00");
-  ///     assert_eq!(json["nbf"], "2019-01-01T00:00:00+00:00");
-  ///     assert_eq!(json["sub"], "loyal subjects");
-  ///     assert_eq!(json["pi to 6 digits"], 3.141526);
-  ///     assert_eq!(json["seats"], 4);
-  ///  # }
+  ///
+  ///     let result = GenericParser::<V4, Local>::default()
+  ///       .require_fresh(time::Duration::minutes(2))
+  ///       .parse(&token, &key);
+  ///
+  ///     assert!(matches!(result, Err(GenericParserError::TokenTooOld { .. })));
+  /// # }
   /// # Ok::<(),anyhow::Error>(())
-  ///   ```
-
-  pub fn parse(
-    &mut self,
-    potential_token: &'a str,
-    key: &'a PasetoSymmetricKey<V2, Local>,
-  ) -> Result<Value, GenericParserError> {
-    //first we need to verify the token
-    let token = Paseto::<V2, Local>::try_decrypt(potential_token, key, self.get_footer())?;
-
-    self.verify_claims(&token)
+  ///```
+  pub fn require_fresh(&mut self, max_age: time::Duration) -> &mut Self {
+    self.max_age = Some(max_age);
+    self
   }
-}
 
-#[cfg(feature = "v3_local")]
-impl<'a, 'b> GenericParser<'a, 'b, V3, Local> {
-  /// Given a [PasetoSymmetricKey], attempts to decrypt a (V3, Local) encrypted PASETO token string and then validate
-  /// claims provided to the GenericParser.
+  ///Reads time claims given as a whole number since the Unix epoch in the given unit, for tokens from systems which
+  ///don't write RFC3339 dates.  RFC3339 strings are still accepted alongside.
   ///
-  /// Returns a serde_json [Value] with the decrypted [claims](PasetoClaim) and payload on success.
+  ///Numeric time claims are rejected with [Unexpected](PasetoClaimError::Unexpected) until a unit is set, as a number
+  ///read in the wrong unit makes a token look expired since 1970 or dated tens of millennia ahead.  The unit applies
+  ///to the `iat` claim of [require_fresh](Self::require_fresh), the rules of a [policy](Self::set_policy) and the time
+  ///claim validators of the [PasetoParser](crate::prelude::PasetoParser).
   ///
-  /// # Errors
+  /// # Example
+  ///```
+  ///# #[cfg(all(feature = "generic", feature="v4_local"))]
+  ///# {
+  ///   use rusty_paseto::generic::*;
   ///
-  /// Returns [`GenericParserError`] for any errors when decrypting the encrypted payload or when validating claims.
+  ///     # let key = PasetoSymmetricKey::<V4, Local>::from(Key::<32>::from(*b"wubbalubbadubdubwubbalubbadubdub"));
+  ///     //an upstream system issuing its tokens at 2019-01-01T00:00:00Z
+  ///     let token = GenericBuilder::<V4, Local>::default()
+  ///       .set_raw_payload(r#"{"iat":1546300800000}"#.to_string())
+  ///       .try_encrypt(&key)?;
   ///
-  /// # Example
+  ///     let result = GenericParser::<V4, Local>::default()
+  ///       .set_numeric_time_unit(NumericTimeUnit::Milliseconds)
+  ///       .require_fresh(time::Duration::minutes(2))
+  ///       .parse(&token, &key);
   ///
+  ///     assert!(matches!(result, Err(GenericParserError::TokenTooOld { .. })));
+  /// # }
+  /// # Ok::<(),anyhow::Error>(())
+  ///```
+  pub fn set_numeric_time_unit(&mut self, unit: NumericTimeUnit) -> &mut Self {
+    self.numeric_time_unit.set(Some(unit));
+    self
+  }
+
+  ///Applies an organization wide [ParserPolicy], replacing any policy applied before.  The allowed versions of the
+  ///policy, if it has any, replace those [set](Self::set_allowed_versions) on the parser.  Its claim rules are checked
+  ///with the rest of the claims, failing with [Missing](PasetoClaimError::Missing) for an absent `exp` or required
+  ///claim and with [ValidityTooLong](GenericParserError::ValidityTooLong) for a token valid for too long.
   ///
+  /// # Example
   ///```
-  ///# #[cfg(all(feature = "generic", feature="v3_local"))]
+  ///# #[cfg(all(feature = "generic", feature="v4_local"))]
   ///# {
   ///   use rusty_paseto::generic::*;
-
-  ///     let key = PasetoSymmetricKey::<V3, Local>::from(Key::<32>::from(*b"wubbalubbadubdubwubbalubbadubdub"));
-
-  ///     let footer = Footer::from("some footer");
-  ///     let implicit_assertion = ImplicitAssertion::from("some assertion");
-
-  ///     //create a builder, add some claims and then build the token with the key
-  ///     let token = GenericBuilder::<V3, Local>::default()
-  ///       .set_claim(AudienceClaim::from("customers"))
-  ///       .set_claim(SubjectClaim::from("loyal subjects"))
-  ///       .set_claim(IssuerClaim::from("me"))
-  ///       .set_claim(TokenIdentifierClaim::from("me"))
-  ///       .set_claim(IssuedAtClaim::try_from("2019-01-01T00:00:00+00:00")?)
-  ///       .set_claim(NotBeforeClaim::try_from("2019-01-01T00:00:00+00:00")?)
-  ///       .set_claim(ExpirationClaim::try_from("2019-01-01T00:00:00+00:00")?)
-  ///       .set_claim(CustomClaim::try_from(("data", "this is a secret message"))?)
-  ///       .set_claim(CustomClaim::try_from(("seats", 4))?)
-  ///       .set_claim(CustomClaim::try_from(("pi to 6 digits", 3.141526))?)
-  ///       .set_footer(footer)
-  ///       .set_implicit_assertion(implicit_assertion)
+  ///
+  ///     # let key = PasetoSymmetricKey::<V4, Local>::from(Key::<32>::from(*b"wubbalubbadubdubwubbalubbadubdub"));
+  ///     let token = GenericBuilder::<V4, Local>::default()
+  ///       .set_claim(IssuedAtClaim::try_from("2019-01-01T00:00:00Z")?)
+  ///       .set_claim(ExpirationClaim::try_from("2019-02-01T00:00:00Z")?)
   ///       .try_encrypt(&key)?;
+  ///
+  ///     let policy = ParserPolicy {
+  ///       max_validity: Some(time::Duration::days(1)),
+  ///       ..ParserPolicy::default()
+  ///     };
+  ///     let result = GenericParser::<V4, Local>::default()
+  ///       .set_policy(&policy)
+  ///       .parse(&token, &key);
+  ///
+  ///     assert!(matches!(result, Err(GenericParserError::ValidityTooLong { .. })));
+  /// # }
+  /// # Ok::<(),anyhow::Error>(())
+  ///```
+  pub fn set_policy(&mut self, policy: &ParserPolicy) -> &mut Self {
+    if let Some(versions) = &policy.allowed_versions {
+      self.set_allowed_versions(versions);
+    }
+    self.leeway.set(policy.leeway);
+    self.policy = Some(policy.clone());
+    self
+  }
 
-  ///     //now let's decrypt the token and verify the values
-  ///     let json = GenericParser::<V3, Local>::default()
-  ///       .set_footer(footer)
-  ///       .set_implicit_assertion(implicit_assertion)
-  ///       .parse(&token, &key)?;
+  //the clock of the parser, set by the prelude parser to inject the current time
+  #[cfg(any(test, feature = "batteries_included"))]
+  pub(crate) fn clock(&self) -> Rc<Cell<Option<OffsetDateTime>>> {
+    Rc::clone(&self.now)
+  }
 
-  ///     assert_eq!(json["aud"], "customers");
-  ///     assert_eq!(json["jti"], "me");
-  ///     assert_eq!(json["iss"], "me");
-  ///     assert_eq!(json["data"], "this is a secret message");
-  ///     assert_eq!(json["exp"], "2019-01-01T00:00:00+00:00");
-  ///     assert_eq!(json["iat"], "2019-01-01T00:00:00+00:00");
-  ///     assert_eq!(json["nbf"], "2019-01-01T00:00:00+00:00");
-  ///     assert_eq!(json["sub"], "loyal subjects");
-  ///     assert_eq!(json["pi to 6 digits"], 3.141526);
-  ///     assert_eq!(json["seats"], 4);
-  ///  # }
-  /// # Ok::<(),anyhow::Error>(())
-  ///   ```
+  //the clock skew tolerated by the prelude parser's time claim validators, set by the policy
+  #[cfg(any(test, feature = "batteries_included"))]
+  pub(crate) fn leeway(&self) -> Rc<Cell<time::Duration>> {
+    Rc::clone(&self.leeway)
+  }
 
-  pub fn parse(
-    &mut self,
-    potential_token: &'a str,
-    key: &'a PasetoSymmetricKey<V3, Local>,
-  ) -> Result<Value, GenericParserError> {
-    //first we need to verify the token
-    let token =
-      Paseto::<V3, Local>::try_decrypt(potential_token, key, self.get_footer(), self.get_implicit_assertion())?;
+  //the server ceiling on the validity of every token, which the prelude parser's cache recommendation can't pass
+  #[cfg(any(test, feature = "batteries_included"))]
+  pub(crate) fn ceiling(&self) -> Option<OffsetDateTime> {
+    self.not_valid_after
+  }
 
-    self.verify_claims(&token)
+  //the unit the prelude parser's time claim validators read numeric time claims in
+  #[cfg(any(test, feature = "batteries_included"))]
+  pub(crate) fn numeric_time_unit(&self) -> Rc<Cell<Option<NumericTimeUnit>>> {
+    Rc::clone(&self.numeric_time_unit)
   }
-}
 
-#[cfg(feature = "v4_local")]
-impl<'a, 'b> GenericParser<'a, 'b, V4, Local> {
-  /// Given a [PasetoSymmetricKey], attempts to decrypt a (V4, Local) encrypted PASETO token string and then validate
-  /// claims provided to the GenericParser.
+  /// Runs the checks which only depend on the header and footer of the untrusted token, such as the
+  /// [allowed versions](Self::set_allowed_versions), [expected purpose](Self::expect_purpose) and
+  /// [expected key id](Self::expect_key_id), without any cryptography.
   ///
-  /// Returns a serde_json [Value] with the decrypted [claims](PasetoClaim) and payload on success.
+  /// Returns the [UntrustedToken] on success so the caller can route the token, for instance to look up its key.
+  /// Nothing in it has been authenticated. The same checks run again when the token is parsed.
   ///
   /// # Errors
   ///
-  /// Returns [`GenericParserError`] for any errors when decrypting the encrypted payload or when validating claims.
+  /// Returns [`GenericParserError`] if the token is malformed or doesn't meet the parser's header policy.
+  pub fn check_header<'t>(
+    &self,
+    potential_token: &'t (impl AsRef<str> + ?Sized),
+  ) -> Result<UntrustedToken<'t>, GenericParserError> {
+    let untrusted_token = UntrustedToken::try_from(potential_token.as_ref())?;
+    if let Some(allowed_versions) = &self.allowed_versions {
+      if !allowed_versions.contains(&untrusted_token.version()) {
+        return Err(GenericParserError::VersionNotAllowed(untrusted_token.version()));
+      }
+    }
+    if let Some(expected) = self.expected_purpose {
+      if untrusted_token.purpose() != expected {
+        return Err(GenericParserError::UnexpectedPurpose {
+          expected,
+          found: untrusted_token.purpose(),
+        });
+      }
+    }
+    if let Some(expected) = self.expected_key_id {
+      let footer = untrusted_token.footer()?.unwrap_or_default();
+      let footer_key_id = footer.parse::<KeyRoutingFooter>().ok().map(|footer| footer.kid);
+      if footer_key_id.as_deref() != Some(expected) {
+        return Err(GenericParserError::KeyIdMismatch(expected.to_string()));
+      }
+    }
+    Ok(untrusted_token)
+  }
+
+  /// Given a key able to open tokens of this parser's version and purpose, attempts to decrypt or verify the
+  /// untrusted token, validates the claims provided to the GenericParser and deserializes the payload into `T`.
   ///
-  /// # Example
+  /// Returns the claims wrapped in [Verified], which can only be obtained this way and so proves to downstream code
+  /// that validation has taken place.
+  ///
+  /// # Errors
+  ///
+  /// Returns [`GenericParserError`] for any errors when decrypting or verifying the token, when validating claims
+  /// or when the payload doesn't deserialize into `T`.
   ///
+  /// # Example
   ///
   ///```
   ///# #[cfg(all(feature = "generic", feature="v4_local"))]
   ///# {
   ///   use rusty_paseto::generic::*;
-
-  ///     let key = PasetoSymmetricKey::<V4, Local>::from(Key::<32>::from(*b"wubbalubbadubdubwubbalubbadubdub"));
-
-  ///     let footer = Footer::from("some footer");
-  ///     let implicit_assertion = ImplicitAssertion::from("some assertion");
-
-  ///     //create a builder, add some claims and then build the token with the key
-  ///     let token = GenericBuilder::<V4, Local>::default()
-  ///       .set_claim(AudienceClaim::from("customers"))
-  ///       .set_claim(SubjectClaim::from("loyal subjects"))
-  ///       .set_claim(IssuerClaim::from("me"))
-  ///       .set_claim(TokenIdentifierClaim::from("me"))
-  ///       .set_claim(IssuedAtClaim::try_from("2019-01-01T00:00:00+00:00")?)
-  ///       .set_claim(NotBeforeClaim::try_from("2019-01-01T00:00:00+00:00")?)
-  ///       .set_claim(ExpirationClaim::try_from("2019-01-01T00:00:00+00:00")?)
-  ///       .set_claim(CustomClaim::try_from(("data", "this is a secret message"))?)
-  ///       .set_claim(CustomClaim::try_from(("seats", 4))?)
-  ///       .set_claim(CustomClaim::try_from(("pi to 6 digits", 3.141526))?)
-  ///       .set_footer(footer)
-  ///       .set_implicit_assertion(implicit_assertion)
-  ///       .try_encrypt(&key)?;
-
-  ///     //now let's decrypt the token and verify the values
-  ///     let json = GenericParser::<V4, Local>::default()
-  ///       .set_footer(footer)
-  ///       .set_implicit_assertion(implicit_assertion)
-  ///       .parse(&token, &key)?;
-
-  ///     assert_eq!(json["aud"], "customers");
-  ///     assert_eq!(json["jti"], "me");
-  ///     assert_eq!(json["iss"], "me");
-  ///     assert_eq!(json["data"], "this is a secret message");
-  ///     assert_eq!(json["exp"], "2019-01-01T00:00:00+00:00");
-  ///     assert_eq!(json["iat"], "2019-01-01T00:00:00+00:00");
-  ///     assert_eq!(json["nbf"], "2019-01-01T00:00:00+00:00");
-  ///     assert_eq!(json["sub"], "loyal subjects");
-  ///     assert_eq!(json["pi to 6 digits"], 3.141526);
-  ///     assert_eq!(json["seats"], 4);
-  ///  # }
-  /// # Ok::<(),anyhow::Error>(())
-  ///   ```
-
-  pub fn parse(
-    &mut self,
-    potential_token: &'a str,
-    key: &'a PasetoSymmetricKey<V4, Local>,
-  ) -> Result<Value, GenericParserError> {
-    //first we need to verify the token
-    let token =
-      Paseto::<V4, Local>::try_decrypt(potential_token, key, self.get_footer(), self.get_implicit_assertion())?;
-
-    self.verify_claims(&token)
+  ///   use serde::Deserialize;
+  ///
+  ///   #[derive(Deserialize)]
+  ///   struct Session {
+  ///     sub: String,
+  ///     seats: u8,
+  ///   }
+  ///
+  ///   //only ever called with claims which passed validation
+  ///   fn admit(session: Verified<Session>) -> u8 {
+  ///     session.seats
+  ///   }
+  ///
+  ///   let key = PasetoSymmetricKey::<V4, Local>::from(Key::<32>::from(*b"wubbalubbadubdubwubbalubbadubdub"));
+  ///
+  ///   let token = GenericBuilder::<V4, Local>::default()
+  ///     .set_claim(SubjectClaim::from("loyal subjects"))
+  ///     .set_claim(CustomClaim::try_from(("seats", 4))?)
+  ///     .try_encrypt(&key)?;
+  ///
+  ///   let session = GenericParser::<V4, Local>::default()
+  ///     .check_claim(SubjectClaim::from("loyal subjects"))
+  ///     .parse_verified::<Session, _>(&token, &key)?;
+  ///
+  ///   assert_eq!(session.sub, "loyal subjects");
+  ///   assert_eq!(admit(session), 4);
+  ///# }
+  ///# Ok::<(),anyhow::Error>(())
+  ///```
+  pub fn parse_verified<T, K>(
+    &self,
+    potential_token: impl AsRef<str>,
+    key: &K,
+  ) -> Result<Verified<T>, GenericParserError>
+  where
+    T: DeserializeOwned,
+    K: PasetoOpeningKey<Version, Purpose>,
+  {
+    let potential_token = potential_token.as_ref();
+    metrics::recorded(
+      self
+        .open_token(potential_token, || {
+          key.try_open(potential_token, self.footer, self.implicit_assertion)
+        })
+        .and_then(|json| Ok(Verified::new(serde_json::from_value(json)?))),
+    )
   }
-}
 
-#[cfg(feature = "v1_public")]
-impl<'a, 'b> GenericParser<'a, 'b, V1, Public> {
-  /// Given a [PasetoAsymmetricPublicKey], attempts to verify a (V1, Public) signed PASETO token string and then validate
-  /// claims provided to the GenericParser.
+  ///Decrypts or verifies the token with the given key and validates its claims like [parse](Self::parse), writing
+  ///the plaintext payload into the given buffer instead of returning a [Value]
   ///
-  /// Returns a serde_json [Value] with the verified [claims](PasetoClaim) and payload on success.
+  ///The buffer is cleared first and only holds the payload once its claims have validated, on failure it is left
+  ///empty.  Reusing one buffer across calls makes for an allocation light verification loop, (V4, Local) tokens are
+  ///decrypted in place so the buffer's capacity is all the payload ever needs once it has grown to the largest
+  ///token.  Validating the claims still parses the payload.
   ///
   /// # Errors
   ///
-  /// Returns [`GenericParserError`] for any errors when decrypting the encrypted payload or when validating claims.
+  /// Returns [`GenericParserError`] for any errors when decrypting or verifying the token or when validating claims.
   ///
   /// # Example
-  ///
   ///```
-  ///# #[cfg(all(feature = "generic", feature="v1_public"))]
+  ///# #[cfg(all(feature = "generic", feature="v4_local"))]
   ///# {
-  ///   # use rusty_paseto::generic::*;
-
-  ///    //obtain a private key (pk)
-  ///   # let private_key = include_bytes!("../../../tests/v1_public_test_vectors_private_key.pk8");
-  ///   # let pk: &[u8] = private_key;
-  ///    let private_key = PasetoAsymmetricPrivateKey::<V1, Public>::from(pk);
-
-  ///     let footer = Footer::from("some footer");
-
-  ///     //sign a public V1 token
-  ///     let token = GenericBuilder::<V1, Public>::default()
-  ///       .set_claim(AudienceClaim::from("customers"))
-  ///       .set_claim(SubjectClaim::from("loyal subjects"))
-  ///       .set_claim(IssuerClaim::from("me"))
-  ///       .set_claim(TokenIdentifierClaim::from("me"))
-  ///       .set_claim(IssuedAtClaim::try_from("2019-01-01T00:00:00+00:00")?)
-  ///       .set_claim(NotBeforeClaim::try_from("2019-01-01T00:00:00+00:00")?)
-  ///       .set_claim(ExpirationClaim::try_from("2019-01-01T00:00:00+00:00")?)
-  ///       .set_claim(CustomClaim::try_from(("data", "this is a secret message"))?)
-  ///       .set_claim(CustomClaim::try_from(("seats", 4))?)
-  ///       .set_claim(CustomClaim::try_from(("pi to 6 digits", 3.141526))?)
-  ///       .set_footer(footer)
-  ///       .try_sign(&private_key)?;
-
-  /// //obtain a public key (pubk)
-  ///   #  let public_key = include_bytes!("../../../tests/v1_public_test_vectors_public_key.der");
-  ///   #  let pubk: &[u8] = public_key;
-  ///     let public_key = PasetoAsymmetricPublicKey::<V1, Public>::from(pubk);
-  ///     //now let's try to verify it
-  ///     let json = GenericParser::<V1, Public>::default()
-  ///       .set_footer(footer)
-  ///       .check_claim(AudienceClaim::from("customers"))
-  ///       .check_claim(SubjectClaim::from("loyal subjects"))
-  ///       .check_claim(IssuerClaim::from("me"))
-  ///       .check_claim(TokenIdentifierClaim::from("me"))
-  ///       .check_claim(IssuedAtClaim::try_from("2019-01-01T00:00:00+00:00")?)
-  ///       .check_claim(NotBeforeClaim::try_from("2019-01-01T00:00:00+00:00")?)
-  ///       .check_claim(ExpirationClaim::try_from("2019-01-01T00:00:00+00:00")?)
-  ///       .check_claim(CustomClaim::try_from(("data", "this is a secret message"))?)
-  ///       .check_claim(CustomClaim::try_from(("seats", 4))?)
-  ///       .check_claim(CustomClaim::try_from(("pi to 6 digits", 3.141526))?)
-  ///       .parse(&token, &public_key)?;
-  ///     // we can access all the values from the serde Value object returned by the parser
-  ///     assert_eq!(json["aud"], "customers");
-  ///     assert_eq!(json["jti"], "me");
-  ///     assert_eq!(json["iss"], "me");
-  ///     assert_eq!(json["data"], "this is a secret message");
-  ///     assert_eq!(json["exp"], "2019-01-01T00:00:00+00:00");
-  ///     assert_eq!(json["iat"], "2019-01-01T00:00:00+00:00");
-  ///     assert_eq!(json["nbf"], "2019-01-01T00:00:00+00:00");
-  ///     assert_eq!(json["sub"], "loyal subjects");
-  ///     assert_eq!(json["pi to 6 digits"], 3.141526);
-  ///     assert_eq!(json["seats"], 4);
-  ///  # }
-  /// # Ok::<(),anyhow::Error>(())
+  ///   use rusty_paseto::generic::*;
+  ///
+  ///   let key = PasetoSymmetricKey::<V4, Local>::from(Key::<32>::from(*b"wubbalubbadubdubwubbalubbadubdub"));
+  ///   let tokens = (0..3)
+  ///     .map(|seat| GenericBuilder::<V4, Local>::default().set_claim(CustomClaim::try_from(("seat", seat))?).try_encrypt(&key))
+  ///     .collect::<Result<Vec<_>, _>>()?;
+  ///
+  ///   let parser = GenericParser::<V4, Local>::default();
+  ///   let mut payload = Vec::with_capacity(256);
+  ///   for (seat, token) in tokens.iter().enumerate() {
+  ///     parser.parse_into(token, &key, &mut payload)?;
+  ///     assert_eq!(payload, format!(r#"{{"seat":{}}}"#, seat).as_bytes());
+  ///   }
+  ///# }
+  ///# Ok::<(),anyhow::Error>(())
   ///```
+  pub fn parse_into<K>(
+    &self,
+    potential_token: impl AsRef<str>,
+    key: &K,
+    payload: &mut Vec<u8>,
+  ) -> Result<(), GenericParserError>
+  where
+    K: PasetoOpeningKey<Version, Purpose>,
+  {
+    metrics::recorded(self.open_token_into(potential_token.as_ref(), key, payload))
+  }
 
-  pub fn parse(
-    &mut self,
-    potential_token: &'a str,
-    key: &'a PasetoAsymmetricPublicKey<V1, Public>,
-  ) -> Result<Value, GenericParserError> {
-    //first we need to verify the token
-    let token = Paseto::<V1, Public>::try_verify(potential_token, key, self.get_footer())?;
+  ///Verifies the token with the given key like [parse](Self::parse) and returns it re-encoded in canonical form,
+  ///strict unpadded base64url for the payload and footer, so tokens from sources which pad their segments compare
+  ///equal as strings
+  ///
+  ///The canonical token carries exactly the same bytes, only their encoding changes, and it is the canonical token
+  ///which gets verified so the result is always accepted by this parser.
+  ///
+  /// # Errors
+  ///
+  /// Returns [`GenericParserError`] if a segment isn't base64url or the canonical token doesn't parse
+  ///
+  /// # Example
+  ///```
+  ///# #[cfg(all(feature = "generic", feature="v4_local"))]
+  ///# {
+  ///   use rusty_paseto::generic::*;
+  ///
+  ///   let key = PasetoSymmetricKey::<V4, Local>::from(Key::<32>::from(*b"wubbalubbadubdubwubbalubbadubdub"));
+  ///   let token = GenericBuilder::<V4, Local>::default()
+  ///     .set_footer(Footer::from("key-id:1"))
+  ///     .try_encrypt(&key)?;
+  ///
+  ///   //another source padded the footer segment, "key-id:1" takes 11 characters and a pad
+  ///   let padded = format!("{}=", token);
+  ///
+  ///   let normalized = GenericParser::<V4, Local>::default()
+  ///     .set_footer(Footer::from("key-id:1"))
+  ///     .normalize(&padded, &key)?;
+  ///   assert_eq!(normalized, token);
+  ///# }
+  ///# Ok::<(),anyhow::Error>(())
+  ///```
+  pub fn normalize<K>(&self, potential_token: impl AsRef<str>, key: &K) -> Result<String, GenericParserError>
+  where
+    K: PasetoOpeningKey<Version, Purpose>,
+  {
+    let canonical = UntrustedToken::try_from(potential_token.as_ref())?.canonical_string()?;
+    metrics::recorded(
+      self
+        .open_token(&canonical, || {
+          key.try_open(&canonical, self.footer, self.implicit_assertion)
+        })
+        .map(|_| canonical.clone()),
+    )
+  }
 
-    self.verify_claims(&token)
+  ///Decrypts or verifies the token with the given key and validates its claims like [parse](Self::parse), returning
+  ///the [claims_hash] of its claims rather than the claims themselves
+  ///
+  ///Tokens with the same claims give the same hash whatever their nonce or signature, which makes it a key for
+  ///deduplicating tokens by what they say.
+  ///
+  /// # Errors
+  ///
+  /// Returns [`GenericParserError`] for any errors when decrypting or verifying the token or when validating claims.
+  ///
+  /// # Example
+  ///```
+  ///# #[cfg(all(feature = "generic", feature="v4_local"))]
+  ///# {
+  ///   use rusty_paseto::generic::*;
+  ///
+  ///   let key = PasetoSymmetricKey::<V4, Local>::from(Key::<32>::from(*b"wubbalubbadubdubwubbalubbadubdub"));
+  ///   let first = GenericBuilder::<V4, Local>::default()
+  ///     .set_claim(SubjectClaim::from("loyal subjects"))
+  ///     .try_encrypt(&key)?;
+  ///   let second = GenericBuilder::<V4, Local>::default()
+  ///     .set_claim(SubjectClaim::from("loyal subjects"))
+  ///     .try_encrypt(&key)?;
+  ///   assert_ne!(first, second);
+  ///
+  ///   let parser = GenericParser::<V4, Local>::default();
+  ///   assert_eq!(parser.parse_claims_hash(&first, &key)?, parser.parse_claims_hash(&second, &key)?);
+  ///# }
+  ///# Ok::<(),anyhow::Error>(())
+  ///```
+  pub fn parse_claims_hash<K>(&self, potential_token: impl AsRef<str>, key: &K) -> Result<String, GenericParserError>
+  where
+    K: PasetoOpeningKey<Version, Purpose>,
+  {
+    let claims = self.parse_verified::<Value, _>(potential_token, key)?;
+    Ok(claims_hash(&claims))
   }
 }
 
-#[cfg(feature = "v2_public")]
-impl<'a, 'b> GenericParser<'a, 'b, V2, Public> {
-  /// Given a [PasetoAsymmetricPublicKey], attempts to verify a (V2, Public) signed PASETO token string and then validate
-  /// claims provided to the GenericParser.
+impl<'a, 'b, Version> GenericParser<'a, 'b, Version, Public> {
+  ///Verifies a public token with the key a [PublicKeyResolver] supplies for the `kid` of its
+  ///[key routing footer](KeyRoutingFooter) and validates its claims like [parse](Self::parse)
   ///
-  /// Returns a serde_json [Value] with the verified [claims](PasetoClaim) and payload on success.
+  ///The header checks run before the key is resolved.  Unless a footer was [set](Self::set_footer) on the parser, the
+  ///token is verified with its own footer, which the signature authenticates.  Supported for [V2], [V3] and [V4]
+  ///tokens.
   ///
   /// # Errors
   ///
-  /// Returns [`GenericParserError`] for any errors when decrypting the encrypted payload or when validating claims.
+  /// Unless the resolver has a [default key](PublicKeyResolver::resolve_default), returns
+  /// [MissingKeyId](GenericParserError::MissingKeyId) for a token without a footer,
+  /// [MalformedFooter](GenericParserError::MalformedFooter) if it isn't a key routing footer and
+  /// [UnknownKeyId](GenericParserError::UnknownKeyId) if the resolver doesn't know the key.  Returns any other
+  /// [`GenericParserError`] when verifying the token or validating its claims.
   ///
   /// # Example
-  ///
   ///```
-  ///# #[cfg(all(feature = "generic", feature="v1_public"))]
+  ///# #[cfg(all(feature = "generic", feature="v4_public"))]
   ///# {
-  ///   # use rusty_paseto::generic::*;
-
-  ///    //obtain a key
-  /// let private_key = Key::<64>::try_from("b4cbfb43df4ce210727d953e4a713307fa19bb7d9f85041438d9e11b942a37741eb9dbbbbc047c03fd70604e0071f0987e16b28b757225c11f00415d0e20b1a2")?;
-  /// let private_key = PasetoAsymmetricPrivateKey::<V2, Public>::from(&private_key);
-
-  /// let public_key = Key::<32>::try_from("1eb9dbbbbc047c03fd70604e0071f0987e16b28b757225c11f00415d0e20b1a2")?;
-  /// let public_key = PasetoAsymmetricPublicKey::<V2, Public>::from(&public_key);
+  ///   use rusty_paseto::generic::*;
+  ///   use std::collections::HashMap;
+  ///
+  ///   # let private_key = Key::<64>::try_from("b4cbfb43df4ce210727d953e4a713307fa19bb7d9f85041438d9e11b942a37741eb9dbbbbc047c03fd70604e0071f0987e16b28b757225c11f00415d0e20b1a2")?;
+  ///   # let private_key = PasetoAsymmetricPrivateKey::<V4, Public>::from(&private_key);
+  ///   let footer = KeyRoutingFooter::new("2024-06").to_string();
+  ///   let token = GenericBuilder::<V4, Public>::default()
+  ///     .set_claim(SubjectClaim::from("loyal subjects"))
+  ///     .set_footer(Footer::from(footer.as_str()))
+  ///     .try_sign(&private_key)?;
+  ///
+  ///   //the key set fetched from the issuer, kept by id
+  ///   let key_set: HashMap<String, Vec<u8>> = HashMap::from([(
+  ///     "2024-06".to_string(),
+  ///     Key::<32>::try_from("1eb9dbbbbc047c03fd70604e0071f0987e16b28b757225c11f00415d0e20b1a2")?.to_vec(),
+  ///   )]);
+  ///
+  ///   let json = GenericParser::<V4, Public>::default()
+  ///     .parse_with_resolver(&token, &|kid: &str| key_set.get(kid).cloned())?;
+  ///   assert_eq!(json["sub"], "loyal subjects");
+  ///# }
+  ///# Ok::<(),anyhow::Error>(())
+  ///```
+  pub fn parse_with_resolver<R>(
+    &self,
+    potential_token: impl AsRef<str>,
+    resolver: &R,
+  ) -> Result<Value, GenericParserError>
+  where
+    R: PublicKeyResolver<Version> + ?Sized,
+    for<'k> PasetoAsymmetricPublicKey<'k, Version, Public>:
+      TryFrom<&'k [u8], Error = PasetoError> + PasetoOpeningKey<Version, Public>,
+  {
+    metrics::recorded(self.open_resolved_token(potential_token.as_ref(), resolver))
+  }
 
-  /// let footer = Footer::from("some footer");
-
-  /// //sign a public V2 token
-  /// let token = GenericBuilder::<V2, Public>::default()
-  ///   .set_claim(AudienceClaim::from("customers"))
-  ///   .set_claim(SubjectClaim::from("loyal subjects"))
-  ///   .set_claim(IssuerClaim::from("me"))
-  ///   .set_claim(TokenIdentifierClaim::from("me"))
-  ///   .set_claim(IssuedAtClaim::try_from("2019-01-01T00:00:00+00:00")?)
-  ///   .set_claim(NotBeforeClaim::try_from("2019-01-01T00:00:00+00:00")?)
-  ///   .set_claim(ExpirationClaim::try_from("2019-01-01T00:00:00+00:00")?)
-  ///   .set_claim(CustomClaim::try_from(("data", "this is a secret message"))?)
-  ///   .set_claim(CustomClaim::try_from(("seats", 4))?)
-  ///   .set_claim(CustomClaim::try_from(("pi to 6 digits", 3.141526))?)
-  ///   .set_footer(footer)
-  ///   .try_sign(&private_key)?;
-
-  /// //now let's try to verify it
-  /// let json = GenericParser::<V2, Public>::default()
-  ///   .set_footer(footer)
-  ///   .check_claim(AudienceClaim::from("customers"))
-  ///   .check_claim(SubjectClaim::from("loyal subjects"))
-  ///   .check_claim(IssuerClaim::from("me"))
-  ///   .check_claim(TokenIdentifierClaim::from("me"))
-  ///   .check_claim(IssuedAtClaim::try_from("2019-01-01T00:00:00+00:00")?)
-  ///   .check_claim(NotBeforeClaim::try_from("2019-01-01T00:00:00+00:00")?)
-  ///   .check_claim(ExpirationClaim::try_from("2019-01-01T00:00:00+00:00")?)
-  ///   .check_claim(CustomClaim::try_from(("data", "this is a secret message"))?)
-  ///   .check_claim(CustomClaim::try_from(("seats", 4))?)
-  ///   .check_claim(CustomClaim::try_from(("pi to 6 digits", 3.141526))?)
-  ///   .parse(&token, &public_key)?;
-  /// // we can access all the values from the serde Value object returned by the parser
-  /// assert_eq!(json["aud"], "customers");
-  /// assert_eq!(json["jti"], "me");
-  /// assert_eq!(json["iss"], "me");
-  /// assert_eq!(json["data"], "this is a secret message");
-  /// assert_eq!(json["exp"], "2019-01-01T00:00:00+00:00");
-  /// assert_eq!(json["iat"], "2019-01-01T00:00:00+00:00");
-  /// assert_eq!(json["nbf"], "2019-01-01T00:00:00+00:00");
-  /// assert_eq!(json["sub"], "loyal subjects");
-  /// assert_eq!(json["pi to 6 digits"], 3.141526);
-  /// assert_eq!(json["seats"], 4);
-  ///  # }
-  /// # Ok::<(),anyhow::Error>(())
-  ///```
-  pub fn parse(
-    &mut self,
-    potential_token: &'a str,
-    key: &'a PasetoAsymmetricPublicKey<V2, Public>,
-  ) -> Result<Value, GenericParserError> {
-    //first we need to verify the token
-    let token = Paseto::<V2, Public>::try_verify(potential_token, key, self.get_footer())?;
-
-    self.verify_claims(&token)
-  }
-}
-
-#[cfg(feature = "v3_public")]
-impl<'a, 'b> GenericParser<'a, 'b, V3, Public> {
-  /// Given a [PasetoAsymmetricPublicKey], attempts to verify a (V3, Public) signed PASETO token string and then validate
-  /// claims provided to the GenericParser.
-  ///
-  /// Returns a serde_json [Value] with the verified [claims](PasetoClaim) and payload on success.
+  ///Verifies a batch of public tokens signed by several keys like [parse_with_resolver](Self::parse_with_resolver),
+  ///resolving the key of each `kid` only once
   ///
-  /// # Errors
-  ///
-  /// Returns [`GenericParserError`] for any errors when decrypting the encrypted payload or when validating claims.
+  ///The headers and footers of every token are read first, without any cryptography, to group the tokens by the `kid`
+  ///they are routed to.  Each group is then verified with the key resolved once for all of its tokens, which saves
+  ///the lookups of a resolver fetching or decoding keys.  The results are returned in the order of the tokens, a
+  ///token failing to route or verify fails on its own without affecting the others.
   ///
   /// # Example
-  ///
   ///```
-  ///# #[cfg(all(feature = "generic", feature="v3_public"))]
+  ///# #[cfg(all(feature = "generic", feature="v4_public"))]
   ///# {
-  ///   # use rusty_paseto::generic::*;
+  ///   use rusty_paseto::generic::*;
+  ///
+  ///   # let private_key = Key::<64>::try_from("b4cbfb43df4ce210727d953e4a713307fa19bb7d9f85041438d9e11b942a37741eb9dbbbbc047c03fd70604e0071f0987e16b28b757225c11f00415d0e20b1a2")?;
+  ///   # let private_key = PasetoAsymmetricPrivateKey::<V4, Public>::from(&private_key);
+  ///   # let public_key = Key::<32>::try_from("1eb9dbbbbc047c03fd70604e0071f0987e16b28b757225c11f00415d0e20b1a2")?;
+  ///   # let public_key = PasetoAsymmetricPublicKey::<V4, Public>::from(&public_key);
+  ///   let footer = KeyRoutingFooter::new("2024-06").to_string();
+  ///   let token = GenericBuilder::<V4, Public>::default()
+  ///     .set_claim(SubjectClaim::from("loyal subjects"))
+  ///     .set_footer(Footer::from(footer.as_str()))
+  ///     .try_sign(&private_key)?;
+  ///
+  ///   let keyring = Keyring::new().with_key("2024-06", &public_key);
+  ///   let results = GenericParser::<V4, Public>::default()
+  ///     .parse_batch_with_resolver(&[token.as_str(), "not a token", token.as_str()], &keyring);
+  ///   assert!(results[0].is_ok() && results[1].is_err() && results[2].is_ok());
+  ///# }
+  ///# Ok::<(),anyhow::Error>(())
+  ///```
+  pub fn parse_batch_with_resolver<T, R>(&self, tokens: &[T], resolver: &R) -> Vec<Result<Value, GenericParserError>>
+  where
+    T: AsRef<str>,
+    R: PublicKeyResolver<Version> + ?Sized,
+    for<'k> PasetoAsymmetricPublicKey<'k, Version, Public>:
+      TryFrom<&'k [u8], Error = PasetoError> + PasetoOpeningKey<Version, Public>,
+  {
+    let mut results = tokens.iter().map(|_| None).collect::<Vec<_>>();
+
+    //peek at every footer first to group the tokens by key id, there is no cryptography involved yet
+    let mut groups = HashMap::<Option<String>, Vec<_>>::new();
+    for (index, token) in tokens.iter().enumerate() {
+      match self.check_header(token.as_ref()) {
+        Ok(untrusted_token) => {
+          let routing = KeyRoutingFooter::from_untrusted_token(&untrusted_token);
+          let key_id = routing
+            .as_ref()
+            .ok()
+            .and_then(|footer| footer.as_ref().map(|footer| footer.kid.clone()));
+          groups
+            .entry(key_id)
+            .or_default()
+            .push((index, untrusted_token, routing));
+        }
+        Err(error) => results[index] = Some(metrics::recorded(Err(error))),
+      }
+    }
 
-  ///    //obtain a key
-  /// let private_key = Key::<48>::try_from(
-  ///   "20347609607477aca8fbfbc5e6218455f3199669792ef8b466faa87bdc67798144c848dd03661eed5ac62461340cea96",
-  /// )?;
-  /// let private_key = PasetoAsymmetricPrivateKey::<V3, Public>::from(&private_key);
+    //then resolve the key of each group once for all of its tokens
+    for (key_id, group) in groups {
+      let key_bytes = key_id
+        .as_deref()
+        .and_then(|key_id| resolver.resolve(key_id))
+        .or_else(|| resolver.resolve_default());
+      for (index, untrusted_token, routing) in group {
+        results[index] = Some(metrics::recorded(self.open_routed_token(
+          tokens[index].as_ref(),
+          &untrusted_token,
+          routing,
+          key_bytes.as_ref(),
+        )));
+      }
+    }
+    //every token was either rejected by its header or verified within its group
+    results.into_iter().flatten().collect()
+  }
 
-  /// let public_key = Key::<49>::try_from(
-  ///   "02fbcb7c69ee1c60579be7a334134878d9c5c5bf35d552dab63c0140397ed14cef637d7720925c44699ea30e72874c72fb",
-  /// )?;
-  /// let public_key = PasetoAsymmetricPublicKey::<V3, Public>::try_from(&public_key)?;
+  fn open_resolved_token<R>(&self, potential_token: &str, resolver: &R) -> Result<Value, GenericParserError>
+  where
+    R: PublicKeyResolver<Version> + ?Sized,
+    for<'k> PasetoAsymmetricPublicKey<'k, Version, Public>:
+      TryFrom<&'k [u8], Error = PasetoError> + PasetoOpeningKey<Version, Public>,
+  {
+    let untrusted_token = self.check_header(potential_token)?;
+    let routing = KeyRoutingFooter::from_untrusted_token(&untrusted_token);
+    let key_bytes = match &routing {
+      Ok(Some(footer)) => resolver.resolve(&footer.kid),
+      _ => None,
+    }
+    .or_else(|| resolver.resolve_default());
+    self.open_routed_token(potential_token, &untrusted_token, routing, key_bytes)
+  }
 
-  /// let footer = Footer::from("some footer");
+  //verifies a token with the key resolved from its routing, a token which can't be routed falls back on the default
+  //key and fails for the reason it can't without one
+  fn open_routed_token<B>(
+    &self,
+    potential_token: &str,
+    untrusted_token: &UntrustedToken<'_>,
+    routing: Result<Option<KeyRoutingFooter>, GenericParserError>,
+    key_bytes: Option<B>,
+  ) -> Result<Value, GenericParserError>
+  where
+    B: AsRef<[u8]>,
+    for<'k> PasetoAsymmetricPublicKey<'k, Version, Public>:
+      TryFrom<&'k [u8], Error = PasetoError> + PasetoOpeningKey<Version, Public>,
+  {
+    let key_bytes = match (key_bytes, routing) {
+      (Some(key_bytes), _) => key_bytes,
+      (None, Ok(Some(footer))) => return Err(GenericParserError::UnknownKeyId(footer.kid)),
+      (None, Ok(None)) => return Err(GenericParserError::MissingKeyId),
+      (None, Err(error)) => return Err(error),
+    };
+    let key = PasetoAsymmetricPublicKey::<Version, Public>::try_from(key_bytes.as_ref())?;
+
+    //the footer was decoded once already for the key id, it is authenticated along with the payload
+    let token_footer = untrusted_token.footer()?.unwrap_or_default();
+    let footer = if self.footer == Footer::default() {
+      Footer::from(token_footer.as_str())
+    } else {
+      self.footer
+    };
+    self.open_token(potential_token, || {
+      key.try_open(potential_token, footer, self.implicit_assertion)
+    })
+  }
+}
 
-  /// let implicit_assertion = ImplicitAssertion::from("some assertion");
-  /// //sign a public V3 token
-  /// let token = GenericBuilder::<V3, Public>::default()
-  ///   .set_claim(AudienceClaim::from("customers"))
-  ///   .set_claim(SubjectClaim::from("loyal subjects"))
-  ///   .set_claim(IssuerClaim::from("me"))
-  ///   .set_claim(TokenIdentifierClaim::from("me"))
-  ///   .set_claim(IssuedAtClaim::try_from("2019-01-01T00:00:00+00:00")?)
-  ///   .set_claim(NotBeforeClaim::try_from("2019-01-01T00:00:00+00:00")?)
-  ///   .set_claim(ExpirationClaim::try_from("2019-01-01T00:00:00+00:00")?)
-  ///   .set_claim(CustomClaim::try_from(("data", "this is a secret message"))?)
-  ///   .set_claim(CustomClaim::try_from(("seats", 4))?)
-  ///   .set_claim(CustomClaim::try_from(("pi to 6 digits", 3.141526))?)
-  ///   .set_footer(footer)
-  ///   .set_implicit_assertion(implicit_assertion)
-  ///   .try_sign(&private_key)?;
+impl<'a, 'b, Version: ImplicitAssertionCapable, Purpose> GenericParser<'a, 'b, Version, Purpose> {
+  ///Sets an optional [ImplicitAssertion] to use during parsing ([V3], [V4] tokens only)
+  pub fn set_implicit_assertion(&mut self, implicit_assertion: ImplicitAssertion<'a>) -> &mut Self {
+    self.implicit_assertion = implicit_assertion;
+    self
+  }
 
-  /// //now let's try to verify it
-  /// let json = GenericParser::<V3, Public>::default()
-  ///   .set_footer(footer)
-  ///   .check_claim(AudienceClaim::from("customers"))
-  ///   .set_implicit_assertion(implicit_assertion)
-  ///   .check_claim(SubjectClaim::from("loyal subjects"))
-  ///   .check_claim(IssuerClaim::from("me"))
-  ///   .check_claim(TokenIdentifierClaim::from("me"))
-  ///   .check_claim(IssuedAtClaim::try_from("2019-01-01T00:00:00+00:00")?)
-  ///   .check_claim(NotBeforeClaim::try_from("2019-01-01T00:00:00+00:00")?)
-  ///   .check_claim(ExpirationClaim::try_from("2019-01-01T00:00:00+00:00")?)
-  ///   .check_claim(CustomClaim::try_from(("data", "this is a secret message"))?)
-  ///   .check_claim(CustomClaim::try_from(("seats", 4))?)
-  ///   .check_claim(CustomClaim::try_from(("pi to 6 digits", 3.141526))?)
-  ///   .parse(&token, &public_key)?;
-  /// // we can access all the values from the serde Value object returned by the parser
-  /// assert_eq!(json["aud"], "customers");
-  /// assert_eq!(json["jti"], "me");
-  /// assert_eq!(json["iss"], "me");
-  /// assert_eq!(json["data"], "this is a secret message");
-  /// assert_eq!(json["exp"], "2019-01-01T00:00:00+00:00");
-  /// assert_eq!(json["iat"], "2019-01-01T00:00:00+00:00");
-  /// assert_eq!(json["nbf"], "2019-01-01T00:00:00+00:00");
-  /// assert_eq!(json["sub"], "loyal subjects");
-  /// assert_eq!(json["pi to 6 digits"], 3.141526);
-  /// assert_eq!(json["seats"], 4);
-  ///  # }
-  /// # Ok::<(),anyhow::Error>(())
-  ///```
+  ///Gets an optional [ImplicitAssertion] to use during parsing ([V3], [V4] tokens only)
+  pub fn get_implicit_assertion(&self) -> ImplicitAssertion<'a> {
+    self.implicit_assertion
+  }
+}
 
-  pub fn parse(
-    &mut self,
-    potential_token: &'a str,
-    key: &'a PasetoAsymmetricPublicKey<V3, Public>,
-  ) -> Result<Value, GenericParserError> {
-    //first we need to verify the token
-    let token =
-      Paseto::<V3, Public>::try_verify(potential_token, key, self.get_footer(), self.get_implicit_assertion())?;
+impl<'a, 'b, Version, Purpose> GenericParser<'a, 'b, Version, Purpose> {
+  //checks which only require the structure of the untrusted token and run before any cryptography
+  fn verify_untrusted_token(&self, potential_token: &str) -> Result<(), GenericParserError> {
+    //without a header policy, malformed tokens are left for the cryptographic core to reject
+    if self.allowed_versions.is_some() || self.expected_purpose.is_some() || self.expected_key_id.is_some() {
+      self.check_header(potential_token)?;
+    }
+    Ok(())
+  }
 
+  //runs the header policy, opens the token with the cryptographic operation of its version and purpose and then
+  //validates the claims of the payload
+  fn open_token(
+    &self,
+    potential_token: &str,
+    open: impl FnOnce() -> Result<String, PasetoError>,
+  ) -> Result<Value, GenericParserError> {
+    self.verify_untrusted_token(potential_token)?;
+    let token = open().map_err(|source| Self::open_error(potential_token, source))?;
     self.verify_claims(&token)
   }
+
+  //like open_token but opens the token into the caller's buffer, which is left empty unless the claims validate
+  fn open_token_into<K>(&self, potential_token: &str, key: &K, payload: &mut Vec<u8>) -> Result<(), GenericParserError>
+  where
+    K: PasetoOpeningKey<Version, Purpose>,
+  {
+    let result = self.verify_untrusted_token(potential_token).and_then(|_| {
+      key
+        .try_open_into(potential_token, self.footer, self.implicit_assertion, payload)
+        .map_err(|source| Self::open_error(potential_token, source))?;
+      //the payload was checked to be UTF-8 when it was opened
+      self.verify_claims(std::str::from_utf8(payload).map_err(PasetoError::from)?)
+    });
+    if result.is_err() {
+      payload.clear();
+    }
+    result.map(|_| ())
+  }
+
+  fn open_error(potential_token: &str, source: PasetoError) -> GenericParserError {
+    if source.is_authentication_failure() {
+      //the footer is public, so peeking at it for the error leaks nothing the token doesn't already carry
+      let footer = UntrustedToken::try_from(potential_token)
+        .ok()
+        .and_then(|token| token.footer().ok().flatten());
+      GenericParserError::AuthenticationFailed { source, footer }
+    } else {
+      source.into()
+    }
+  }
+
+  fn verify_policy(&self, policy: &ParserPolicy, json: &Value) -> Result<(), GenericParserError> {
+    policy.validate_at(
+      json,
+      self.now.get().unwrap_or_else(OffsetDateTime::now_utc),
+      self.numeric_time_unit.get(),
+    )
+  }
+
+  fn verify_claims(&self, token: &str) -> Result<Value, GenericParserError> {
+    if let Some(not_valid_after) = self.not_valid_after {
+      if self.now.get().unwrap_or_else(OffsetDateTime::now_utc) > not_valid_after {
+        return Err(GenericParserError::ExceededServerLimit(
+          not_valid_after
+            .format(&Rfc3339)
+            .unwrap_or_else(|_| not_valid_after.to_string()),
+        ));
+      }
+    }
+    //the claims are counted as they are deserialized, before any other check looks at them
+    let json = match parse_bounded(token, self.max_claims)? {
+      BoundedPayload::Parsed(json) => json,
+      BoundedPayload::TooManyClaims(found) => {
+        return Err(GenericParserError::TooManyClaims {
+          found,
+          max: self.max_claims,
+        })
+      }
+    };
+    if !json.is_object() && !self.allow_non_object_payload {
+      return Err(GenericParserError::PayloadNotObject);
+    }
+    if let Some(max_age) = self.max_age {
+      let issued_at = time_claim("iat", &json["iat"], self.numeric_time_unit.get())?
+        .ok_or_else(|| PasetoClaimError::Missing("iat".to_string()))?;
+      let age = self.now.get().unwrap_or_else(OffsetDateTime::now_utc) - issued_at;
+      if age > max_age {
+        return Err(GenericParserError::TokenTooOld { age, max_age });
+      }
+    }
+    if let Some(policy) = &self.policy {
+      self.verify_policy(policy, &json)?;
+    }
+    if let Some(required) = &self.exact_claims {
+      let found = json
+        .as_object()
+        .map(|claims| claims.keys().cloned().collect::<BTreeSet<_>>())
+        .unwrap_or_default();
+      if &found != required {
+        return Err(GenericParserError::ClaimSetMismatch {
+          missing: required.difference(&found).cloned().collect(),
+          unexpected: found.difference(required).cloned().collect(),
+        });
+      }
+    }
+
+    // here we want to traverse all of the claims to validate and verify their values
+    for (key, box_val) in &self.claims {
+      //ensure the claim exists
+      //get the raw value of the claim
+      let raw = serde_json::to_value(box_val)?;
+
+      //now let's run any custom validation if there is any
+      if self.claim_validators.contains_key(key) {
+        let box_validator = &self.claim_validators[key];
+        let validator = box_validator.as_ref();
+        validator(key, &json[&key])?;
+      } else {
+        //otherwise, simply verify the claim exists and matches the value passed in
+        if json[&key] == Value::Null {
+          return Err(PasetoClaimError::Missing(key.to_string()).into());
+        }
+
+        let matches = match (raw[&key].as_str(), json[&key].as_str()) {
+          (Some(expected), Some(found)) if self.case_insensitive_claims.contains(key) => {
+            expected.eq_ignore_ascii_case(found)
+          }
+          _ => raw[&key] == json[&key],
+        };
+        if !matches {
+          return Err(
+            PasetoClaimError::Invalid(
+              key.to_string(),
+              json[&key]
+                .as_str()
+                .ok_or_else(|| PasetoClaimError::Unexpected(key.to_string()))?
+                .into(),
+              raw[&key]
+                .as_str()
+                .ok_or_else(|| PasetoClaimError::Unexpected(key.to_string()))?
+                .into(),
+            )
+            .into(),
+          );
+        }
+      }
+    }
+
+    //then any invariants spanning several claims
+    for validator in &self.cross_claim_validators {
+      validator(&json).map_err(PasetoClaimError::CrossClaimValidation)?;
+    }
+
+    Ok(json)
+  }
 }
 
-#[cfg(feature = "v4_public")]
-impl<'a, 'b> GenericParser<'a, 'b, V4, Public> {
-  /// Given a [PasetoAsymmetricPublicKey], attempts to verify a (V4, Public) signed PASETO token string and then validate
+#[cfg(feature = "v1_local")]
+impl<'a, 'b> GenericParser<'a, 'b, V1, Local> {
+  /// Given a [PasetoSymmetricKey], attempts to decrypt a (V1, Local) encrypted PASETO token string and then validate
   /// claims provided to the GenericParser.
   ///
-  /// Returns a serde_json [Value] with the verified [claims](PasetoClaim) and payload on success.
+  /// Returns a serde_json [Value] with the decrypted [claims](PasetoClaim) and payload on success.
   ///
   /// # Errors
   ///
@@ -752,341 +1121,1754 @@ impl<'a, 'b> GenericParser<'a, 'b, V4, Public> {
   ///
   /// # Example
   ///
+  ///
   ///```
-  ///# #[cfg(all(feature = "generic", feature="v4_public"))]
+  ///# #[cfg(all(feature = "generic", feature="v1_local"))]
   ///# {
-  ///   # use rusty_paseto::generic::*;
-
-  /// //create a key
-  /// let private_key = Key::<64>::try_from("b4cbfb43df4ce210727d953e4a713307fa19bb7d9f85041438d9e11b942a37741eb9dbbbbc047c03fd70604e0071f0987e16b28b757225c11f00415d0e20b1a2")?;
-  /// let pk: &[u8] = private_key.as_slice();
-  /// let private_key = PasetoAsymmetricPrivateKey::<V4, Public>::from(pk);
-
-  /// let public_key = Key::<32>::try_from("1eb9dbbbbc047c03fd70604e0071f0987e16b28b757225c11f00415d0e20b1a2")?;
-  /// let public_key = PasetoAsymmetricPublicKey::<V4, Public>::from(&public_key);
-
-  /// let footer = Footer::from("some footer");
-  /// let implicit_assertion = ImplicitAssertion::from("some assertion");
-
-  /// //sign a public V4 token
-  /// let token = GenericBuilder::<V4, Public>::default()
-  ///   .set_claim(AudienceClaim::from("customers"))
-  ///   .set_claim(SubjectClaim::from("loyal subjects"))
-  ///   .set_claim(IssuerClaim::from("me"))
-  ///   .set_claim(TokenIdentifierClaim::from("me"))
-  ///   .set_claim(IssuedAtClaim::try_from("2019-01-01T00:00:00+00:00")?)
-  ///   .set_claim(NotBeforeClaim::try_from("2019-01-01T00:00:00+00:00")?)
-  ///   .set_claim(ExpirationClaim::try_from("2019-01-01T00:00:00+00:00")?)
-  ///   .set_claim(CustomClaim::try_from(("data", "this is a secret message"))?)
-  ///   .set_claim(CustomClaim::try_from(("seats", 4))?)
-  ///   .set_claim(CustomClaim::try_from(("pi to 6 digits", 3.141526))?)
-  ///   .set_footer(footer)
-  ///   .set_implicit_assertion(implicit_assertion)
-  ///   .try_sign(&private_key)?;
-
-  /// //now let's try to verify it
-  /// let json = GenericParser::<V4, Public>::default()
-  ///   .set_footer(footer)
-  ///   .set_implicit_assertion(implicit_assertion)
-  ///   .check_claim(AudienceClaim::from("customers"))
-  ///   .check_claim(SubjectClaim::from("loyal subjects"))
-  ///   .check_claim(IssuerClaim::from("me"))
-  ///   .check_claim(TokenIdentifierClaim::from("me"))
-  ///   .check_claim(IssuedAtClaim::try_from("2019-01-01T00:00:00+00:00")?)
-  ///   .check_claim(NotBeforeClaim::try_from("2019-01-01T00:00:00+00:00")?)
-  ///   .check_claim(ExpirationClaim::try_from("2019-01-01T00:00:00+00:00")?)
-  ///   .check_claim(CustomClaim::try_from(("data", "this is a secret message"))?)
-  ///   .check_claim(CustomClaim::try_from(("seats", 4))?)
-  ///   .check_claim(CustomClaim::try_from(("pi to 6 digits", 3.141526))?)
-  ///   .parse(&token, &public_key)?;
-  /// // we can access all the values from the serde Value object returned by the parser
-  /// assert_eq!(json["aud"], "customers");
-  /// assert_eq!(json["jti"], "me");
-  /// assert_eq!(json["iss"], "me");
-  /// assert_eq!(json["data"], "this is a secret message");
-  /// assert_eq!(json["exp"], "2019-01-01T00:00:00+00:00");
-  /// assert_eq!(json["iat"], "2019-01-01T00:00:00+00:00");
-  /// assert_eq!(json["nbf"], "2019-01-01T00:00:00+00:00");
-  /// assert_eq!(json["sub"], "loyal subjects");
-  /// assert_eq!(json["pi to 6 digits"], 3.141526);
-  /// assert_eq!(json["seats"], 4);
-
+  ///   use rusty_paseto::generic::*;
+  ///
+  ///     let key = PasetoSymmetricKey::<V1, Local>::from(Key::<32>::from(*b"wubbalubbadubdubwubbalubbadubdub"));
+  ///
+  ///     let footer = Footer::from("some footer");
+  ///
+  ///     //create a builder, add some claims and then build the token with the key
+  ///     let token = GenericBuilder::<V1, Local>::default()
+  ///       .set_claim(AudienceClaim::from("customers"))
+  ///       .set_claim(SubjectClaim::from("loyal subjects"))
+  ///       .set_claim(IssuerClaim::from("me"))
+  ///       .set_claim(TokenIdentifierClaim::from("me"))
+  ///       .set_claim(IssuedAtClaim::try_from("2019-01-01T00:00:00+00:00")?)
+  ///       .set_claim(NotBeforeClaim::try_from("2019-01-01T00:00:00+00:00")?)
+  ///       .set_claim(ExpirationClaim::try_from("2019-01-01T00:00:00+00:00")?)
+  ///       .set_claim(CustomClaim::try_from(("data", "this is a secret message"))?)
+  ///       .set_claim(CustomClaim::try_from(("seats", 4))?)
+  ///       .set_claim(CustomClaim::try_from(("pi to 6 digits", 3.141526))?)
+  ///       .set_footer(footer)
+  ///       .try_encrypt(&key)?;
+  ///
+  ///     //now let's decrypt the token and verify the values
+  ///     let json = GenericParser::<V1, Local>::default()
+  ///       .set_footer(footer)
+  ///       .parse(&token, &key)?;
+  ///
+  ///     assert_eq!(json["aud"], "customers");
+  ///     assert_eq!(json["jti"], "me");
+  ///     assert_eq!(json["iss"], "me");
+  ///     assert_eq!(json["data"], "this is a secret message");
+  ///     assert_eq!(json["exp"], "2019-01-01T00:00:00+00:00");
+  ///     assert_eq!(json["iat"], "2019-01-01T00:00:00+00:00");
+  ///     assert_eq!(json["nbf"], "2019-01-01T00:00:00+00:00");
+  ///     assert_eq!(json["sub"], "loyal subjects");
+  ///     assert_eq!(json["pi to 6 digits"], 3.141526);
+  ///     assert_eq!(json["seats"], 4);
   ///  # }
   /// # Ok::<(),anyhow::Error>(())
+  ///   ```
+  pub fn parse(
+    &self,
+    potential_token: impl AsRef<str>,
+    key: &'a PasetoSymmetricKey<V1, Local>,
+  ) -> Result<Value, GenericParserError> {
+    let potential_token = potential_token.as_ref();
+    metrics::recorded(self.open_token(potential_token, || {
+      Paseto::<V1, Local>::try_decrypt(potential_token, key, self.get_footer())
+    }))
+  }
+}
+
+#[cfg(feature = "v2_local")]
+impl<'a, 'b> GenericParser<'a, 'b, V2, Local> {
+  /// Given a [PasetoSymmetricKey], attempts to decrypt a (V2, Local) encrypted PASETO token string and then validate
+  /// claims provided to the GenericParser.
+  ///
+  /// Returns a serde_json [Value] with the decrypted [claims](PasetoClaim) and payload on success.
+  ///
+  /// # Errors
+  ///
+  /// Returns [`GenericParserError`] for any errors when decrypting the encrypted payload or when validating claims.
+  ///
+  /// # Example
+  ///
+  ///
   ///```
+  ///# #[cfg(all(feature = "generic", feature="v2_local"))]
+  ///# {
+  ///   use rusty_paseto::generic::*;
+  ///
+  ///     let key = PasetoSymmetricKey::<V2, Local>::from(Key::<32>::from(*b"wubbalubbadubdubwubbalubbadubdub"));
+  ///
+  ///     let footer = Footer::from("some footer");
+  ///
+  ///     //create a builder, add some claims and then build the token with the key
+  ///     let token = GenericBuilder::<V2, Local>::default()
+  ///       .set_claim(AudienceClaim::from("customers"))
+  ///       .set_claim(SubjectClaim::from("loyal subjects"))
+  ///       .set_claim(IssuerClaim::from("me"))
+  ///       .set_claim(TokenIdentifierClaim::from("me"))
+  ///       .set_claim(IssuedAtClaim::try_from("2019-01-01T00:00:00+00:00")?)
+  ///       .set_claim(NotBeforeClaim::try_from("2019-01-01T00:00:00+00:00")?)
+  ///       .set_claim(ExpirationClaim::try_from("2019-01-01T00:00:00+00:00")?)
+  ///       .set_claim(CustomClaim::try_from(("data", "this is a secret message"))?)
+  ///       .set_claim(CustomClaim::try_from(("seats", 4))?)
+  ///       .set_claim(CustomClaim::try_from(("pi to 6 digits", 3.141526))?)
+  ///       .set_footer(footer)
+  ///       .try_encrypt(&key)?;
+  ///
+  ///     //now let's decrypt the token and verify the values
+  ///     let json = GenericParser::<V2, Local>::default()
+  ///       .set_footer(footer)
+  ///       .parse(&token, &key)?;
+  ///
+  ///     assert_eq!(json["aud"], "customers");
+  ///     assert_eq!(json["jti"], "me");
+  ///     assert_eq!(json["iss"], "me");
+  ///     assert_eq!(json["data"], "this is a secret message");
+  ///     assert_eq!(json["exp"], "2019-01-01T00:00:00+00:00");
+  ///     assert_eq!(json["iat"], "2019-01-01T00:00:00+00:00");
+  ///     assert_eq!(json["nbf"], "2019-01-01T00:00:00+00:00");
+  ///     assert_eq!(json["sub"], "loyal subjects");
+  ///     assert_eq!(json["pi to 6 digits"], 3.141526);
+  ///     assert_eq!(json["seats"], 4);
+  ///  # }
+  /// # Ok::<(),anyhow::Error>(())
+  ///   ```
+  pub fn parse(
+    &mut self,
+    potential_token: impl AsRef<str>,
+    key: &'a PasetoSymmetricKey<V2, Local>,
+  ) -> Result<Value, GenericParserError> {
+    let potential_token = potential_token.as_ref();
+    metrics::recorded(self.open_token(potential_token, || {
+      Paseto::<V2, Local>::try_decrypt(potential_token, key, self.get_footer())
+    }))
+  }
+}
 
+#[cfg(feature = "v3_local")]
+impl<'a, 'b> GenericParser<'a, 'b, V3, Local> {
+  /// Given a [PasetoSymmetricKey], attempts to decrypt a (V3, Local) encrypted PASETO token string and then validate
+  /// claims provided to the GenericParser.
+  ///
+  /// Returns a serde_json [Value] with the decrypted [claims](PasetoClaim) and payload on success.
+  ///
+  /// # Errors
+  ///
+  /// Returns [`GenericParserError`] for any errors when decrypting the encrypted payload or when validating claims.
+  ///
+  /// # Example
+  ///
+  ///
+  ///```
+  ///# #[cfg(all(feature = "generic", feature="v3_local"))]
+  ///# {
+  ///   use rusty_paseto::generic::*;
+  ///
+  ///     let key = PasetoSymmetricKey::<V3, Local>::from(Key::<32>::from(*b"wubbalubbadubdubwubbalubbadubdub"));
+  ///
+  ///     let footer = Footer::from("some footer");
+  ///     let implicit_assertion = ImplicitAssertion::from("some assertion");
+  ///
+  ///     //create a builder, add some claims and then build the token with the key
+  ///     let token = GenericBuilder::<V3, Local>::default()
+  ///       .set_claim(AudienceClaim::from("customers"))
+  ///       .set_claim(SubjectClaim::from("loyal subjects"))
+  ///       .set_claim(IssuerClaim::from("me"))
+  ///       .set_claim(TokenIdentifierClaim::from("me"))
+  ///       .set_claim(IssuedAtClaim::try_from("2019-01-01T00:00:00+00:00")?)
+  ///       .set_claim(NotBeforeClaim::try_from("2019-01-01T00:00:00+00:00")?)
+  ///       .set_claim(ExpirationClaim::try_from("2019-01-01T00:00:00+00:00")?)
+  ///       .set_claim(CustomClaim::try_from(("data", "this is a secret message"))?)
+  ///       .set_claim(CustomClaim::try_from(("seats", 4))?)
+  ///       .set_claim(CustomClaim::try_from(("pi to 6 digits", 3.141526))?)
+  ///       .set_footer(footer)
+  ///       .set_implicit_assertion(implicit_assertion)
+  ///       .try_encrypt(&key)?;
+  ///
+  ///     //now let's decrypt the token and verify the values
+  ///     let json = GenericParser::<V3, Local>::default()
+  ///       .set_footer(footer)
+  ///       .set_implicit_assertion(implicit_assertion)
+  ///       .parse(&token, &key)?;
+  ///
+  ///     assert_eq!(json["aud"], "customers");
+  ///     assert_eq!(json["jti"], "me");
+  ///     assert_eq!(json["iss"], "me");
+  ///     assert_eq!(json["data"], "this is a secret message");
+  ///     assert_eq!(json["exp"], "2019-01-01T00:00:00+00:00");
+  ///     assert_eq!(json["iat"], "2019-01-01T00:00:00+00:00");
+  ///     assert_eq!(json["nbf"], "2019-01-01T00:00:00+00:00");
+  ///     assert_eq!(json["sub"], "loyal subjects");
+  ///     assert_eq!(json["pi to 6 digits"], 3.141526);
+  ///     assert_eq!(json["seats"], 4);
+  ///  # }
+  /// # Ok::<(),anyhow::Error>(())
+  ///   ```
   pub fn parse(
     &mut self,
-    potential_token: &'a str,
-    key: &'a PasetoAsymmetricPublicKey<V4, Public>,
+    potential_token: impl AsRef<str>,
+    key: &'a PasetoSymmetricKey<V3, Local>,
   ) -> Result<Value, GenericParserError> {
-    //first we need to verify the token
-    let token =
-      Paseto::<V4, Public>::try_verify(potential_token, key, self.get_footer(), self.get_implicit_assertion())?;
+    let potential_token = potential_token.as_ref();
+    metrics::recorded(self.open_token(potential_token, || {
+      Paseto::<V3, Local>::try_decrypt(potential_token, key, self.get_footer(), self.get_implicit_assertion())
+    }))
+  }
+}
+
+#[cfg(feature = "v4_local")]
+impl<'a, 'b> GenericParser<'a, 'b, V4, Local> {
+  /// Given a [PasetoSymmetricKey], attempts to decrypt a (V4, Local) encrypted PASETO token string and then validate
+  /// claims provided to the GenericParser.
+  ///
+  /// Returns a serde_json [Value] with the decrypted [claims](PasetoClaim) and payload on success.
+  ///
+  /// # Errors
+  ///
+  /// Returns [`GenericParserError`] for any errors when decrypting the encrypted payload or when validating claims.
+  ///
+  /// # Example
+  ///
+  ///
+  ///```
+  ///# #[cfg(all(feature = "generic", feature="v4_local"))]
+  ///# {
+  ///   use rusty_paseto::generic::*;
+  ///
+  ///     let key = PasetoSymmetricKey::<V4, Local>::from(Key::<32>::from(*b"wubbalubbadubdubwubbalubbadubdub"));
+  ///
+  ///     let footer = Footer::from("some footer");
+  ///     let implicit_assertion = ImplicitAssertion::from("some assertion");
+  ///
+  ///     //create a builder, add some claims and then build the token with the key
+  ///     let token = GenericBuilder::<V4, Local>::default()
+  ///       .set_claim(AudienceClaim::from("customers"))
+  ///       .set_claim(SubjectClaim::from("loyal subjects"))
+  ///       .set_claim(IssuerClaim::from("me"))
+  ///       .set_claim(TokenIdentifierClaim::from("me"))
+  ///       .set_claim(IssuedAtClaim::try_from("2019-01-01T00:00:00+00:00")?)
+  ///       .set_claim(NotBeforeClaim::try_from("2019-01-01T00:00:00+00:00")?)
+  ///       .set_claim(ExpirationClaim::try_from("2019-01-01T00:00:00+00:00")?)
+  ///       .set_claim(CustomClaim::try_from(("data", "this is a secret message"))?)
+  ///       .set_claim(CustomClaim::try_from(("seats", 4))?)
+  ///       .set_claim(CustomClaim::try_from(("pi to 6 digits", 3.141526))?)
+  ///       .set_footer(footer)
+  ///       .set_implicit_assertion(implicit_assertion)
+  ///       .try_encrypt(&key)?;
+  ///
+  ///     //now let's decrypt the token and verify the values
+  ///     let json = GenericParser::<V4, Local>::default()
+  ///       .set_footer(footer)
+  ///       .set_implicit_assertion(implicit_assertion)
+  ///       .parse(&token, &key)?;
+  ///
+  ///     assert_eq!(json["aud"], "customers");
+  ///     assert_eq!(json["jti"], "me");
+  ///     assert_eq!(json["iss"], "me");
+  ///     assert_eq!(json["data"], "this is a secret message");
+  ///     assert_eq!(json["exp"], "2019-01-01T00:00:00+00:00");
+  ///     assert_eq!(json["iat"], "2019-01-01T00:00:00+00:00");
+  ///     assert_eq!(json["nbf"], "2019-01-01T00:00:00+00:00");
+  ///     assert_eq!(json["sub"], "loyal subjects");
+  ///     assert_eq!(json["pi to 6 digits"], 3.141526);
+  ///     assert_eq!(json["seats"], 4);
+  ///  # }
+  /// # Ok::<(),anyhow::Error>(())
+  ///   ```
+  pub fn parse(
+    &mut self,
+    potential_token: impl AsRef<str>,
+    key: &'a PasetoSymmetricKey<V4, Local>,
+  ) -> Result<Value, GenericParserError> {
+    let potential_token = potential_token.as_ref();
+    metrics::recorded(self.open_token(potential_token, || {
+      Paseto::<V4, Local>::try_decrypt(potential_token, key, self.get_footer(), self.get_implicit_assertion())
+    }))
+  }
+}
+
+#[cfg(feature = "v1_public")]
+impl<'a, 'b> GenericParser<'a, 'b, V1, Public> {
+  /// Given a [PasetoAsymmetricPublicKey], attempts to verify a (V1, Public) signed PASETO token string and then validate
+  /// claims provided to the GenericParser.
+  ///
+  /// Returns a serde_json [Value] with the verified [claims](PasetoClaim) and payload on success.
+  ///
+  /// # Errors
+  ///
+  /// Returns [`GenericParserError`] for any errors when decrypting the encrypted payload or when validating claims.
+  ///
+  /// # Example
+  ///
+  ///```
+  ///# #[cfg(all(feature = "generic", feature="v1_public"))]
+  ///# {
+  ///   # use rusty_paseto::generic::*;
+  ///
+  ///    //obtain a private key (pk)
+  ///   # let private_key = include_bytes!("../../../tests/v1_public_test_vectors_private_key.pk8");
+  ///   # let pk: &[u8] = private_key;
+  ///    let private_key = PasetoAsymmetricPrivateKey::<V1, Public>::from(pk);
+  ///
+  ///     let footer = Footer::from("some footer");
+  ///
+  ///     //sign a public V1 token
+  ///     let token = GenericBuilder::<V1, Public>::default()
+  ///       .set_claim(AudienceClaim::from("customers"))
+  ///       .set_claim(SubjectClaim::from("loyal subjects"))
+  ///       .set_claim(IssuerClaim::from("me"))
+  ///       .set_claim(TokenIdentifierClaim::from("me"))
+  ///       .set_claim(IssuedAtClaim::try_from("2019-01-01T00:00:00+00:00")?)
+  ///       .set_claim(NotBeforeClaim::try_from("2019-01-01T00:00:00+00:00")?)
+  ///       .set_claim(ExpirationClaim::try_from("2019-01-01T00:00:00+00:00")?)
+  ///       .set_claim(CustomClaim::try_from(("data", "this is a secret message"))?)
+  ///       .set_claim(CustomClaim::try_from(("seats", 4))?)
+  ///       .set_claim(CustomClaim::try_from(("pi to 6 digits", 3.141526))?)
+  ///       .set_footer(footer)
+  ///       .try_sign(&private_key)?;
+  ///
+  /// //obtain a public key (pubk)
+  ///   #  let public_key = include_bytes!("../../../tests/v1_public_test_vectors_public_key.der");
+  ///   #  let pubk: &[u8] = public_key;
+  ///     let public_key = PasetoAsymmetricPublicKey::<V1, Public>::from(pubk);
+  ///     //now let's try to verify it
+  ///     let json = GenericParser::<V1, Public>::default()
+  ///       .set_footer(footer)
+  ///       .check_claim(AudienceClaim::from("customers"))
+  ///       .check_claim(SubjectClaim::from("loyal subjects"))
+  ///       .check_claim(IssuerClaim::from("me"))
+  ///       .check_claim(TokenIdentifierClaim::from("me"))
+  ///       .check_claim(IssuedAtClaim::try_from("2019-01-01T00:00:00+00:00")?)
+  ///       .check_claim(NotBeforeClaim::try_from("2019-01-01T00:00:00+00:00")?)
+  ///       .check_claim(ExpirationClaim::try_from("2019-01-01T00:00:00+00:00")?)
+  ///       .check_claim(CustomClaim::try_from(("data", "this is a secret message"))?)
+  ///       .check_claim(CustomClaim::try_from(("seats", 4))?)
+  ///       .check_claim(CustomClaim::try_from(("pi to 6 digits", 3.141526))?)
+  ///       .parse(&token, &public_key)?;
+  ///     // we can access all the values from the serde Value object returned by the parser
+  ///     assert_eq!(json["aud"], "customers");
+  ///     assert_eq!(json["jti"], "me");
+  ///     assert_eq!(json["iss"], "me");
+  ///     assert_eq!(json["data"], "this is a secret message");
+  ///     assert_eq!(json["exp"], "2019-01-01T00:00:00+00:00");
+  ///     assert_eq!(json["iat"], "2019-01-01T00:00:00+00:00");
+  ///     assert_eq!(json["nbf"], "2019-01-01T00:00:00+00:00");
+  ///     assert_eq!(json["sub"], "loyal subjects");
+  ///     assert_eq!(json["pi to 6 digits"], 3.141526);
+  ///     assert_eq!(json["seats"], 4);
+  ///  # }
+  /// # Ok::<(),anyhow::Error>(())
+  ///```
+  pub fn parse(
+    &mut self,
+    potential_token: impl AsRef<str>,
+    key: &'a PasetoAsymmetricPublicKey<V1, Public>,
+  ) -> Result<Value, GenericParserError> {
+    let potential_token = potential_token.as_ref();
+    metrics::recorded(self.open_token(potential_token, || {
+      Paseto::<V1, Public>::try_verify(potential_token, key, self.get_footer())
+    }))
+  }
+}
+
+#[cfg(feature = "v2_public")]
+impl<'a, 'b> GenericParser<'a, 'b, V2, Public> {
+  /// Given a [PasetoAsymmetricPublicKey], attempts to verify a (V2, Public) signed PASETO token string and then validate
+  /// claims provided to the GenericParser.
+  ///
+  /// Returns a serde_json [Value] with the verified [claims](PasetoClaim) and payload on success.
+  ///
+  /// # Errors
+  ///
+  /// Returns [`GenericParserError`] for any errors when decrypting the encrypted payload or when validating claims.
+  ///
+  /// # Example
+  ///
+  ///```
+  ///# #[cfg(all(feature = "generic", feature="v1_public"))]
+  ///# {
+  ///   # use rusty_paseto::generic::*;
+  ///
+  ///    //obtain a key
+  /// let private_key = Key::<64>::try_from("b4cbfb43df4ce210727d953e4a713307fa19bb7d9f85041438d9e11b942a37741eb9dbbbbc047c03fd70604e0071f0987e16b28b757225c11f00415d0e20b1a2")?;
+  /// let private_key = PasetoAsymmetricPrivateKey::<V2, Public>::from(&private_key);
+  ///
+  /// let public_key = Key::<32>::try_from("1eb9dbbbbc047c03fd70604e0071f0987e16b28b757225c11f00415d0e20b1a2")?;
+  /// let public_key = PasetoAsymmetricPublicKey::<V2, Public>::from(&public_key);
+  ///
+  /// let footer = Footer::from("some footer");
+  ///
+  /// //sign a public V2 token
+  /// let token = GenericBuilder::<V2, Public>::default()
+  ///   .set_claim(AudienceClaim::from("customers"))
+  ///   .set_claim(SubjectClaim::from("loyal subjects"))
+  ///   .set_claim(IssuerClaim::from("me"))
+  ///   .set_claim(TokenIdentifierClaim::from("me"))
+  ///   .set_claim(IssuedAtClaim::try_from("2019-01-01T00:00:00+00:00")?)
+  ///   .set_claim(NotBeforeClaim::try_from("2019-01-01T00:00:00+00:00")?)
+  ///   .set_claim(ExpirationClaim::try_from("2019-01-01T00:00:00+00:00")?)
+  ///   .set_claim(CustomClaim::try_from(("data", "this is a secret message"))?)
+  ///   .set_claim(CustomClaim::try_from(("seats", 4))?)
+  ///   .set_claim(CustomClaim::try_from(("pi to 6 digits", 3.141526))?)
+  ///   .set_footer(footer)
+  ///   .try_sign(&private_key)?;
+  ///
+  /// //now let's try to verify it
+  /// let json = GenericParser::<V2, Public>::default()
+  ///   .set_footer(footer)
+  ///   .check_claim(AudienceClaim::from("customers"))
+  ///   .check_claim(SubjectClaim::from("loyal subjects"))
+  ///   .check_claim(IssuerClaim::from("me"))
+  ///   .check_claim(TokenIdentifierClaim::from("me"))
+  ///   .check_claim(IssuedAtClaim::try_from("2019-01-01T00:00:00+00:00")?)
+  ///   .check_claim(NotBeforeClaim::try_from("2019-01-01T00:00:00+00:00")?)
+  ///   .check_claim(ExpirationClaim::try_from("2019-01-01T00:00:00+00:00")?)
+  ///   .check_claim(CustomClaim::try_from(("data", "this is a secret message"))?)
+  ///   .check_claim(CustomClaim::try_from(("seats", 4))?)
+  ///   .check_claim(CustomClaim::try_from(("pi to 6 digits", 3.141526))?)
+  ///   .parse(&token, &public_key)?;
+  /// // we can access all the values from the serde Value object returned by the parser
+  /// assert_eq!(json["aud"], "customers");
+  /// assert_eq!(json["jti"], "me");
+  /// assert_eq!(json["iss"], "me");
+  /// assert_eq!(json["data"], "this is a secret message");
+  /// assert_eq!(json["exp"], "2019-01-01T00:00:00+00:00");
+  /// assert_eq!(json["iat"], "2019-01-01T00:00:00+00:00");
+  /// assert_eq!(json["nbf"], "2019-01-01T00:00:00+00:00");
+  /// assert_eq!(json["sub"], "loyal subjects");
+  /// assert_eq!(json["pi to 6 digits"], 3.141526);
+  /// assert_eq!(json["seats"], 4);
+  ///  # }
+  /// # Ok::<(),anyhow::Error>(())
+  ///```
+  pub fn parse(
+    &mut self,
+    potential_token: impl AsRef<str>,
+    key: &'a PasetoAsymmetricPublicKey<V2, Public>,
+  ) -> Result<Value, GenericParserError> {
+    let potential_token = potential_token.as_ref();
+    metrics::recorded(self.open_token(potential_token, || {
+      Paseto::<V2, Public>::try_verify(potential_token, key, self.get_footer())
+    }))
+  }
+}
+
+#[cfg(feature = "v3_public")]
+impl<'a, 'b> GenericParser<'a, 'b, V3, Public> {
+  /// Given a [PasetoAsymmetricPublicKey], attempts to verify a (V3, Public) signed PASETO token string and then validate
+  /// claims provided to the GenericParser.
+  ///
+  /// Returns a serde_json [Value] with the verified [claims](PasetoClaim) and payload on success.
+  ///
+  /// # Errors
+  ///
+  /// Returns [`GenericParserError`] for any errors when decrypting the encrypted payload or when validating claims.
+  ///
+  /// # Example
+  ///
+  ///```
+  ///# #[cfg(all(feature = "generic", feature="v3_public"))]
+  ///# {
+  ///   # use rusty_paseto::generic::*;
+  ///
+  ///    //obtain a key
+  /// let private_key = Key::<48>::try_from(
+  ///   "20347609607477aca8fbfbc5e6218455f3199669792ef8b466faa87bdc67798144c848dd03661eed5ac62461340cea96",
+  /// )?;
+  /// let private_key = PasetoAsymmetricPrivateKey::<V3, Public>::from(&private_key);
+  ///
+  /// let public_key = Key::<49>::try_from(
+  ///   "02fbcb7c69ee1c60579be7a334134878d9c5c5bf35d552dab63c0140397ed14cef637d7720925c44699ea30e72874c72fb",
+  /// )?;
+  /// let public_key = PasetoAsymmetricPublicKey::<V3, Public>::try_from(&public_key)?;
+  ///
+  /// let footer = Footer::from("some footer");
+  ///
+  /// let implicit_assertion = ImplicitAssertion::from("some assertion");
+  /// //sign a public V3 token
+  /// let token = GenericBuilder::<V3, Public>::default()
+  ///   .set_claim(AudienceClaim::from("customers"))
+  ///   .set_claim(SubjectClaim::from("loyal subjects"))
+  ///   .set_claim(IssuerClaim::from("me"))
+  ///   .set_claim(TokenIdentifierClaim::from("me"))
+  ///   .set_claim(IssuedAtClaim::try_from("2019-01-01T00:00:00+00:00")?)
+  ///   .set_claim(NotBeforeClaim::try_from("2019-01-01T00:00:00+00:00")?)
+  ///   .set_claim(ExpirationClaim::try_from("2019-01-01T00:00:00+00:00")?)
+  ///   .set_claim(CustomClaim::try_from(("data", "this is a secret message"))?)
+  ///   .set_claim(CustomClaim::try_from(("seats", 4))?)
+  ///   .set_claim(CustomClaim::try_from(("pi to 6 digits", 3.141526))?)
+  ///   .set_footer(footer)
+  ///   .set_implicit_assertion(implicit_assertion)
+  ///   .try_sign(&private_key)?;
+  ///
+  /// //now let's try to verify it
+  /// let json = GenericParser::<V3, Public>::default()
+  ///   .set_footer(footer)
+  ///   .check_claim(AudienceClaim::from("customers"))
+  ///   .set_implicit_assertion(implicit_assertion)
+  ///   .check_claim(SubjectClaim::from("loyal subjects"))
+  ///   .check_claim(IssuerClaim::from("me"))
+  ///   .check_claim(TokenIdentifierClaim::from("me"))
+  ///   .check_claim(IssuedAtClaim::try_from("2019-01-01T00:00:00+00:00")?)
+  ///   .check_claim(NotBeforeClaim::try_from("2019-01-01T00:00:00+00:00")?)
+  ///   .check_claim(ExpirationClaim::try_from("2019-01-01T00:00:00+00:00")?)
+  ///   .check_claim(CustomClaim::try_from(("data", "this is a secret message"))?)
+  ///   .check_claim(CustomClaim::try_from(("seats", 4))?)
+  ///   .check_claim(CustomClaim::try_from(("pi to 6 digits", 3.141526))?)
+  ///   .parse(&token, &public_key)?;
+  /// // we can access all the values from the serde Value object returned by the parser
+  /// assert_eq!(json["aud"], "customers");
+  /// assert_eq!(json["jti"], "me");
+  /// assert_eq!(json["iss"], "me");
+  /// assert_eq!(json["data"], "this is a secret message");
+  /// assert_eq!(json["exp"], "2019-01-01T00:00:00+00:00");
+  /// assert_eq!(json["iat"], "2019-01-01T00:00:00+00:00");
+  /// assert_eq!(json["nbf"], "2019-01-01T00:00:00+00:00");
+  /// assert_eq!(json["sub"], "loyal subjects");
+  /// assert_eq!(json["pi to 6 digits"], 3.141526);
+  /// assert_eq!(json["seats"], 4);
+  ///  # }
+  /// # Ok::<(),anyhow::Error>(())
+  ///```
+  pub fn parse(
+    &mut self,
+    potential_token: impl AsRef<str>,
+    key: &'a PasetoAsymmetricPublicKey<V3, Public>,
+  ) -> Result<Value, GenericParserError> {
+    let potential_token = potential_token.as_ref();
+    metrics::recorded(self.open_token(potential_token, || {
+      Paseto::<V3, Public>::try_verify(potential_token, key, self.get_footer(), self.get_implicit_assertion())
+    }))
+  }
+}
+
+#[cfg(feature = "v4_public")]
+impl<'a, 'b> GenericParser<'a, 'b, V4, Public> {
+  /// Given a [PasetoAsymmetricPublicKey], attempts to verify a (V4, Public) signed PASETO token string and then validate
+  /// claims provided to the GenericParser.
+  ///
+  /// Returns a serde_json [Value] with the verified [claims](PasetoClaim) and payload on success.
+  ///
+  /// # Errors
+  ///
+  /// Returns [`GenericParserError`] for any errors when decrypting the encrypted payload or when validating claims.
+  ///
+  /// # Example
+  ///
+  ///```
+  ///# #[cfg(all(feature = "generic", feature="v4_public"))]
+  ///# {
+  ///   # use rusty_paseto::generic::*;
+  ///
+  /// //create a key
+  /// let private_key = Key::<64>::try_from("b4cbfb43df4ce210727d953e4a713307fa19bb7d9f85041438d9e11b942a37741eb9dbbbbc047c03fd70604e0071f0987e16b28b757225c11f00415d0e20b1a2")?;
+  /// let pk: &[u8] = private_key.as_slice();
+  /// let private_key = PasetoAsymmetricPrivateKey::<V4, Public>::try_from(pk)?;
+  ///
+  /// let public_key = Key::<32>::try_from("1eb9dbbbbc047c03fd70604e0071f0987e16b28b757225c11f00415d0e20b1a2")?;
+  /// let public_key = PasetoAsymmetricPublicKey::<V4, Public>::from(&public_key);
+  ///
+  /// let footer = Footer::from("some footer");
+  /// let implicit_assertion = ImplicitAssertion::from("some assertion");
+  ///
+  /// //sign a public V4 token
+  /// let token = GenericBuilder::<V4, Public>::default()
+  ///   .set_claim(AudienceClaim::from("customers"))
+  ///   .set_claim(SubjectClaim::from("loyal subjects"))
+  ///   .set_claim(IssuerClaim::from("me"))
+  ///   .set_claim(TokenIdentifierClaim::from("me"))
+  ///   .set_claim(IssuedAtClaim::try_from("2019-01-01T00:00:00+00:00")?)
+  ///   .set_claim(NotBeforeClaim::try_from("2019-01-01T00:00:00+00:00")?)
+  ///   .set_claim(ExpirationClaim::try_from("2019-01-01T00:00:00+00:00")?)
+  ///   .set_claim(CustomClaim::try_from(("data", "this is a secret message"))?)
+  ///   .set_claim(CustomClaim::try_from(("seats", 4))?)
+  ///   .set_claim(CustomClaim::try_from(("pi to 6 digits", 3.141526))?)
+  ///   .set_footer(footer)
+  ///   .set_implicit_assertion(implicit_assertion)
+  ///   .try_sign(&private_key)?;
+  ///
+  /// //now let's try to verify it
+  /// let json = GenericParser::<V4, Public>::default()
+  ///   .set_footer(footer)
+  ///   .set_implicit_assertion(implicit_assertion)
+  ///   .check_claim(AudienceClaim::from("customers"))
+  ///   .check_claim(SubjectClaim::from("loyal subjects"))
+  ///   .check_claim(IssuerClaim::from("me"))
+  ///   .check_claim(TokenIdentifierClaim::from("me"))
+  ///   .check_claim(IssuedAtClaim::try_from("2019-01-01T00:00:00+00:00")?)
+  ///   .check_claim(NotBeforeClaim::try_from("2019-01-01T00:00:00+00:00")?)
+  ///   .check_claim(ExpirationClaim::try_from("2019-01-01T00:00:00+00:00")?)
+  ///   .check_claim(CustomClaim::try_from(("data", "this is a secret message"))?)
+  ///   .check_claim(CustomClaim::try_from(("seats", 4))?)
+  ///   .check_claim(CustomClaim::try_from(("pi to 6 digits", 3.141526))?)
+  ///   .parse(&token, &public_key)?;
+  /// // we can access all the values from the serde Value object returned by the parser
+  /// assert_eq!(json["aud"], "customers");
+  /// assert_eq!(json["jti"], "me");
+  /// assert_eq!(json["iss"], "me");
+  /// assert_eq!(json["data"], "this is a secret message");
+  /// assert_eq!(json["exp"], "2019-01-01T00:00:00+00:00");
+  /// assert_eq!(json["iat"], "2019-01-01T00:00:00+00:00");
+  /// assert_eq!(json["nbf"], "2019-01-01T00:00:00+00:00");
+  /// assert_eq!(json["sub"], "loyal subjects");
+  /// assert_eq!(json["pi to 6 digits"], 3.141526);
+  /// assert_eq!(json["seats"], 4);
+  ///
+  ///  # }
+  /// # Ok::<(),anyhow::Error>(())
+  ///```
+  pub fn parse(
+    &mut self,
+    potential_token: impl AsRef<str>,
+    key: &'a PasetoAsymmetricPublicKey<V4, Public>,
+  ) -> Result<Value, GenericParserError> {
+    let potential_token = potential_token.as_ref();
+    metrics::recorded(self.open_token(potential_token, || {
+      Paseto::<V4, Public>::try_verify(potential_token, key, self.get_footer(), self.get_implicit_assertion())
+    }))
+  }
+}
+
+impl<'a, 'b, Version, Purpose> Default for GenericParser<'a, 'b, Version, Purpose> {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+#[cfg(all(test, feature = "v2"))]
+mod parsers {
+
+  use std::convert::TryFrom;
+
+  use crate::generic::claims::*;
+  use crate::generic::*;
+  use anyhow::Result;
+  use serde_json::json;
+
+  #[cfg(feature = "public")]
+  #[test]
+  fn full_parser_test_v2_public() -> Result<()> {
+    //create a key
+    let private_key = Key::<64>::try_from("b4cbfb43df4ce210727d953e4a713307fa19bb7d9f85041438d9e11b942a37741eb9dbbbbc047c03fd70604e0071f0987e16b28b757225c11f00415d0e20b1a2")?;
+    let private_key = PasetoAsymmetricPrivateKey::<V2, Public>::from(&private_key);
+
+    let public_key = Key::<32>::try_from("1eb9dbbbbc047c03fd70604e0071f0987e16b28b757225c11f00415d0e20b1a2")?;
+    let public_key = PasetoAsymmetricPublicKey::<V2, Public>::from(&public_key);
+
+    //    let key = Key::<V2, Public>::from(*b"wubbalubbadubdubwubbalubbadubdub");
+    let footer = Footer::from("some footer");
+
+    //create a builder, add some claims and then build the token with the key
+    let token = GenericBuilder::<V2, Public>::default()
+      .set_claim(AudienceClaim::from("customers"))
+      .set_claim(SubjectClaim::from("loyal subjects"))
+      .set_claim(IssuerClaim::from("me"))
+      .set_claim(TokenIdentifierClaim::from("me"))
+      .set_claim(IssuedAtClaim::try_from("2019-01-01T00:00:00+00:00")?)
+      .set_claim(NotBeforeClaim::try_from("2019-01-01T00:00:00+00:00")?)
+      .set_claim(ExpirationClaim::try_from("2019-01-01T00:00:00+00:00")?)
+      .set_claim(CustomClaim::try_from(("data", "this is a secret message"))?)
+      .set_claim(CustomClaim::try_from(("seats", 4))?)
+      .set_claim(CustomClaim::try_from(("pi to 6 digits", 3.141526))?)
+      .set_footer(footer)
+      .try_sign(&private_key)?;
+
+    //now let's decrypt the token and verify the values
+    let json = GenericParser::<V2, Public>::default()
+      .check_claim(AudienceClaim::from("customers"))
+      .check_claim(SubjectClaim::from("loyal subjects"))
+      .check_claim(IssuerClaim::from("me"))
+      .check_claim(TokenIdentifierClaim::from("me"))
+      .check_claim(IssuedAtClaim::try_from("2019-01-01T00:00:00+00:00")?)
+      .check_claim(NotBeforeClaim::try_from("2019-01-01T00:00:00+00:00")?)
+      .check_claim(ExpirationClaim::try_from("2019-01-01T00:00:00+00:00")?)
+      .check_claim(CustomClaim::try_from(("data", "this is a secret message"))?)
+      .check_claim(CustomClaim::try_from(("seats", 4))?)
+      .check_claim(CustomClaim::try_from(("pi to 6 digits", 3.141526))?)
+      .set_footer(footer)
+      .parse(&token, &public_key)?;
+
+    // we can access all the values from the serde Value object returned by the parser
+    assert_eq!(json["aud"], "customers");
+    assert_eq!(json["jti"], "me");
+    assert_eq!(json["iss"], "me");
+    assert_eq!(json["data"], "this is a secret message");
+    assert_eq!(json["exp"], "2019-01-01T00:00:00+00:00");
+    assert_eq!(json["iat"], "2019-01-01T00:00:00+00:00");
+    assert_eq!(json["nbf"], "2019-01-01T00:00:00+00:00");
+    assert_eq!(json["sub"], "loyal subjects");
+    assert_eq!(json["pi to 6 digits"], 3.141526);
+    assert_eq!(json["seats"], 4);
+    Ok(())
+  }
+
+  #[cfg(feature = "local")]
+  #[test]
+  fn full_parser_test() -> Result<()> {
+    //create a key
+    let key = PasetoSymmetricKey::<V2, Local>::from(Key::from(*b"wubbalubbadubdubwubbalubbadubdub"));
+    let footer = Footer::from("some footer");
+
+    //create a builder, add some claims and then build the token with the key
+    let token = GenericBuilder::<V2, Local>::default()
+      .set_claim(AudienceClaim::from("customers"))
+      .set_claim(SubjectClaim::from("loyal subjects"))
+      .set_claim(IssuerClaim::from("me"))
+      .set_claim(TokenIdentifierClaim::from("me"))
+      .set_claim(IssuedAtClaim::try_from("2019-01-01T00:00:00+00:00")?)
+      .set_claim(NotBeforeClaim::try_from("2019-01-01T00:00:00+00:00")?)
+      .set_claim(ExpirationClaim::try_from("2019-01-01T00:00:00+00:00")?)
+      .set_claim(CustomClaim::try_from(("data", "this is a secret message"))?)
+      .set_claim(CustomClaim::try_from(("seats", 4))?)
+      .set_claim(CustomClaim::try_from(("pi to 6 digits", 3.141526))?)
+      .set_footer(footer)
+      .try_encrypt(&key)?;
+
+    //now let's decrypt the token and verify the values
+    let json = GenericParser::<V2, Local>::default()
+      .check_claim(AudienceClaim::from("customers"))
+      .check_claim(SubjectClaim::from("loyal subjects"))
+      .check_claim(IssuerClaim::from("me"))
+      .check_claim(TokenIdentifierClaim::from("me"))
+      .check_claim(IssuedAtClaim::try_from("2019-01-01T00:00:00+00:00")?)
+      .check_claim(NotBeforeClaim::try_from("2019-01-01T00:00:00+00:00")?)
+      .check_claim(ExpirationClaim::try_from("2019-01-01T00:00:00+00:00")?)
+      .check_claim(CustomClaim::try_from(("data", "this is a secret message"))?)
+      .check_claim(CustomClaim::try_from(("seats", 4))?)
+      .check_claim(CustomClaim::try_from(("pi to 6 digits", 3.141526))?)
+      .set_footer(footer)
+      .parse(&token, &key)?;
+
+    // we can access all the values from the serde Value object returned by the parser
+    assert_eq!(json["aud"], "customers");
+    assert_eq!(json["jti"], "me");
+    assert_eq!(json["iss"], "me");
+    assert_eq!(json["data"], "this is a secret message");
+    assert_eq!(json["exp"], "2019-01-01T00:00:00+00:00");
+    assert_eq!(json["iat"], "2019-01-01T00:00:00+00:00");
+    assert_eq!(json["nbf"], "2019-01-01T00:00:00+00:00");
+    assert_eq!(json["sub"], "loyal subjects");
+    assert_eq!(json["pi to 6 digits"], 3.141526);
+    assert_eq!(json["seats"], 4);
+    Ok(())
+  }
+
+  #[cfg(feature = "local")]
+  #[test]
+  fn basic_claim_validation_test() -> Result<()> {
+    //create a key
+
+    let key = PasetoSymmetricKey::<V2, Local>::from(Key::from(*b"wubbalubbadubdubwubbalubbadubdub"));
+    //create a builder, add some claims and then build the token with the key
+    let token = GenericBuilder::<V2, Local>::default()
+      .set_claim(AudienceClaim::from("customers"))
+      .try_encrypt(&key)?;
+
+    //now let's decrypt the token and verify the values
+    let actual_error_kind = format!(
+      "{}",
+      GenericParser::<V2, Local>::default()
+        .check_claim(AudienceClaim::from("not the same customers"))
+        .parse(&token, &key)
+        .unwrap_err()
+    );
+
+    let expected_error_kind =
+      "The claim 'aud' failed validation.  Expected 'customers' but received 'not the same customers'";
+    assert_eq!(expected_error_kind, actual_error_kind);
+
+    Ok(())
+  }
+
+  #[cfg(feature = "local")]
+  #[test]
+  fn claim_custom_validator_test() -> Result<()> {
+    //create a key
+
+    let key = PasetoSymmetricKey::<V2, Local>::from(Key::from(*b"wubbalubbadubdubwubbalubbadubdub"));
+    //create a builder, add some claims and then build the token with the key
+    let token = GenericBuilder::<V2, Local>::default()
+      .set_claim(AudienceClaim::from("customers"))
+      .try_encrypt(&key)?;
+
+    //now let's decrypt the token and verify the values with a custom validation closure
+    let json = GenericParser::<V2, Local>::default()
+      .validate_claim(
+        //no need to provide a value to check against for the claim when we are using
+        //a custom closure since the value will be passed to the closure for evaluation by your
+        //validation function
+        AudienceClaim::default(),
+        &|key, value| {
+          //we receive the value of the claim so we can do whatever we like with it
+          //get the value of the claim
+          let val = value.as_str().ok_or(PasetoClaimError::Unexpected(key.to_string()))?;
+
+          match val {
+            "customers" => Ok(()),
+            _ => Err(PasetoClaimError::Invalid(
+              key.to_string(),
+              String::from("customers"),
+              val.to_string(),
+            )),
+          }
+        },
+      )
+      .parse(&token, &key)?;
+
+    assert_eq!(json["aud"], "customers");
+    Ok(())
+  }
+
+  #[cfg(feature = "local")]
+  #[test]
+  fn claim_custom_validator_failure_test() -> Result<()> {
+    //create a key
+
+    let key = PasetoSymmetricKey::<V2, Local>::from(Key::from(*b"wubbalubbadubdubwubbalubbadubdub"));
+    //create a builder, add some claims and then build the token with the key
+    let token = GenericBuilder::<V2, Local>::default()
+      .set_claim(AudienceClaim::from("customers"))
+      .try_encrypt(&key)?;
+
+    //now let's decrypt the token and verify the values with a custom validation closure
+    let actual_error_kind = format!(
+      "{}",
+      GenericParser::<V2, Local>::default()
+        .validate_claim(
+          //no need to provide a value to check against for the claim when we are using
+          //a custom closure since the value will be passed to the closure for evaluation by your
+          //validation function
+          AudienceClaim::default(),
+          &|key, value| {
+            //we receive the value of the claim so we can do whatever we like with it
+            //get the value of the claim
+            let val = value.as_str().ok_or(PasetoClaimError::Unexpected(key.to_string()))?;
+
+            //let's fail on purpose
+            Err(PasetoClaimError::Invalid(
+              key.to_string(),
+              "".to_string(),
+              val.to_string(),
+            ))
+          }
+        )
+        .parse(&token, &key)
+        .unwrap_err()
+    );
+
+    let expected_error_kind = "The claim 'aud' failed validation.  Expected '' but received 'customers'";
+    assert_eq!(expected_error_kind, actual_error_kind);
+    Ok(())
+  }
+
+  #[cfg(feature = "local")]
+  #[test]
+  fn custom_claim_custom_validator_test() -> Result<()> {
+    //create a key
+
+    let key = PasetoSymmetricKey::<V2, Local>::from(Key::from(*b"wubbalubbadubdubwubbalubbadubdub"));
+    //create a builder, add some claims and then build the token with the key
+    let token = GenericBuilder::<V2, Local>::default()
+      .set_claim(CustomClaim::try_from(("seats", 4))?)
+      .try_encrypt(&key)?;
+
+    //now let's decrypt the token and verify the values with a custom validation closure
+    let actual_error_kind = format!(
+      "{}",
+      GenericParser::<V2, Local>::default()
+        .validate_claim(
+          //no need to provide a value to check against for the claim when we are using
+          //a custom closure since the value will be passed to the closure for evaluation by your
+          //validation function
+          CustomClaim::try_from("seats")?,
+          &|key, value| {
+            //we receive the value of the claim so we can do whatever we like with it
+            //get the value of the claim
+            let val = value.as_u64().ok_or(PasetoClaimError::Unexpected(key.to_string()))?;
+
+            //let's fail on purpose
+            Err(PasetoClaimError::Invalid(
+              key.to_string(),
+              "".to_string(),
+              val.to_string(),
+            ))
+          }
+        )
+        .parse(&token, &key)
+        .unwrap_err()
+    );
+
+    let expected_error_kind = "The claim 'seats' failed validation.  Expected '' but received '4'";
+    assert_eq!(expected_error_kind, actual_error_kind);
+
+    Ok(())
+  }
+
+  #[cfg(feature = "local")]
+  #[test]
+  fn cross_claim_validator_test() -> Result<()> {
+    let key = PasetoSymmetricKey::<V2, Local>::from(Key::from(*b"wubbalubbadubdubwubbalubbadubdub"));
+    let tenant_matches_subject: &CrossClaimValidatorFn = &|claims| {
+      let tenant = claims["tenant_id"].as_str().unwrap_or_default();
+      let subject = claims["sub"].as_str().unwrap_or_default();
+      match subject.split_once(':') {
+        Some((subject_tenant, _)) if subject_tenant == tenant => Ok(()),
+        _ => Err(format!("subject '{}' does not belong to tenant '{}'", subject, tenant)),
+      }
+    };
+
+    let token = GenericBuilder::<V2, Local>::default()
+      .set_claim(SubjectClaim::from("acme:wile.e.coyote"))
+      .set_claim(CustomClaim::try_from(("tenant_id", "acme"))?)
+      .try_encrypt(&key)?;
+    let json = GenericParser::<V2, Local>::default()
+      .validate_across(tenant_matches_subject)
+      .parse(&token, &key)?;
+    assert_eq!(json["tenant_id"], "acme");
+
+    let token = GenericBuilder::<V2, Local>::default()
+      .set_claim(SubjectClaim::from("acme:wile.e.coyote"))
+      .set_claim(CustomClaim::try_from(("tenant_id", "roadrunner-inc"))?)
+      .try_encrypt(&key)?;
+    let error = GenericParser::<V2, Local>::default()
+      .validate_across(tenant_matches_subject)
+      .parse(&token, &key)
+      .unwrap_err();
+    assert_eq!(
+      error.to_string(),
+      "subject 'acme:wile.e.coyote' does not belong to tenant 'roadrunner-inc'"
+    );
+    Ok(())
+  }
+
+  #[cfg(feature = "local")]
+  #[test]
+  fn parse_verified_test() -> Result<()> {
+    #[derive(serde::Deserialize)]
+    struct Claims {
+      sub: String,
+      tenant_id: String,
+    }
+
+    let key = PasetoSymmetricKey::<V2, Local>::from(Key::from(*b"wubbalubbadubdubwubbalubbadubdub"));
+    let token = GenericBuilder::<V2, Local>::default()
+      .set_claim(SubjectClaim::from("acme:wile.e.coyote"))
+      .set_claim(CustomClaim::try_from(("tenant_id", "acme"))?)
+      .try_encrypt(&key)?;
+
+    let claims = GenericParser::<V2, Local>::default()
+      .check_claim(CustomClaim::try_from(("tenant_id", "acme"))?)
+      .parse_verified::<Claims, _>(&token, &key)?;
+    assert_eq!(claims.sub, "acme:wile.e.coyote");
+    assert_eq!(claims.into_inner().tenant_id, "acme");
+
+    //failed validation never produces a verified value
+    assert!(GenericParser::<V2, Local>::default()
+      .check_claim(CustomClaim::try_from(("tenant_id", "roadrunner-inc"))?)
+      .parse_verified::<Claims, _>(&token, &key)
+      .is_err());
+
+    //nor does a payload which doesn't match the expected shape
+    let result = GenericParser::<V2, Local>::default().parse_verified::<Vec<String>, _>(&token, &key);
+    assert!(matches!(result, Err(GenericParserError::PayloadJsonError { .. })));
+    Ok(())
+  }
+
+  #[cfg(feature = "local")]
+  #[test]
+  fn allowed_versions_test() -> Result<()> {
+    let key = PasetoSymmetricKey::<V2, Local>::from(Key::from(*b"wubbalubbadubdubwubbalubbadubdub"));
+    let token = GenericBuilder::<V2, Local>::default()
+      .set_claim(AudienceClaim::from("customers"))
+      .try_encrypt(&key)?;
+
+    let json = GenericParser::<V2, Local>::default()
+      .set_allowed_versions(&[PasetoVersion::V2, PasetoVersion::V4])
+      .parse(&token, &key)?;
+    assert_eq!(json["aud"], "customers");
+
+    let error = GenericParser::<V2, Local>::default()
+      .set_allowed_versions(&[PasetoVersion::V4])
+      .parse(&token, &key)
+      .unwrap_err();
+    assert!(matches!(
+      error,
+      GenericParserError::VersionNotAllowed(PasetoVersion::V2)
+    ));
+
+    //an empty set honors nothing
+    let error = GenericParser::<V2, Local>::default()
+      .set_allowed_versions(&[])
+      .parse(&token, &key)
+      .unwrap_err();
+    assert!(matches!(
+      error,
+      GenericParserError::VersionNotAllowed(PasetoVersion::V2)
+    ));
+    Ok(())
+  }
+
+  #[cfg(feature = "v4_local")]
+  #[test]
+  fn allowed_versions_downgrade_guard_test() -> Result<()> {
+    let v2_key = PasetoSymmetricKey::<V2, Local>::from(Key::from(*b"wubbalubbadubdubwubbalubbadubdub"));
+    let v4_key = PasetoSymmetricKey::<V4, Local>::from(Key::from(*b"wubbalubbadubdubwubbalubbadubdub"));
+    let legacy_token = GenericBuilder::<V2, Local>::default()
+      .set_claim(AudienceClaim::from("customers"))
+      .try_encrypt(&v2_key)?;
+    let token = GenericBuilder::<V4, Local>::default()
+      .set_claim(AudienceClaim::from("customers"))
+      .try_encrypt(&v4_key)?;
+
+    //the v4 verifier's header check decides whether a v2 token goes on to a v2 parser
+    let mut verifier = GenericParser::<V4, Local>::default();
+    verifier.set_allowed_versions(&[PasetoVersion::V2, PasetoVersion::V4]);
+    assert_eq!(verifier.check_header(&legacy_token)?.version(), PasetoVersion::V2);
+    assert_eq!(verifier.check_header(&token)?.version(), PasetoVersion::V4);
+    assert_eq!(verifier.parse(&token, &v4_key)?["aud"], "customers");
+    //allowing a version never lets the v4 parser itself open a v2 token
+    assert!(matches!(
+      verifier.parse(&legacy_token, &v4_key),
+      Err(GenericParserError::CipherError {
+        source: PasetoError::WrongHeader,
+        ..
+      })
+    ));
+
+    verifier.set_allowed_versions(&[PasetoVersion::V4]);
+    assert!(matches!(
+      verifier.check_header(&legacy_token),
+      Err(GenericParserError::VersionNotAllowed(PasetoVersion::V2))
+    ));
+    assert!(verifier.check_header(&token).is_ok());
+    Ok(())
+  }
+
+  #[cfg(all(feature = "local", feature = "public"))]
+  #[test]
+  fn expect_purpose_test() -> Result<()> {
+    let key = PasetoSymmetricKey::<V2, Local>::from(Key::from(*b"wubbalubbadubdubwubbalubbadubdub"));
+    let token = GenericBuilder::<V2, Local>::default()
+      .set_claim(AudienceClaim::from("customers"))
+      .try_encrypt(&key)?;
+
+    let public_key = Key::<32>::try_from("1eb9dbbbbc047c03fd70604e0071f0987e16b28b757225c11f00415d0e20b1a2")?;
+    let public_key = PasetoAsymmetricPublicKey::<V2, Public>::from(&public_key);
+    let mut parser = GenericParser::<V2, Public>::default();
+    parser.expect_purpose(PasetoPurpose::Public);
+
+    //rejected from the header alone
+    let error = parser.check_header(&token).unwrap_err();
+    assert!(matches!(
+      error,
+      GenericParserError::UnexpectedPurpose {
+        expected: PasetoPurpose::Public,
+        found: PasetoPurpose::Local
+      }
+    ));
+    assert!(matches!(
+      parser.parse(&token, &public_key),
+      Err(GenericParserError::UnexpectedPurpose { .. })
+    ));
+
+    let untrusted_token = GenericParser::<V2, Local>::default()
+      .expect_purpose(PasetoPurpose::Local)
+      .check_header(&token)?;
+    assert_eq!(untrusted_token.version(), PasetoVersion::V2);
+    Ok(())
+  }
+
+  #[cfg(feature = "local")]
+  #[test]
+  fn expect_key_id_test() -> Result<()> {
+    let key = PasetoSymmetricKey::<V2, Local>::from(Key::from(*b"wubbalubbadubdubwubbalubbadubdub"));
+    let footer = Footer::from(r#"{"kid":"2024-06"}"#);
+    let token = GenericBuilder::<V2, Local>::default()
+      .set_claim(AudienceClaim::from("customers"))
+      .set_footer(footer)
+      .try_encrypt(&key)?;
+
+    let json = GenericParser::<V2, Local>::default()
+      .set_footer(footer)
+      .expect_key_id("2024-06")
+      .parse(&token, &key)?;
+    assert_eq!(json["aud"], "customers");
+
+    let error = GenericParser::<V2, Local>::default()
+      .set_footer(footer)
+      .expect_key_id("2024-07")
+      .parse(&token, &key)
+      .unwrap_err();
+    assert!(matches!(error, GenericParserError::KeyIdMismatch(ref key_id) if key_id == "2024-07"));
+
+    //tokens without a footer, or without a kid in it, never match
+    let token = GenericBuilder::<V2, Local>::default().try_encrypt(&key)?;
+    let mut parser = GenericParser::<V2, Local>::default();
+    parser.expect_key_id("2024-06");
+    assert!(matches!(
+      parser.check_header(&token),
+      Err(GenericParserError::KeyIdMismatch(_))
+    ));
+
+    let footer = Footer::from("2024-06");
+    let token = GenericBuilder::<V2, Local>::default()
+      .set_footer(footer)
+      .try_encrypt(&key)?;
+    assert!(matches!(
+      parser.check_header(&token),
+      Err(GenericParserError::KeyIdMismatch(_))
+    ));
+    Ok(())
+  }
+
+  #[cfg(feature = "local")]
+  #[test]
+  fn max_claims_test() -> Result<()> {
+    let key = PasetoSymmetricKey::<V2, Local>::from(Key::from(*b"wubbalubbadubdubwubbalubbadubdub"));
+    let claims = (0..GenericParser::<V2, Local>::DEFAULT_MAX_CLAIMS + 1)
+      .map(|i| (format!("claim {}", i), i))
+      .collect::<Vec<_>>();
+
+    let mut builder = GenericBuilder::<V2, Local>::default();
+    for (key, value) in &claims[..3] {
+      builder.set_claim(CustomClaim::try_from((key.as_str(), value))?);
+    }
+    let token = builder.try_encrypt(&key)?;
+    assert!(GenericParser::<V2, Local>::default()
+      .max_claims(3)
+      .parse(&token, &key)
+      .is_ok());
+    let error = GenericParser::<V2, Local>::default()
+      .max_claims(2)
+      .parse(&token, &key)
+      .unwrap_err();
+    assert!(matches!(error, GenericParserError::TooManyClaims { found: 3, max: 2 }));
+
+    //the default limit is generous but still a limit
+    let mut builder = GenericBuilder::<V2, Local>::default();
+    for (key, value) in &claims {
+      builder.set_claim(CustomClaim::try_from((key.as_str(), value))?);
+    }
+    let token = builder.try_encrypt(&key)?;
+    assert!(matches!(
+      GenericParser::<V2, Local>::default().parse(&token, &key),
+      Err(GenericParserError::TooManyClaims { found: 1025, max: 1024 })
+    ));
+
+    //the claims are counted before a stale iat or a failing policy is noticed
+    let token = GenericBuilder::<V2, Local>::default()
+      .set_claim(SubjectClaim::from("loyal subjects"))
+      .set_claim(IssuedAtClaim::try_from("2020-01-01T00:00:00Z")?)
+      .try_encrypt(&key)?;
+    let policy = ParserPolicy {
+      require_expiration: false,
+      required_claims: vec!["aud".to_string()],
+      ..ParserPolicy::default()
+    };
+    let mut parser = GenericParser::<V2, Local>::default();
+    parser.set_policy(&policy);
+    assert!(matches!(
+      parser.parse(&token, &key),
+      Err(GenericParserError::ClaimError {
+        source: PasetoClaimError::Missing(claim)
+      }) if claim == "aud"
+    ));
+    parser.require_fresh(time::Duration::minutes(2));
+    assert!(matches!(
+      parser.parse(&token, &key),
+      Err(GenericParserError::TokenTooOld { .. })
+    ));
+    assert!(matches!(
+      parser.max_claims(1).parse(&token, &key),
+      Err(GenericParserError::TooManyClaims { found: 2, max: 1 })
+    ));
+    Ok(())
+  }
+
+  #[cfg(feature = "local")]
+  #[test]
+  fn authentication_failure_footer_test() -> Result<()> {
+    let key = PasetoSymmetricKey::<V2, Local>::from(Key::from(*b"wubbalubbadubdubwubbalubbadubdub"));
+    let other_key = PasetoSymmetricKey::<V2, Local>::from(Key::from([0u8; 32]));
+    let token = GenericBuilder::<V2, Local>::default()
+      .set_claim(CustomClaim::try_from(("data", "this is a secret message"))?)
+      .set_footer(Footer::from(r#"{"kid":"partner-2024"}"#))
+      .try_encrypt(&key)?;
+
+    let error = GenericParser::<V2, Local>::default()
+      .set_footer(Footer::from(r#"{"kid":"partner-2024"}"#))
+      .parse(&token, &other_key)
+      .unwrap_err();
+    assert!(matches!(
+      &error,
+      GenericParserError::AuthenticationFailed { footer: Some(footer), .. } if footer == r#"{"kid":"partner-2024"}"#
+    ));
+    //only the footer is carried, never anything from the payload
+    assert!(!format!("{:?}", error).contains("secret message"));
+
+    //a footer other than the one the parser expects fails authentication too
+    assert!(matches!(
+      GenericParser::<V2, Local>::default().parse(&token, &key),
+      Err(GenericParserError::AuthenticationFailed { footer: Some(_), .. })
+    ));
+
+    //malformed tokens aren't authentication failures
+    assert!(matches!(
+      GenericParser::<V2, Local>::default().parse("v2.local", &key),
+      Err(GenericParserError::CipherError { .. })
+    ));
+    Ok(())
+  }
+
+  #[cfg(feature = "local")]
+  #[test]
+  fn not_valid_after_test() -> Result<()> {
+    let key = PasetoSymmetricKey::<V2, Local>::from(Key::from(*b"wubbalubbadubdubwubbalubbadubdub"));
+    let token = GenericBuilder::<V2, Local>::default()
+      .set_claim(SubjectClaim::from("loyal subjects"))
+      .try_encrypt(&key)?;
+
+    let tomorrow = time::OffsetDateTime::now_utc() + time::Duration::days(1);
+    assert!(GenericParser::<V2, Local>::default()
+      .not_valid_after(tomorrow)
+      .parse(&token, &key)
+      .is_ok());
+
+    let cap = time::OffsetDateTime::from_unix_timestamp(1_577_836_800)?;
+    let mut parser = GenericParser::<V2, Local>::default();
+    parser.not_valid_after(cap);
+    assert!(matches!(
+      parser.parse(&token, &key),
+      Err(GenericParserError::ExceededServerLimit(not_valid_after)) if not_valid_after == "2020-01-01T00:00:00Z"
+    ));
+
+    //the ceiling follows an injected clock
+    parser.clock().set(Some(cap - time::Duration::seconds(1)));
+    assert!(parser.parse(&token, &key).is_ok());
+    Ok(())
+  }
+
+  #[cfg(feature = "local")]
+  #[test]
+  fn require_fresh_test() -> Result<()> {
+    let key = PasetoSymmetricKey::<V2, Local>::from(Key::from(*b"wubbalubbadubdubwubbalubbadubdub"));
+    let token = GenericBuilder::<V2, Local>::default()
+      .set_claim(IssuedAtClaim::try_from("2020-01-01T00:00:00Z")?)
+      .try_encrypt(&key)?;
+    let issued_at = time::OffsetDateTime::from_unix_timestamp(1_577_836_800)?;
+
+    let mut parser = GenericParser::<V2, Local>::default();
+    parser.require_fresh(time::Duration::minutes(2));
+    parser.clock().set(Some(issued_at + time::Duration::minutes(2)));
+    assert!(parser.parse(&token, &key).is_ok());
+
+    parser.clock().set(Some(issued_at + time::Duration::minutes(3)));
+    assert!(matches!(
+      parser.parse(&token, &key),
+      Err(GenericParserError::TokenTooOld { age, max_age })
+        if age == time::Duration::minutes(3) && max_age == time::Duration::minutes(2)
+    ));
+
+    let token = GenericBuilder::<V2, Local>::default().try_encrypt(&key)?;
+    assert!(matches!(
+      parser.parse(&token, &key),
+      Err(GenericParserError::ClaimError {
+        source: PasetoClaimError::Missing(claim)
+      }) if claim == "iat"
+    ));
+    Ok(())
+  }
+
+  #[cfg(feature = "local")]
+  #[test]
+  fn case_insensitive_audience_test() -> Result<()> {
+    let key = PasetoSymmetricKey::<V2, Local>::from(Key::<32>::from(*b"wubbalubbadubdubwubbalubbadubdub"));
+    let token = GenericBuilder::<V2, Local>::default()
+      .set_claim(AudienceClaim::from("API.Example.com"))
+      .try_encrypt(&key)?;
 
-    self.verify_claims(&token)
+    //exact by default
+    assert!(matches!(
+      GenericParser::<V2, Local>::default()
+        .check_claim(AudienceClaim::from("api.example.com"))
+        .parse(&token, &key),
+      Err(GenericParserError::ClaimError {
+        source: PasetoClaimError::Invalid(..)
+      })
+    ));
+    let json = GenericParser::<V2, Local>::default()
+      .check_claim(AudienceClaim::from("api.example.com").case_insensitive(true))
+      .parse(&token, &key)?;
+    assert_eq!(json["aud"], "API.Example.com");
+    assert!(GenericParser::<V2, Local>::default()
+      .check_claim(AudienceClaim::from("www.example.com").case_insensitive(true))
+      .parse(&token, &key)
+      .is_err());
+
+    //checking the claim again exactly takes the flag back
+    assert!(GenericParser::<V2, Local>::default()
+      .check_claim(AudienceClaim::from("api.example.com").case_insensitive(true))
+      .check_claim(AudienceClaim::from("api.example.com"))
+      .parse(&token, &key)
+      .is_err());
+    Ok(())
   }
-}
 
-impl<'a, 'b, Version, Purpose> Default for GenericParser<'a, 'b, Version, Purpose> {
-  fn default() -> Self {
-    Self::new()
+  #[cfg(feature = "local")]
+  #[test]
+  fn policy_test() -> Result<()> {
+    let key = PasetoSymmetricKey::<V2, Local>::from(Key::from(*b"wubbalubbadubdubwubbalubbadubdub"));
+    let token = GenericBuilder::<V2, Local>::default()
+      .set_claim(SubjectClaim::from("loyal subjects"))
+      .set_claim(IssuedAtClaim::try_from("2020-01-01T00:00:00Z")?)
+      .set_claim(ExpirationClaim::try_from("2020-01-01T12:00:00Z")?)
+      .try_encrypt(&key)?;
+    let non_expiring = GenericBuilder::<V2, Local>::default()
+      .set_claim(SubjectClaim::from("loyal subjects"))
+      .try_encrypt(&key)?;
+
+    //the default policy only requires an expiration
+    let mut parser = GenericParser::<V2, Local>::default();
+    parser.set_policy(&ParserPolicy::default());
+    assert!(parser.parse(&token, &key).is_ok());
+    assert!(matches!(
+      parser.parse(&non_expiring, &key),
+      Err(GenericParserError::ClaimError {
+        source: PasetoClaimError::Missing(claim)
+      }) if claim == "exp"
+    ));
+
+    let policy = ParserPolicy {
+      require_expiration: false,
+      required_claims: vec!["sub".to_string(), "aud".to_string()],
+      ..ParserPolicy::default()
+    };
+    assert!(matches!(
+      GenericParser::<V2, Local>::default().set_policy(&policy).parse(&non_expiring, &key),
+      Err(GenericParserError::ClaimError {
+        source: PasetoClaimError::Missing(claim)
+      }) if claim == "aud"
+    ));
+
+    let mut policy = ParserPolicy {
+      max_validity: Some(time::Duration::hours(12)),
+      ..ParserPolicy::default()
+    };
+    assert!(GenericParser::<V2, Local>::default()
+      .set_policy(&policy)
+      .parse(&token, &key)
+      .is_ok());
+    policy.max_validity = Some(time::Duration::hours(11));
+    assert!(matches!(
+      GenericParser::<V2, Local>::default().set_policy(&policy).parse(&token, &key),
+      Err(GenericParserError::ValidityTooLong { validity, max_validity })
+        if validity == time::Duration::hours(12) && max_validity == time::Duration::hours(11)
+    ));
+
+    //without an issued at claim the validity is counted from now
+    let token = GenericBuilder::<V2, Local>::default()
+      .set_claim(ExpirationClaim::try_from("2020-01-01T12:00:00Z")?)
+      .try_encrypt(&key)?;
+    let mut parser = GenericParser::<V2, Local>::default();
+    parser.set_policy(&policy);
+    parser
+      .clock()
+      .set(Some(time::OffsetDateTime::from_unix_timestamp(1_577_836_800 + 3_600)?));
+    assert!(parser.parse(&token, &key).is_ok());
+
+    policy.allowed_versions = Some(vec![PasetoVersion::V4]);
+    assert!(matches!(
+      parser.set_policy(&policy).parse(&token, &key),
+      Err(GenericParserError::VersionNotAllowed(PasetoVersion::V2))
+    ));
+    Ok(())
   }
-}
 
-#[cfg(all(test, feature = "v2"))]
-mod parsers {
+  #[cfg(feature = "local")]
+  #[test]
+  fn parse_into_test() -> Result<()> {
+    let key = PasetoSymmetricKey::<V2, Local>::from(Key::from(*b"wubbalubbadubdubwubbalubbadubdub"));
+    let token = GenericBuilder::<V2, Local>::default()
+      .set_claim(SubjectClaim::from("loyal subjects"))
+      .try_encrypt(&key)?;
+    let mut payload = b"left over".to_vec();
 
-  use std::convert::TryFrom;
+    GenericParser::<V2, Local>::default().parse_into(&token, &key, &mut payload)?;
+    assert_eq!(payload, br#"{"sub":"loyal subjects"}"#);
 
-  use crate::generic::claims::*;
-  use crate::generic::*;
-  use anyhow::Result;
+    //a payload failing its claims is never handed out
+    assert!(matches!(
+      GenericParser::<V2, Local>::default()
+        .check_claim(SubjectClaim::from("someone else"))
+        .parse_into(&token, &key, &mut payload),
+      Err(GenericParserError::ClaimError { .. })
+    ));
+    assert!(payload.is_empty());
+
+    let other_key = PasetoSymmetricKey::<V2, Local>::from(Key::from([0u8; 32]));
+    payload.extend_from_slice(b"left over");
+    assert!(matches!(
+      GenericParser::<V2, Local>::default().parse_into(&token, &other_key, &mut payload),
+      Err(GenericParserError::AuthenticationFailed { .. })
+    ));
+    assert!(payload.is_empty());
+    Ok(())
+  }
 
   #[cfg(feature = "public")]
   #[test]
-  fn full_parser_test_v2_public() -> Result<()> {
-    //create a key
+  fn parse_batch_with_resolver_test() -> Result<()> {
     let private_key = Key::<64>::try_from("b4cbfb43df4ce210727d953e4a713307fa19bb7d9f85041438d9e11b942a37741eb9dbbbbc047c03fd70604e0071f0987e16b28b757225c11f00415d0e20b1a2")?;
     let private_key = PasetoAsymmetricPrivateKey::<V2, Public>::from(&private_key);
-
     let public_key = Key::<32>::try_from("1eb9dbbbbc047c03fd70604e0071f0987e16b28b757225c11f00415d0e20b1a2")?;
-    let public_key = PasetoAsymmetricPublicKey::<V2, Public>::from(&public_key);
+    let lookups = std::cell::Cell::new(0);
+    let resolver = |kid: &str| {
+      lookups.set(lookups.get() + 1);
+      ["2024-06", "2024-07"].contains(&kid).then(|| public_key.to_vec())
+    };
+    let sign = |kid: &str, subject: &str| {
+      let footer = KeyRoutingFooter::new(kid).to_string();
+      GenericBuilder::<V2, Public>::default()
+        .set_claim(SubjectClaim::from(subject))
+        .set_footer(Footer::from(footer.as_str()))
+        .try_sign(&private_key)
+    };
+    let tokens = vec![
+      sign("2024-06", "first")?,
+      sign("2024-07", "second")?,
+      sign("2019-01", "unknown")?,
+      "not a token".to_string(),
+      sign("2024-06", "fifth")?,
+      sign("2024-07", "sixth")?,
+    ];
+
+    let results = GenericParser::<V2, Public>::default().parse_batch_with_resolver(&tokens, &resolver);
+    //one lookup per key id, not per token
+    assert_eq!(lookups.get(), 3);
+    assert_eq!(results.len(), tokens.len());
+    let subjects = results
+      .iter()
+      .map(|result| result.as_ref().ok().map(|json| json["sub"].clone()))
+      .collect::<Vec<_>>();
+    assert_eq!(
+      subjects,
+      vec![
+        Some("first".into()),
+        Some("second".into()),
+        None,
+        None,
+        Some("fifth".into()),
+        Some("sixth".into())
+      ]
+    );
+    assert!(matches!(&results[2], Err(GenericParserError::UnknownKeyId(kid)) if kid == "2019-01"));
+    Ok(())
+  }
 
-    //    let key = Key::<V2, Public>::from(*b"wubbalubbadubdubwubbalubbadubdub");
-    let footer = Footer::from("some footer");
+  #[cfg(feature = "public")]
+  #[test]
+  fn parse_with_resolver_test() -> Result<()> {
+    let private_key = Key::<64>::try_from("b4cbfb43df4ce210727d953e4a713307fa19bb7d9f85041438d9e11b942a37741eb9dbbbbc047c03fd70604e0071f0987e16b28b757225c11f00415d0e20b1a2")?;
+    let private_key = PasetoAsymmetricPrivateKey::<V2, Public>::from(&private_key);
+    let public_key = Key::<32>::try_from("1eb9dbbbbc047c03fd70604e0071f0987e16b28b757225c11f00415d0e20b1a2")?;
+    let resolver = |kid: &str| (kid == "2024-06").then(|| std::sync::Arc::<[u8]>::from(&public_key[..]));
 
-    //create a builder, add some claims and then build the token with the key
+    let footer = KeyRoutingFooter::new("2024-06").to_string();
     let token = GenericBuilder::<V2, Public>::default()
-      .set_claim(AudienceClaim::from("customers"))
       .set_claim(SubjectClaim::from("loyal subjects"))
-      .set_claim(IssuerClaim::from("me"))
-      .set_claim(TokenIdentifierClaim::from("me"))
-      .set_claim(IssuedAtClaim::try_from("2019-01-01T00:00:00+00:00")?)
-      .set_claim(NotBeforeClaim::try_from("2019-01-01T00:00:00+00:00")?)
-      .set_claim(ExpirationClaim::try_from("2019-01-01T00:00:00+00:00")?)
-      .set_claim(CustomClaim::try_from(("data", "this is a secret message"))?)
-      .set_claim(CustomClaim::try_from(("seats", 4))?)
-      .set_claim(CustomClaim::try_from(("pi to 6 digits", 3.141526))?)
-      .set_footer(footer)
+      .set_footer(Footer::from(footer.as_str()))
       .try_sign(&private_key)?;
-
-    //now let's decrypt the token and verify the values
     let json = GenericParser::<V2, Public>::default()
-      .check_claim(AudienceClaim::from("customers"))
       .check_claim(SubjectClaim::from("loyal subjects"))
-      .check_claim(IssuerClaim::from("me"))
-      .check_claim(TokenIdentifierClaim::from("me"))
-      .check_claim(IssuedAtClaim::try_from("2019-01-01T00:00:00+00:00")?)
-      .check_claim(NotBeforeClaim::try_from("2019-01-01T00:00:00+00:00")?)
-      .check_claim(ExpirationClaim::try_from("2019-01-01T00:00:00+00:00")?)
-      .check_claim(CustomClaim::try_from(("data", "this is a secret message"))?)
-      .check_claim(CustomClaim::try_from(("seats", 4))?)
-      .check_claim(CustomClaim::try_from(("pi to 6 digits", 3.141526))?)
-      .set_footer(footer)
-      .parse(&token, &public_key)?;
-
-    // we can access all the values from the serde Value object returned by the parser
-    assert_eq!(json["aud"], "customers");
-    assert_eq!(json["jti"], "me");
-    assert_eq!(json["iss"], "me");
-    assert_eq!(json["data"], "this is a secret message");
-    assert_eq!(json["exp"], "2019-01-01T00:00:00+00:00");
-    assert_eq!(json["iat"], "2019-01-01T00:00:00+00:00");
-    assert_eq!(json["nbf"], "2019-01-01T00:00:00+00:00");
+      .parse_with_resolver(&token, &resolver)?;
     assert_eq!(json["sub"], "loyal subjects");
-    assert_eq!(json["pi to 6 digits"], 3.141526);
-    assert_eq!(json["seats"], 4);
+
+    //an explicit footer on the parser still has to match
+    assert!(GenericParser::<V2, Public>::default()
+      .set_footer(Footer::from("some footer"))
+      .parse_with_resolver(&token, &resolver)
+      .is_err());
+
+    let footer = KeyRoutingFooter::new("2019-01").to_string();
+    let unknown = GenericBuilder::<V2, Public>::default()
+      .set_footer(Footer::from(footer.as_str()))
+      .try_sign(&private_key)?;
+    assert!(matches!(
+      GenericParser::<V2, Public>::default().parse_with_resolver(&unknown, &resolver),
+      Err(GenericParserError::UnknownKeyId(kid)) if kid == "2019-01"
+    ));
+
+    let no_footer = GenericBuilder::<V2, Public>::default().try_sign(&private_key)?;
+    assert!(matches!(
+      GenericParser::<V2, Public>::default().parse_with_resolver(&no_footer, &resolver),
+      Err(GenericParserError::MissingKeyId)
+    ));
+
+    //a resolved key of the wrong size is rejected rather than trusted
+    let short_key = |_: &str| Some(vec![0u8; 31]);
+    assert!(matches!(
+      GenericParser::<V2, Public>::default().parse_with_resolver(&token, &short_key),
+      Err(GenericParserError::CipherError {
+        source: PasetoError::InvalidKey
+      })
+    ));
     Ok(())
   }
 
   #[cfg(feature = "local")]
   #[test]
-  fn full_parser_test() -> Result<()> {
-    //create a key
+  fn normalize_test() -> Result<()> {
     let key = PasetoSymmetricKey::<V2, Local>::from(Key::from(*b"wubbalubbadubdubwubbalubbadubdub"));
-    let footer = Footer::from("some footer");
-
-    //create a builder, add some claims and then build the token with the key
     let token = GenericBuilder::<V2, Local>::default()
-      .set_claim(AudienceClaim::from("customers"))
       .set_claim(SubjectClaim::from("loyal subjects"))
-      .set_claim(IssuerClaim::from("me"))
-      .set_claim(TokenIdentifierClaim::from("me"))
-      .set_claim(IssuedAtClaim::try_from("2019-01-01T00:00:00+00:00")?)
-      .set_claim(NotBeforeClaim::try_from("2019-01-01T00:00:00+00:00")?)
-      .set_claim(ExpirationClaim::try_from("2019-01-01T00:00:00+00:00")?)
-      .set_claim(CustomClaim::try_from(("data", "this is a secret message"))?)
-      .set_claim(CustomClaim::try_from(("seats", 4))?)
-      .set_claim(CustomClaim::try_from(("pi to 6 digits", 3.141526))?)
-      .set_footer(footer)
+      .set_footer(Footer::from("footer1"))
       .try_encrypt(&key)?;
+    let mut parser = GenericParser::<V2, Local>::default();
+    parser.set_footer(Footer::from("footer1"));
+
+    //the canonical form of a canonical token is itself
+    assert_eq!(parser.normalize(&token, &key)?, token);
+
+    //pad the payload and footer segments the way padded base64 would
+    let pad = |segment: &str| format!("{}{}", segment, "=".repeat((4 - segment.len() % 4) % 4));
+    let segments = token.split('.').collect::<Vec<_>>();
+    let padded = format!("v2.local.{}.{}", pad(segments[2]), pad(segments[3]));
+    assert_ne!(padded, token);
+    assert!(parser.parse(&padded, &key).is_err());
+    assert_eq!(parser.normalize(&padded, &key)?, token);
+
+    //normalizing still verifies
+    let other_key = PasetoSymmetricKey::<V2, Local>::from(Key::from([0u8; 32]));
+    assert!(matches!(
+      parser.normalize(&token, &other_key),
+      Err(GenericParserError::AuthenticationFailed { .. })
+    ));
+    assert!(matches!(
+      parser
+        .check_claim(SubjectClaim::from("someone else"))
+        .normalize(&token, &key),
+      Err(GenericParserError::ClaimError { .. })
+    ));
+    Ok(())
+  }
 
-    //now let's decrypt the token and verify the values
-    let json = GenericParser::<V2, Local>::default()
-      .check_claim(AudienceClaim::from("customers"))
-      .check_claim(SubjectClaim::from("loyal subjects"))
-      .check_claim(IssuerClaim::from("me"))
-      .check_claim(TokenIdentifierClaim::from("me"))
-      .check_claim(IssuedAtClaim::try_from("2019-01-01T00:00:00+00:00")?)
-      .check_claim(NotBeforeClaim::try_from("2019-01-01T00:00:00+00:00")?)
-      .check_claim(ExpirationClaim::try_from("2019-01-01T00:00:00+00:00")?)
-      .check_claim(CustomClaim::try_from(("data", "this is a secret message"))?)
-      .check_claim(CustomClaim::try_from(("seats", 4))?)
-      .check_claim(CustomClaim::try_from(("pi to 6 digits", 3.141526))?)
-      .set_footer(footer)
-      .parse(&token, &key)?;
+  #[cfg(feature = "local")]
+  #[test]
+  fn parse_as_ref_str_test() -> Result<()> {
+    let key = PasetoSymmetricKey::<V2, Local>::from(Key::from(*b"wubbalubbadubdubwubbalubbadubdub"));
+    let token = GenericBuilder::<V2, Local>::default()
+      .set_claim(SubjectClaim::from("loyal subjects"))
+      .try_encrypt(&key)?;
+    let mut parser = GenericParser::<V2, Local>::default();
 
-    // we can access all the values from the serde Value object returned by the parser
-    assert_eq!(json["aud"], "customers");
-    assert_eq!(json["jti"], "me");
-    assert_eq!(json["iss"], "me");
-    assert_eq!(json["data"], "this is a secret message");
-    assert_eq!(json["exp"], "2019-01-01T00:00:00+00:00");
-    assert_eq!(json["iat"], "2019-01-01T00:00:00+00:00");
-    assert_eq!(json["nbf"], "2019-01-01T00:00:00+00:00");
-    assert_eq!(json["sub"], "loyal subjects");
-    assert_eq!(json["pi to 6 digits"], 3.141526);
-    assert_eq!(json["seats"], 4);
+    assert_eq!(parser.parse(token.clone(), &key)?["sub"], "loyal subjects");
+    assert_eq!(
+      parser.parse(std::borrow::Cow::Borrowed(token.as_str()), &key)?["sub"],
+      "loyal subjects"
+    );
+    assert_eq!(parser.check_header(&token)?.version(), PasetoVersion::V2);
+    assert_eq!(parser.check_header(token.as_str())?.purpose(), PasetoPurpose::Local);
     Ok(())
   }
 
   #[cfg(feature = "local")]
   #[test]
-  fn basic_claim_validation_test() -> Result<()> {
-    //create a key
-
+  fn expect_type_test() -> Result<()> {
     let key = PasetoSymmetricKey::<V2, Local>::from(Key::from(*b"wubbalubbadubdubwubbalubbadubdub"));
-    //create a builder, add some claims and then build the token with the key
     let token = GenericBuilder::<V2, Local>::default()
-      .set_claim(AudienceClaim::from("customers"))
+      .set_claim(TokenType::Refresh)
       .try_encrypt(&key)?;
 
-    //now let's decrypt the token and verify the values
-    let actual_error_kind = format!(
-      "{}",
+    assert!(GenericParser::<V2, Local>::default()
+      .expect_type(TokenType::Refresh)
+      .parse(&token, &key)
+      .is_ok());
+    assert!(matches!(
       GenericParser::<V2, Local>::default()
-        .check_claim(AudienceClaim::from("not the same customers"))
-        .parse(&token, &key)
-        .unwrap_err()
-    );
-
-    let expected_error_kind =
-      "The claim 'aud' failed validation.  Expected 'customers' but received 'not the same customers'";
-    assert_eq!(expected_error_kind, actual_error_kind);
+        .expect_type(TokenType::Access)
+        .parse(&token, &key),
+      Err(GenericParserError::ClaimError {
+        source: PasetoClaimError::Invalid(..)
+      })
+    ));
 
+    let token = GenericBuilder::<V2, Local>::default().try_encrypt(&key)?;
+    assert!(matches!(
+      GenericParser::<V2, Local>::default()
+        .expect_type(TokenType::Access)
+        .parse(&token, &key),
+      Err(GenericParserError::ClaimError {
+        source: PasetoClaimError::Missing(claim)
+      }) if claim == "typ"
+    ));
     Ok(())
   }
 
   #[cfg(feature = "local")]
   #[test]
-  fn claim_custom_validator_test() -> Result<()> {
-    //create a key
-
+  fn require_exact_claims_test() -> Result<()> {
     let key = PasetoSymmetricKey::<V2, Local>::from(Key::from(*b"wubbalubbadubdubwubbalubbadubdub"));
-    //create a builder, add some claims and then build the token with the key
     let token = GenericBuilder::<V2, Local>::default()
+      .set_claim(IssuerClaim::from("issuer"))
+      .set_claim(SubjectClaim::from("subject"))
       .set_claim(AudienceClaim::from("customers"))
       .try_encrypt(&key)?;
 
-    //now let's decrypt the token and verify the values with a custom validation closure
-    let json = GenericParser::<V2, Local>::default()
-      .validate_claim(
-        //no need to provide a value to check against for the claim when we are using
-        //a custom closure since the value will be passed to the closure for evaluation by your
-        //validation function
-        AudienceClaim::default(),
-        &|key, value| {
-          //we receive the value of the claim so we can do whatever we like with it
-          //get the value of the claim
-          let val = value.as_str().ok_or(PasetoClaimError::Unexpected(key.to_string()))?;
-
-          match val {
-            "customers" => Ok(()),
-            _ => Err(PasetoClaimError::Invalid(key.to_string(), String::from("customers"), val.to_string()).into()),
-          }
-        },
-      )
-      .parse(&token, &key)?;
-
-    assert_eq!(json["aud"], "customers");
+    //order doesn't matter
+    assert!(GenericParser::<V2, Local>::default()
+      .require_exact_claims(&["aud", "sub", "iss"])
+      .parse(&token, &key)
+      .is_ok());
+
+    let error = GenericParser::<V2, Local>::default()
+      .require_exact_claims(&["iss", "sub"])
+      .parse(&token, &key)
+      .unwrap_err();
+    assert!(matches!(
+      &error,
+      GenericParserError::ClaimSetMismatch { missing, unexpected } if missing.is_empty() && unexpected == &["aud"]
+    ));
+
+    let error = GenericParser::<V2, Local>::default()
+      .require_exact_claims(&["iss", "sub", "aud", "exp", "jti"])
+      .parse(&token, &key)
+      .unwrap_err();
+    assert!(matches!(
+      &error,
+      GenericParserError::ClaimSetMismatch { missing, unexpected } if missing == &["exp", "jti"] && unexpected.is_empty()
+    ));
+    assert_eq!(
+      error.to_string(),
+      r#"The token claims don't match the required set, missing: ["exp", "jti"], unexpected: []"#
+    );
     Ok(())
   }
 
   #[cfg(feature = "local")]
   #[test]
-  fn claim_custom_validator_failure_test() -> Result<()> {
-    //create a key
+  fn non_object_payload_test() -> Result<()> {
+    let key = PasetoSymmetricKey::<V2, Local>::from(Key::from(*b"wubbalubbadubdubwubbalubbadubdub"));
+    let nonce = Key::<32>::from([0u8; 32]);
+    let nonce = PasetoNonce::<V2, Local>::from(&nonce);
+
+    for payload in ["[\"not\",\"claims\"]", "4", "\"exp\"", "null"] {
+      let token = Paseto::<V2, Local>::builder()
+        .set_payload(Payload::from(payload))
+        .try_encrypt(&key, &nonce)?;
+      assert!(
+        matches!(
+          GenericParser::<V2, Local>::default().parse(&token, &key),
+          Err(GenericParserError::PayloadNotObject)
+        ),
+        "{}",
+        payload
+      );
+      let json = GenericParser::<V2, Local>::default()
+        .allow_non_object_payload()
+        .parse(&token, &key)?;
+      assert_eq!(json, serde_json::from_str::<serde_json::Value>(payload)?);
+      //the core decryption doesn't look at the payload
+      assert_eq!(Paseto::<V2, Local>::try_decrypt(&token, &key, None)?, payload);
+    }
+    Ok(())
+  }
 
+  #[cfg(feature = "local")]
+  #[test]
+  fn claims_hash_test() -> Result<()> {
     let key = PasetoSymmetricKey::<V2, Local>::from(Key::from(*b"wubbalubbadubdubwubbalubbadubdub"));
-    //create a builder, add some claims and then build the token with the key
+    let parser = GenericParser::<V2, Local>::default();
+
+    //the same claims in another order, under other nonces and with a float of integral value
+    let mut hashes = Vec::new();
+    for (nonce, payload) in [
+      ([0u8; 32], r#"{"sub":"loyal subjects","seats":4}"#),
+      ([1u8; 32], r#"{"seats":4.0,"sub":"loyal subjects"}"#),
+    ] {
+      let nonce = Key::<32>::from(nonce);
+      let token = Paseto::<V2, Local>::builder()
+        .set_payload(Payload::from(payload))
+        .try_encrypt(&key, &PasetoNonce::<V2, Local>::from(&nonce))?;
+      hashes.push(parser.parse_claims_hash(&token, &key)?);
+    }
+    assert_eq!(hashes[0], hashes[1]);
+    assert_eq!(hashes[0], claims_hash(&json!({"seats": 4, "sub": "loyal subjects"})));
+
     let token = GenericBuilder::<V2, Local>::default()
-      .set_claim(AudienceClaim::from("customers"))
+      .set_claim(SubjectClaim::from("loyal subjects"))
+      .set_claim(CustomClaim::try_from(("seats", 5))?)
       .try_encrypt(&key)?;
+    assert_ne!(parser.parse_claims_hash(&token, &key)?, hashes[0]);
 
-    //now let's decrypt the token and verify the values with a custom validation closure
-    let actual_error_kind = format!(
-      "{}",
+    //the claims are validated before they are hashed
+    assert!(matches!(
       GenericParser::<V2, Local>::default()
-        .validate_claim(
-          //no need to provide a value to check against for the claim when we are using
-          //a custom closure since the value will be passed to the closure for evaluation by your
-          //validation function
-          AudienceClaim::default(),
-          &|key, value| {
-            //we receive the value of the claim so we can do whatever we like with it
-            //get the value of the claim
-            let val = value.as_str().ok_or(PasetoClaimError::Unexpected(key.to_string()))?;
+        .check_claim(SubjectClaim::from("someone else"))
+        .parse_claims_hash(&token, &key),
+      Err(GenericParserError::ClaimError {
+        source: PasetoClaimError::Invalid(..)
+      })
+    ));
+    Ok(())
+  }
 
-            //let's fail on purpose
-            Err(PasetoClaimError::Invalid(key.to_string(), "".to_string(), val.to_string()).into())
-          }
-        )
-        .parse(&token, &key)
-        .unwrap_err()
-    );
+  #[cfg(feature = "local")]
+  #[test]
+  fn claims_btree_test() -> Result<()> {
+    let key = PasetoSymmetricKey::<V2, Local>::from(Key::from(*b"wubbalubbadubdubwubbalubbadubdub"));
+    let token = GenericBuilder::<V2, Local>::default()
+      .set_claim(CustomClaim::try_from(("zebra", 1))?)
+      .set_claim(SubjectClaim::from("loyal subjects"))
+      .set_claim(CustomClaim::try_from(("apple", 2))?)
+      .try_encrypt(&key)?;
 
-    let expected_error_kind = "The claim 'aud' failed validation.  Expected '' but received 'customers'";
-    assert_eq!(expected_error_kind, actual_error_kind);
+    let json = GenericParser::<V2, Local>::default().parse(&token, &key)?;
+    let claims = json.claims_btree()?;
+    assert_eq!(claims.keys().collect::<Vec<_>>(), ["apple", "sub", "zebra"]);
+    assert_eq!(claims["zebra"], 1);
+    assert_eq!(claims["sub"], "loyal subjects");
+
+    assert!(matches!(
+      json!(["not", "claims"]).claims_btree(),
+      Err(GenericParserError::PayloadNotObject)
+    ));
     Ok(())
   }
 
   #[cfg(feature = "local")]
   #[test]
-  fn custom_claim_custom_validator_test() -> Result<()> {
-    //create a key
-
+  fn serialize_error_test() -> Result<()> {
     let key = PasetoSymmetricKey::<V2, Local>::from(Key::from(*b"wubbalubbadubdubwubbalubbadubdub"));
-    //create a builder, add some claims and then build the token with the key
     let token = GenericBuilder::<V2, Local>::default()
-      .set_claim(CustomClaim::try_from(("seats", 4))?)
+      .set_claim(AudienceClaim::from("customers"))
       .try_encrypt(&key)?;
 
-    //now let's decrypt the token and verify the values with a custom validation closure
-    let actual_error_kind = format!(
-      "{}",
-      GenericParser::<V2, Local>::default()
-        .validate_claim(
-          //no need to provide a value to check against for the claim when we are using
-          //a custom closure since the value will be passed to the closure for evaluation by your
-          //validation function
-          CustomClaim::try_from("seats")?,
-          &|key, value| {
-            //we receive the value of the claim so we can do whatever we like with it
-            //get the value of the claim
-            let val = value.as_u64().ok_or(PasetoClaimError::Unexpected(key.to_string()))?;
-
-            //let's fail on purpose
-            Err(PasetoClaimError::Invalid(key.to_string(), "".to_string(), val.to_string()).into())
-          }
-        )
-        .parse(&token, &key)
-        .unwrap_err()
+    let error = GenericParser::<V2, Local>::default()
+      .check_claim(AudienceClaim::from("internal"))
+      .parse(&token, &key)
+      .unwrap_err();
+    assert_eq!(
+      serde_json::to_value(&error)?,
+      json!({
+        "reason": "invalid",
+        "claim": "aud",
+        "expected": "customers",
+        "actual": "internal",
+        "message": "The claim 'aud' failed validation.  Expected 'customers' but received 'internal'"
+      })
+    );
+    assert_eq!(
+      serde_json::to_value(error.redacted())?,
+      json!({ "reason": "invalid", "claim": "aud" })
     );
 
-    let expected_error_kind = "The claim 'seats' failed validation.  Expected '' but received '4'";
-    assert_eq!(expected_error_kind, actual_error_kind);
-
+    let error = GenericParser::<V2, Local>::default()
+      .max_claims(0)
+      .parse(&token, &key)
+      .unwrap_err();
+    assert_eq!(
+      serde_json::to_value(error.redacted())?,
+      json!({ "reason": "too_many_claims" })
+    );
     Ok(())
   }
 
@@ -0,0 +1,142 @@
+use super::{GenericParserError, ParserPolicy, PasetoOpeningKey};
+use crate::core::{Footer, ImplicitAssertion, PasetoPurpose, PasetoVersion, UntrustedToken};
+use serde_json::Value;
+use std::fmt;
+
+/// What [inspect] found out about a token, displayed as a multi-line report for debugging tools
+///
+/// Meant for a person reading it, not for deciding whether to accept the token: the claims are shown whatever the
+/// outcome of the policy, and the time claims aren't checked against the clock.
+#[derive(Debug)]
+pub struct InspectReport {
+  /// The version of the token's header
+  pub version: PasetoVersion,
+  /// The purpose of the token's header
+  pub purpose: PasetoPurpose,
+  /// The decoded footer, if the token has one
+  pub footer: Option<String>,
+  /// The decrypted or verified claims, or why the token couldn't be opened, when a key was given
+  pub claims: Option<Result<Value, GenericParserError>>,
+  /// Whether the claims satisfy the policy, when a policy was given and the token could be opened
+  pub policy: Option<Result<(), GenericParserError>>,
+}
+
+/// Reads the header and footer of a token and, given a key, opens it and checks its claims against the policy
+///
+/// The token is opened with its own footer and without an implicit assertion, so a V3 or V4 token bound to an
+/// implicit assertion is reported as failing to open.  Only the rules of the [ParserPolicy] apply, unlike parsing
+/// neither the expiration nor any other claim is validated.
+///
+/// # Errors
+///
+/// Returns [`GenericParserError`] if the token isn't shaped like a PASETO token or its footer can't be decoded.
+/// Failing to open the token or to satisfy the policy is part of the report instead.
+///
+/// # Example
+/// ```
+/// # #[cfg(all(feature = "generic", feature = "v4_local"))]
+/// # {
+/// use rusty_paseto::generic::*;
+///
+/// let key = PasetoSymmetricKey::<V4, Local>::from(Key::<32>::from(*b"wubbalubbadubdubwubbalubbadubdub"));
+/// let token = GenericBuilder::<V4, Local>::default()
+///   .set_claim(SubjectClaim::from("loyal subjects"))
+///   .set_footer(Footer::from("key-id:1"))
+///   .try_encrypt(&key)?;
+///
+/// let report = inspect(&token, Some(&key), Some(&ParserPolicy::default()))?;
+/// println!("{}", report);
+/// assert!(report.to_string().starts_with("version: v4\npurpose: local\nfooter:  key-id:1\n"));
+///
+/// //without a key only the header and footer are known
+/// let report = inspect(&token, None::<&PasetoSymmetricKey<V4, Local>>, None)?;
+/// assert!(report.claims.is_none());
+/// # }
+/// # Ok::<(),anyhow::Error>(())
+/// ```
+pub fn inspect<Version, Purpose, K>(
+  token: &str,
+  key: Option<&K>,
+  policy: Option<&ParserPolicy>,
+) -> Result<InspectReport, GenericParserError>
+where
+  K: PasetoOpeningKey<Version, Purpose>,
+{
+  let untrusted = UntrustedToken::try_from(token)?;
+  let footer = untrusted.footer()?;
+  let claims = key.map(|key| {
+    let footer = footer.as_deref().map(Footer::from).unwrap_or_default();
+    let payload = key.try_open(token, footer, ImplicitAssertion::default())?;
+    Ok(serde_json::from_str::<Value>(&payload)?)
+  });
+  let policy = match (&claims, policy) {
+    (Some(Ok(claims)), Some(policy)) => Some(policy.validate(claims)),
+    _ => None,
+  };
+  Ok(InspectReport {
+    version: untrusted.version(),
+    purpose: untrusted.purpose(),
+    footer,
+    claims,
+    policy,
+  })
+}
+
+impl fmt::Display for InspectReport {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    writeln!(f, "version: {}", self.version)?;
+    writeln!(f, "purpose: {}", self.purpose)?;
+    writeln!(f, "footer:  {}", self.footer.as_deref().unwrap_or("(none)"))?;
+    match &self.claims {
+      None => writeln!(f, "claims:  (no key given)")?,
+      Some(Err(error)) => writeln!(f, "claims:  (failed to open: {})", error)?,
+      Some(Ok(claims)) => {
+        let claims = serde_json::to_string_pretty(claims).map_err(|_| fmt::Error)?;
+        writeln!(f, "claims:  {}", claims.replace('\n', "\n         "))?;
+      }
+    }
+    match &self.policy {
+      None => writeln!(f, "policy:  (not checked)"),
+      Some(Ok(())) => writeln!(f, "policy:  satisfied"),
+      Some(Err(error)) => writeln!(f, "policy:  violated, {}", error),
+    }
+  }
+}
+
+#[cfg(all(test, feature = "v4_local"))]
+mod unit_tests {
+  use super::*;
+  use crate::generic::*;
+  use anyhow::Result;
+
+  #[test]
+  fn test_inspect_report() -> Result<()> {
+    let key = PasetoSymmetricKey::<V4, Local>::from(Key::from(*b"wubbalubbadubdubwubbalubbadubdub"));
+    let token = GenericBuilder::<V4, Local>::default()
+      .set_claim(SubjectClaim::from("loyal subjects"))
+      .set_footer(Footer::from("key-id:1"))
+      .try_encrypt(&key)?;
+
+    let report = inspect(&token, Some(&key), Some(&ParserPolicy::default()))?;
+    assert_eq!(report.footer.as_deref(), Some("key-id:1"));
+    assert!(matches!(&report.claims, Some(Ok(claims)) if claims["sub"] == "loyal subjects"));
+    assert_eq!(
+      report.to_string(),
+      "version: v4\npurpose: local\nfooter:  key-id:1\nclaims:  {\n           \"sub\": \"loyal subjects\"\n         }\npolicy:  violated, The expected claim 'exp' was not found in the payload\n"
+    );
+
+    let other_key = PasetoSymmetricKey::<V4, Local>::from(Key::from([0u8; 32]));
+    let report = inspect(&token, Some(&other_key), Some(&ParserPolicy::default()))?;
+    assert!(matches!(report.claims, Some(Err(_))));
+    assert!(report.policy.is_none());
+    assert!(report.to_string().contains("claims:  (failed to open: "));
+
+    let report = inspect(&token, None::<&PasetoSymmetricKey<V4, Local>>, None)?;
+    assert!(report
+      .to_string()
+      .ends_with("claims:  (no key given)\npolicy:  (not checked)\n"));
+
+    assert!(inspect("not a token", Some(&key), None).is_err());
+    Ok(())
+  }
+}
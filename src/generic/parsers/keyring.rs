@@ -0,0 +1,140 @@
+use super::PublicKeyResolver;
+use crate::core::{PasetoAsymmetricPublicKey, Public};
+use std::collections::HashMap;
+use std::marker::PhantomData;
+use std::sync::Arc;
+
+/// A set of public keys held in memory, each resolved by the `kid` of a token's
+/// [key routing footer](crate::generic::KeyRoutingFooter)
+///
+/// A [default](Self::with_default) key verifies the tokens which can't be routed: those without a footer or whose
+/// footer carries no `kid`, and those whose `kid` isn't in the keyring.  This lets the footerless tokens of a single
+/// key setup keep verifying while migrating to routed keys.  Without a default those tokens fail with
+/// [MissingKeyId](crate::generic::GenericParserError::MissingKeyId) or
+/// [UnknownKeyId](crate::generic::GenericParserError::UnknownKeyId).
+///
+/// Used with [parse_with_resolver](crate::generic::GenericParser::parse_with_resolver).
+///
+/// # Usage
+/// ```
+/// # #[cfg(all(feature = "generic", feature = "v4_public"))]
+/// # {
+/// use rusty_paseto::generic::*;
+///
+/// # let private_key = Key::<64>::try_from("b4cbfb43df4ce210727d953e4a713307fa19bb7d9f85041438d9e11b942a37741eb9dbbbbc047c03fd70604e0071f0987e16b28b757225c11f00415d0e20b1a2")?;
+/// # let private_key = PasetoAsymmetricPrivateKey::<V4, Public>::from(&private_key);
+/// # let public_key = Key::<32>::try_from("1eb9dbbbbc047c03fd70604e0071f0987e16b28b757225c11f00415d0e20b1a2")?;
+/// let public_key = PasetoAsymmetricPublicKey::<V4, Public>::from(&public_key);
+/// let keyring = Keyring::new()
+///   .with_key("2024-06", &public_key)
+///   .with_default(&public_key);
+///
+/// //a legacy token without a footer verifies with the default key
+/// let token = GenericBuilder::<V4, Public>::default()
+///   .set_claim(SubjectClaim::from("loyal subjects"))
+///   .try_sign(&private_key)?;
+/// let json = GenericParser::<V4, Public>::default().parse_with_resolver(&token, &keyring)?;
+/// assert_eq!(json["sub"], "loyal subjects");
+/// # }
+/// # Ok::<(),anyhow::Error>(())
+/// ```
+#[derive(Debug, Clone)]
+pub struct Keyring<Version> {
+  keys: HashMap<String, Arc<[u8]>>,
+  default: Option<Arc<[u8]>>,
+  version: PhantomData<Version>,
+}
+
+impl<Version> Keyring<Version> {
+  /// Creates an empty keyring without a default key
+  pub fn new() -> Self {
+    Self {
+      keys: HashMap::new(),
+      default: None,
+      version: PhantomData,
+    }
+  }
+
+  /// Adds the key resolved by the given `kid`, replacing any key already held under it
+  pub fn with_key(mut self, kid: impl Into<String>, key: &PasetoAsymmetricPublicKey<'_, Version, Public>) -> Self {
+    self.keys.insert(kid.into(), Arc::from(key.as_ref()));
+    self
+  }
+
+  /// Sets the key falling back on when a token's `kid` is absent or unknown
+  pub fn with_default(mut self, key: &PasetoAsymmetricPublicKey<'_, Version, Public>) -> Self {
+    self.default = Some(Arc::from(key.as_ref()));
+    self
+  }
+}
+
+impl<Version> Default for Keyring<Version> {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+impl<Version> PublicKeyResolver<Version> for Keyring<Version> {
+  type KeyBytes = Arc<[u8]>;
+
+  fn resolve(&self, kid: &str) -> Option<Arc<[u8]>> {
+    self.keys.get(kid).cloned()
+  }
+
+  fn resolve_default(&self) -> Option<Arc<[u8]>> {
+    self.default.clone()
+  }
+}
+
+#[cfg(all(test, feature = "v2_public"))]
+mod unit_tests {
+  use crate::generic::*;
+  use anyhow::Result;
+
+  #[test]
+  fn test_keyring_default_key() -> Result<()> {
+    let private_key = Key::<64>::try_from("b4cbfb43df4ce210727d953e4a713307fa19bb7d9f85041438d9e11b942a37741eb9dbbbbc047c03fd70604e0071f0987e16b28b757225c11f00415d0e20b1a2")?;
+    let private_key = PasetoAsymmetricPrivateKey::<V2, Public>::from(&private_key);
+    let public_key = Key::<32>::try_from("1eb9dbbbbc047c03fd70604e0071f0987e16b28b757225c11f00415d0e20b1a2")?;
+    let public_key = PasetoAsymmetricPublicKey::<V2, Public>::from(&public_key);
+    let other_key = Key::<32>::from([1u8; 32]);
+    let other_key = PasetoAsymmetricPublicKey::<V2, Public>::from(&other_key);
+    let parser = GenericParser::<V2, Public>::default();
+
+    let footer = KeyRoutingFooter::new("2024-06").to_string();
+    let routed = GenericBuilder::<V2, Public>::default()
+      .set_footer(Footer::from(footer.as_str()))
+      .try_sign(&private_key)?;
+    let footerless = GenericBuilder::<V2, Public>::default().try_sign(&private_key)?;
+    let legacy = GenericBuilder::<V2, Public>::default()
+      .set_footer(Footer::from("legacy"))
+      .try_sign(&private_key)?;
+
+    //without a default, tokens which can't be routed fail with the reason
+    let keyring = Keyring::new().with_key("2024-06", &public_key);
+    assert!(parser.parse_with_resolver(&routed, &keyring).is_ok());
+    assert!(matches!(
+      parser.parse_with_resolver(&footerless, &keyring),
+      Err(GenericParserError::MissingKeyId)
+    ));
+    assert!(matches!(
+      parser.parse_with_resolver(&legacy, &keyring),
+      Err(GenericParserError::MalformedFooter { .. })
+    ));
+    assert!(matches!(
+      parser.parse_with_resolver(&routed, &Keyring::new().with_key("2019-01", &public_key)),
+      Err(GenericParserError::UnknownKeyId(kid)) if kid == "2024-06"
+    ));
+
+    let keyring = keyring.with_default(&public_key);
+    assert!(parser.parse_with_resolver(&footerless, &keyring).is_ok());
+    assert!(parser.parse_with_resolver(&legacy, &keyring).is_ok());
+    let keyring = Keyring::new().with_key("2019-01", &other_key).with_default(&public_key);
+    assert!(parser.parse_with_resolver(&routed, &keyring).is_ok());
+
+    //the routed key is preferred, the default isn't tried when it fails to verify
+    let keyring = Keyring::new().with_key("2024-06", &other_key).with_default(&public_key);
+    assert!(parser.parse_with_resolver(&routed, &keyring).is_err());
+    Ok(())
+  }
+}
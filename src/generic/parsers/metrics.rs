@@ -0,0 +1,139 @@
+//! Instrumentation of parse outcomes.  Without the `metrics` feature recording compiles away entirely.
+use super::GenericParserError;
+
+//the counter incremented for every parse, labelled with its outcome
+#[cfg(feature = "metrics")]
+const PARSE_TOTAL: &str = "paseto_parse_total";
+
+#[cfg(feature = "metrics")]
+pub(crate) fn outcome<T>(result: &Result<T, GenericParserError>) -> &'static str {
+  use crate::core::PasetoError;
+
+  match result {
+    Ok(_) => "success",
+    Err(GenericParserError::CipherError { source } | GenericParserError::AuthenticationFailed { source, .. }) => {
+      match source {
+        PasetoError::InvalidSignature => "invalid_signature",
+        PasetoError::FooterInvalid => "footer_mismatch",
+        PasetoError::IncorrectSize
+        | PasetoError::MalformedPayload
+        | PasetoError::WrongHeader
+        | PasetoError::PayloadBase64Decode { .. }
+        | PasetoError::Utf8Error { .. }
+        | PasetoError::FromUtf8Error { .. } => "malformed_token",
+        _ => "decryption_failed",
+      }
+    }
+    Err(error) => error.report().reason(),
+  }
+}
+
+#[inline(always)]
+pub(crate) fn recorded<T>(result: Result<T, GenericParserError>) -> Result<T, GenericParserError> {
+  #[cfg(feature = "metrics")]
+  ::metrics::counter!(PARSE_TOTAL, "outcome" => outcome(&result)).increment(1);
+  result
+}
+
+#[cfg(all(test, feature = "metrics", feature = "v4_local"))]
+mod unit_tests {
+  use super::*;
+  use crate::generic::*;
+  use ::metrics::{
+    Counter, CounterFn, Gauge, Histogram, Key as MetricKey, KeyName, Metadata, Recorder, SharedString, Unit,
+  };
+  use anyhow::Result;
+  use std::collections::HashMap;
+  use std::sync::atomic::{AtomicU64, Ordering};
+  use std::sync::{Arc, Mutex};
+
+  //counts the parses of each outcome, installed for the current thread only so parses run by other tests in
+  //parallel aren't counted
+  #[derive(Default)]
+  struct OutcomeRecorder {
+    outcomes: Mutex<HashMap<String, Arc<OutcomeCounter>>>,
+  }
+
+  #[derive(Default)]
+  struct OutcomeCounter(AtomicU64);
+
+  impl CounterFn for OutcomeCounter {
+    fn increment(&self, value: u64) {
+      self.0.fetch_add(value, Ordering::SeqCst);
+    }
+
+    fn absolute(&self, value: u64) {
+      self.0.store(value, Ordering::SeqCst);
+    }
+  }
+
+  impl OutcomeRecorder {
+    fn count(&self, outcome: &str) -> u64 {
+      self
+        .outcomes
+        .lock()
+        .unwrap()
+        .get(outcome)
+        .map_or(0, |counter| counter.0.load(Ordering::SeqCst))
+    }
+  }
+
+  impl Recorder for OutcomeRecorder {
+    fn describe_counter(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+    fn describe_gauge(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+    fn describe_histogram(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+
+    fn register_counter(&self, key: &MetricKey, _: &Metadata<'_>) -> Counter {
+      assert_eq!(key.name(), PARSE_TOTAL);
+      let outcome = key
+        .labels()
+        .find(|label| label.key() == "outcome")
+        .map(|label| label.value().to_string())
+        .unwrap_or_default();
+      Counter::from_arc(self.outcomes.lock().unwrap().entry(outcome).or_default().clone())
+    }
+
+    fn register_gauge(&self, _: &MetricKey, _: &Metadata<'_>) -> Gauge {
+      Gauge::noop()
+    }
+
+    fn register_histogram(&self, _: &MetricKey, _: &Metadata<'_>) -> Histogram {
+      Histogram::noop()
+    }
+  }
+
+  #[test]
+  fn test_parse_outcomes() -> Result<()> {
+    let key = PasetoSymmetricKey::<V4, Local>::from(Key::from(*b"wubbalubbadubdubwubbalubbadubdub"));
+    let other_key = PasetoSymmetricKey::<V4, Local>::from(Key::from([0u8; 32]));
+    let token = GenericBuilder::<V4, Local>::default()
+      .set_claim(ExpirationClaim::try_from("2019-01-01T00:00:00Z")?)
+      .set_footer(Footer::from("footer"))
+      .try_encrypt(&key)?;
+    let mut parser = GenericParser::<V4, Local>::default();
+    parser.set_footer(Footer::from("footer"));
+
+    assert_eq!(outcome(&parser.parse(&token, &key)), "success");
+    assert_eq!(outcome(&parser.parse(&token, &other_key)), "decryption_failed");
+    assert_eq!(outcome(&parser.parse("not a token", &key)), "malformed_token");
+    assert_eq!(outcome(&parser.parse("v4.local.", &key)), "malformed_token");
+    assert_eq!(
+      outcome(&GenericParser::<V4, Local>::default().parse(&token, &key)),
+      "footer_mismatch"
+    );
+
+    let recorder = OutcomeRecorder::default();
+    ::metrics::with_local_recorder(&recorder, || {
+      assert!(parser.parse(&token, &key).is_ok());
+      assert!(parser.parse(&token, &key).is_ok());
+      assert!(parser.parse(&token, &other_key).is_err());
+      let expired = parser.validate_claim(ExpirationClaim::default(), &|_, _| Err(PasetoClaimError::Expired));
+      assert!(expired.parse(&token, &key).is_err());
+    });
+    assert_eq!(recorder.count("success"), 2);
+    assert_eq!(recorder.count("decryption_failed"), 1);
+    assert_eq!(recorder.count("expired"), 1);
+    assert_eq!(recorder.count("footer_mismatch"), 0);
+    Ok(())
+  }
+}
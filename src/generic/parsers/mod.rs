@@ -1,5 +1,25 @@
+mod bounded_payload;
+mod claims_hash;
 mod error;
 mod generic_parser;
+mod inspect;
+mod keyring;
+mod metrics;
+mod policy;
+mod resolver;
+mod time_claim;
+mod traits;
+mod verified;
 
-pub use error::GenericParserError;
+pub use claims_hash::claims_hash;
+pub use error::{ClaimAccessError, GenericParserError};
 pub use generic_parser::GenericParser;
+pub use inspect::{inspect, InspectReport};
+pub use keyring::Keyring;
+pub use policy::{matches_policy, ParserPolicy};
+pub use resolver::PublicKeyResolver;
+#[cfg(feature = "batteries_included")]
+pub(crate) use time_claim::time_claim;
+pub use time_claim::NumericTimeUnit;
+pub use traits::{ParsedClaims, PasetoOpeningKey};
+pub use verified::Verified;
@@ -0,0 +1,194 @@
+use super::time_claim::{time_claim, NumericTimeUnit};
+use super::GenericParserError;
+use crate::core::PasetoVersion;
+use crate::generic::PasetoClaimError;
+use serde_json::Value;
+use time::OffsetDateTime;
+
+/// An organization wide set of validation rules, configured once and [applied](crate::generic::GenericParser::set_policy)
+/// to every parser which needs them
+///
+/// Unlike the field by field setters of the parsers, one policy keeps every call site in agreement on what a valid
+/// token is.  The [default](Default) policy rejects tokens without an `exp` claim and changes nothing else, the other
+/// rules are off until set.
+///
+/// Without a policy a token missing its `exp` claim never expires and one missing its `nbf` claim is valid
+/// immediately, [require_expiration](Self::require_expiration) and [require_not_before](Self::require_not_before)
+/// reject those tokens instead.
+///
+/// # Usage
+/// ```
+/// # #[cfg(feature = "default")]
+/// # {
+/// use rusty_paseto::prelude::*;
+///
+/// let policy = ParserPolicy {
+///   leeway: time::Duration::seconds(30),
+///   required_claims: vec!["sub".to_string()],
+///   max_validity: Some(time::Duration::hours(12)),
+///   allowed_versions: Some(vec![PasetoVersion::V4]),
+///   ..ParserPolicy::default()
+/// };
+///
+/// # let key = PasetoSymmetricKey::<V4, Local>::from(Key::<32>::from(*b"wubbalubbadubdubwubbalubbadubdub"));
+/// let token = PasetoBuilder::<V4, Local>::default()
+///   .set_claim(SubjectClaim::from("loyal subjects"))
+///   .build(&key)?;
+///
+/// let json = PasetoParser::<V4, Local>::default()
+///   .set_policy(&policy)
+///   .parse(&token, &key)?;
+/// assert_eq!(json["sub"], "loyal subjects");
+///
+/// //a non-expiring token falls foul of the policy
+/// let token = PasetoBuilder::<V4, Local>::default()
+///   .set_claim(SubjectClaim::from("loyal subjects"))
+///   .set_no_expiration_danger_acknowledged()
+///   .build(&key)?;
+/// let result = PasetoParser::<V4, Local>::default()
+///   .set_policy(&policy)
+///   .parse(&token, &key);
+/// assert!(matches!(
+///   result,
+///   Err(GenericParserError::ClaimError { source: PasetoClaimError::Missing(claim) }) if claim == "exp"
+/// ));
+/// # }
+/// # Ok::<(),anyhow::Error>(())
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParserPolicy {
+  /// The clock skew tolerated by the [PasetoParser](crate::prelude::PasetoParser) expiration and not before checks,
+  /// zero by default
+  pub leeway: time::Duration,
+  /// Whether tokens without an `exp` claim are rejected, true by default
+  pub require_expiration: bool,
+  /// Whether tokens without an `nbf` claim are rejected, false by default
+  pub require_not_before: bool,
+  /// Claims every token must carry, whatever their value
+  pub required_claims: Vec<String>,
+  /// The longest a token may be valid for, from its `iat` claim or else the current time until its `exp` claim
+  pub max_validity: Option<time::Duration>,
+  /// The versions of tokens accepted, see [set_allowed_versions](crate::generic::GenericParser::set_allowed_versions)
+  pub allowed_versions: Option<Vec<PasetoVersion>>,
+}
+
+impl ParserPolicy {
+  /// Checks the claims of an already decrypted or verified token against the claim rules of the policy, the same
+  /// checks a parser [set](crate::generic::GenericParser::set_policy) with the policy runs
+  ///
+  /// The allowed versions are a rule on the token's header and the leeway one on the validators of its time claims,
+  /// both are left to parsing and not checked here.  Time claims are read as RFC3339 dates, outside of a parser
+  /// there is no [numeric unit](NumericTimeUnit) to read them in.
+  ///
+  /// # Errors
+  ///
+  /// Returns [Missing](PasetoClaimError::Missing) for a required claim the token doesn't carry and
+  /// [ValidityTooLong](GenericParserError::ValidityTooLong) when it is valid for longer than allowed
+  pub fn validate(&self, claims: &Value) -> Result<(), GenericParserError> {
+    self.validate_at(claims, OffsetDateTime::now_utc(), None)
+  }
+
+  pub(crate) fn validate_at(
+    &self,
+    claims: &Value,
+    now: OffsetDateTime,
+    numeric_time_unit: Option<NumericTimeUnit>,
+  ) -> Result<(), GenericParserError> {
+    let required = [
+      self.require_expiration.then_some("exp"),
+      self.require_not_before.then_some("nbf"),
+    ];
+    if let Some(claim) = required
+      .into_iter()
+      .flatten()
+      .chain(self.required_claims.iter().map(String::as_str))
+      .find(|claim| claims[claim].is_null())
+    {
+      return Err(PasetoClaimError::Missing(claim.to_string()).into());
+    }
+    if let Some(max_validity) = self.max_validity {
+      let Some(expiration) = time_claim("exp", &claims["exp"], numeric_time_unit)? else {
+        return Ok(());
+      };
+      let start = time_claim("iat", &claims["iat"], numeric_time_unit)?.unwrap_or(now);
+      let validity = expiration - start;
+      if validity > max_validity {
+        return Err(GenericParserError::ValidityTooLong { validity, max_validity });
+      }
+    }
+    Ok(())
+  }
+}
+
+/// Whether the claims of an already decrypted or verified token satisfy the policy, for branching on it where a
+/// failure isn't exceptional
+///
+/// The same checks as [ParserPolicy::validate], without the reason of a failure.
+///
+/// # Example
+/// ```
+/// # #[cfg(all(feature = "generic", feature = "v4_local"))]
+/// # {
+/// use rusty_paseto::generic::*;
+/// use serde_json::json;
+///
+/// let beta_access = ParserPolicy {
+///   required_claims: vec!["beta".to_string()],
+///   ..ParserPolicy::default()
+/// };
+/// let claims = json!({"sub": "loyal subjects", "exp": "2030-01-01T00:00:00Z"});
+/// assert!(!matches_policy(&claims, &beta_access));
+/// # }
+/// ```
+pub fn matches_policy(claims: &Value, policy: &ParserPolicy) -> bool {
+  policy.validate(claims).is_ok()
+}
+
+impl Default for ParserPolicy {
+  fn default() -> Self {
+    Self {
+      leeway: time::Duration::ZERO,
+      require_expiration: true,
+      require_not_before: false,
+      required_claims: Vec::new(),
+      max_validity: None,
+      allowed_versions: None,
+    }
+  }
+}
+
+#[cfg(test)]
+mod unit_tests {
+  use super::*;
+  use serde_json::json;
+
+  #[test]
+  fn test_matches_policy() {
+    let policy = ParserPolicy {
+      required_claims: vec!["sub".to_string()],
+      max_validity: Some(time::Duration::hours(12)),
+      ..ParserPolicy::default()
+    };
+    let claims = json!({"sub": "loyal subjects", "iat": "2030-01-01T00:00:00Z", "exp": "2030-01-01T08:00:00Z"});
+    assert!(matches_policy(&claims, &policy));
+    assert!(policy.validate(&claims).is_ok());
+
+    let too_long = json!({"sub": "loyal subjects", "iat": "2030-01-01T00:00:00Z", "exp": "2030-01-02T00:00:00Z"});
+    assert!(!matches_policy(&too_long, &policy));
+    assert!(matches!(
+      policy.validate(&too_long),
+      Err(GenericParserError::ValidityTooLong { .. })
+    ));
+
+    let no_expiration = json!({"sub": "loyal subjects"});
+    assert!(!matches_policy(&no_expiration, &policy));
+    assert!(!matches_policy(&json!({"exp": "2030-01-01T08:00:00Z"}), &policy));
+    assert!(matches_policy(
+      &no_expiration,
+      &ParserPolicy {
+        require_expiration: false,
+        ..ParserPolicy::default()
+      }
+    ));
+  }
+}
@@ -0,0 +1,38 @@
+/// Resolves the public key which verifies a token from the `kid` of its [key routing footer](crate::generic::KeyRoutingFooter),
+/// for verifier keys distributed from elsewhere, such as a key set fetched from a remote endpoint
+///
+/// The parser extracts the `kid` and verifies the token, the resolver only supplies the key bytes for an id, the 32
+/// byte Ed25519 key of [V2](crate::core::V2) and [V4](crate::core::V4) tokens or the 49 byte compressed point of
+/// [V3](crate::core::V3) tokens.  Fetching, caching and refreshing the keys are left to the implementation, which is
+/// why the bytes are returned as any owned type able to lend them, such as an `Arc<[u8]>` shared with a cache.
+///
+/// Resolution happens before the token is verified, so the `kid` is untrusted input chosen by whoever presents the
+/// token.  Only ever resolve it among known keys, never use it to build a URL or a path to fetch from.
+///
+/// Implemented for closures taking the `kid` and for the in memory [Keyring](crate::generic::Keyring), see
+/// [parse_with_resolver](crate::generic::GenericParser::parse_with_resolver).
+pub trait PublicKeyResolver<Version> {
+  /// The owned bytes of a resolved key
+  type KeyBytes: AsRef<[u8]>;
+
+  /// Looks up the key with the given id, `None` if there is no such key
+  fn resolve(&self, kid: &str) -> Option<Self::KeyBytes>;
+
+  /// The key verifying tokens which can't be routed, because they carry no `kid` or one this resolver has no key
+  /// for.  `None` by default, failing those tokens instead.
+  fn resolve_default(&self) -> Option<Self::KeyBytes> {
+    None
+  }
+}
+
+impl<Version, F, B> PublicKeyResolver<Version> for F
+where
+  F: Fn(&str) -> Option<B>,
+  B: AsRef<[u8]>,
+{
+  type KeyBytes = B;
+
+  fn resolve(&self, kid: &str) -> Option<B> {
+    self(kid)
+  }
+}
@@ -0,0 +1,87 @@
+use crate::generic::PasetoClaimError;
+use serde_json::Value;
+use time::{format_description::well_known::Rfc3339, OffsetDateTime};
+
+/// The unit of time claims given as a number since the Unix epoch rather than an RFC3339 string, see
+/// [GenericParser::set_numeric_time_unit](crate::generic::GenericParser::set_numeric_time_unit)
+///
+/// There is no default: the same number read in the wrong unit is off by a factor of a thousand, which makes tokens
+/// look expired since 1970 or dated tens of millennia ahead, so numeric time claims are rejected until the unit is set.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum NumericTimeUnit {
+  /// Whole seconds since the Unix epoch, as in JWT
+  Seconds,
+  /// Whole milliseconds since the Unix epoch, as in JavaScript's `Date.now()`
+  Milliseconds,
+}
+
+//reads the time claim of a payload, `None` when it is absent.  RFC3339 strings are always accepted and whole numbers
+//only in the configured unit, any other value is an error rather than being taken for an absent claim.
+pub(crate) fn time_claim(
+  key: &str,
+  value: &Value,
+  unit: Option<NumericTimeUnit>,
+) -> Result<Option<OffsetDateTime>, PasetoClaimError> {
+  let unexpected = || PasetoClaimError::Unexpected(key.to_string());
+  match value {
+    Value::Null => Ok(None),
+    Value::String(value) => OffsetDateTime::parse(value, &Rfc3339)
+      .map(Some)
+      .map_err(|_| PasetoClaimError::rfc3339_date(value)),
+    Value::Number(number) => {
+      let (number, unit) = number.as_i64().zip(unit).ok_or_else(unexpected)?;
+      let nanos = match unit {
+        NumericTimeUnit::Seconds => i128::from(number) * 1_000_000_000,
+        NumericTimeUnit::Milliseconds => i128::from(number) * 1_000_000,
+      };
+      OffsetDateTime::from_unix_timestamp_nanos(nanos)
+        .map(Some)
+        .map_err(|_| unexpected())
+    }
+    _ => Err(unexpected()),
+  }
+}
+
+#[cfg(test)]
+mod unit_tests {
+  use super::*;
+  use serde_json::json;
+
+  #[test]
+  fn test_numeric_time_units() {
+    let expected = OffsetDateTime::parse("2030-01-01T00:00:00Z", &Rfc3339).ok();
+    assert_eq!(
+      time_claim("exp", &json!(1_893_456_000), Some(NumericTimeUnit::Seconds)).ok(),
+      Some(expected)
+    );
+    assert_eq!(
+      time_claim("exp", &json!(1_893_456_000_000u64), Some(NumericTimeUnit::Milliseconds)).ok(),
+      Some(expected)
+    );
+    //the same number in the other unit is a date in 1970
+    let misread = time_claim("exp", &json!(1_893_456_000), Some(NumericTimeUnit::Milliseconds));
+    assert_eq!(misread.ok().flatten().map(|time| time.year()), Some(1970));
+
+    //the string form is understood whatever the unit
+    for unit in [
+      None,
+      Some(NumericTimeUnit::Seconds),
+      Some(NumericTimeUnit::Milliseconds),
+    ] {
+      assert_eq!(
+        time_claim("exp", &json!("2030-01-01T00:00:00Z"), unit).ok(),
+        Some(expected)
+      );
+      assert_eq!(time_claim("exp", &Value::Null, unit).ok(), Some(None));
+    }
+
+    //numbers need an explicit unit and have to be whole
+    assert!(matches!(
+      time_claim("exp", &json!(1_893_456_000), None),
+      Err(PasetoClaimError::Unexpected(claim)) if claim == "exp"
+    ));
+    assert!(time_claim("exp", &json!(1.5), Some(NumericTimeUnit::Seconds)).is_err());
+    assert!(time_claim("exp", &json!(i64::MAX), Some(NumericTimeUnit::Seconds)).is_err());
+    assert!(time_claim("exp", &json!(true), Some(NumericTimeUnit::Seconds)).is_err());
+  }
+}
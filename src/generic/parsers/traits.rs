@@ -0,0 +1,317 @@
+use crate::generic::*;
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+/// A key which is able to open (decrypt or verify the signature of) PASETO tokens of a specific version and purpose
+///
+/// Implemented for [PasetoSymmetricKey] (Local) and [PasetoAsymmetricPublicKey] (Public) for every enabled version so
+/// that parser functionality can be written once for all versions and purposes.
+///
+/// The key types only exist for their own purpose, so handing a public key to a local parse, or the reverse, is a
+/// compile error rather than a runtime failure, see [PasetoSymmetricKey] and [PasetoAsymmetricPublicKey].
+pub trait PasetoOpeningKey<Version, Purpose> {
+  /// Decrypts or verifies the untrusted token with this key, returning the raw payload on success.
+  ///
+  /// The implicit assertion is ignored by versions which do not support one.
+  fn try_open<'a>(
+    &self,
+    potential_token: &'a str,
+    footer: Footer<'a>,
+    implicit_assertion: ImplicitAssertion<'a>,
+  ) -> Result<String, PasetoError>;
+
+  /// Decrypts or verifies the untrusted token like [try_open](Self::try_open), writing the raw payload into the
+  /// cleared buffer instead of returning a new string.  The buffer is left empty on failure.
+  ///
+  /// Keys whose version can decrypt in place reuse the buffer's capacity, the others copy the payload into it.
+  fn try_open_into<'a>(
+    &self,
+    potential_token: &'a str,
+    footer: Footer<'a>,
+    implicit_assertion: ImplicitAssertion<'a>,
+    payload: &mut Vec<u8>,
+  ) -> Result<(), PasetoError> {
+    payload.clear();
+    payload.extend_from_slice(self.try_open(potential_token, footer, implicit_assertion)?.as_bytes());
+    Ok(())
+  }
+}
+
+#[cfg(feature = "v1_local")]
+impl PasetoOpeningKey<V1, Local> for PasetoSymmetricKey<V1, Local> {
+  fn try_open<'a>(
+    &self,
+    potential_token: &'a str,
+    footer: Footer<'a>,
+    _: ImplicitAssertion<'a>,
+  ) -> Result<String, PasetoError> {
+    Paseto::<V1, Local>::try_decrypt(potential_token, self, footer)
+  }
+}
+
+#[cfg(feature = "v2_local")]
+impl PasetoOpeningKey<V2, Local> for PasetoSymmetricKey<V2, Local> {
+  fn try_open<'a>(
+    &self,
+    potential_token: &'a str,
+    footer: Footer<'a>,
+    _: ImplicitAssertion<'a>,
+  ) -> Result<String, PasetoError> {
+    Paseto::<V2, Local>::try_decrypt(potential_token, self, footer)
+  }
+}
+
+#[cfg(feature = "v3_local")]
+impl PasetoOpeningKey<V3, Local> for PasetoSymmetricKey<V3, Local> {
+  fn try_open<'a>(
+    &self,
+    potential_token: &'a str,
+    footer: Footer<'a>,
+    implicit_assertion: ImplicitAssertion<'a>,
+  ) -> Result<String, PasetoError> {
+    Paseto::<V3, Local>::try_decrypt(potential_token, self, footer, implicit_assertion)
+  }
+}
+
+#[cfg(feature = "v4_local")]
+impl PasetoOpeningKey<V4, Local> for PasetoSymmetricKey<V4, Local> {
+  fn try_open<'a>(
+    &self,
+    potential_token: &'a str,
+    footer: Footer<'a>,
+    implicit_assertion: ImplicitAssertion<'a>,
+  ) -> Result<String, PasetoError> {
+    Paseto::<V4, Local>::try_decrypt(potential_token, self, footer, implicit_assertion)
+  }
+
+  fn try_open_into<'a>(
+    &self,
+    potential_token: &'a str,
+    footer: Footer<'a>,
+    implicit_assertion: ImplicitAssertion<'a>,
+    payload: &mut Vec<u8>,
+  ) -> Result<(), PasetoError> {
+    Paseto::<V4, Local>::try_decrypt_into(potential_token, self, footer, implicit_assertion, payload)
+  }
+}
+
+#[cfg(feature = "v1_public")]
+impl<'k> PasetoOpeningKey<V1, Public> for PasetoAsymmetricPublicKey<'k, V1, Public> {
+  fn try_open<'a>(
+    &self,
+    potential_token: &'a str,
+    footer: Footer<'a>,
+    _: ImplicitAssertion<'a>,
+  ) -> Result<String, PasetoError> {
+    Paseto::<V1, Public>::try_verify(potential_token, self, footer)
+  }
+}
+
+#[cfg(feature = "v2_public")]
+impl<'k> PasetoOpeningKey<V2, Public> for PasetoAsymmetricPublicKey<'k, V2, Public> {
+  fn try_open<'a>(
+    &self,
+    potential_token: &'a str,
+    footer: Footer<'a>,
+    _: ImplicitAssertion<'a>,
+  ) -> Result<String, PasetoError> {
+    Paseto::<V2, Public>::try_verify(potential_token, self, footer)
+  }
+}
+
+#[cfg(feature = "v3_public")]
+impl<'k> PasetoOpeningKey<V3, Public> for PasetoAsymmetricPublicKey<'k, V3, Public> {
+  fn try_open<'a>(
+    &self,
+    potential_token: &'a str,
+    footer: Footer<'a>,
+    implicit_assertion: ImplicitAssertion<'a>,
+  ) -> Result<String, PasetoError> {
+    Paseto::<V3, Public>::try_verify(potential_token, self, footer, implicit_assertion)
+  }
+}
+
+#[cfg(feature = "v4_public")]
+impl<'k> PasetoOpeningKey<V4, Public> for PasetoAsymmetricPublicKey<'k, V4, Public> {
+  fn try_open<'a>(
+    &self,
+    potential_token: &'a str,
+    footer: Footer<'a>,
+    implicit_assertion: ImplicitAssertion<'a>,
+  ) -> Result<String, PasetoError> {
+    Paseto::<V4, Public>::try_verify(potential_token, self, footer, implicit_assertion)
+  }
+}
+
+/// Accessors over the claims returned by a parser
+///
+/// Implemented for the serde_json [Value] returned by `parse`, so it is available on parsed claims by bringing the
+/// trait into scope.
+pub trait ParsedClaims {
+  /// Copies the claims into a map sorted by claim name, for processing which must not depend on the order of the
+  /// claims in the payload
+  ///
+  /// # Errors
+  ///
+  /// Returns [PayloadNotObject](GenericParserError::PayloadNotObject) if the claims aren't a JSON object
+  ///
+  /// # Example
+  /// ```
+  /// # #[cfg(all(feature = "generic", feature="v4_local"))]
+  /// # {
+  /// use rusty_paseto::generic::*;
+  ///
+  /// # let key = PasetoSymmetricKey::<V4, Local>::from(Key::<32>::from(*b"wubbalubbadubdubwubbalubbadubdub"));
+  /// let token = GenericBuilder::<V4, Local>::default()
+  ///   .set_claim(SubjectClaim::from("loyal subjects"))
+  ///   .set_claim(AudienceClaim::from("customers"))
+  ///   .try_encrypt(&key)?;
+  ///
+  /// let claims = GenericParser::<V4, Local>::default().parse(&token, &key)?.claims_btree()?;
+  /// assert_eq!(claims.keys().collect::<Vec<_>>(), ["aud", "sub"]);
+  /// # }
+  /// # Ok::<(),anyhow::Error>(())
+  /// ```
+  fn claims_btree(&self) -> Result<BTreeMap<String, Value>, GenericParserError>;
+
+  /// Reads a string claim
+  ///
+  /// # Errors
+  ///
+  /// Returns [Missing](ClaimAccessError::Missing) if there is no such claim and
+  /// [WrongType](ClaimAccessError::WrongType) if it isn't a string
+  ///
+  /// # Example
+  /// ```
+  /// # #[cfg(all(feature = "generic", feature="v4_local"))]
+  /// # {
+  /// use rusty_paseto::generic::*;
+  ///
+  /// # let key = PasetoSymmetricKey::<V4, Local>::from(Key::<32>::from(*b"wubbalubbadubdubwubbalubbadubdub"));
+  /// let token = GenericBuilder::<V4, Local>::default()
+  ///   .set_claim(SubjectClaim::from("loyal subjects"))
+  ///   .set_claim(CustomClaim::try_from(("seats", 4))?)
+  ///   .try_encrypt(&key)?;
+  ///
+  /// let claims = GenericParser::<V4, Local>::default().parse(&token, &key)?;
+  /// assert_eq!(claims.get_str("sub")?, "loyal subjects");
+  /// assert_eq!(claims.get_u64("seats")?, 4);
+  /// assert!(matches!(claims.get_str("seats"), Err(ClaimAccessError::WrongType { .. })));
+  /// assert!(matches!(claims.get_bool("admin"), Err(ClaimAccessError::Missing(_))));
+  /// # }
+  /// # Ok::<(),anyhow::Error>(())
+  /// ```
+  fn get_str(&self, claim: &str) -> Result<&str, ClaimAccessError>;
+
+  /// Reads an unsigned integer claim, see [get_str](Self::get_str)
+  ///
+  /// Negative and fractional numbers are of the wrong type.
+  fn get_u64(&self, claim: &str) -> Result<u64, ClaimAccessError>;
+
+  /// Reads a signed integer claim, see [get_str](Self::get_str)
+  ///
+  /// Fractional numbers and integers above [i64::MAX] are of the wrong type.
+  fn get_i64(&self, claim: &str) -> Result<i64, ClaimAccessError>;
+
+  /// Reads a number claim as a float, see [get_str](Self::get_str)
+  ///
+  /// Integers are converted, possibly losing precision.
+  fn get_f64(&self, claim: &str) -> Result<f64, ClaimAccessError>;
+
+  /// Reads a boolean claim, see [get_str](Self::get_str)
+  fn get_bool(&self, claim: &str) -> Result<bool, ClaimAccessError>;
+}
+
+impl ParsedClaims for Value {
+  fn claims_btree(&self) -> Result<BTreeMap<String, Value>, GenericParserError> {
+    let claims = self.as_object().ok_or(GenericParserError::PayloadNotObject)?;
+    Ok(claims.iter().map(|(key, value)| (key.clone(), value.clone())).collect())
+  }
+
+  fn get_str(&self, claim: &str) -> Result<&str, ClaimAccessError> {
+    get_claim(self, claim, "a string", Value::as_str)
+  }
+
+  fn get_u64(&self, claim: &str) -> Result<u64, ClaimAccessError> {
+    get_claim(self, claim, "an unsigned integer", Value::as_u64)
+  }
+
+  fn get_i64(&self, claim: &str) -> Result<i64, ClaimAccessError> {
+    get_claim(self, claim, "a signed integer", Value::as_i64)
+  }
+
+  fn get_f64(&self, claim: &str) -> Result<f64, ClaimAccessError> {
+    get_claim(self, claim, "a number", Value::as_f64)
+  }
+
+  fn get_bool(&self, claim: &str) -> Result<bool, ClaimAccessError> {
+    get_claim(self, claim, "a boolean", Value::as_bool)
+  }
+}
+
+fn get_claim<'v, T>(
+  claims: &'v Value,
+  claim: &str,
+  expected: &'static str,
+  coerce: impl FnOnce(&'v Value) -> Option<T>,
+) -> Result<T, ClaimAccessError> {
+  let value = match &claims[claim] {
+    Value::Null => return Err(ClaimAccessError::Missing(claim.to_string())),
+    value => value,
+  };
+  coerce(value).ok_or_else(|| ClaimAccessError::WrongType {
+    claim: claim.to_string(),
+    expected,
+    found: match value {
+      Value::Bool(_) => "a boolean",
+      Value::Number(number) if number.is_u64() => "an unsigned integer",
+      Value::Number(number) if number.is_i64() => "a signed integer",
+      Value::Number(_) => "a number",
+      Value::String(_) => "a string",
+      Value::Array(_) => "an array",
+      _ => "an object",
+    },
+  })
+}
+
+#[cfg(test)]
+mod unit_tests {
+  use super::*;
+  use serde_json::json;
+
+  #[test]
+  fn test_typed_claim_accessors() {
+    let claims = json!({"sub": "me", "seats": 4, "offset": -2, "ratio": 0.5, "admin": false, "tags": [], "none": null});
+
+    assert_eq!(claims.get_str("sub"), Ok("me"));
+    assert_eq!(claims.get_u64("seats"), Ok(4));
+    assert_eq!(claims.get_i64("seats"), Ok(4));
+    assert_eq!(claims.get_i64("offset"), Ok(-2));
+    assert_eq!(claims.get_f64("ratio"), Ok(0.5));
+    assert_eq!(claims.get_f64("seats"), Ok(4.0));
+    assert_eq!(claims.get_bool("admin"), Ok(false));
+
+    assert_eq!(claims.get_str("aud"), Err(ClaimAccessError::Missing("aud".to_string())));
+    assert_eq!(
+      claims.get_bool("none"),
+      Err(ClaimAccessError::Missing("none".to_string()))
+    );
+    assert_eq!(
+      claims.get_u64("offset"),
+      Err(ClaimAccessError::WrongType {
+        claim: "offset".to_string(),
+        expected: "an unsigned integer",
+        found: "a signed integer",
+      })
+    );
+    assert_eq!(
+      claims.get_u64("ratio").unwrap_err().to_string(),
+      "The claim 'ratio' is a number, not an unsigned integer"
+    );
+    assert!(matches!(
+      claims.get_str("tags"),
+      Err(ClaimAccessError::WrongType { found: "an array", .. })
+    ));
+    assert!(matches!(json!("sub").get_str("sub"), Err(ClaimAccessError::Missing(_))));
+  }
+}
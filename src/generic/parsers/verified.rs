@@ -0,0 +1,34 @@
+use std::ops::Deref;
+
+/// Claims deserialized from a PASETO token which was successfully decrypted or verified and which passed every claim
+/// validation configured on the parser.
+///
+/// There is no public constructor, the only way to obtain a `Verified<T>` is through
+/// [parse_verified](crate::generic::GenericParser::parse_verified). Functions which require validated claims can take
+/// a `Verified<MyClaims>` argument to make using unvalidated claims impossible to express.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Verified<T>(T);
+
+impl<T> Verified<T> {
+  pub(crate) fn new(claims: T) -> Self {
+    Self(claims)
+  }
+
+  /// Consumes the wrapper, returning the verified claims
+  pub fn into_inner(self) -> T {
+    self.0
+  }
+}
+
+impl<T> Deref for Verified<T> {
+  type Target = T;
+  fn deref(&self) -> &Self::Target {
+    &self.0
+  }
+}
+
+impl<T> AsRef<T> for Verified<T> {
+  fn as_ref(&self) -> &T {
+    &self.0
+  }
+}
@@ -11,7 +11,7 @@
 //! This crate is a type-driven, ergonomic implementation of the [PASETO](https://github.com/paseto-standard/paseto-spec) protocol for secure stateless tokens.
 //!
 //! > "Paseto is everything you love about JOSE (JWT, JWE, JWS) without any of the
-//! [many design deficits that plague the JOSE standards](https://paragonie.com/blog/2017/03/jwt-json-web-tokens-is-bad-standard-that-everyone-should-avoid)."
+//! > [many design deficits that plague the JOSE standards](https://paragonie.com/blog/2017/03/jwt-json-web-tokens-is-bad-standard-that-everyone-should-avoid)."
 //! > -- [PASETO Specification](https://github.com/paseto-standard/paseto-spec)
 //!
 //!
@@ -64,6 +64,9 @@
 //!
 //! rusty_paseto = {version = "latest", features = ["batteries_included", "v4_local"] }
 //! ```
+//!
+//! Only the cipher crates of the chosen combinations are compiled, so a build with just `v4_local` doesn't pull in the Ed25519 or ECDSA signature crates of the public purpose.
+//!
 //! <img src="https://github.com/rrrodzilla/rusty_paseto/raw/main/assets/RustyPasetoV4LocalArchitecture.png" width="150" />
 //!
 //! #### Feature gates
@@ -151,7 +154,7 @@
 //!
 //! * Has no [footer](https://github.com/paseto-standard/paseto-spec/tree/master/docs)
 //! * Has no [implicit assertion](https://github.com/paseto-standard/paseto-spec/tree/master/docs)
-//! for V3 or V4 versioned tokens
+//!   for V3 or V4 versioned tokens
 //! * Expires in **1 hour** after creation (due to an included default ExpirationClaim)
 //! * Contains an IssuedAtClaim defaulting to the current utc time the token was created
 //! * Contains a NotBeforeClaim defaulting to the current utc time the token was created
@@ -181,7 +184,7 @@
 //!
 //! * Validates the token structure and decryptes the payload or verifies the signature of the content
 //! * Validates the [footer](https://github.com/paseto-standard/paseto-spec/tree/master/docs) if
-//! one was provided
+//!   one was provided
 //! * Validates the [implicit assertion](https://github.com/paseto-standard/paseto-spec/tree/master/docs) if one was provided (for V3 or V4 versioned tokens only)
 //!
 //! ## A token with a footer
@@ -539,3 +542,5 @@ pub mod core;
 pub mod generic;
 #[cfg(feature = "batteries_included")]
 pub mod prelude;
+#[cfg(feature = "test-util")]
+pub mod test_util;
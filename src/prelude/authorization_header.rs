@@ -0,0 +1,139 @@
+use crate::prelude::*;
+use serde_json::Value;
+
+/// Parses and validates the token of an HTTP `Authorization` header value with the default [PasetoParser] rules and
+/// the given policy, for services handling authentication without a web framework integration
+///
+/// The value is trimmed and an optional `Bearer` scheme, in any case, is stripped, so both `Bearer v4.local.…` and a
+/// bare `v4.local.…` are accepted.
+///
+/// # Errors
+///
+/// Returns [MissingToken](GenericParserError::MissingToken) for an empty value or a scheme without a token and
+/// [UnsupportedScheme](GenericParserError::UnsupportedScheme) for a scheme other than `Bearer`.  Otherwise returns
+/// the [`GenericParserError`] of a malformed token, a failure to decrypt or verify it or a claim which doesn't
+/// validate.
+///
+/// # Example
+/// ```
+/// # #[cfg(feature = "default")]
+/// # {
+/// use rusty_paseto::prelude::*;
+///
+/// # let key = PasetoSymmetricKey::<V4, Local>::from(Key::<32>::from(*b"wubbalubbadubdubwubbalubbadubdub"));
+/// let token = PasetoBuilder::<V4, Local>::default()
+///   .set_claim(SubjectClaim::from("loyal subjects"))
+///   .build(&key)?;
+/// let header_value = format!("Bearer {}", token);
+///
+/// let json = from_authorization_header(&header_value, &key, &ParserPolicy::default())?;
+/// assert_eq!(json["sub"], "loyal subjects");
+///
+/// assert!(matches!(
+///   from_authorization_header("Basic d3ViYmE6bHViYmE=", &key, &ParserPolicy::default()),
+///   Err(GenericParserError::UnsupportedScheme(scheme)) if scheme == "Basic"
+/// ));
+/// # }
+/// # Ok::<(),anyhow::Error>(())
+/// ```
+pub fn from_authorization_header<Version, Purpose, K>(
+  header_value: &str,
+  key: &K,
+  policy: &ParserPolicy,
+) -> Result<Value, GenericParserError>
+where
+  K: PasetoOpeningKey<Version, Purpose>,
+{
+  let token = bearer_token(header_value)?;
+  PasetoParser::<Version, Purpose>::default()
+    .set_policy(policy)
+    .parse_verified::<Value, _>(token, key)
+    .map(Verified::into_inner)
+}
+
+//the token of the header value, which is either `<scheme> <token>` or the bare token
+fn bearer_token(header_value: &str) -> Result<&str, GenericParserError> {
+  let header_value = header_value.trim();
+  match header_value.split_once(char::is_whitespace) {
+    Some((scheme, token)) if scheme.eq_ignore_ascii_case("bearer") => match token.trim_start() {
+      "" => Err(GenericParserError::MissingToken),
+      token => Ok(token),
+    },
+    Some((scheme, _)) => Err(GenericParserError::UnsupportedScheme(scheme.to_string())),
+    None if header_value.is_empty() || header_value.eq_ignore_ascii_case("bearer") => {
+      Err(GenericParserError::MissingToken)
+    }
+    None => Ok(header_value),
+  }
+}
+
+#[cfg(all(test, feature = "v4_local"))]
+mod unit_tests {
+  use super::*;
+  use anyhow::Result;
+  use std::convert::TryFrom;
+
+  #[test]
+  fn test_bearer_token() {
+    for header_value in [
+      "Bearer v4.local.abc",
+      "bearer  v4.local.abc",
+      " BEARER\tv4.local.abc \r\n",
+      "v4.local.abc",
+    ] {
+      assert_eq!(
+        bearer_token(header_value).ok(),
+        Some("v4.local.abc"),
+        "{:?}",
+        header_value
+      );
+    }
+    for header_value in ["", "   ", "Bearer", "Bearer   "] {
+      assert!(
+        matches!(bearer_token(header_value), Err(GenericParserError::MissingToken)),
+        "{:?}",
+        header_value
+      );
+    }
+    assert!(matches!(
+      bearer_token("Token v4.local.abc"),
+      Err(GenericParserError::UnsupportedScheme(scheme)) if scheme == "Token"
+    ));
+  }
+
+  #[test]
+  fn test_from_authorization_header() -> Result<()> {
+    let key = PasetoSymmetricKey::<V4, Local>::from(Key::from(*b"wubbalubbadubdubwubbalubbadubdub"));
+    let policy = ParserPolicy::default();
+    let token = PasetoBuilder::<V4, Local>::default()
+      .set_claim(SubjectClaim::from("loyal subjects"))
+      .build(&key)?;
+
+    let json = from_authorization_header(&format!("Bearer {}\r\n", token), &key, &policy)?;
+    assert_eq!(json["sub"], "loyal subjects");
+
+    assert!(matches!(
+      from_authorization_header("Bearer v4.local", &key, &policy),
+      Err(GenericParserError::CipherError { .. })
+    ));
+    let expired = PasetoBuilder::<V4, Local>::default()
+      .set_claim(ExpirationClaim::try_from("2019-01-01T00:00:00Z")?)
+      .build(&key)?;
+    assert!(matches!(
+      from_authorization_header(&format!("Bearer {}", expired), &key, &policy),
+      Err(GenericParserError::ClaimError {
+        source: PasetoClaimError::Expired
+      })
+    ));
+    //the policy applies on top of the default rules
+    let policy = ParserPolicy {
+      required_claims: vec!["aud".to_string()],
+      ..ParserPolicy::default()
+    };
+    assert!(matches!(
+      from_authorization_header(&token, &key, &policy),
+      Err(GenericParserError::ClaimError { source: PasetoClaimError::Missing(claim) }) if claim == "aud"
+    ));
+    Ok(())
+  }
+}
@@ -24,11 +24,19 @@
 //! - "v3_public" (NIST Modern Asymmetric Authentication)
 //! - "v4_public" (Sodium Modern Asymmetric Authentication)
 
+mod authorization_header;
 mod error;
 mod paseto_builder;
 mod paseto_parser;
+mod session_issuer;
+mod token_builder_config;
+mod token_pair;
 
 pub use crate::generic::*;
+pub use authorization_header::from_authorization_header;
 pub use error::GeneralPasetoError;
 pub use paseto_builder::PasetoBuilder;
 pub use paseto_parser::PasetoParser;
+pub use session_issuer::SessionIssuer;
+pub use token_builder_config::TokenBuilderConfig;
+pub use token_pair::{issue_token_pair, TokenPair};
@@ -1,6 +1,6 @@
 use crate::generic::*;
 use core::marker::PhantomData;
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::convert::TryFrom;
 use time::format_description::well_known::Rfc3339;
 
@@ -20,11 +20,11 @@ use time::format_description::well_known::Rfc3339;
 ///# #[cfg(all(feature = "prelude", feature="v2_local"))]
 ///# {
 ///   use rusty_paseto::prelude::*;
-
+///
 ///     let key = PasetoSymmetricKey::<V2, Local>::from(Key::<32>::from(*b"wubbalubbadubdubwubbalubbadubdub"));
-
+///
 ///     let footer = Footer::from("some footer");
-
+///
 ///     //create a builder, add some claims and then build the token with the key
 ///     let token = PasetoBuilder::<V2, Local>::default()
 ///       .set_claim(AudienceClaim::from("customers"))
@@ -39,12 +39,12 @@ use time::format_description::well_known::Rfc3339;
 ///       .set_claim(CustomClaim::try_from(("pi to 6 digits", 3.141526))?)
 ///       .set_footer(footer)
 ///       .try_encrypt(&key)?;
-
+///
 ///     //now let's decrypt the token and verify the values
 ///     let json = PasetoParser::<V2, Local>::default()
 ///       .set_footer(footer)
 ///       .parse(&token, &key)?;
-
+///
 ///     assert_eq!(json["aud"], "customers");
 ///     assert_eq!(json["jti"], "me");
 ///     assert_eq!(json["iss"], "me");
@@ -58,7 +58,6 @@ use time::format_description::well_known::Rfc3339;
 ///  # }
 /// # Ok::<(),anyhow::Error>(())
 ///   ```
-
 pub struct PasetoBuilder<'a, Version, Purpose> {
   version: PhantomData<Version>,
   purpose: PhantomData<Purpose>,
@@ -66,6 +65,9 @@ pub struct PasetoBuilder<'a, Version, Purpose> {
   top_level_claims: HashSet<String>,
   dup_top_level_found: (bool, String),
   non_expiring_token: bool,
+  relative_expiration: Option<time::Duration>,
+  auto_jti: bool,
+  last_jti: Option<String>,
 }
 
 impl<'a, Version, Purpose> PasetoBuilder<'a, Version, Purpose> {
@@ -76,6 +78,9 @@ impl<'a, Version, Purpose> PasetoBuilder<'a, Version, Purpose> {
       builder: GenericBuilder::default(),
       top_level_claims: HashSet::new(),
       non_expiring_token: false,
+      relative_expiration: None,
+      auto_jti: false,
+      last_jti: None,
       dup_top_level_found: (false, String::default()),
     }
   }
@@ -101,15 +106,14 @@ impl<'a, Version, Purpose> PasetoBuilder<'a, Version, Purpose> {
   ///     let token = PasetoBuilder::<V2, Local>::default()
   ///       .set_claim(AudienceClaim::from("customers"))
   ///       .try_encrypt(&key)?;
-
+  ///
   ///  #   //now let's decrypt the token and verify the values
   ///  #  let json = PasetoParser::<V2, Local>::default().parse(&token, &key)?;
-
+  ///
   ///  # assert_eq!(json["aud"], "customers");
   /// # }
   /// # Ok::<(),anyhow::Error>(())
   ///   ```
-
   pub fn set_claim<T: PasetoClaim + erased_serde::Serialize + Sized + 'a>(&mut self, value: T) -> &mut Self {
     //we need to inspect all the claims and verify there are no duplicates
     //overwrite nbf default claim if provided
@@ -125,11 +129,89 @@ impl<'a, Version, Purpose> PasetoBuilder<'a, Version, Purpose> {
     self
   }
 
+  /// Adds a [claim](PasetoClaim) to the token only when `condition` is true, following the same rules as
+  /// [set_claim](Self::set_claim) otherwise, so conditional claims don't break up the builder chain.
+  ///
+  /// Returns a mutable reference to the builder on success.
+  ///
+  /// # Errors
+  ///
+  /// none
+  ///
+  /// # Example
+  ///```
+  ///# #[cfg(feature = "default")]
+  ///# {
+  ///   use rusty_paseto::prelude::*;
+  ///     let key = PasetoSymmetricKey::<V4, Local>::from(Key::<32>::from(*b"wubbalubbadubdubwubbalubbadubdub"));
+  ///     let is_admin = false;
+  ///     let token = PasetoBuilder::<V4, Local>::default()
+  ///       .set_claim(SubjectClaim::from("loyal subjects"))
+  ///       .set_claim_if(is_admin, CustomClaim::try_from(("role", "admin"))?)
+  ///       .build(&key)?;
+  ///
+  ///  #  let json = PasetoParser::<V4, Local>::default().parse(&token, &key)?;
+  ///  # assert!(json["role"].is_null());
+  /// # }
+  /// # Ok::<(),anyhow::Error>(())
+  ///   ```
+  pub fn set_claim_if<T: PasetoClaim + erased_serde::Serialize + Sized + 'a>(
+    &mut self,
+    condition: bool,
+    value: T,
+  ) -> &mut Self {
+    if condition {
+      self.set_claim(value);
+    }
+    self
+  }
+
+  /// Scopes the setting of several [claims](PasetoClaim) within a closure which receives this
+  /// builder, grouping claim configuration (including any conditional logic) apart from the rest of
+  /// the builder chain. Claims set within the closure follow the same rules as [set_claim](Self::set_claim).
+  ///
+  /// Returns a mutable reference to the builder on success.
+  ///
+  /// # Errors
+  ///
+  /// none
+  ///
+  /// # Example
+  ///```
+  ///# #[cfg(feature = "default")]
+  ///# {
+  ///   use rusty_paseto::prelude::*;
+  ///     let key = PasetoSymmetricKey::<V4, Local>::from(Key::<32>::from(*b"wubbalubbadubdubwubbalubbadubdub"));
+  ///     let is_admin = true;
+  ///     let token = PasetoBuilder::<V4, Local>::default()
+  ///       .claims(|c| {
+  ///         c.set_claim(AudienceClaim::from("customers"));
+  ///         c.set_claim(SubjectClaim::from("loyal subjects"));
+  ///         if is_admin {
+  ///           c.set_claim(CustomClaim::try_from(("role", "admin")).unwrap());
+  ///         }
+  ///       })
+  ///       .build(&key)?;
+  ///
+  ///  #  let json = PasetoParser::<V4, Local>::default().parse(&token, &key)?;
+  ///  # assert_eq!(json["aud"], "customers");
+  ///  # assert_eq!(json["role"], "admin");
+  /// # }
+  /// # Ok::<(),anyhow::Error>(())
+  ///   ```
+  pub fn claims<F>(&mut self, scope: F) -> &mut Self
+  where
+    F: FnOnce(&mut Self),
+  {
+    scope(self);
+    self
+  }
+
   /// Sets the token to have no expiration date.
   /// A **1 hour** ExpirationClaim is set by default because the use case for non-expiring tokens in the world of security tokens is fairly limited.
   ///  Omitting an expiration claim or forgetting to require one when processing them
   ///  is almost certainly an oversight rather than a deliberate choice.  
-
+  ///
   ///  When it is a deliberate choice, you have the opportunity to deliberately remove this claim from the Builder.
   ///  This method call ensures readers of the code understand the implicit risk.
   ///
@@ -153,15 +235,127 @@ impl<'a, Version, Purpose> PasetoBuilder<'a, Version, Purpose> {
   /// # }
   /// # Ok::<(),anyhow::Error>(())
   ///   ```
-
   pub fn set_no_expiration_danger_acknowledged(&mut self) -> &mut Self {
     self.top_level_claims.insert("exp".to_string());
     self.non_expiring_token = true;
     self
   }
 
+  /// Sets the token to expire a duration after it is built, resolving the [ExpirationClaim] from the clock each
+  /// time [build](Self::build) is called rather than when this method is called.
+  ///
+  /// Builders configured long before issuance, such as templates kept for the lifetime of a service, then issue
+  /// tokens expiring relative to their actual issuance.  The relative expiration follows the same rules as setting
+  /// an [ExpirationClaim] with [set_claim](Self::set_claim), and is ignored for
+  /// [non-expiring](Self::set_no_expiration_danger_acknowledged) tokens.
+  ///
+  /// Returns a mutable reference to the builder on success.
+  ///
+  /// # Errors
+  /// none, [build](Self::build) fails with [ExpirationOutOfRange](GenericBuilderError::ExpirationOutOfRange) if the
+  /// expiration can't be represented as a date
+  ///
+  /// # Example
+  ///```
+  ///# #[cfg(feature = "default")]
+  ///# {
+  ///   use rusty_paseto::prelude::*;
+  ///     let key = PasetoSymmetricKey::<V4, Local>::from(Key::<32>::from(*b"wubbalubbadubdubwubbalubbadubdub"));
+  ///  let mut template = PasetoBuilder::<V4, Local>::default();
+  ///  template.set_expiration_relative(time::Duration::minutes(5));
+  ///
+  ///  //much later, the token still expires five minutes from now
+  ///  let token = template.build(&key)?;
+  /// # }
+  /// # Ok::<(),anyhow::Error>(())
+  ///   ```
+  pub fn set_expiration_relative(&mut self, ttl: time::Duration) -> &mut Self {
+    if !self.top_level_claims.insert("exp".to_string()) {
+      self.dup_top_level_found = (true, "exp".to_string());
+    }
+    self.relative_expiration = Some(ttl);
+    self
+  }
+
+  /// Generates a random [TokenIdentifierClaim] each time [build](Self::build) is called, so every token issued by the
+  /// builder carries a unique `jti` for replay tracking.  The identifier is 16 bytes from the system's secure random
+  /// number generator, base64url encoded, and can be read back with [last_jti](Self::last_jti) after building.
+  ///
+  /// Returns a mutable reference to the builder on success.
+  ///
+  /// # Errors
+  /// none, [build](Self::build) fails with [DuplicateTopLevelPayloadClaim](GenericBuilderError::DuplicateTopLevelPayloadClaim)
+  /// if a `jti` claim is set as well, whether as a [TokenIdentifierClaim] or an [extra](Self::set_extra) claim, and
+  /// [last_jti](Self::last_jti) only changes once a token is built
+  ///
+  /// # Example
+  ///```
+  ///# #[cfg(feature = "default")]
+  ///# {
+  ///   use rusty_paseto::prelude::*;
+  ///     let key = PasetoSymmetricKey::<V4, Local>::from(Key::<32>::from(*b"wubbalubbadubdubwubbalubbadubdub"));
+  ///  let mut builder = PasetoBuilder::<V4, Local>::default();
+  ///  let token = builder.with_auto_jti().build(&key)?;
+  ///  let jti = builder.last_jti().unwrap_or_default().to_string();
+  ///
+  ///  let json = PasetoParser::<V4, Local>::default().parse(&token, &key)?;
+  ///  assert_eq!(json["jti"], jti);
+  ///
+  ///  //the next token gets its own identifier
+  ///  builder.build(&key)?;
+  ///  assert_ne!(builder.last_jti(), Some(jti.as_str()));
+  /// # }
+  /// # Ok::<(),anyhow::Error>(())
+  ///   ```
+  pub fn with_auto_jti(&mut self) -> &mut Self {
+    if !self.top_level_claims.insert("jti".to_string()) {
+      self.dup_top_level_found = (true, "jti".to_string());
+    }
+    self.auto_jti = true;
+    self
+  }
+
+  /// The `jti` generated for the token built last by a builder set up [with_auto_jti](Self::with_auto_jti), none
+  /// before the first build
+  pub fn last_jti(&self) -> Option<&str> {
+    self.last_jti.as_deref()
+  }
+
+  /// Sets a [KeyIdClaim] in the payload of the token, for consumers which expect the `kid` inside
+  /// the claims rather than in the footer.
+  ///
+  /// A payload `kid` is encrypted for [Local] tokens while a footer `kid` is always public, but only a
+  /// footer `kid` can be read before the token is decrypted. See [KeyIdClaim] for details.
+  ///
+  /// Returns a mutable reference to the builder on success.
+  ///
+  /// # Errors
+  /// none
+  ///
+  /// # Example
+  ///```
+  ///# #[cfg(feature = "default")]
+  ///# {
+  ///   use rusty_paseto::prelude::*;
+  ///     let key = PasetoSymmetricKey::<V4, Local>::from(Key::<32>::from(*b"wubbalubbadubdubwubbalubbadubdub"));
+  ///  let token = PasetoBuilder::<V4, Local>::default()
+  ///    .set_key_id_claim("2024-06")
+  ///    .build(&key)?;
+  ///
+  ///  # let json = PasetoParser::<V4, Local>::default().parse(&token, &key)?;
+  ///  # assert_eq!(json["kid"], "2024-06");
+  /// # }
+  /// # Ok::<(),anyhow::Error>(())
+  ///   ```
+  pub fn set_key_id_claim(&mut self, key_id: &'a str) -> &mut Self {
+    self.set_claim(KeyIdClaim::from(key_id))
+  }
+
   /// Sets an optional [Footer] on the token.
   ///
+  /// The footer is authenticated but never encrypted, even in Local tokens.  Prefer
+  /// [set_public_footer](Self::set_public_footer) which says so at the call site.
+  ///
   /// Returns a mutable reference to the builder on success.
   ///
   /// # Errors
@@ -179,22 +373,225 @@ impl<'a, Version, Purpose> PasetoBuilder<'a, Version, Purpose> {
   /// # }
   /// # Ok::<(),anyhow::Error>(())
   ///   ```
-
   pub fn set_footer(&mut self, footer: Footer<'a>) -> &mut Self {
     self.builder.set_footer(footer);
     self
   }
 
-  fn verify_ready_to_build(&mut self) -> Result<(), GenericBuilderError> {
-    if self.non_expiring_token {
-      self.builder.remove_claim("exp");
+  /// Sets an optional [Footer] on the token, which anyone holding the token can read.
+  ///
+  /// The footer is base64 encoded into the token in the clear, whatever the purpose of the token, so it must never
+  /// carry confidential data.  Secrets belong in the claims of a Local token.
+  ///
+  /// Returns a mutable reference to the builder on success.
+  ///
+  /// # Errors
+  ///none
+  ///
+  /// # Example
+  ///```
+  ///# #[cfg(feature = "default")]
+  ///# {
+  ///   use rusty_paseto::prelude::*;
+  ///     let key = PasetoSymmetricKey::<V4, Local>::from(Key::<32>::from(*b"wubbalubbadubdubwubbalubbadubdub"));
+  ///  let token = PasetoBuilder::<V4, Local>::default()
+  ///    .set_public_footer(Footer::from(r#"{"kid":"2024-06"}"#))
+  ///    .build(&key)?;
+  ///
+  ///  //no key is needed to read the footer
+  ///  let footer = UntrustedToken::try_from(token.as_str())?.footer()?;
+  ///  assert_eq!(footer.as_deref(), Some(r#"{"kid":"2024-06"}"#));
+  /// # }
+  /// # Ok::<(),anyhow::Error>(())
+  ///   ```
+  pub fn set_public_footer(&mut self, footer: Footer<'a>) -> &mut Self {
+    self.builder.set_public_footer(footer);
+    self
+  }
+
+  /// Refuses to build tokens whose footer appears to carry secret key material, failing with
+  /// [SecretInFooter](GenericBuilderError::SecretInFooter) instead.
+  ///
+  /// This is a heuristic guard, see [GenericBuilder::forbid_secrets_in_footer] for what it looks for.
+  ///
+  /// Returns a mutable reference to the builder on success.
+  ///
+  /// # Errors
+  /// none
+  ///
+  /// # Example
+  ///```
+  ///# #[cfg(feature = "default")]
+  ///# {
+  ///   use rusty_paseto::prelude::*;
+  ///     let key = PasetoSymmetricKey::<V4, Local>::from(Key::<32>::from(*b"wubbalubbadubdubwubbalubbadubdub"));
+  ///  let result = PasetoBuilder::<V4, Local>::default()
+  ///    .forbid_secrets_in_footer()
+  ///    .set_public_footer(Footer::from(r#"{"kid":"2024-06","secret":"hunter2"}"#))
+  ///    .build(&key);
+  ///
+  ///  assert!(matches!(result, Err(GenericBuilderError::SecretInFooter(_))));
+  /// # }
+  /// # Ok::<(),anyhow::Error>(())
+  ///   ```
+  pub fn forbid_secrets_in_footer(&mut self) -> &mut Self {
+    self.builder.forbid_secrets_in_footer();
+    self
+  }
+
+  /// Refuses to build tokens with a claim holding a number which isn't an integer, failing with
+  /// [FloatClaim](GenericBuilderError::FloatClaim) naming the claim instead.  See
+  /// [GenericBuilder::forbid_float_claims].
+  ///
+  /// Returns a mutable reference to the builder on success.
+  ///
+  /// # Example
+  ///```
+  ///# #[cfg(feature = "default")]
+  ///# {
+  ///   use rusty_paseto::prelude::*;
+  ///     let key = PasetoSymmetricKey::<V4, Local>::from(Key::<32>::from(*b"wubbalubbadubdubwubbalubbadubdub"));
+  ///  let result = PasetoBuilder::<V4, Local>::default()
+  ///    .forbid_float_claims()
+  ///    .set_claim(CustomClaim::try_from(("price", 2.5))?)
+  ///    .build(&key);
+  ///
+  ///  assert!(matches!(result, Err(GenericBuilderError::FloatClaim(claim)) if claim == "price"));
+  /// # }
+  /// # Ok::<(),anyhow::Error>(())
+  ///   ```
+  pub fn forbid_float_claims(&mut self) -> &mut Self {
+    self.builder.forbid_float_claims();
+    self
+  }
+
+  /// Refuses to build tokens whose serialized payload, generated claims included, is longer than the given number of
+  /// bytes, see [GenericBuilder::max_payload_bytes]
+  pub fn max_payload_bytes(&mut self, max: usize) -> &mut Self {
+    self.builder.max_payload_bytes(max);
+    self
+  }
+
+  /// Refuses to build tokens with a claim of another JSON type than the [ClaimSpec] expects, see
+  /// [GenericBuilder::set_claim_spec]
+  pub fn set_claim_spec(&mut self, spec: ClaimSpec) -> &mut Self {
+    self.builder.set_claim_spec(spec);
+    self
+  }
+
+  /// Merges a map of dynamic claims into the payload under the typed claims, see [GenericBuilder::set_extra] for the
+  /// collision policy.  The default `exp`, `iat` and `nbf` claims of this builder are typed, so they win over extras
+  /// of the same name.
+  ///
+  /// Returns a mutable reference to the builder on success.
+  ///
+  /// # Example
+  ///```
+  ///# #[cfg(feature = "default")]
+  ///# {
+  ///   use rusty_paseto::prelude::*;
+  ///     let key = PasetoSymmetricKey::<V4, Local>::from(Key::<32>::from(*b"wubbalubbadubdubwubbalubbadubdub"));
+  ///  let extras = serde_json::json!({"tenant": "acme", "sub": "overridden"});
+  ///  let token = PasetoBuilder::<V4, Local>::default()
+  ///    .set_claim(SubjectClaim::from("loyal subjects"))
+  ///    .set_extra(extras.as_object().cloned().unwrap_or_default())
+  ///    .build(&key)?;
+  ///
+  ///  let json = PasetoParser::<V4, Local>::default().parse(&token, &key)?;
+  ///  assert_eq!(json["tenant"], "acme");
+  ///  assert_eq!(json["sub"], "loyal subjects");
+  /// # }
+  /// # Ok::<(),anyhow::Error>(())
+  ///   ```
+  pub fn set_extra(&mut self, extra_claims: serde_json::Map<String, serde_json::Value>) -> &mut Self {
+    self.builder.set_extra(extra_claims);
+    self
+  }
+
+  /// Captures what this builder would issue as a serializable [BuilderConfig] without consuming it, for an audit
+  /// record of what was requested alongside the token
+  ///
+  /// Holds the staged claims including the defaults, the footer and the
+  /// [relative expiration](Self::set_expiration_relative) as `ttl_seconds`.  An `exp` claim which building would
+  /// replace or remove is left out of the claims.
+  ///
+  /// # Errors
+  ///
+  /// Returns [`GenericBuilderError`] if the staged claims wouldn't build
+  ///
+  /// # Example
+  ///```
+  ///# #[cfg(feature = "default")]
+  ///# {
+  ///   use rusty_paseto::prelude::*;
+  ///
+  ///     # let key = PasetoSymmetricKey::<V4, Local>::from(Key::<32>::from(*b"wubbalubbadubdubwubbalubbadubdub"));
+  ///     let mut builder = PasetoBuilder::<V4, Local>::default();
+  ///     builder
+  ///       .set_claim(SubjectClaim::from("loyal subjects"))
+  ///       .set_expiration_relative(time::Duration::minutes(15));
+  ///
+  ///     let snapshot = builder.config_snapshot()?;
+  ///     let token = builder.build(&key)?;
+  ///
+  ///     assert_eq!(snapshot.claims["sub"], "loyal subjects");
+  ///     assert_eq!(snapshot.ttl_seconds, Some(900));
+  ///     assert!(snapshot.claims.get("exp").is_none());
+  /// # }
+  /// # Ok::<(),anyhow::Error>(())
+  ///   ```
+  pub fn config_snapshot(&self) -> Result<BuilderConfig, GenericBuilderError> {
+    let mut snapshot = self.builder.config_snapshot()?;
+    if self.relative_expiration.is_some() || self.non_expiring_token {
+      snapshot.claims.remove("exp");
+    }
+    snapshot.ttl_seconds = self.relative_expiration.map(|ttl| ttl.whole_seconds());
+    Ok(snapshot)
+  }
+
+  //builds with any key able to seal tokens of this version and purpose, for functionality written once for all of them
+  pub(crate) fn build_with<K>(&mut self, key: &K) -> Result<String, GenericBuilderError>
+  where
+    K: PasetoSealingKey<Version, Purpose>,
+  {
+    let jti = self.verify_ready_to_build()?;
+    let token = key.try_seal(&mut self.builder)?;
+    //only a token actually built has its identifier remembered
+    if jti.is_some() {
+      self.last_jti = jti;
     }
-    //  //raise an error if there were duplicates
+    Ok(token)
+  }
+
+  //stages the claims resolved when building, returning the generated jti if any
+  fn verify_ready_to_build(&mut self) -> Result<Option<String>, GenericBuilderError> {
+    //  //raise an error if there were duplicates, before anything is staged
     let (dup_found, dup_key) = &self.dup_top_level_found;
     if *dup_found {
       return Err(GenericBuilderError::DuplicateTopLevelPayloadClaim(dup_key.to_string()));
     }
-    Ok(())
+    //an extra jti would otherwise be silently replaced by the generated one
+    if self.auto_jti && self.builder.has_extra("jti") {
+      return Err(GenericBuilderError::DuplicateTopLevelPayloadClaim("jti".to_string()));
+    }
+    if let Some(ttl) = self.relative_expiration {
+      let expiration = time::OffsetDateTime::now_utc()
+        .checked_add(ttl)
+        .and_then(|expiration| expiration.format(&Rfc3339).ok())
+        .ok_or(GenericBuilderError::ExpirationOutOfRange)?;
+      self.builder.set_claim(ExpirationClaim::try_from(expiration)?);
+    }
+    if self.non_expiring_token {
+      self.builder.remove_claim("exp");
+    }
+    if self.auto_jti {
+      let jti = base64::encode_config(*Key::<16>::try_new_random()?, base64::URL_SAFE_NO_PAD);
+      //the identifier is generated here so it can't be borrowed by a TokenIdentifierClaim
+      let claim: Box<dyn erased_serde::Serialize> = Box::new(serde_json::json!({ "jti": jti }));
+      self.builder.extend_claims(HashMap::from([("jti".to_string(), claim)]));
+      return Ok(Some(jti));
+    }
+    Ok(None)
   }
 }
 impl<'a, Version, Purpose> PasetoBuilder<'a, Version, Purpose>
@@ -220,11 +617,17 @@ where
   /// # }
   /// # Ok::<(),anyhow::Error>(())
   ///   ```
-
   pub fn set_implicit_assertion(&mut self, implicit_assertion: ImplicitAssertion<'a>) -> &mut Self {
     self.builder.set_implicit_assertion(implicit_assertion);
     self
   }
+
+  ///Defers the [implicit assertion](ImplicitAssertion) to a function run each time a token is built, see
+  ///[GenericBuilder::set_implicit_assertion_with]
+  pub fn set_implicit_assertion_with(&mut self, compute: &'a dyn Fn() -> Vec<u8>) -> &mut Self {
+    self.builder.set_implicit_assertion_with(compute);
+    self
+  }
 }
 
 impl<'a, Version, Purpose> Default for PasetoBuilder<'a, Version, Purpose> {
@@ -261,11 +664,11 @@ impl PasetoBuilder<'_, V1, Local> {
   ///# #[cfg(all(feature = "prelude", feature="v1_local"))]
   ///# {
   ///   use rusty_paseto::prelude::*;
-
+  ///
   ///     let key = PasetoSymmetricKey::<V1, Local>::from(Key::<32>::from(*b"wubbalubbadubdubwubbalubbadubdub"));
-
+  ///
   ///     let footer = Footer::from("some footer");
-
+  ///
   ///     //create a builder, add some claims and then build the token with the key
   ///     let token = PasetoBuilder::<V1, Local>::default()
   ///       .set_claim(AudienceClaim::from("customers"))
@@ -280,12 +683,12 @@ impl PasetoBuilder<'_, V1, Local> {
   ///       .set_claim(CustomClaim::try_from(("pi to 6 digits", 3.141526))?)
   ///       .set_footer(footer)
   ///       .build(&key)?;
-
+  ///
   ///     //now let's decrypt the token and verify the values
   ///     let json = PasetoParser::<V1, Local>::default()
   ///       .set_footer(footer)
   ///       .parse(&token, &key)?;
-
+  ///
   ///     assert_eq!(json["aud"], "customers");
   ///     assert_eq!(json["jti"], "me");
   ///     assert_eq!(json["iss"], "me");
@@ -299,10 +702,8 @@ impl PasetoBuilder<'_, V1, Local> {
   ///  # }
   /// # Ok::<(),anyhow::Error>(())
   ///   ```
-
   pub fn build(&mut self, key: &PasetoSymmetricKey<V1, Local>) -> Result<String, GenericBuilderError> {
-    self.verify_ready_to_build()?;
-    self.builder.try_encrypt(key)
+    self.build_with(key)
   }
 }
 
@@ -322,11 +723,11 @@ impl PasetoBuilder<'_, V2, Local> {
   ///# #[cfg(all(feature = "prelude", feature="v2_local"))]
   ///# {
   ///   use rusty_paseto::prelude::*;
-
+  ///
   ///     let key = PasetoSymmetricKey::<V2, Local>::from(Key::<32>::from(*b"wubbalubbadubdubwubbalubbadubdub"));
-
+  ///
   ///     let footer = Footer::from("some footer");
-
+  ///
   ///     //create a builder, add some claims and then build the token with the key
   ///     let token = PasetoBuilder::<V2, Local>::default()
   ///       .set_claim(AudienceClaim::from("customers"))
@@ -341,12 +742,12 @@ impl PasetoBuilder<'_, V2, Local> {
   ///       .set_claim(CustomClaim::try_from(("pi to 6 digits", 3.141526))?)
   ///       .set_footer(footer)
   ///       .try_encrypt(&key)?;
-
+  ///
   ///     //now let's decrypt the token and verify the values
   ///     let json = PasetoParser::<V2, Local>::default()
   ///       .set_footer(footer)
   ///       .parse(&token, &key)?;
-
+  ///
   ///     assert_eq!(json["aud"], "customers");
   ///     assert_eq!(json["jti"], "me");
   ///     assert_eq!(json["iss"], "me");
@@ -360,10 +761,8 @@ impl PasetoBuilder<'_, V2, Local> {
   ///  # }
   /// # Ok::<(),anyhow::Error>(())
   ///   ```
-
   pub fn build(&mut self, key: &PasetoSymmetricKey<V2, Local>) -> Result<String, GenericBuilderError> {
-    self.verify_ready_to_build()?;
-    self.builder.try_encrypt(key)
+    self.build_with(key)
   }
 }
 
@@ -382,12 +781,12 @@ impl PasetoBuilder<'_, V3, Local> {
   ///# #[cfg(all(feature = "prelude", feature="v3_local"))]
   ///# {
   ///   use rusty_paseto::prelude::*;
-
+  ///
   ///     let key = PasetoSymmetricKey::<V3, Local>::from(Key::<32>::from(*b"wubbalubbadubdubwubbalubbadubdub"));
-
+  ///
   ///     let footer = Footer::from("some footer");
   ///     let implicit_assertion = ImplicitAssertion::from("some assertion");
-
+  ///
   ///     //create a builder, add some claims and then build the token with the key
   ///     let token = PasetoBuilder::<V3, Local>::default()
   ///       .set_claim(AudienceClaim::from("customers"))
@@ -403,13 +802,13 @@ impl PasetoBuilder<'_, V3, Local> {
   ///       .set_footer(footer)
   ///       .set_implicit_assertion(implicit_assertion)
   ///       .try_encrypt(&key)?;
-
+  ///
   ///     //now let's decrypt the token and verify the values
   ///     let json = PasetoParser::<V3, Local>::default()
   ///       .set_footer(footer)
   ///       .set_implicit_assertion(implicit_assertion)
   ///       .parse(&token, &key)?;
-
+  ///
   ///     assert_eq!(json["aud"], "customers");
   ///     assert_eq!(json["jti"], "me");
   ///     assert_eq!(json["iss"], "me");
@@ -423,10 +822,8 @@ impl PasetoBuilder<'_, V3, Local> {
   ///  # }
   /// # Ok::<(),anyhow::Error>(())
   ///   ```
-
   pub fn build(&mut self, key: &PasetoSymmetricKey<V3, Local>) -> Result<String, GenericBuilderError> {
-    self.verify_ready_to_build()?;
-    self.builder.try_encrypt(key)
+    self.build_with(key)
   }
 }
 
@@ -445,12 +842,12 @@ impl PasetoBuilder<'_, V4, Local> {
   ///# #[cfg(all(feature = "prelude", feature="v4_local"))]
   ///# {
   ///   use rusty_paseto::prelude::*;
-
+  ///
   ///     let key = PasetoSymmetricKey::<V4, Local>::from(Key::<32>::from(*b"wubbalubbadubdubwubbalubbadubdub"));
-
+  ///
   ///     let footer = Footer::from("some footer");
   ///     let implicit_assertion = ImplicitAssertion::from("some assertion");
-
+  ///
   ///     //create a builder, add some claims and then build the token with the key
   ///     let token = PasetoBuilder::<V4, Local>::default()
   ///       .set_claim(AudienceClaim::from("customers"))
@@ -466,13 +863,13 @@ impl PasetoBuilder<'_, V4, Local> {
   ///       .set_footer(footer)
   ///       .set_implicit_assertion(implicit_assertion)
   ///       .try_encrypt(&key)?;
-
+  ///
   ///     //now let's decrypt the token and verify the values
   ///     let json = PasetoParser::<V4, Local>::default()
   ///       .set_footer(footer)
   ///       .set_implicit_assertion(implicit_assertion)
   ///       .parse(&token, &key)?;
-
+  ///
   ///     assert_eq!(json["aud"], "customers");
   ///     assert_eq!(json["jti"], "me");
   ///     assert_eq!(json["iss"], "me");
@@ -486,10 +883,8 @@ impl PasetoBuilder<'_, V4, Local> {
   ///  # }
   /// # Ok::<(),anyhow::Error>(())
   ///   ```
-
   pub fn build(&mut self, key: &PasetoSymmetricKey<V4, Local>) -> Result<String, GenericBuilderError> {
-    self.verify_ready_to_build()?;
-    self.builder.try_encrypt(key)
+    self.build_with(key)
   }
 }
 
@@ -508,14 +903,14 @@ impl PasetoBuilder<'_, V1, Public> {
   ///# #[cfg(all(feature = "prelude", feature="v1_public"))]
   ///# {
   ///   # use rusty_paseto::prelude::*;
-
+  ///
   ///    //obtain a private key (pk)
   ///   # let private_key = include_bytes!("../../../tests/v1_public_test_vectors_private_key.pk8");
   ///   # let pk: &[u8] = private_key;
   ///    let private_key = PasetoAsymmetricPrivateKey::<V1, Public>::from(pk);
-
+  ///
   ///     let footer = Footer::from("some footer");
-
+  ///
   ///     //sign a public V1 token
   ///     let token = PasetoBuilder::<V1, Public>::default()
   ///       .set_claim(AudienceClaim::from("customers"))
@@ -530,7 +925,7 @@ impl PasetoBuilder<'_, V1, Public> {
   ///       .set_claim(CustomClaim::try_from(("pi to 6 digits", 3.141526))?)
   ///       .set_footer(footer)
   ///       .try_sign(&private_key)?;
-
+  ///
   /// //obtain a public key (pubk)
   ///   #  let public_key = include_bytes!("../../../tests/v1_public_test_vectors_public_key.der");
   ///   #  let pubk: &[u8] = public_key;
@@ -563,10 +958,8 @@ impl PasetoBuilder<'_, V1, Public> {
   ///  # }
   /// # Ok::<(),anyhow::Error>(())
   ///```
-
   pub fn build(&mut self, key: &PasetoAsymmetricPrivateKey<V1, Public>) -> Result<String, GenericBuilderError> {
-    self.verify_ready_to_build()?;
-    self.builder.try_sign(key)
+    self.build_with(key)
   }
 }
 
@@ -584,16 +977,16 @@ impl PasetoBuilder<'_, V2, Public> {
   ///# #[cfg(all(feature = "prelude", feature="v2_public"))]
   ///# {
   ///   # use rusty_paseto::prelude::*;
-
+  ///
   ///    //obtain a key
   /// let private_key = Key::<64>::try_from("b4cbfb43df4ce210727d953e4a713307fa19bb7d9f85041438d9e11b942a37741eb9dbbbbc047c03fd70604e0071f0987e16b28b757225c11f00415d0e20b1a2")?;
   /// let private_key = PasetoAsymmetricPrivateKey::<V2, Public>::from(&private_key);
-
+  ///
   /// let public_key = Key::<32>::try_from("1eb9dbbbbc047c03fd70604e0071f0987e16b28b757225c11f00415d0e20b1a2")?;
   /// let public_key = PasetoAsymmetricPublicKey::<V2, Public>::from(&public_key);
-
+  ///
   /// let footer = Footer::from("some footer");
-
+  ///
   /// //sign a public V2 token
   /// let token = PasetoBuilder::<V2, Public>::default()
   ///   .set_claim(AudienceClaim::from("customers"))
@@ -608,7 +1001,7 @@ impl PasetoBuilder<'_, V2, Public> {
   ///   .set_claim(CustomClaim::try_from(("pi to 6 digits", 3.141526))?)
   ///   .set_footer(footer)
   ///   .try_sign(&private_key)?;
-
+  ///
   /// //now let's try to verify it
   /// let json = PasetoParser::<V2, Public>::default()
   ///   .set_footer(footer)
@@ -637,10 +1030,8 @@ impl PasetoBuilder<'_, V2, Public> {
   ///  # }
   /// # Ok::<(),anyhow::Error>(())
   ///```
-
   pub fn build(&mut self, key: &PasetoAsymmetricPrivateKey<V2, Public>) -> Result<String, GenericBuilderError> {
-    self.verify_ready_to_build()?;
-    self.builder.try_sign(key)
+    self.build_with(key)
   }
 }
 
@@ -658,20 +1049,20 @@ impl PasetoBuilder<'_, V3, Public> {
   ///# #[cfg(all(feature = "prelude", feature="v3_public"))]
   ///# {
   ///   # use rusty_paseto::prelude::*;
-
+  ///
   ///    //obtain a key
   /// let private_key = Key::<48>::try_from(
   ///   "20347609607477aca8fbfbc5e6218455f3199669792ef8b466faa87bdc67798144c848dd03661eed5ac62461340cea96",
   /// )?;
   /// let private_key = PasetoAsymmetricPrivateKey::<V3, Public>::from(&private_key);
-
+  ///
   /// let public_key = Key::<49>::try_from(
   ///   "02fbcb7c69ee1c60579be7a334134878d9c5c5bf35d552dab63c0140397ed14cef637d7720925c44699ea30e72874c72fb",
   /// )?;
   /// let public_key = PasetoAsymmetricPublicKey::<V3, Public>::try_from(&public_key)?;
-
+  ///
   /// let footer = Footer::from("some footer");
-
+  ///
   /// let implicit_assertion = ImplicitAssertion::from("some assertion");
   /// //sign a public V3 token
   /// let token = PasetoBuilder::<V3, Public>::default()
@@ -688,7 +1079,7 @@ impl PasetoBuilder<'_, V3, Public> {
   ///   .set_footer(footer)
   ///   .set_implicit_assertion(implicit_assertion)
   ///   .try_sign(&private_key)?;
-
+  ///
   /// //now let's try to verify it
   /// let json = PasetoParser::<V3, Public>::default()
   ///   .set_footer(footer)
@@ -718,10 +1109,8 @@ impl PasetoBuilder<'_, V3, Public> {
   ///  # }
   /// # Ok::<(),anyhow::Error>(())
   ///```
-
   pub fn build(&mut self, key: &PasetoAsymmetricPrivateKey<V3, Public>) -> Result<String, GenericBuilderError> {
-    self.verify_ready_to_build()?;
-    self.builder.try_sign(key)
+    self.build_with(key)
   }
 }
 
@@ -739,18 +1128,18 @@ impl PasetoBuilder<'_, V4, Public> {
   ///# #[cfg(all(feature = "prelude", feature="v4_public"))]
   ///# {
   ///   # use rusty_paseto::prelude::*;
-
+  ///
   /// //create a key
   /// let private_key = Key::<64>::try_from("b4cbfb43df4ce210727d953e4a713307fa19bb7d9f85041438d9e11b942a37741eb9dbbbbc047c03fd70604e0071f0987e16b28b757225c11f00415d0e20b1a2")?;
   /// let pk: &[u8] = private_key.as_slice();
-  /// let private_key = PasetoAsymmetricPrivateKey::<V4, Public>::from(pk);
-
+  /// let private_key = PasetoAsymmetricPrivateKey::<V4, Public>::try_from(pk)?;
+  ///
   /// let public_key = Key::<32>::try_from("1eb9dbbbbc047c03fd70604e0071f0987e16b28b757225c11f00415d0e20b1a2")?;
   /// let public_key = PasetoAsymmetricPublicKey::<V4, Public>::from(&public_key);
-
+  ///
   /// let footer = Footer::from("some footer");
   /// let implicit_assertion = ImplicitAssertion::from("some assertion");
-
+  ///
   /// //sign a public V4 token
   /// let token = PasetoBuilder::<V4, Public>::default()
   ///   .set_claim(AudienceClaim::from("customers"))
@@ -766,7 +1155,7 @@ impl PasetoBuilder<'_, V4, Public> {
   ///   .set_footer(footer)
   ///   .set_implicit_assertion(implicit_assertion)
   ///   .try_sign(&private_key)?;
-
+  ///
   /// //now let's try to verify it
   /// let json = PasetoParser::<V4, Public>::default()
   ///   .set_footer(footer)
@@ -793,19 +1182,17 @@ impl PasetoBuilder<'_, V4, Public> {
   /// assert_eq!(json["sub"], "loyal subjects");
   /// assert_eq!(json["pi to 6 digits"], 3.141526);
   /// assert_eq!(json["seats"], 4);
-
+  ///
   ///  # }
   /// # Ok::<(),anyhow::Error>(())
   ///```
-
   pub fn build(&mut self, key: &PasetoAsymmetricPrivateKey<V4, Public>) -> Result<String, GenericBuilderError> {
-    self.verify_ready_to_build()?;
-    self.builder.try_sign(key)
+    self.build_with(key)
   }
 }
 
 #[cfg(all(test, feature = "v2_local"))]
-mod paseto_builder {
+mod paseto_builder_unit_tests {
 
   use crate::prelude::*;
   use anyhow::Result;
@@ -839,6 +1226,84 @@ mod paseto_builder {
     Ok(())
   }
 
+  #[test]
+  fn claims_closure_test() -> Result<()> {
+    let key = PasetoSymmetricKey::<V2, Local>::from(Key::from(*b"wubbalubbadubdubwubbalubbadubdub"));
+    let include_seats = false;
+
+    let token = PasetoBuilder::<V2, Local>::default()
+      .claims(|c| {
+        c.set_claim(AudienceClaim::from("customers"));
+        if include_seats {
+          c.set_claim(CustomClaim::try_from(("seats", 4)).unwrap());
+        }
+      })
+      .set_claim(SubjectClaim::from("loyal subjects"))
+      .build(&key)?;
+
+    let json = PasetoParser::<V2, Local>::default().parse(&token, &key)?;
+    assert_eq!(json["aud"], "customers");
+    assert_eq!(json["sub"], "loyal subjects");
+    assert!(json["seats"].is_null());
+
+    //claims set within the closure still take part in duplicate detection
+    let error = PasetoBuilder::<V2, Local>::default()
+      .set_claim(AudienceClaim::from("customers"))
+      .claims(|c| {
+        c.set_claim(AudienceClaim::from("other customers"));
+      })
+      .build(&key)
+      .unwrap_err();
+    assert!(matches!(error, GenericBuilderError::DuplicateTopLevelPayloadClaim(_)));
+    Ok(())
+  }
+
+  #[test]
+  fn set_claim_if_test() -> Result<()> {
+    let key = PasetoSymmetricKey::<V2, Local>::from(Key::from(*b"wubbalubbadubdubwubbalubbadubdub"));
+
+    let token = PasetoBuilder::<V2, Local>::default()
+      .set_claim_if(true, AudienceClaim::from("customers"))
+      .set_claim_if(false, CustomClaim::try_from(("seats", 4))?)
+      .build(&key)?;
+    let json = PasetoParser::<V2, Local>::default().parse(&token, &key)?;
+    assert_eq!(json["aud"], "customers");
+    assert!(json["seats"].is_null());
+
+    //a skipped claim takes no part in duplicate detection
+    assert!(PasetoBuilder::<V2, Local>::default()
+      .set_claim(AudienceClaim::from("customers"))
+      .set_claim_if(false, AudienceClaim::from("other customers"))
+      .build(&key)
+      .is_ok());
+    Ok(())
+  }
+
+  #[test]
+  fn key_id_claim_test() -> Result<()> {
+    let key = PasetoSymmetricKey::<V2, Local>::from(Key::from(*b"wubbalubbadubdubwubbalubbadubdub"));
+
+    let token = PasetoBuilder::<V2, Local>::default()
+      .set_key_id_claim("2024-06")
+      .build(&key)?;
+
+    let json = PasetoParser::<V2, Local>::default()
+      .check_claim(KeyIdClaim::from("2024-06"))
+      .parse(&token, &key)?;
+    assert_eq!(json["kid"], "2024-06");
+
+    //the kid lives in the encrypted payload, not in a footer
+    assert_eq!(token.split('.').count(), 3);
+
+    let error = PasetoBuilder::<V2, Local>::default()
+      .set_key_id_claim("2024-06")
+      .set_claim(KeyIdClaim::from("2024-07"))
+      .build(&key)
+      .unwrap_err();
+    assert!(matches!(error, GenericBuilderError::DuplicateTopLevelPayloadClaim(_)));
+    Ok(())
+  }
+
   #[test]
   fn update_default_not_before_claim_test() -> Result<()> {
     //create a key
@@ -970,6 +1435,108 @@ mod paseto_builder {
     Ok(())
   }
 
+  #[test]
+  fn config_snapshot_test() -> Result<()> {
+    let key = PasetoSymmetricKey::<V2, Local>::from(Key::from(*b"wubbalubbadubdubwubbalubbadubdub"));
+    let mut builder = PasetoBuilder::<V2, Local>::default();
+    builder
+      .set_claim(AudienceClaim::from("customers"))
+      .set_claim(CustomClaim::try_from(("seats", 4))?)
+      .set_footer(Footer::from("key-id:1"));
+
+    let snapshot = builder.config_snapshot()?;
+    assert_eq!(snapshot.claims["aud"], "customers");
+    assert_eq!(snapshot.claims["seats"], 4);
+    assert_eq!(snapshot.footer.as_deref(), Some("key-id:1"));
+    assert_eq!(snapshot.ttl_seconds, None);
+
+    //the snapshot leaves the builder untouched and records what ends up in the payload
+    let token = builder.build(&key)?;
+    let json = PasetoParser::<V2, Local>::default()
+      .set_footer(Footer::from("key-id:1"))
+      .parse(&token, &key)?;
+    assert_eq!(serde_json::Value::Object(snapshot.claims), json);
+
+    builder.set_no_expiration_danger_acknowledged();
+    assert!(builder.config_snapshot()?.claims.get("exp").is_none());
+    Ok(())
+  }
+
+  #[test]
+  fn auto_jti_test() -> Result<()> {
+    let key = PasetoSymmetricKey::<V2, Local>::from(Key::from(*b"wubbalubbadubdubwubbalubbadubdub"));
+    let mut builder = PasetoBuilder::<V2, Local>::default();
+    assert_eq!(builder.with_auto_jti().last_jti(), None);
+
+    let first = builder.build(&key)?;
+    let first_jti = builder.last_jti().unwrap_or_default().to_string();
+    let second = builder.build(&key)?;
+    let json = PasetoParser::<V2, Local>::default().parse(&first, &key)?;
+    assert_eq!(json["jti"], first_jti);
+    assert_eq!(first_jti.len(), 22);
+    let json = PasetoParser::<V2, Local>::default().parse(&second, &key)?;
+    assert_eq!(json["jti"], builder.last_jti().unwrap_or_default());
+    assert_ne!(json["jti"], first_jti);
+
+    //a build which fails leaves the identifier of the last token built
+    let last_jti = builder.last_jti().unwrap_or_default().to_string();
+    assert!(matches!(
+      builder.max_payload_bytes(10).build(&key),
+      Err(GenericBuilderError::PayloadTooLarge { .. })
+    ));
+    assert_eq!(builder.last_jti(), Some(last_jti.as_str()));
+
+    //a jti set alongside fails before one is generated, leaving the claim set as it was
+    let mut builder = PasetoBuilder::<V2, Local>::default();
+    builder.set_claim(TokenIdentifierClaim::from("mine")).with_auto_jti();
+    let error = builder.build(&key).unwrap_err();
+    assert!(matches!(error, GenericBuilderError::DuplicateTopLevelPayloadClaim(claim) if claim == "jti"));
+    assert_eq!(builder.last_jti(), None);
+    assert_eq!(builder.config_snapshot()?.claims["jti"], "mine");
+
+    let mut extra_claims = serde_json::Map::new();
+    extra_claims.insert("jti".to_string(), serde_json::json!("mine"));
+    let error = PasetoBuilder::<V2, Local>::default()
+      .set_extra(extra_claims)
+      .with_auto_jti()
+      .build(&key)
+      .unwrap_err();
+    assert!(matches!(error, GenericBuilderError::DuplicateTopLevelPayloadClaim(claim) if claim == "jti"));
+    Ok(())
+  }
+
+  #[test]
+  fn relative_expiration_claim_test() -> Result<()> {
+    let key = PasetoSymmetricKey::<V2, Local>::from(Key::from(*b"wubbalubbadubdubwubbalubbadubdub"));
+    let mut template = PasetoBuilder::<V2, Local>::default();
+    template.set_expiration_relative(time::Duration::days(4));
+    std::thread::sleep(std::time::Duration::from_millis(1100));
+
+    //the expiration is resolved when building, not when it was configured
+    let before_build = time::OffsetDateTime::now_utc().replace_nanosecond(0)?;
+    let token = template.build(&key)?;
+    let json = PasetoParser::<V2, Local>::default().parse(&token, &key)?;
+    let expiration = time::OffsetDateTime::parse(json["exp"].as_str().unwrap_or_default(), &Rfc3339)?;
+    assert!(expiration >= before_build + time::Duration::days(4));
+    assert!(expiration <= time::OffsetDateTime::now_utc() + time::Duration::days(4));
+
+    //it is the expiration claim, so setting both is a duplicate
+    assert!(matches!(
+      PasetoBuilder::<V2, Local>::default()
+        .set_claim(ExpirationClaim::try_from("2030-01-01T00:00:00Z")?)
+        .set_expiration_relative(time::Duration::days(4))
+        .build(&key),
+      Err(GenericBuilderError::DuplicateTopLevelPayloadClaim(claim)) if claim == "exp"
+    ));
+    assert!(matches!(
+      PasetoBuilder::<V2, Local>::default()
+        .set_expiration_relative(time::Duration::MAX)
+        .build(&key),
+      Err(GenericBuilderError::ExpirationOutOfRange)
+    ));
+    Ok(())
+  }
+
   #[test]
   fn check_for_default_expiration_claim_test() -> Result<()> {
     //create a key
@@ -1,7 +1,9 @@
 use crate::generic::*;
 use core::marker::PhantomData;
 use serde_json::Value;
-use time::format_description::well_known::Rfc3339;
+use std::cell::Cell;
+use std::rc::Rc;
+use time::OffsetDateTime;
 
 ///The PasetoParser is created at compile time by specifying a PASETO version and purpose and
 ///providing a key of the same version and purpose. This structure allows setting [PASETO claims](https://github.com/paseto-standard/paseto-spec/blob/master/docs/02-Implementation-Guide/04-Claims.md),
@@ -20,11 +22,11 @@ use time::format_description::well_known::Rfc3339;
 ///# #[cfg(all(feature = "prelude", feature="v2_local"))]
 ///# {
 ///   use rusty_paseto::prelude::*;
-
+///
 ///     let key = PasetoSymmetricKey::<V2, Local>::from(Key::<32>::from(*b"wubbalubbadubdubwubbalubbadubdub"));
-
+///
 ///     let footer = Footer::from("some footer");
-
+///
 ///     //create a builder, add some claims and then build the token with the key
 ///     let token = PasetoBuilder::<V2, Local>::default()
 ///       .set_claim(AudienceClaim::from("customers"))
@@ -39,12 +41,12 @@ use time::format_description::well_known::Rfc3339;
 ///       .set_claim(CustomClaim::try_from(("pi to 6 digits", 3.141526))?)
 ///       .set_footer(footer)
 ///       .try_encrypt(&key)?;
-
+///
 ///     //now let's decrypt the token and verify the values
 ///     let json = PasetoParser::<V2, Local>::default()
 ///       .set_footer(footer)
 ///       .parse(&token, &key)?;
-
+///
 ///     assert_eq!(json["aud"], "customers");
 ///     assert_eq!(json["jti"], "me");
 ///     assert_eq!(json["iss"], "me");
@@ -58,11 +60,15 @@ use time::format_description::well_known::Rfc3339;
 ///  # }
 /// # Ok::<(),anyhow::Error>(())
 ///   ```
-
 pub struct PasetoParser<'a, Version, Purpose> {
   version: PhantomData<Version>,
   purpose: PhantomData<Purpose>,
   parser: GenericParser<'a, 'a, Version, Purpose>,
+  //the time the default expiration and not before validators and the server ceiling compare against, the system
+  //clock when unset
+  now: Rc<Cell<Option<OffsetDateTime>>>,
+  //the clock skew tolerated by the default expiration and not before validators, set by a policy
+  leeway: Rc<Cell<time::Duration>>,
 }
 
 impl<'a, Version, Purpose> PasetoParser<'a, Version, Purpose> {
@@ -78,28 +84,30 @@ impl<'a, Version, Purpose> PasetoParser<'a, Version, Purpose> {
   ///# #[cfg(all(feature = "prelude", feature="v2_local"))]
   ///# {
   ///   use rusty_paseto::prelude::*;
-
+  ///
   ///     # let key = PasetoSymmetricKey::<V2, Local>::from(Key::<32>::from(*b"wubbalubbadubdubwubbalubbadubdub"));
-
+  ///
   ///     # let footer = Footer::from("some footer");
-
+  ///
   ///     # //create a builder, add some claims and then build the token with the key
   ///     # let token = PasetoBuilder::<V2, Local>::default()
   ///     #  .try_encrypt(&key)?;
-
+  ///
   ///     //decrypt and parse claims from the PASETO token string
   ///     let json = PasetoParser::<V2, Local>::default()
   ///       .parse(&token, &key)?;
-
+  ///
   /// # }
   /// # Ok::<(),anyhow::Error>(())
   ///   ```
-
   pub fn new() -> Self {
+    let parser = GenericParser::default();
     PasetoParser::<'a, Version, Purpose> {
       version: PhantomData::<Version>,
       purpose: PhantomData::<Purpose>,
-      parser: GenericParser::default(),
+      now: parser.clock(),
+      leeway: parser.leeway(),
+      parser,
     }
   }
   /// Takes a [PasetoClaim] and a [ValidatorFn] and uses the function to validate the claim during
@@ -115,23 +123,23 @@ impl<'a, Version, Purpose> PasetoParser<'a, Version, Purpose> {
   ///# #[cfg(all(feature = "prelude", feature="v2_local"))]
   ///# {
   ///   use rusty_paseto::prelude::*;
-
+  ///
   ///     # let key = PasetoSymmetricKey::<V2, Local>::from(Key::<32>::from(*b"wubbalubbadubdubwubbalubbadubdub"));
-
+  ///
   ///     # let footer = Footer::from("some footer");
-
+  ///
   ///     # //create a builder, add some claims and then build the token with the key
   ///     # let token = PasetoBuilder::<V2, Local>::default()
   ///      # .try_encrypt(&key)?;
-
+  ///
   ///     //decrypt and parse claims from the PASETO token string
   ///     let json = PasetoParser::<V2, Local>::default()
   ///   .validate_claim(ExpirationClaim::default(), &|key, value| {
   ///     //let's get the value
   ///     let val = value.as_str().ok_or(PasetoClaimError::Unexpected(key.to_string()))?;
-
+  ///
   ///     let datetime = iso8601::datetime(val).unwrap();
-
+  ///
   ///     let in_an_hour = (time::OffsetDateTime::now_utc() + time::Duration::hours(1))
   ///       .time()
   ///       .hour()
@@ -140,15 +148,14 @@ impl<'a, Version, Purpose> PasetoParser<'a, Version, Purpose> {
   ///     assert_eq!(key, "exp");
   ///     //date should be today
   ///     assert_eq!(datetime.time.hour.to_string(), in_an_hour);
-
+  ///
   ///     Ok(())
   ///   })
   ///       .parse(&token, &key)?;
-
+  ///
   /// # }
   /// # Ok::<(),anyhow::Error>(())
   ///   ```
-
   pub fn validate_claim<T: PasetoClaim + 'a + serde::Serialize>(
     &mut self,
     value: T,
@@ -170,35 +177,523 @@ impl<'a, Version, Purpose> PasetoParser<'a, Version, Purpose> {
   ///# #[cfg(all(feature = "prelude", feature="v2_local"))]
   ///# {
   ///   use rusty_paseto::prelude::*;
-
+  ///
   ///     # let key = PasetoSymmetricKey::<V2, Local>::from(Key::<32>::from(*b"wubbalubbadubdubwubbalubbadubdub"));
-
+  ///
   ///     # let footer = Footer::from("some footer");
-
+  ///
   ///     # //create a builder, add some claims and then build the token with the key
   ///     # let token = PasetoBuilder::<V2, Local>::default()
   /// # .set_claim(AudienceClaim::from("customers"))
   ///  #      .try_encrypt(&key)?;
-
+  ///
   ///     //decrypt and parse claims from the PASETO token string
   ///     let json = PasetoParser::<V2, Local>::default()
   /// .check_claim(AudienceClaim::from("customers"))
   ///       .parse(&token, &key)?;
-
+  ///
   /// # }
   /// # Ok::<(),anyhow::Error>(())
   ///   ```
-
   pub fn check_claim<T: PasetoClaim + 'static + serde::Serialize>(&mut self, value: T) -> &mut Self {
     self.parser.check_claim(value);
     self
   }
 
+  /// Takes a [CrossClaimValidatorFn] which receives the full payload after decryption or signature
+  /// verification and uses it to enforce relationships between several claims.
+  ///
+  /// Returns a mutable reference to the parser
+  ///
+  /// # Errors
+  /// none
+  ///
+  /// # Example
+  ///```
+  ///# #[cfg(feature = "default")]
+  ///# {
+  ///   use rusty_paseto::prelude::*;
+  ///     # let key = PasetoSymmetricKey::<V4, Local>::from(Key::<32>::from(*b"wubbalubbadubdubwubbalubbadubdub"));
+  ///     # let token = PasetoBuilder::<V4, Local>::default()
+  ///     #  .set_claim(SubjectClaim::from("acme:wile.e.coyote"))
+  ///     #  .set_claim(CustomClaim::try_from(("tenant_id", "acme"))?)
+  ///     #  .build(&key)?;
+  ///     let json = PasetoParser::<V4, Local>::default()
+  ///       .validate_across(&|claims| {
+  ///         let tenant = claims["tenant_id"].as_str().unwrap_or_default();
+  ///         let subject = claims["sub"].as_str().unwrap_or_default();
+  ///         if subject.starts_with(&format!("{}:", tenant)) {
+  ///           Ok(())
+  ///         } else {
+  ///           Err(format!("subject does not belong to tenant '{}'", tenant))
+  ///         }
+  ///       })
+  ///       .parse(&token, &key)?;
+  /// # }
+  /// # Ok::<(),anyhow::Error>(())
+  ///   ```
+  pub fn validate_across(&mut self, validation_closure: &'static CrossClaimValidatorFn) -> &mut Self {
+    self.parser.validate_across(validation_closure);
+    self
+  }
+
+  ///Sets the current time the default [expiration](ExpirationClaim) and [not before](NotBeforeClaim) checks compare
+  ///against, in place of the system clock
+  ///
+  ///This only affects the checks installed by [Default]; validators set with [validate_claim](Self::validate_claim)
+  ///bring their own notion of time.
+  ///
+  /// # Example
+  ///```
+  ///# #[cfg(feature = "default")]
+  ///# {
+  ///   use rusty_paseto::prelude::*;
+  ///
+  ///     # let key = PasetoSymmetricKey::<V4, Local>::from(Key::<32>::from(*b"wubbalubbadubdubwubbalubbadubdub"));
+  ///     let token = PasetoBuilder::<V4, Local>::default()
+  ///       .set_claim(ExpirationClaim::try_from("2030-01-01T00:00:00Z")?)
+  ///       .build(&key)?;
+  ///
+  ///     //2031-01-01T00:00:00Z
+  ///     let in_2031 = time::OffsetDateTime::from_unix_timestamp(1_924_992_000)?;
+  ///     let result = PasetoParser::<V4, Local>::default()
+  ///       .set_now(in_2031)
+  ///       .parse(&token, &key);
+  ///
+  ///     assert!(matches!(
+  ///       result,
+  ///       Err(GenericParserError::ClaimError { source: PasetoClaimError::Expired })
+  ///     ));
+  /// # }
+  /// # Ok::<(),anyhow::Error>(())
+  ///   ```
+  pub fn set_now(&mut self, now: OffsetDateTime) -> &mut Self {
+    self.now.set(Some(now));
+    self
+  }
+
+  ///Sets the current time for the default time checks as seconds since the Unix epoch, for callers without an
+  ///[OffsetDateTime] at hand.  See [set_now](Self::set_now).
+  ///
+  /// # Errors
+  ///
+  /// Returns [TimestampOutOfRange](GenericParserError::TimestampOutOfRange) if the timestamp can't be represented as a
+  /// date
+  ///
+  /// # Example
+  ///```
+  ///# #[cfg(feature = "default")]
+  ///# {
+  ///   use rusty_paseto::prelude::*;
+  ///
+  ///     # let key = PasetoSymmetricKey::<V4, Local>::from(Key::<32>::from(*b"wubbalubbadubdubwubbalubbadubdub"));
+  ///     let token = PasetoBuilder::<V4, Local>::default()
+  ///       .set_claim(NotBeforeClaim::try_from("2030-01-01T00:00:00Z")?)
+  ///       .set_claim(ExpirationClaim::try_from("2030-01-02T00:00:00Z")?)
+  ///       .build(&key)?;
+  ///
+  ///     //2030-01-01T00:00:01Z
+  ///     let json = PasetoParser::<V4, Local>::default()
+  ///       .set_now_unix(1_893_456_001)?
+  ///       .parse(&token, &key)?;
+  /// # }
+  /// # Ok::<(),anyhow::Error>(())
+  ///   ```
+  pub fn set_now_unix(&mut self, now_unix: i64) -> Result<&mut Self, GenericParserError> {
+    let now =
+      OffsetDateTime::from_unix_timestamp(now_unix).map_err(|_| GenericParserError::TimestampOutOfRange(now_unix))?;
+    Ok(self.set_now(now))
+  }
+
+  ///Reads the `exp`, `nbf` and `iat` claims given as a whole number since the Unix epoch in the given unit, see
+  ///[GenericParser::set_numeric_time_unit]
+  ///
+  /// # Example
+  ///```
+  ///# #[cfg(feature = "default")]
+  ///# {
+  ///   use rusty_paseto::prelude::*;
+  ///
+  ///     # let key = PasetoSymmetricKey::<V4, Local>::from(Key::<32>::from(*b"wubbalubbadubdubwubbalubbadubdub"));
+  ///     //2030-01-01T00:00:00Z in milliseconds
+  ///     let token = GenericBuilder::<V4, Local>::default()
+  ///       .set_raw_payload(r#"{"exp":1893456000000}"#.to_string())
+  ///       .try_encrypt(&key)?;
+  ///
+  ///     let json = PasetoParser::<V4, Local>::default()
+  ///       .set_numeric_time_unit(NumericTimeUnit::Milliseconds)
+  ///       .set_now_unix(1_893_455_999)?
+  ///       .parse(&token, &key)?;
+  /// # }
+  /// # Ok::<(),anyhow::Error>(())
+  ///   ```
+  pub fn set_numeric_time_unit(&mut self, unit: NumericTimeUnit) -> &mut Self {
+    self.parser.set_numeric_time_unit(unit);
+    self
+  }
+
+  ///Sets a ceiling on the validity of every token, rejecting them with
+  ///[ExceededServerLimit](GenericParserError::ExceededServerLimit) once the current time is past it, even when
+  ///their own expiration is later.  The ceiling is compared against the [injected time](Self::set_now) if there is one.
+  ///
+  /// # Example
+  ///```
+  ///# #[cfg(feature = "default")]
+  ///# {
+  ///   use rusty_paseto::prelude::*;
+  ///
+  ///     # let key = PasetoSymmetricKey::<V4, Local>::from(Key::<32>::from(*b"wubbalubbadubdubwubbalubbadubdub"));
+  ///     let token = PasetoBuilder::<V4, Local>::default()
+  ///       .set_claim(ExpirationClaim::try_from("2030-01-02T00:00:00Z")?)
+  ///       .build(&key)?;
+  ///
+  ///     //the session is capped at 2030-01-01T00:00:00Z
+  ///     let session_cap = time::OffsetDateTime::from_unix_timestamp(1_893_456_000)?;
+  ///     let result = PasetoParser::<V4, Local>::default()
+  ///       .not_valid_after(session_cap)
+  ///       .set_now_unix(1_893_456_001)?
+  ///       .parse(&token, &key);
+  ///
+  ///     assert!(matches!(result, Err(GenericParserError::ExceededServerLimit(_))));
+  /// # }
+  /// # Ok::<(),anyhow::Error>(())
+  ///   ```
+  pub fn not_valid_after(&mut self, not_valid_after: OffsetDateTime) -> &mut Self {
+    self.parser.not_valid_after(not_valid_after);
+    self
+  }
+
+  ///Requires the token to have been issued within the given window, rejecting tokens whose `iat` claim is older
+  ///with [TokenTooOld](GenericParserError::TokenTooOld).  The age is measured against the
+  ///[injected time](Self::set_now) if there is one.
+  ///
+  /// # Example
+  ///```
+  ///# #[cfg(feature = "default")]
+  ///# {
+  ///   use rusty_paseto::prelude::*;
+  ///
+  ///     # let key = PasetoSymmetricKey::<V4, Local>::from(Key::<32>::from(*b"wubbalubbadubdubwubbalubbadubdub"));
+  ///     //the builder sets the issued at claim to the current time
+  ///     let token = PasetoBuilder::<V4, Local>::default().build(&key)?;
+  ///
+  ///     //step up authentication for a sensitive operation
+  ///     let json = PasetoParser::<V4, Local>::default()
+  ///       .require_fresh(time::Duration::minutes(2))
+  ///       .parse(&token, &key)?;
+  /// # }
+  /// # Ok::<(),anyhow::Error>(())
+  ///   ```
+  pub fn require_fresh(&mut self, max_age: time::Duration) -> &mut Self {
+    self.parser.require_fresh(max_age);
+    self
+  }
+
+  ///Applies an organization wide [ParserPolicy], see [GenericParser::set_policy].  The leeway of the policy widens
+  ///the default [expiration](ExpirationClaim) and [not before](NotBeforeClaim) checks to tolerate clock skew between
+  ///the issuer and this parser.
+  pub fn set_policy(&mut self, policy: &ParserPolicy) -> &mut Self {
+    self.parser.set_policy(policy);
+    self
+  }
+
   ///Sets an optional [Footer] to use during parsing
   pub fn set_footer(&mut self, footer: Footer<'a>) -> &mut Self {
     self.parser.set_footer(footer);
     self
   }
+
+  ///Restricts the tokens honored to those whose header declares one of the given [PasetoVersion]s, rejecting any
+  ///other with [VersionNotAllowed](GenericParserError::VersionNotAllowed) before decryption or signature
+  ///verification is attempted
+  ///
+  ///The parser only opens tokens of its own version, so during a migration the set guards
+  ///[check_header](Self::check_header), which decides whether a token of an older version goes on to a parser of
+  ///that version.  See [GenericParser::set_allowed_versions].
+  ///
+  /// # Example
+  ///```
+  ///# #[cfg(all(feature = "batteries_included", feature="v2_local"))]
+  ///# {
+  ///   use rusty_paseto::prelude::*;
+  ///
+  ///     # let key = PasetoSymmetricKey::<V2, Local>::from(Key::<32>::from(*b"wubbalubbadubdubwubbalubbadubdub"));
+  ///     # let token = PasetoBuilder::<V2, Local>::default().build(&key)?;
+  ///     //this service has finished migrating to v4 and no longer honors v2 tokens
+  ///     let result = PasetoParser::<V2, Local>::default()
+  ///       .set_allowed_versions(&[PasetoVersion::V4])
+  ///       .parse(&token, &key);
+  ///
+  ///     assert!(matches!(result, Err(GenericParserError::VersionNotAllowed(PasetoVersion::V2))));
+  /// # }
+  /// # Ok::<(),anyhow::Error>(())
+  ///   ```
+  pub fn set_allowed_versions(&mut self, versions: &[PasetoVersion]) -> &mut Self {
+    self.parser.set_allowed_versions(versions);
+    self
+  }
+
+  ///Rejects tokens whose header declares any other [PasetoPurpose] with
+  ///[UnexpectedPurpose](GenericParserError::UnexpectedPurpose) before decryption or signature
+  ///verification is attempted
+  ///
+  /// # Example
+  ///```
+  ///# #[cfg(feature = "default")]
+  ///# {
+  ///   use rusty_paseto::prelude::*;
+  ///
+  ///     # let key = PasetoSymmetricKey::<V4, Local>::from(Key::<32>::from(*b"wubbalubbadubdubwubbalubbadubdub"));
+  ///     # let token = PasetoBuilder::<V4, Local>::default().build(&key)?;
+  ///     //this middleware only ever handles public tokens
+  ///     let mut parser = PasetoParser::<V4, Public>::default();
+  ///     parser.expect_purpose(PasetoPurpose::Public);
+  ///
+  ///     //so a local token is turned away before any key is looked up
+  ///     let result = parser.check_header(&token);
+  ///     assert!(matches!(result, Err(GenericParserError::UnexpectedPurpose { .. })));
+  /// # }
+  /// # Ok::<(),anyhow::Error>(())
+  ///   ```
+  pub fn expect_purpose(&mut self, purpose: PasetoPurpose) -> &mut Self {
+    self.parser.expect_purpose(purpose);
+    self
+  }
+
+  ///Rejects tokens whose footer doesn't carry the expected `kid` with
+  ///[KeyIdMismatch](GenericParserError::KeyIdMismatch) before decryption or signature verification is
+  ///attempted
+  pub fn expect_key_id(&mut self, key_id: &'a str) -> &mut Self {
+    self.parser.expect_key_id(key_id);
+    self
+  }
+
+  ///Requires the token to carry the given [TokenType] as its `typ` claim, rejecting tokens of another
+  ///kind, such as a refresh token presented where an access token is expected
+  ///
+  /// # Example
+  ///```
+  ///# #[cfg(feature = "default")]
+  ///# {
+  ///   use rusty_paseto::prelude::*;
+  ///
+  ///     # let key = PasetoSymmetricKey::<V4, Local>::from(Key::<32>::from(*b"wubbalubbadubdubwubbalubbadubdub"));
+  ///     let pair = issue_token_pair(|_| {}, time::Duration::minutes(15), time::Duration::days(30), &key)?;
+  ///
+  ///     let mut access_parser = PasetoParser::<V4, Local>::default();
+  ///     access_parser.expect_type(TokenType::Access);
+  ///     assert!(access_parser.parse(&pair.access_token, &key).is_ok());
+  ///     assert!(access_parser.parse(&pair.refresh_token, &key).is_err());
+  /// # }
+  /// # Ok::<(),anyhow::Error>(())
+  ///   ```
+  pub fn expect_type(&mut self, token_type: TokenType) -> &mut Self {
+    self.parser.expect_type(token_type);
+    self
+  }
+
+  ///Sets the maximum number of top level claims accepted in a payload, rejecting payloads with more
+  ///with [TooManyClaims](GenericParserError::TooManyClaims)
+  pub fn max_claims(&mut self, max_claims: usize) -> &mut Self {
+    self.parser.max_claims(max_claims);
+    self
+  }
+
+  ///Accepts payloads which are JSON but not an object of claims, see [GenericParser::allow_non_object_payload]
+  pub fn allow_non_object_payload(&mut self) -> &mut Self {
+    self.parser.allow_non_object_payload();
+    self
+  }
+
+  ///Requires the payload to hold exactly the given top level claims, rejecting any other payload with
+  ///[ClaimSetMismatch](GenericParserError::ClaimSetMismatch)
+  pub fn require_exact_claims(&mut self, claims: &[&str]) -> &mut Self {
+    self.parser.require_exact_claims(claims);
+    self
+  }
+
+  ///Runs the checks which only depend on the header and footer of the untrusted token without any
+  ///cryptography, returning the [UntrustedToken] on success
+  pub fn check_header<'t>(
+    &self,
+    token: &'t (impl AsRef<str> + ?Sized),
+  ) -> Result<UntrustedToken<'t>, GenericParserError> {
+    self.parser.check_header(token)
+  }
+
+  ///Decrypts or verifies the token with the given key, validates its claims including the default
+  ///expiration and not-before rules and deserializes the payload into a [Verified] `T`
+  ///
+  /// # Example
+  ///```
+  ///# #[cfg(feature = "default")]
+  ///# {
+  ///   use rusty_paseto::prelude::*;
+  ///   use serde::Deserialize;
+  ///
+  ///     #[derive(Deserialize)]
+  ///     struct Claims {
+  ///       sub: String,
+  ///     }
+  ///
+  ///     # let key = PasetoSymmetricKey::<V4, Local>::from(Key::<32>::from(*b"wubbalubbadubdubwubbalubbadubdub"));
+  ///     # let token = PasetoBuilder::<V4, Local>::default().set_claim(SubjectClaim::from("customers")).build(&key)?;
+  ///     let claims: Verified<Claims> = PasetoParser::<V4, Local>::default().parse_verified(&token, &key)?;
+  ///
+  ///     assert_eq!(claims.sub, "customers");
+  /// # }
+  /// # Ok::<(),anyhow::Error>(())
+  ///   ```
+  pub fn parse_verified<T, K>(&self, token: impl AsRef<str>, key: &K) -> Result<Verified<T>, GenericParserError>
+  where
+    T: serde::de::DeserializeOwned,
+    K: PasetoOpeningKey<Version, Purpose>,
+  {
+    self.parser.parse_verified(token, key)
+  }
+
+  ///Verifies the token with the given key and returns it re-encoded in canonical form, strict unpadded base64url
+  ///for the payload and footer, for storing tokens from several sources for later exact string comparison.  See
+  ///[GenericParser::normalize].
+  ///
+  /// # Errors
+  ///
+  /// Returns [`GenericParserError`] if a segment isn't base64url or the canonical token doesn't parse
+  pub fn normalize<K>(&self, token: impl AsRef<str>, key: &K) -> Result<String, GenericParserError>
+  where
+    K: PasetoOpeningKey<Version, Purpose>,
+  {
+    self.parser.normalize(token, key)
+  }
+
+  ///Decrypts or verifies the token with the given key and validates its claims like `parse`, returning the
+  ///[claims_hash] of its claims, see [GenericParser::parse_claims_hash]
+  ///
+  /// # Errors
+  ///
+  /// Returns [`GenericParserError`] for any errors when decrypting or verifying the token or when validating claims.
+  pub fn parse_claims_hash<K>(&self, token: impl AsRef<str>, key: &K) -> Result<String, GenericParserError>
+  where
+    K: PasetoOpeningKey<Version, Purpose>,
+  {
+    self.parser.parse_claims_hash(token, key)
+  }
+
+  ///Decrypts or verifies the token with the given key and validates its claims like `parse`, writing the plaintext
+  ///payload into the given buffer instead of returning a [Value].  The buffer is cleared first and left empty on
+  ///failure, see [GenericParser::parse_into] for reusing it across calls.
+  ///
+  /// # Errors
+  ///
+  /// Returns [`GenericParserError`] for any errors when decrypting or verifying the token or when validating claims.
+  ///
+  /// # Example
+  ///```
+  ///# #[cfg(feature = "default")]
+  ///# {
+  ///   use rusty_paseto::prelude::*;
+  ///
+  ///     # let key = PasetoSymmetricKey::<V4, Local>::from(Key::<32>::from(*b"wubbalubbadubdubwubbalubbadubdub"));
+  ///     # let token = PasetoBuilder::<V4, Local>::default().set_claim(SubjectClaim::from("customers")).build(&key)?;
+  ///     let parser = PasetoParser::<V4, Local>::default();
+  ///     let mut payload = Vec::with_capacity(1024);
+  ///     parser.parse_into(&token, &key, &mut payload)?;
+  ///
+  ///     let json: serde_json::Value = serde_json::from_slice(&payload)?;
+  ///     assert_eq!(json["sub"], "customers");
+  /// # }
+  /// # Ok::<(),anyhow::Error>(())
+  ///   ```
+  pub fn parse_into<K>(&self, token: impl AsRef<str>, key: &K, payload: &mut Vec<u8>) -> Result<(), GenericParserError>
+  where
+    K: PasetoOpeningKey<Version, Purpose>,
+  {
+    self.parser.parse_into(token, key, payload)
+  }
+
+  ///Decrypts or verifies the token with the given key and validates its claims like [parse](Self::parse), returning
+  ///along with the claims how long the result may be cached for
+  ///
+  ///The recommendation is the remaining validity of the token, `exp` minus the current time, capped at `max_ttl` and
+  ///at the [ceiling](Self::not_valid_after) of the parser if it has one.  It never reaches past `exp`, so a token
+  ///accepted only thanks to the leeway of a policy is not to be cached at all, and a token which never expires may
+  ///be cached for `max_ttl`.
+  ///
+  /// # Errors
+  ///
+  /// Returns [`GenericParserError`] for any errors when decrypting or verifying the token or when validating claims.
+  ///
+  /// # Example
+  ///```
+  ///# #[cfg(feature = "default")]
+  ///# {
+  ///   use rusty_paseto::prelude::*;
+  ///
+  ///     # let key = PasetoSymmetricKey::<V4, Local>::from(Key::<32>::from(*b"wubbalubbadubdubwubbalubbadubdub"));
+  ///     let token = PasetoBuilder::<V4, Local>::default()
+  ///       .set_claim(ExpirationClaim::try_from("2030-01-01T00:10:00Z")?)
+  ///       .build(&key)?;
+  ///
+  ///     //2030-01-01T00:00:00Z
+  ///     let (json, cache_ttl) = PasetoParser::<V4, Local>::default()
+  ///       .set_now_unix(1_893_456_000)?
+  ///       .parse_with_cache_ttl(&token, &key, time::Duration::minutes(5))?;
+  ///     assert_eq!(cache_ttl, time::Duration::minutes(5));
+  /// # }
+  /// # Ok::<(),anyhow::Error>(())
+  ///   ```
+  pub fn parse_with_cache_ttl<K>(
+    &self,
+    token: impl AsRef<str>,
+    key: &K,
+    max_ttl: time::Duration,
+  ) -> Result<(Value, time::Duration), GenericParserError>
+  where
+    K: PasetoOpeningKey<Version, Purpose>,
+  {
+    let json = self.parser.parse_verified::<Value, _>(token, key)?.into_inner();
+    let now = self.now.get().unwrap_or_else(OffsetDateTime::now_utc);
+    let expiration = time_claim("exp", &json["exp"], self.parser.numeric_time_unit().get())?;
+    let cache_ttl = expiration
+      .into_iter()
+      .chain(self.parser.ceiling())
+      .map(|until| until - now)
+      .fold(max_ttl, time::Duration::min)
+      .max(time::Duration::ZERO);
+    Ok((json, cache_ttl))
+  }
+}
+
+impl<'a, Version> PasetoParser<'a, Version, Public> {
+  ///Verifies a public token with the key a [PublicKeyResolver] supplies for the `kid` of its
+  ///[key routing footer](KeyRoutingFooter) and validates its claims like `parse`, see
+  ///[GenericParser::parse_with_resolver]
+  ///
+  /// # Errors
+  ///
+  /// Returns [`GenericParserError`] if the key can't be resolved, or for any errors when verifying the token or when
+  /// validating claims.
+  pub fn parse_with_resolver<R>(&self, token: impl AsRef<str>, resolver: &R) -> Result<Value, GenericParserError>
+  where
+    R: PublicKeyResolver<Version> + ?Sized,
+    for<'k> PasetoAsymmetricPublicKey<'k, Version, Public>:
+      TryFrom<&'k [u8], Error = PasetoError> + PasetoOpeningKey<Version, Public>,
+  {
+    self.parser.parse_with_resolver(token, resolver)
+  }
+
+  ///Verifies a batch of public tokens signed by several keys like `parse_with_resolver`, resolving the key of each
+  ///`kid` only once and returning the results in the order of the tokens, see
+  ///[GenericParser::parse_batch_with_resolver]
+  pub fn parse_batch_with_resolver<T, R>(&self, tokens: &[T], resolver: &R) -> Vec<Result<Value, GenericParserError>>
+  where
+    T: AsRef<str>,
+    R: PublicKeyResolver<Version> + ?Sized,
+    for<'k> PasetoAsymmetricPublicKey<'k, Version, Public>:
+      TryFrom<&'k [u8], Error = PasetoError> + PasetoOpeningKey<Version, Public>,
+  {
+    self.parser.parse_batch_with_resolver(tokens, resolver)
+  }
 }
 
 impl<'a, Version, Purpose> PasetoParser<'a, Version, Purpose>
@@ -212,53 +707,56 @@ where
   }
 }
 
+/// The parser validating the `exp` and `nbf` claims of each token against the current time
+///
+/// A token without an `exp` claim never expires and one without an `nbf` claim is valid immediately.  Set a
+/// [ParserPolicy] requiring the [expiration](ParserPolicy::require_expiration) or the
+/// [not before](ParserPolicy::require_not_before) claim to reject such tokens instead.  A claim which is present has
+/// to be an RFC3339 date, or a number in the [unit](Self::set_numeric_time_unit) set on the parser, and is never
+/// taken for an absent one.
 impl<'a, Version, Purpose> Default for PasetoParser<'a, Version, Purpose> {
   fn default() -> Self {
     let mut me = Self::new();
-    me.validate_claim(ExpirationClaim::default(), &|_, value| {
-      //let's get the expiration claim value
-      let val = value.as_str().unwrap_or_default();
-
-      //check if this is a non-expiring token
-      if val.is_empty() {
-        //this means the claim wasn't found, which means this is a non-expiring token
-        //and we can just skip this validation
-        return Ok(());
-      }
-      //turn the value into a datetime
-      let datetime =
-        time::OffsetDateTime::parse(val, &Rfc3339).map_err(|_| PasetoClaimError::RFC3339Date(val.to_string()))?;
-      //get the current datetime
-      let now = time::OffsetDateTime::now_utc();
-
-      //here we do the actual validation check for the expiration claim
-      if datetime <= now {
-        Err(PasetoClaimError::Expired)
-      } else {
-        Ok(())
-      }
-    })
-    .validate_claim(NotBeforeClaim::default(), &|_, value| {
-      //let's get the expiration claim value
-      let val = value.as_str().unwrap_or_default();
-      //if there is no value here, then the user didn't provide the claim so we just move on
-      if val.is_empty() {
-        return Ok(());
-      }
-      //otherwise let's continue with the validation
-      //turn the value into a datetime
-      let not_before_time =
-        time::OffsetDateTime::parse(val, &Rfc3339).map_err(|_| PasetoClaimError::RFC3339Date(val.to_string()))?;
-      //get the current datetime
-      let now = time::OffsetDateTime::now_utc();
-
-      //here we do the actual validation check for the expiration claim
-      if now <= not_before_time {
-        Err(PasetoClaimError::UseBeforeAvailable(not_before_time.to_string()))
-      } else {
-        Ok(())
-      }
-    });
+    let now = Rc::clone(&me.now);
+    let leeway = Rc::clone(&me.leeway);
+    let unit = me.parser.numeric_time_unit();
+    me.parser.set_validation_claim(
+      ExpirationClaim::default(),
+      Some(Box::new(move |key, value| {
+        //a token without an expiration claim never expires
+        let Some(expiration) = time_claim(key, value, unit.get())? else {
+          return Ok(());
+        };
+        let now = now.get().unwrap_or_else(OffsetDateTime::now_utc);
+
+        //here we do the actual validation check for the expiration claim
+        if expiration + leeway.get() <= now {
+          Err(PasetoClaimError::Expired)
+        } else {
+          Ok(())
+        }
+      })),
+    );
+    let now = Rc::clone(&me.now);
+    let leeway = Rc::clone(&me.leeway);
+    let unit = me.parser.numeric_time_unit();
+    me.parser.set_validation_claim(
+      NotBeforeClaim::default(),
+      Some(Box::new(move |key, value| {
+        //a token without a not before claim can be used immediately
+        let Some(not_before_time) = time_claim(key, value, unit.get())? else {
+          return Ok(());
+        };
+        let now = now.get().unwrap_or_else(OffsetDateTime::now_utc);
+
+        //here we do the actual validation check for the not before claim
+        if now + leeway.get() <= not_before_time {
+          Err(PasetoClaimError::UseBeforeAvailable(not_before_time.to_string()))
+        } else {
+          Ok(())
+        }
+      })),
+    );
     me
   }
 }
@@ -281,11 +779,11 @@ impl<'a> PasetoParser<'a, V1, Local> {
   ///# #[cfg(all(feature = "prelude", feature="v1_local"))]
   ///# {
   ///   use rusty_paseto::prelude::*;
-
+  ///
   ///     let key = PasetoSymmetricKey::<V1, Local>::from(Key::<32>::from(*b"wubbalubbadubdubwubbalubbadubdub"));
-
+  ///
   ///     let footer = Footer::from("some footer");
-
+  ///
   ///     //create a builder, add some claims and then build the token with the key
   ///     let token = ParserBuilder::<V1, Local>::default()
   ///       .set_claim(AudienceClaim::from("customers"))
@@ -300,12 +798,12 @@ impl<'a> PasetoParser<'a, V1, Local> {
   ///       .set_claim(CustomClaim::try_from(("pi to 6 digits", 3.141526))?)
   ///       .set_footer(footer)
   ///       .try_encrypt(&key)?;
-
+  ///
   ///     //now let's decrypt the token and verify the values
   ///     let json = ParserParser::<V1, Local>::default()
   ///       .set_footer(footer)
   ///       .parse(&token, &key)?;
-
+  ///
   ///     assert_eq!(json["aud"], "customers");
   ///     assert_eq!(json["jti"], "me");
   ///     assert_eq!(json["iss"], "me");
@@ -319,8 +817,11 @@ impl<'a> PasetoParser<'a, V1, Local> {
   ///  # }
   /// # Ok::<(),anyhow::Error>(())
   ///   ```
-
-  pub fn parse(&mut self, token: &'a str, key: &'a PasetoSymmetricKey<V1, Local>) -> Result<Value, GenericParserError> {
+  pub fn parse(
+    &mut self,
+    token: impl AsRef<str>,
+    key: &'a PasetoSymmetricKey<V1, Local>,
+  ) -> Result<Value, GenericParserError> {
     //return the full json value to the user
     self.parser.parse(token, key)
   }
@@ -342,11 +843,11 @@ impl<'a> PasetoParser<'a, V2, Local> {
   ///# #[cfg(all(feature = "prelude", feature="v2_local"))]
   ///# {
   ///   use rusty_paseto::prelude::*;
-
+  ///
   ///     let key = PasetoSymmetricKey::<V2, Local>::from(Key::<32>::from(*b"wubbalubbadubdubwubbalubbadubdub"));
-
+  ///
   ///     let footer = Footer::from("some footer");
-
+  ///
   ///     //create a builder, add some claims and then build the token with the key
   ///     let token = PasetoBuilder::<V2, Local>::default()
   ///       .set_claim(AudienceClaim::from("customers"))
@@ -361,12 +862,12 @@ impl<'a> PasetoParser<'a, V2, Local> {
   ///       .set_claim(CustomClaim::try_from(("pi to 6 digits", 3.141526))?)
   ///       .set_footer(footer)
   ///       .try_encrypt(&key)?;
-
+  ///
   ///     //now let's decrypt the token and verify the values
   ///     let json = PasetoParser::<V2, Local>::default()
   ///       .set_footer(footer)
   ///       .parse(&token, &key)?;
-
+  ///
   ///     assert_eq!(json["aud"], "customers");
   ///     assert_eq!(json["jti"], "me");
   ///     assert_eq!(json["iss"], "me");
@@ -380,8 +881,11 @@ impl<'a> PasetoParser<'a, V2, Local> {
   ///  # }
   /// # Ok::<(),anyhow::Error>(())
   ///   ```
-
-  pub fn parse(&mut self, token: &'a str, key: &'a PasetoSymmetricKey<V2, Local>) -> Result<Value, GenericParserError> {
+  pub fn parse(
+    &mut self,
+    token: impl AsRef<str>,
+    key: &'a PasetoSymmetricKey<V2, Local>,
+  ) -> Result<Value, GenericParserError> {
     //return the full json value to the user
     self.parser.parse(token, key)
   }
@@ -403,12 +907,12 @@ impl<'a> PasetoParser<'a, V3, Local> {
   ///# #[cfg(all(feature = "prelude", feature="v3_local"))]
   ///# {
   ///   use rusty_paseto::prelude::*;
-
+  ///
   ///     let key = PasetoSymmetricKey::<V3, Local>::from(Key::<32>::from(*b"wubbalubbadubdubwubbalubbadubdub"));
-
+  ///
   ///     let footer = Footer::from("some footer");
   ///     let implicit_assertion = ImplicitAssertion::from("some assertion");
-
+  ///
   ///     //create a builder, add some claims and then build the token with the key
   ///     let token = PasetoBuilder::<V3, Local>::default()
   ///       .set_claim(AudienceClaim::from("customers"))
@@ -424,13 +928,13 @@ impl<'a> PasetoParser<'a, V3, Local> {
   ///       .set_footer(footer)
   ///       .set_implicit_assertion(implicit_assertion)
   ///       .try_encrypt(&key)?;
-
+  ///
   ///     //now let's decrypt the token and verify the values
   ///     let json = PasetoParser::<V3, Local>::default()
   ///       .set_footer(footer)
   ///       .set_implicit_assertion(implicit_assertion)
   ///       .parse(&token, &key)?;
-
+  ///
   ///     assert_eq!(json["aud"], "customers");
   ///     assert_eq!(json["jti"], "me");
   ///     assert_eq!(json["iss"], "me");
@@ -444,8 +948,11 @@ impl<'a> PasetoParser<'a, V3, Local> {
   ///  # }
   /// # Ok::<(),anyhow::Error>(())
   ///   ```
-
-  pub fn parse(&mut self, token: &'a str, key: &'a PasetoSymmetricKey<V3, Local>) -> Result<Value, GenericParserError> {
+  pub fn parse(
+    &mut self,
+    token: impl AsRef<str>,
+    key: &'a PasetoSymmetricKey<V3, Local>,
+  ) -> Result<Value, GenericParserError> {
     //return the full json value to the user
     self.parser.parse(token, key)
   }
@@ -467,12 +974,12 @@ impl<'a> PasetoParser<'a, V4, Local> {
   ///# #[cfg(all(feature = "prelude", feature="v4_local"))]
   ///# {
   ///   use rusty_paseto::prelude::*;
-
+  ///
   ///     let key = PasetoSymmetricKey::<V4, Local>::from(Key::<32>::from(*b"wubbalubbadubdubwubbalubbadubdub"));
-
+  ///
   ///     let footer = Footer::from("some footer");
   ///     let implicit_assertion = ImplicitAssertion::from("some assertion");
-
+  ///
   ///     //create a builder, add some claims and then build the token with the key
   ///     let token = PasetoBuilder::<V4, Local>::default()
   ///       .set_claim(AudienceClaim::from("customers"))
@@ -488,13 +995,13 @@ impl<'a> PasetoParser<'a, V4, Local> {
   ///       .set_footer(footer)
   ///       .set_implicit_assertion(implicit_assertion)
   ///       .try_encrypt(&key)?;
-
+  ///
   ///     //now let's decrypt the token and verify the values
   ///     let json = PasetoParser::<V4, Local>::default()
   ///       .set_footer(footer)
   ///       .set_implicit_assertion(implicit_assertion)
   ///       .parse(&token, &key)?;
-
+  ///
   ///     assert_eq!(json["aud"], "customers");
   ///     assert_eq!(json["jti"], "me");
   ///     assert_eq!(json["iss"], "me");
@@ -508,8 +1015,11 @@ impl<'a> PasetoParser<'a, V4, Local> {
   ///  # }
   /// # Ok::<(),anyhow::Error>(())
   ///   ```
-
-  pub fn parse(&mut self, token: &'a str, key: &'a PasetoSymmetricKey<V4, Local>) -> Result<Value, GenericParserError> {
+  pub fn parse(
+    &mut self,
+    token: impl AsRef<str>,
+    key: &'a PasetoSymmetricKey<V4, Local>,
+  ) -> Result<Value, GenericParserError> {
     //return the full json value to the user
     self.parser.parse(token, key)
   }
@@ -531,14 +1041,14 @@ impl<'a> PasetoParser<'a, V1, Public> {
   ///# #[cfg(all(feature = "prelude", feature="v1_public"))]
   ///# {
   ///   # use rusty_paseto::prelude::*;
-
+  ///
   ///    //obtain a private key (pk)
   ///   # let private_key = include_bytes!("../../../tests/v1_public_test_vectors_private_key.pk8");
   ///   # let pk: &[u8] = private_key;
   ///    let private_key = PasetoAsymmetricPrivateKey::<V1, Public>::from(pk);
-
+  ///
   ///     let footer = Footer::from("some footer");
-
+  ///
   ///     //sign a public V1 token
   ///     let token = PasetoBuilder::<V1, Public>::default()
   ///       .set_claim(AudienceClaim::from("customers"))
@@ -553,7 +1063,7 @@ impl<'a> PasetoParser<'a, V1, Public> {
   ///       .set_claim(CustomClaim::try_from(("pi to 6 digits", 3.141526))?)
   ///       .set_footer(footer)
   ///       .try_sign(&private_key)?;
-
+  ///
   /// //obtain a public key (pubk)
   ///   #  let public_key = include_bytes!("../../../tests/v1_public_test_vectors_public_key.der");
   ///   #  let pubk: &[u8] = public_key;
@@ -586,10 +1096,9 @@ impl<'a> PasetoParser<'a, V1, Public> {
   ///  # }
   /// # Ok::<(),anyhow::Error>(())
   ///```
-
   pub fn parse(
     &mut self,
-    token: &'a str,
+    token: impl AsRef<str>,
     key: &'a PasetoAsymmetricPublicKey<V1, Public>,
   ) -> Result<Value, GenericParserError> {
     //return the full json value to the user
@@ -613,16 +1122,16 @@ impl<'a> PasetoParser<'a, V2, Public> {
   ///# #[cfg(all(feature = "prelude", feature="v1_public"))]
   ///# {
   ///   # use rusty_paseto::prelude::*;
-
+  ///
   ///    //obtain a key
   /// let private_key = Key::<64>::try_from("b4cbfb43df4ce210727d953e4a713307fa19bb7d9f85041438d9e11b942a37741eb9dbbbbc047c03fd70604e0071f0987e16b28b757225c11f00415d0e20b1a2")?;
   /// let private_key = PasetoAsymmetricPrivateKey::<V2, Public>::from(&private_key);
-
+  ///
   /// let public_key = Key::<32>::try_from("1eb9dbbbbc047c03fd70604e0071f0987e16b28b757225c11f00415d0e20b1a2")?;
   /// let public_key = PasetoAsymmetricPublicKey::<V2, Public>::from(&public_key);
-
+  ///
   /// let footer = Footer::from("some footer");
-
+  ///
   /// //sign a public V2 token
   /// let token = PasetoBuilder::<V2, Public>::default()
   ///   .set_claim(AudienceClaim::from("customers"))
@@ -637,7 +1146,7 @@ impl<'a> PasetoParser<'a, V2, Public> {
   ///   .set_claim(CustomClaim::try_from(("pi to 6 digits", 3.141526))?)
   ///   .set_footer(footer)
   ///   .try_sign(&private_key)?;
-
+  ///
   /// //now let's try to verify it
   /// let json = PasetoParser::<V2, Public>::default()
   ///   .set_footer(footer)
@@ -666,10 +1175,9 @@ impl<'a> PasetoParser<'a, V2, Public> {
   ///  # }
   /// # Ok::<(),anyhow::Error>(())
   ///```
-
   pub fn parse(
     &mut self,
-    token: &'a str,
+    token: impl AsRef<str>,
     key: &'a PasetoAsymmetricPublicKey<V2, Public>,
   ) -> Result<Value, GenericParserError> {
     //return the full json value to the user
@@ -693,20 +1201,20 @@ impl<'a> PasetoParser<'a, V3, Public> {
   ///# #[cfg(all(feature = "prelude", feature="v3_public"))]
   ///# {
   ///   # use rusty_paseto::prelude::*;
-
+  ///
   ///    //obtain a key
   /// let private_key = Key::<48>::try_from(
   ///   "20347609607477aca8fbfbc5e6218455f3199669792ef8b466faa87bdc67798144c848dd03661eed5ac62461340cea96",
   /// )?;
   /// let private_key = PasetoAsymmetricPrivateKey::<V3, Public>::from(&private_key);
-
+  ///
   /// let public_key = Key::<49>::try_from(
   ///   "02fbcb7c69ee1c60579be7a334134878d9c5c5bf35d552dab63c0140397ed14cef637d7720925c44699ea30e72874c72fb",
   /// )?;
   /// let public_key = PasetoAsymmetricPublicKey::<V3, Public>::try_from(&public_key)?;
-
+  ///
   /// let footer = Footer::from("some footer");
-
+  ///
   /// let implicit_assertion = ImplicitAssertion::from("some assertion");
   /// //sign a public V3 token
   /// let token = PasetoBuilder::<V3, Public>::default()
@@ -723,7 +1231,7 @@ impl<'a> PasetoParser<'a, V3, Public> {
   ///   .set_footer(footer)
   ///   .set_implicit_assertion(implicit_assertion)
   ///   .try_sign(&private_key)?;
-
+  ///
   /// //now let's try to verify it
   /// let json = PasetoParser::<V3, Public>::default()
   ///   .set_footer(footer)
@@ -753,10 +1261,9 @@ impl<'a> PasetoParser<'a, V3, Public> {
   ///  # }
   /// # Ok::<(),anyhow::Error>(())
   ///```
-
   pub fn parse(
     &mut self,
-    token: &'a str,
+    token: impl AsRef<str>,
     key: &'a PasetoAsymmetricPublicKey<V3, Public>,
   ) -> Result<Value, GenericParserError> {
     //return the full json value to the user
@@ -780,18 +1287,18 @@ impl<'a> PasetoParser<'a, V4, Public> {
   ///# #[cfg(all(feature = "prelude", feature="v4_public"))]
   ///# {
   ///   # use rusty_paseto::prelude::*;
-
+  ///
   /// //create a key
   /// let private_key = Key::<64>::try_from("b4cbfb43df4ce210727d953e4a713307fa19bb7d9f85041438d9e11b942a37741eb9dbbbbc047c03fd70604e0071f0987e16b28b757225c11f00415d0e20b1a2")?;
   /// let pk: &[u8] = private_key.as_slice();
-  /// let private_key = PasetoAsymmetricPrivateKey::<V4, Public>::from(pk);
-
+  /// let private_key = PasetoAsymmetricPrivateKey::<V4, Public>::try_from(pk)?;
+  ///
   /// let public_key = Key::<32>::try_from("1eb9dbbbbc047c03fd70604e0071f0987e16b28b757225c11f00415d0e20b1a2")?;
   /// let public_key = PasetoAsymmetricPublicKey::<V4, Public>::from(&public_key);
-
+  ///
   /// let footer = Footer::from("some footer");
   /// let implicit_assertion = ImplicitAssertion::from("some assertion");
-
+  ///
   /// //sign a public V4 token
   /// let token = PasetoBuilder::<V4, Public>::default()
   ///   .set_claim(AudienceClaim::from("customers"))
@@ -807,7 +1314,7 @@ impl<'a> PasetoParser<'a, V4, Public> {
   ///   .set_footer(footer)
   ///   .set_implicit_assertion(implicit_assertion)
   ///   .try_sign(&private_key)?;
-
+  ///
   /// //now let's try to verify it
   /// let json = PasetoParser::<V4, Public>::default()
   ///   .set_footer(footer)
@@ -834,14 +1341,13 @@ impl<'a> PasetoParser<'a, V4, Public> {
   /// assert_eq!(json["sub"], "loyal subjects");
   /// assert_eq!(json["pi to 6 digits"], 3.141526);
   /// assert_eq!(json["seats"], 4);
-
+  ///
   ///  # }
   /// # Ok::<(),anyhow::Error>(())
   ///```
-
   pub fn parse(
     &mut self,
-    token: &'a str,
+    token: impl AsRef<str>,
     key: &'a PasetoAsymmetricPublicKey<V4, Public>,
   ) -> Result<Value, GenericParserError> {
     //return the full json value to the user
@@ -943,6 +1449,181 @@ mod paseto_parser_unit_tests {
     Ok(())
   }
 
+  #[cfg(feature = "local")]
+  #[test]
+  fn absent_time_claims_test() -> Result<()> {
+    let key = PasetoSymmetricKey::<V2, Local>::from(Key::from(*b"wubbalubbadubdubwubbalubbadubdub"));
+    let token_with = |payload: serde_json::Value| {
+      GenericBuilder::<V2, Local>::default()
+        .set_raw_payload(payload.to_string())
+        .try_encrypt(&key)
+    };
+
+    //without an exp claim the token never expires and without an nbf claim it is valid immediately
+    let neither = token_with(serde_json::json!({"sub": "loyal subjects"}))?;
+    let json = PasetoParser::<V2, Local>::default().parse(&neither, &key)?;
+    assert!(json["exp"].is_null());
+    assert!(json["nbf"].is_null());
+
+    //unless a policy requires them
+    let policy = ParserPolicy {
+      require_expiration: true,
+      ..ParserPolicy::default()
+    };
+    assert!(matches!(
+      PasetoParser::<V2, Local>::default().set_policy(&policy).parse(&neither, &key),
+      Err(GenericParserError::ClaimError { source: PasetoClaimError::Missing(claim) }) if claim == "exp"
+    ));
+    let policy = ParserPolicy {
+      require_expiration: false,
+      require_not_before: true,
+      ..ParserPolicy::default()
+    };
+    assert!(matches!(
+      PasetoParser::<V2, Local>::default().set_policy(&policy).parse(&neither, &key),
+      Err(GenericParserError::ClaimError { source: PasetoClaimError::Missing(claim) }) if claim == "nbf"
+    ));
+    let with_not_before = token_with(serde_json::json!({"nbf": "2019-01-01T00:00:00Z"}))?;
+    assert!(PasetoParser::<V2, Local>::default()
+      .set_policy(&policy)
+      .parse(&with_not_before, &key)
+      .is_ok());
+    Ok(())
+  }
+
+  #[cfg(feature = "local")]
+  #[test]
+  fn malformed_time_claims_test() -> Result<()> {
+    let key = PasetoSymmetricKey::<V2, Local>::from(Key::from(*b"wubbalubbadubdubwubbalubbadubdub"));
+    let token_with = |payload: serde_json::Value| {
+      GenericBuilder::<V2, Local>::default()
+        .set_raw_payload(payload.to_string())
+        .try_encrypt(&key)
+    };
+
+    //a time claim which is present is never mistaken for an absent one
+    for claim in ["exp", "nbf"] {
+      let token = token_with(serde_json::json!({ claim: 4_102_444_800u64 }))?;
+      assert!(matches!(
+        PasetoParser::<V2, Local>::default().parse(&token, &key),
+        Err(GenericParserError::ClaimError { source: PasetoClaimError::Unexpected(found) }) if found == claim
+      ));
+      let token = token_with(serde_json::json!({ claim: "" }))?;
+      assert!(matches!(
+        PasetoParser::<V2, Local>::default().parse(&token, &key),
+        Err(GenericParserError::ClaimError {
+          source: PasetoClaimError::RFC3339Date(_)
+        })
+      ));
+    }
+    Ok(())
+  }
+
+  #[cfg(feature = "local")]
+  #[test]
+  fn numeric_time_claims_test() -> Result<()> {
+    let key = PasetoSymmetricKey::<V2, Local>::from(Key::from(*b"wubbalubbadubdubwubbalubbadubdub"));
+    //2030-01-01T00:00:00Z
+    let in_seconds = GenericBuilder::<V2, Local>::default()
+      .set_raw_payload(r#"{"exp":1893456000,"nbf":1893455000}"#.to_string())
+      .try_encrypt(&key)?;
+    let in_milliseconds = GenericBuilder::<V2, Local>::default()
+      .set_raw_payload(r#"{"exp":1893456000000,"nbf":1893455000000}"#.to_string())
+      .try_encrypt(&key)?;
+    let parser = |unit| {
+      let mut parser = PasetoParser::<V2, Local>::default();
+      parser.set_numeric_time_unit(unit).set_now_unix(1_893_455_999)?;
+      Ok::<_, GenericParserError>(parser)
+    };
+
+    assert!(parser(NumericTimeUnit::Seconds)?.parse(&in_seconds, &key).is_ok());
+    assert!(parser(NumericTimeUnit::Milliseconds)?
+      .parse(&in_milliseconds, &key)
+      .is_ok());
+
+    //seconds read as milliseconds expired in 1970
+    assert!(matches!(
+      parser(NumericTimeUnit::Milliseconds)?.parse(&in_seconds, &key),
+      Err(GenericParserError::ClaimError {
+        source: PasetoClaimError::Expired
+      })
+    ));
+    //and milliseconds read as seconds are tens of millennia away, past any date the parser represents
+    assert!(matches!(
+      parser(NumericTimeUnit::Seconds)?.parse(&in_milliseconds, &key),
+      Err(GenericParserError::ClaimError {
+        source: PasetoClaimError::Unexpected(_)
+      })
+    ));
+    Ok(())
+  }
+
+  #[cfg(feature = "local")]
+  #[test]
+  fn cache_ttl_test() -> Result<()> {
+    let key = PasetoSymmetricKey::<V2, Local>::from(Key::from(*b"wubbalubbadubdubwubbalubbadubdub"));
+    let token = PasetoBuilder::<V2, Local>::default()
+      .set_claim(ExpirationClaim::try_from("2030-01-01T00:10:00Z")?)
+      .build(&key)?;
+    let max_ttl = time::Duration::hours(1);
+    //2030-01-01T00:00:00Z
+    let mut parser = PasetoParser::<V2, Local>::default();
+    parser.set_now_unix(1_893_456_000)?;
+
+    //the remaining validity is below the cap
+    let (json, cache_ttl) = parser.parse_with_cache_ttl(&token, &key, max_ttl)?;
+    assert_eq!(json["exp"], "2030-01-01T00:10:00Z");
+    assert_eq!(cache_ttl, time::Duration::minutes(10));
+
+    //and the ceiling of the server is below the remaining validity
+    parser.not_valid_after(time::OffsetDateTime::parse("2030-01-01T00:02:00Z", &Rfc3339)?);
+    assert_eq!(
+      parser.parse_with_cache_ttl(&token, &key, max_ttl)?.1,
+      time::Duration::minutes(2)
+    );
+
+    //a token accepted past its expiration thanks to the leeway is never cached
+    let policy = ParserPolicy {
+      leeway: time::Duration::minutes(5),
+      ..ParserPolicy::default()
+    };
+    let (_, cache_ttl) = PasetoParser::<V2, Local>::default()
+      .set_policy(&policy)
+      .set_now_unix(1_893_456_000 + 12 * 60)?
+      .parse_with_cache_ttl(&token, &key, max_ttl)?;
+    assert_eq!(cache_ttl, time::Duration::ZERO);
+
+    let non_expiring = PasetoBuilder::<V2, Local>::default()
+      .set_no_expiration_danger_acknowledged()
+      .build(&key)?;
+    let (_, cache_ttl) = PasetoParser::<V2, Local>::default().parse_with_cache_ttl(&non_expiring, &key, max_ttl)?;
+    assert_eq!(cache_ttl, max_ttl);
+    Ok(())
+  }
+
+  #[cfg(feature = "local")]
+  #[test]
+  fn disallowed_version_test() -> Result<()> {
+    let key = PasetoSymmetricKey::<V2, Local>::from(Key::from(*b"wubbalubbadubdubwubbalubbadubdub"));
+    let token = PasetoBuilder::<V2, Local>::default().build(&key)?;
+
+    let expected_error = format!(
+      "{}",
+      PasetoParser::<V2, Local>::default()
+        .set_allowed_versions(&[PasetoVersion::V3, PasetoVersion::V4])
+        .parse(&token, &key)
+        .unwrap_err()
+    );
+    assert_eq!(expected_error, "Tokens of version 'v2' are not allowed by this parser");
+
+    //the same token is honored while v2 remains in the allowed set
+    let json = PasetoParser::<V2, Local>::default()
+      .set_allowed_versions(&[PasetoVersion::V2, PasetoVersion::V4])
+      .parse(&token, &key)?;
+    assert!(json["exp"].is_string());
+    Ok(())
+  }
+
   #[cfg(feature = "local")]
   #[test]
   fn expired_token_claim_test() -> Result<()> {
@@ -963,6 +1644,82 @@ mod paseto_parser_unit_tests {
     Ok(())
   }
 
+  #[cfg(feature = "local")]
+  #[test]
+  fn injected_now_test() -> Result<()> {
+    let key = PasetoSymmetricKey::<V2, Local>::from(Key::from(*b"wubbalubbadubdubwubbalubbadubdub"));
+    //valid from 2030-01-01T00:00:00Z until 2030-01-02T00:00:00Z
+    let token = PasetoBuilder::<V2, Local>::default()
+      .set_claim(NotBeforeClaim::try_from("2030-01-01T00:00:00Z")?)
+      .set_claim(ExpirationClaim::try_from("2030-01-02T00:00:00Z")?)
+      .build(&key)?;
+    let not_before = 1_893_456_000;
+
+    //the system clock reads well before the token is usable
+    assert!(PasetoParser::<V2, Local>::default().parse(&token, &key).is_err());
+
+    let mut parser = PasetoParser::<V2, Local>::default();
+    assert!(parser.set_now_unix(not_before + 60)?.parse(&token, &key).is_ok());
+    assert!(matches!(
+      parser.set_now_unix(not_before)?.parse(&token, &key),
+      Err(GenericParserError::ClaimError {
+        source: PasetoClaimError::UseBeforeAvailable(_)
+      })
+    ));
+    assert!(matches!(
+      parser.set_now_unix(not_before + 86_400)?.parse(&token, &key),
+      Err(GenericParserError::ClaimError {
+        source: PasetoClaimError::Expired
+      })
+    ));
+
+    //both ways of setting the time agree
+    let now = time::OffsetDateTime::parse("2030-01-01T12:00:00Z", &Rfc3339)?;
+    assert!(parser.set_now(now).parse(&token, &key).is_ok());
+
+    assert!(matches!(
+      parser.set_now_unix(i64::MAX),
+      Err(GenericParserError::TimestampOutOfRange(i64::MAX))
+    ));
+    Ok(())
+  }
+
+  #[cfg(feature = "local")]
+  #[test]
+  fn policy_leeway_test() -> Result<()> {
+    let key = PasetoSymmetricKey::<V2, Local>::from(Key::from(*b"wubbalubbadubdubwubbalubbadubdub"));
+    let token = PasetoBuilder::<V2, Local>::default()
+      .set_claim(NotBeforeClaim::try_from("2030-01-01T00:00:00Z")?)
+      .set_claim(ExpirationClaim::try_from("2030-01-02T00:00:00Z")?)
+      .build(&key)?;
+    let not_before = 1_893_456_000;
+    let policy = ParserPolicy {
+      leeway: time::Duration::seconds(30),
+      ..ParserPolicy::default()
+    };
+
+    let mut parser = PasetoParser::<V2, Local>::default();
+    parser.set_policy(&policy);
+    assert!(parser.set_now_unix(not_before - 29)?.parse(&token, &key).is_ok());
+    assert!(parser
+      .set_now_unix(not_before + 86_400 + 29)?
+      .parse(&token, &key)
+      .is_ok());
+    assert!(matches!(
+      parser.set_now_unix(not_before - 30)?.parse(&token, &key),
+      Err(GenericParserError::ClaimError {
+        source: PasetoClaimError::UseBeforeAvailable(_)
+      })
+    ));
+    assert!(matches!(
+      parser.set_now_unix(not_before + 86_400 + 30)?.parse(&token, &key),
+      Err(GenericParserError::ClaimError {
+        source: PasetoClaimError::Expired
+      })
+    ));
+    Ok(())
+  }
+
   #[cfg(feature = "public")]
   #[test]
   fn basic_paseto_parser_test_v2_public() -> Result<()> {
@@ -0,0 +1,199 @@
+use crate::prelude::*;
+use core::marker::PhantomData;
+
+/// The access and refresh tokens of an authorization server, configured once with the issuer, audience and key they
+/// share
+///
+/// Every token carries the configured [IssuerClaim] and [AudienceClaim], a [TokenType] claim telling the two kinds
+/// apart, a random [TokenIdentifierClaim] and an expiration its kind's duration after it is built.  Access tokens
+/// expire after 15 minutes and refresh tokens after 30 days unless set otherwise.  The claims of each session, such
+/// as its subject, are set by a closure when issuing, following the same rules as [PasetoBuilder::claims].
+///
+/// # Usage
+/// ```
+/// # #[cfg(feature = "default")]
+/// # {
+/// use rusty_paseto::prelude::*;
+///
+/// # let key = PasetoSymmetricKey::<V4, Local>::from(Key::<32>::from(*b"wubbalubbadubdubwubbalubbadubdub"));
+/// let mut issuer = SessionIssuer::<V4, Local, _>::new("auth.example.com", "api.example.com", &key);
+/// issuer.set_access_ttl(time::Duration::minutes(5));
+///
+/// let user_id = String::from("user-1234");
+/// let pair = issuer.issue_pair(|c| {
+///   c.set_claim(SubjectClaim::from(user_id.as_str()));
+/// })?;
+///
+/// let json = PasetoParser::<V4, Local>::default()
+///   .check_claim(IssuerClaim::from("auth.example.com"))
+///   .expect_type(TokenType::Access)
+///   .parse(&pair.access_token, &key)?;
+/// assert_eq!(json["sub"], "user-1234");
+/// # }
+/// # Ok::<(),anyhow::Error>(())
+/// ```
+pub struct SessionIssuer<'k, Version, Purpose, K> {
+  version: PhantomData<Version>,
+  purpose: PhantomData<Purpose>,
+  issuer: String,
+  audience: String,
+  key: &'k K,
+  access_ttl: time::Duration,
+  refresh_ttl: time::Duration,
+}
+
+impl<'k, Version, Purpose, K> SessionIssuer<'k, Version, Purpose, K>
+where
+  K: PasetoSealingKey<Version, Purpose>,
+{
+  /// How long access tokens are valid for unless [set](Self::set_access_ttl) otherwise
+  pub const DEFAULT_ACCESS_TTL: time::Duration = time::Duration::minutes(15);
+  /// How long refresh tokens are valid for unless [set](Self::set_refresh_ttl) otherwise
+  pub const DEFAULT_REFRESH_TTL: time::Duration = time::Duration::days(30);
+
+  /// Creates an issuer of tokens from the given issuer for the given audience, sealed with the key
+  pub fn new(issuer: impl Into<String>, audience: impl Into<String>, key: &'k K) -> Self {
+    Self {
+      version: PhantomData,
+      purpose: PhantomData,
+      issuer: issuer.into(),
+      audience: audience.into(),
+      key,
+      access_ttl: Self::DEFAULT_ACCESS_TTL,
+      refresh_ttl: Self::DEFAULT_REFRESH_TTL,
+    }
+  }
+
+  /// Sets how long the access tokens issued from now on are valid for
+  pub fn set_access_ttl(&mut self, ttl: time::Duration) -> &mut Self {
+    self.access_ttl = ttl;
+    self
+  }
+
+  /// Sets how long the refresh tokens issued from now on are valid for
+  pub fn set_refresh_ttl(&mut self, ttl: time::Duration) -> &mut Self {
+    self.refresh_ttl = ttl;
+    self
+  }
+
+  /// Issues an access token with the shared claims and those the closure sets
+  ///
+  /// # Errors
+  ///
+  /// Returns [`GenericBuilderError`] if the token fails to build, for instance when `claims` sets a claim the issuer
+  /// sets itself such as `iss`, `aud`, `exp`, `typ` or `jti`
+  pub fn issue_access<'a, F>(&'a self, claims: F) -> Result<String, GenericBuilderError>
+  where
+    F: FnOnce(&mut PasetoBuilder<'a, Version, Purpose>),
+  {
+    self.issue(TokenType::Access, self.access_ttl, claims)
+  }
+
+  /// Issues a refresh token with the shared claims and those the closure sets
+  ///
+  /// # Errors
+  ///
+  /// Returns [`GenericBuilderError`] if the token fails to build, see [issue_access](Self::issue_access)
+  pub fn issue_refresh<'a, F>(&'a self, claims: F) -> Result<String, GenericBuilderError>
+  where
+    F: FnOnce(&mut PasetoBuilder<'a, Version, Purpose>),
+  {
+    self.issue(TokenType::Refresh, self.refresh_ttl, claims)
+  }
+
+  /// Issues an access token and a refresh token for the same session, calling the closure once for each
+  ///
+  /// # Errors
+  ///
+  /// Returns [`GenericBuilderError`] if either token fails to build, see [issue_access](Self::issue_access)
+  pub fn issue_pair<'a, F>(&'a self, claims: F) -> Result<TokenPair, GenericBuilderError>
+  where
+    F: Fn(&mut PasetoBuilder<'a, Version, Purpose>),
+  {
+    Ok(TokenPair {
+      access_token: self.issue_access(&claims)?,
+      refresh_token: self.issue_refresh(&claims)?,
+    })
+  }
+
+  fn issue<'a, F>(
+    &'a self,
+    token_type: TokenType,
+    ttl: time::Duration,
+    claims: F,
+  ) -> Result<String, GenericBuilderError>
+  where
+    F: FnOnce(&mut PasetoBuilder<'a, Version, Purpose>),
+  {
+    PasetoBuilder::<'a, Version, Purpose>::default()
+      .set_claim(IssuerClaim::from(self.issuer.as_str()))
+      .set_claim(AudienceClaim::from(self.audience.as_str()))
+      .set_claim(token_type)
+      .set_expiration_relative(ttl)
+      .with_auto_jti()
+      .claims(claims)
+      .build_with(self.key)
+  }
+}
+
+#[cfg(all(test, feature = "v4_local", feature = "v4_public"))]
+mod unit_tests {
+  use super::*;
+  use anyhow::Result;
+  use std::convert::TryFrom;
+  use time::format_description::well_known::Rfc3339;
+
+  #[test]
+  fn test_session_issuer() -> Result<()> {
+    let key = PasetoSymmetricKey::<V4, Local>::from(Key::from(*b"wubbalubbadubdubwubbalubbadubdub"));
+    let mut issuer = SessionIssuer::<V4, Local, _>::new("auth", "api", &key);
+    issuer.set_refresh_ttl(time::Duration::days(7));
+
+    let pair = issuer.issue_pair(|c| {
+      c.set_claim(SubjectClaim::from("loyal subjects"));
+    })?;
+    let access = PasetoParser::<V4, Local>::default().parse(&pair.access_token, &key)?;
+    let refresh = PasetoParser::<V4, Local>::default().parse(&pair.refresh_token, &key)?;
+    assert_eq!(access["typ"], "access");
+    assert_eq!(refresh["typ"], "refresh");
+    for json in [&access, &refresh] {
+      assert_eq!(json["iss"], "auth");
+      assert_eq!(json["aud"], "api");
+      assert_eq!(json["sub"], "loyal subjects");
+    }
+    assert_ne!(access["jti"], refresh["jti"]);
+
+    let now = time::OffsetDateTime::now_utc();
+    let expiration =
+      |json: &serde_json::Value| time::OffsetDateTime::parse(json["exp"].as_str().unwrap_or_default(), &Rfc3339);
+    assert!(expiration(&access)? <= now + time::Duration::minutes(15));
+    assert!(expiration(&refresh)? <= now + time::Duration::days(7));
+    assert!(expiration(&refresh)? > now + time::Duration::days(6));
+
+    //the shared claims are the issuer's to set
+    assert!(matches!(
+      issuer.issue_access(|c| {
+        c.set_claim(IssuerClaim::from("someone else"));
+      }),
+      Err(GenericBuilderError::DuplicateTopLevelPayloadClaim(claim)) if claim == "iss"
+    ));
+    Ok(())
+  }
+
+  #[test]
+  fn test_public_session_issuer() -> Result<()> {
+    let private_key = Key::<64>::try_from("b4cbfb43df4ce210727d953e4a713307fa19bb7d9f85041438d9e11b942a37741eb9dbbbbc047c03fd70604e0071f0987e16b28b757225c11f00415d0e20b1a2")?;
+    let private_key = PasetoAsymmetricPrivateKey::<V4, Public>::from(&private_key);
+    let public_key = Key::<32>::try_from("1eb9dbbbbc047c03fd70604e0071f0987e16b28b757225c11f00415d0e20b1a2")?;
+    let public_key = PasetoAsymmetricPublicKey::<V4, Public>::from(&public_key);
+
+    let issuer = SessionIssuer::<V4, Public, _>::new("auth", "api", &private_key);
+    let token = issuer.issue_refresh(|_| {})?;
+    let json = PasetoParser::<V4, Public>::default()
+      .expect_type(TokenType::Refresh)
+      .parse(&token, &public_key)?;
+    assert_eq!(json["iss"], "auth");
+    assert!(json["jti"].is_string());
+    Ok(())
+  }
+}
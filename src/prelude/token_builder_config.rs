@@ -0,0 +1,132 @@
+use crate::prelude::*;
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use time::format_description::well_known::Rfc3339;
+
+///A declarative description of the tokens to mint which can be deserialized from any serde format
+///(JSON, YAML, TOML...) and turned into a ready [PasetoBuilder]
+///
+///All fields are optional. A missing `ttl` keeps the builder's default expiration of one hour from now.
+///Extra claims are subject to the same [reserved key](CustomClaim) rules as any other custom claim.
+///
+///# Usage
+///
+///```
+///# #[cfg(feature = "default")]
+///# {
+///   use rusty_paseto::prelude::*;
+///
+///     let key = PasetoSymmetricKey::<V4, Local>::from(Key::<32>::from(*b"wubbalubbadubdubwubbalubbadubdub"));
+///
+///     let config: TokenBuilderConfig = serde_json::from_str(
+///       r#"{
+///         "issuer": "auth.example.com",
+///         "audience": "customers",
+///         "ttl": 900,
+///         "extra_claims": { "role": "reader" },
+///         "footer": "key-id:1"
+///       }"#,
+///     )?;
+///
+///     let token = config.to_builder::<V4, Local>()?.build(&key)?;
+///
+///     let json = PasetoParser::<V4, Local>::default()
+///       .set_footer(Footer::from("key-id:1"))
+///       .parse(&token, &key)?;
+///     assert_eq!(json["iss"], "auth.example.com");
+///     assert_eq!(json["role"], "reader");
+///# }
+///# Ok::<(),anyhow::Error>(())
+///```
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TokenBuilderConfig {
+  /// The value of the [IssuerClaim]
+  pub issuer: Option<String>,
+  /// The value of the [AudienceClaim]
+  pub audience: Option<String>,
+  /// The number of seconds from the time the builder is created until the token expires
+  pub ttl: Option<u32>,
+  /// Any further [custom claims](CustomClaim) to add to the payload
+  #[serde(default)]
+  pub extra_claims: HashMap<String, Value>,
+  /// An optional [Footer]
+  pub footer: Option<String>,
+}
+
+impl TokenBuilderConfig {
+  /// Creates a [PasetoBuilder] preloaded with the claims and footer of this configuration.
+  ///
+  /// The builder borrows the claim values from the configuration, so one configuration can create a builder for
+  /// every token it describes.
+  ///
+  /// # Errors
+  ///
+  /// Returns [`GenericBuilderError`] if an extra claim uses a reserved PASETO claim key
+  pub fn to_builder<Version, Purpose>(&self) -> Result<PasetoBuilder<'_, Version, Purpose>, GenericBuilderError> {
+    let mut builder = PasetoBuilder::<Version, Purpose>::default();
+    if let Some(issuer) = &self.issuer {
+      builder.set_claim(IssuerClaim::from(issuer.as_str()));
+    }
+    if let Some(audience) = &self.audience {
+      builder.set_claim(AudienceClaim::from(audience.as_str()));
+    }
+    if let Some(ttl) = self.ttl {
+      //a ttl of at most u32::MAX seconds always formats as a valid RFC3339 date so this unwrap is infallible
+      let expiration = (time::OffsetDateTime::now_utc() + time::Duration::seconds(ttl.into()))
+        .format(&Rfc3339)
+        .unwrap();
+      builder.set_claim(ExpirationClaim::try_from(expiration)?);
+    }
+    for (key, value) in &self.extra_claims {
+      builder.set_claim(CustomClaim::try_from((key.as_str(), value))?);
+    }
+    if let Some(footer) = &self.footer {
+      builder.set_footer(Footer::from(footer.as_str()));
+    }
+    Ok(builder)
+  }
+}
+
+#[cfg(all(test, feature = "v4_local"))]
+mod token_builder_config_unit_tests {
+  use super::*;
+  use anyhow::Result;
+
+  #[test]
+  fn config_to_builder_test() -> Result<()> {
+    let key = PasetoSymmetricKey::<V4, Local>::from(Key::from(*b"wubbalubbadubdubwubbalubbadubdub"));
+    let config: TokenBuilderConfig =
+      serde_json::from_str(r#"{ "issuer": "me", "ttl": 60, "extra_claims": { "seats": 4, "tags": ["a", "b"] } }"#)?;
+
+    let token = config.to_builder::<V4, Local>()?.build(&key)?;
+    let json = PasetoParser::<V4, Local>::default()
+      .check_claim(IssuerClaim::from("me"))
+      .parse(&token, &key)?;
+    assert_eq!(json["seats"], 4);
+    assert_eq!(json["tags"][1], "b");
+    assert!(json["aud"].is_null());
+
+    //the expiration is roughly a minute away rather than the default hour
+    let expiration = time::OffsetDateTime::parse(json["exp"].as_str().unwrap_or_default(), &Rfc3339)?;
+    assert!(expiration <= time::OffsetDateTime::now_utc() + time::Duration::seconds(60));
+    Ok(())
+  }
+
+  #[test]
+  fn config_reserved_extra_claim_test() -> Result<()> {
+    let config: TokenBuilderConfig = serde_json::from_str(r#"{ "extra_claims": { "sub": "someone" } }"#)?;
+    assert!(matches!(
+      config.to_builder::<V4, Local>(),
+      Err(GenericBuilderError::ClaimError {
+        source: PasetoClaimError::Reserved(_)
+      })
+    ));
+
+    //unknown fields are typos rather than claims
+    assert!(serde_json::from_str::<TokenBuilderConfig>(r#"{ "isuer": "me" }"#).is_err());
+    Ok(())
+  }
+}
@@ -0,0 +1,146 @@
+use crate::prelude::*;
+
+/// An access token and a refresh token issued together for the same claims by [issue_token_pair]
+///
+/// Each token carries a [TokenType] claim, [Access](TokenType::Access) or [Refresh](TokenType::Refresh), so a verifier
+/// can reject a refresh token presented where an access token is expected with
+/// [expect_type](PasetoParser::expect_type).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TokenPair {
+  /// The short lived token presented to resource servers
+  pub access_token: String,
+  /// The long lived token exchanged for a new pair
+  pub refresh_token: String,
+}
+
+/// Issues an access token and a refresh token carrying the same claims, each expiring its own duration after it is
+/// built and each with its own [TokenType] claim
+///
+/// The `claims` closure is called once for each token to set the claims they share, following the same rules as
+/// [PasetoBuilder::claims].
+///
+/// # Errors
+///
+/// Returns [`GenericBuilderError`] if either token fails to build, for instance when `claims` sets `exp` or `typ`
+/// itself
+///
+/// # Example
+/// ```
+/// # #[cfg(feature = "default")]
+/// # {
+/// use rusty_paseto::prelude::*;
+///
+/// # let key = PasetoSymmetricKey::<V4, Local>::from(Key::<32>::from(*b"wubbalubbadubdubwubbalubbadubdub"));
+/// let user_id = String::from("user-1234");
+/// let pair = issue_token_pair(
+///   |c| {
+///     c.set_claim(SubjectClaim::from(user_id.as_str()));
+///   },
+///   time::Duration::minutes(15),
+///   time::Duration::days(30),
+///   &key,
+/// )?;
+///
+/// let json = PasetoParser::<V4, Local>::default().parse(&pair.refresh_token, &key)?;
+/// assert_eq!(json["sub"], "user-1234");
+/// assert_eq!(json["typ"], TokenType::Refresh.as_str());
+/// # }
+/// # Ok::<(),anyhow::Error>(())
+/// ```
+pub fn issue_token_pair<'a, Version, Purpose, F, K>(
+  claims: F,
+  access_ttl: time::Duration,
+  refresh_ttl: time::Duration,
+  key: &K,
+) -> Result<TokenPair, GenericBuilderError>
+where
+  F: Fn(&mut PasetoBuilder<'a, Version, Purpose>),
+  K: PasetoSealingKey<Version, Purpose>,
+{
+  let issue = |token_type: TokenType, ttl: time::Duration| {
+    PasetoBuilder::<'a, Version, Purpose>::default()
+      .claims(&claims)
+      .set_claim(token_type)
+      .set_expiration_relative(ttl)
+      .build_with(key)
+  };
+  Ok(TokenPair {
+    access_token: issue(TokenType::Access, access_ttl)?,
+    refresh_token: issue(TokenType::Refresh, refresh_ttl)?,
+  })
+}
+
+#[cfg(all(test, feature = "v4_local", feature = "v4_public"))]
+mod unit_tests {
+  use super::*;
+  use anyhow::Result;
+  use std::convert::TryFrom;
+  use time::format_description::well_known::Rfc3339;
+
+  fn expiration(json: &serde_json::Value) -> Result<time::OffsetDateTime> {
+    Ok(time::OffsetDateTime::parse(
+      json["exp"].as_str().unwrap_or_default(),
+      &Rfc3339,
+    )?)
+  }
+
+  #[test]
+  fn test_issue_local_token_pair() -> Result<()> {
+    let key = PasetoSymmetricKey::<V4, Local>::from(Key::from(*b"wubbalubbadubdubwubbalubbadubdub"));
+    let pair = issue_token_pair(
+      |c| {
+        c.set_claim(SubjectClaim::from("loyal subjects"))
+          .set_claim(IssuerClaim::from("issuer"));
+      },
+      time::Duration::minutes(15),
+      time::Duration::days(30),
+      &key,
+    )?;
+    assert_ne!(pair.access_token, pair.refresh_token);
+
+    let access = PasetoParser::<V4, Local>::default().parse(&pair.access_token, &key)?;
+    let refresh = PasetoParser::<V4, Local>::default().parse(&pair.refresh_token, &key)?;
+    assert_eq!(access["typ"], "access");
+    assert_eq!(refresh["typ"], "refresh");
+    for json in [&access, &refresh] {
+      assert_eq!(json["sub"], "loyal subjects");
+      assert_eq!(json["iss"], "issuer");
+    }
+
+    let now = time::OffsetDateTime::now_utc();
+    assert!(expiration(&access)? <= now + time::Duration::minutes(15));
+    assert!(expiration(&refresh)? > now + time::Duration::days(29));
+    Ok(())
+  }
+
+  #[test]
+  fn test_issue_public_token_pair() -> Result<()> {
+    let private_key = Key::<64>::try_from("b4cbfb43df4ce210727d953e4a713307fa19bb7d9f85041438d9e11b942a37741eb9dbbbbc047c03fd70604e0071f0987e16b28b757225c11f00415d0e20b1a2")?;
+    let private_key = PasetoAsymmetricPrivateKey::<V4, Public>::from(&private_key);
+    let public_key = Key::<32>::try_from("1eb9dbbbbc047c03fd70604e0071f0987e16b28b757225c11f00415d0e20b1a2")?;
+    let public_key = PasetoAsymmetricPublicKey::<V4, Public>::from(&public_key);
+
+    let pair = issue_token_pair(
+      |_| {},
+      time::Duration::minutes(15),
+      time::Duration::days(30),
+      &private_key,
+    )?;
+    let refresh = PasetoParser::<V4, Public>::default().parse(&pair.refresh_token, &public_key)?;
+    assert_eq!(refresh["typ"], "refresh");
+
+    //the token types are set by the pair and can't be overridden
+    assert!(matches!(
+      issue_token_pair(
+        |c| {
+          c.set_claim(CustomClaim::try_from(("typ", "admin")).unwrap());
+        },
+        time::Duration::minutes(15),
+        time::Duration::days(30),
+        &private_key,
+      ),
+      Err(GenericBuilderError::DuplicateTopLevelPayloadClaim(claim)) if claim == "typ"
+    ));
+    Ok(())
+  }
+}
@@ -0,0 +1,235 @@
+//! Fixtures for testing code which issues or consumes PASETO tokens, enabled by the `test-util` feature
+//!
+//! **For tests only.** Everything here uses one fixed, publicly known key, so any token built or accepted with it
+//! can be forged by anyone.  Enable the feature in `[dev-dependencies]` only, never for a build which ships:
+//!
+//! ```toml
+//! [dev-dependencies]
+//! rusty_paseto = { version = "latest", features = ["test-util"] }
+//! ```
+//!
+//! # Usage
+//! ```
+//! # #[cfg(feature = "test-util")]
+//! # {
+//! use rusty_paseto::prelude::*;
+//! use rusty_paseto::test_util::*;
+//! use serde_json::json;
+//!
+//! let key = test_key();
+//!
+//! //the code under test issues a token with the fixture key
+//! let token = PasetoBuilder::<V4, Local>::default()
+//!   .set_claim(SubjectClaim::from("loyal subjects"))
+//!   .build(&key)?;
+//! assert_claims_eq(&token, &json!({"sub": "loyal subjects"}));
+//!
+//! //or consumes one built from exactly the given claims
+//! let token = build_test_token(&json!({"sub": "loyal subjects", "seats": 4}));
+//! let json = PasetoParser::<V4, Local>::default().parse(&token, &key)?;
+//! assert_eq!(json["seats"], 4);
+//! # }
+//! # Ok::<(),anyhow::Error>(())
+//! ```
+use crate::core::{Footer, ImplicitAssertion, Key, Local, Paseto, PasetoNonce, PasetoSymmetricKey, Payload, V4};
+use crate::generic::BuilderConfig;
+use crate::prelude::PasetoParser;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+//publicly known on purpose, see the module docs
+const TEST_KEY: [u8; 32] = *b"rusty_paseto test key, insecure!";
+
+/// The deterministic (V4, Local) key of the fixtures
+///
+/// **Insecure.** The key is the same for everyone, never use it outside of tests.
+pub fn test_key() -> PasetoSymmetricKey<V4, Local> {
+  PasetoSymmetricKey::from(Key::from(TEST_KEY))
+}
+
+/// Encrypts a (V4, Local) token with the [test_key] whose payload is exactly the given claims
+///
+/// No claims are added, so the token has no expiration unless one is given.
+///
+/// # Panics
+///
+/// Panics if the claims aren't a JSON object or the token can't be encrypted
+pub fn build_test_token(claims: &Value) -> String {
+  assert!(
+    claims.is_object(),
+    "the claims of a test token must be a JSON object, found {}",
+    claims
+  );
+  let payload = claims.to_string();
+  let nonce = Key::<32>::try_new_random().expect("a random nonce for the test token");
+  Paseto::<V4, Local>::builder()
+    .set_payload(Payload::from(payload.as_str()))
+    .try_encrypt(&test_key(), &PasetoNonce::<V4, Local>::from(&nonce))
+    .expect("the test token to encrypt")
+}
+
+/// Asserts that the (V4, Local) token parses with the [test_key] under the default [PasetoParser] rules and carries
+/// every one of the expected claims with the expected value
+///
+/// Claims of the token which aren't expected are ignored, so generated claims like `iat` or `exp` don't have to be
+/// predicted.
+///
+/// # Panics
+///
+/// Panics if the token doesn't parse, if the expected claims aren't a JSON object or if an expected claim is missing
+/// or has another value
+pub fn assert_claims_eq(token: impl AsRef<str>, expected: &Value) {
+  let key = test_key();
+  let json = PasetoParser::<V4, Local>::default()
+    .parse(token, &key)
+    .unwrap_or_else(|error| panic!("the test token failed to parse: {}", error));
+  let expected = expected
+    .as_object()
+    .unwrap_or_else(|| panic!("the expected claims must be a JSON object, found {}", expected));
+  for (claim, value) in expected {
+    assert_eq!(&json[claim], value, "the '{}' claim of the test token", claim);
+  }
+}
+
+/// Every input of a (V4, Local) issuance, recorded to pin the token format in regression tests
+///
+/// Store the fixture next to the token it produced once, then [replay] it after each upgrade of the crate and compare:
+/// with the nonce fixed the token is deterministic, so any difference is a change of the format.  The key and nonce
+/// are recorded hex encoded and in the clear, so only capture fixtures with keys made for the purpose.
+///
+/// # Usage
+/// ```
+/// # #[cfg(feature = "test-util")]
+/// # {
+/// use rusty_paseto::generic::*;
+/// use rusty_paseto::test_util::*;
+///
+/// # let key = PasetoSymmetricKey::<V4, Local>::from(Key::<32>::from(*b"wubbalubbadubdubwubbalubbadubdub"));
+/// let config = GenericBuilder::<V4, Local>::default()
+///   .set_claim(SubjectClaim::from("loyal subjects"))
+///   .set_footer(Footer::from("key-id:1"))
+///   .config_snapshot()?;
+/// let fixture = IssuanceFixture::capture(&config, &key, &Key::from([7u8; 32]));
+/// let known_good = replay(&fixture);
+///
+/// //later, from the stored fixture
+/// let fixture: IssuanceFixture = serde_json::from_str(&serde_json::to_string(&fixture)?)?;
+/// assert_eq!(replay(&fixture), known_good);
+/// # }
+/// # Ok::<(),anyhow::Error>(())
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IssuanceFixture {
+  /// The claims, serialized as the payload in their key order
+  pub claims: Map<String, Value>,
+  /// The footer, left out when there is none
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub footer: Option<String>,
+  /// The implicit assertion, left out when there is none
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub implicit_assertion: Option<String>,
+  /// The 32 byte nonce, hex encoded
+  pub nonce: String,
+  /// The 32 byte symmetric key, hex encoded
+  pub key: String,
+}
+
+impl IssuanceFixture {
+  /// Captures the staged claims, footer and implicit assertion of a builder's
+  /// [snapshot](crate::generic::GenericBuilder::config_snapshot) along with the key and fixed nonce
+  ///
+  /// # Panics
+  ///
+  /// Panics if the snapshot has a [relative expiration](BuilderConfig::ttl_seconds), which depends on the time of
+  /// building and can't be replayed.  Set an absolute `exp` claim instead.
+  pub fn capture(config: &BuilderConfig, key: &PasetoSymmetricKey<V4, Local>, nonce: &Key<32>) -> Self {
+    assert!(
+      config.ttl_seconds.is_none(),
+      "a relative expiration can't be replayed, set an absolute exp claim for the fixture"
+    );
+    Self {
+      claims: config.claims.clone(),
+      footer: config.footer.clone(),
+      implicit_assertion: config.implicit_assertion.clone(),
+      nonce: hex::encode(nonce.as_ref()),
+      key: hex::encode(key.as_ref()),
+    }
+  }
+}
+
+/// Encrypts the token recorded by the [fixture](IssuanceFixture) again, with its key and fixed nonce
+///
+/// The same fixture replays to the same token for as long as the token format doesn't change.
+///
+/// # Panics
+///
+/// Panics if the key or nonce of the fixture isn't 32 hex encoded bytes or the token can't be encrypted
+pub fn replay(fixture: &IssuanceFixture) -> String {
+  let key = Key::<32>::try_from(fixture.key.as_str()).expect("the key of the fixture to be 32 hex encoded bytes");
+  let nonce = Key::<32>::try_from(fixture.nonce.as_str()).expect("the nonce of the fixture to be 32 hex encoded bytes");
+  let payload = Value::Object(fixture.claims.clone()).to_string();
+
+  let mut builder = Paseto::<V4, Local>::builder();
+  builder.set_payload(Payload::from(payload.as_str()));
+  if let Some(footer) = &fixture.footer {
+    builder.set_footer(Footer::from(footer.as_str()));
+  }
+  if let Some(implicit_assertion) = &fixture.implicit_assertion {
+    builder.set_implicit_assertion(ImplicitAssertion::from(implicit_assertion.as_str()));
+  }
+  builder
+    .try_encrypt(&PasetoSymmetricKey::from(key), &PasetoNonce::<V4, Local>::from(&nonce))
+    .expect("the fixture to encrypt")
+}
+
+#[cfg(test)]
+mod unit_tests {
+  use super::*;
+  use serde_json::json;
+
+  #[test]
+  fn test_build_test_token() {
+    let token = build_test_token(&json!({"sub": "loyal subjects", "seats": 4}));
+    assert!(token.starts_with("v4.local."));
+    assert_claims_eq(&token, &json!({"sub": "loyal subjects"}));
+    assert_claims_eq(token, &json!({"sub": "loyal subjects", "seats": 4}));
+  }
+
+  #[test]
+  fn test_replay_issuance_fixture() -> anyhow::Result<()> {
+    let config = crate::generic::GenericBuilder::<V4, Local>::default()
+      .set_claim(crate::generic::SubjectClaim::from("loyal subjects"))
+      .set_footer(Footer::from("key-id:1"))
+      .set_implicit_assertion(ImplicitAssertion::from("tenant:acme"))
+      .config_snapshot()?;
+    let fixture = IssuanceFixture::capture(&config, &test_key(), &Key::from([7u8; 32]));
+
+    let token = replay(&fixture);
+    assert_eq!(token, replay(&serde_json::from_str(&serde_json::to_string(&fixture)?)?));
+    assert_eq!(token, PINNED_TOKEN);
+    let mut parser = crate::generic::GenericParser::<V4, Local>::default();
+    parser
+      .set_footer(Footer::from("key-id:1"))
+      .set_implicit_assertion(ImplicitAssertion::from("tenant:acme"));
+    assert_eq!(parser.parse(&token, &test_key())?, json!({"sub": "loyal subjects"}));
+    Ok(())
+  }
+
+  //the token of the fixture when it was captured, a change here is a change of the token format
+  const PINNED_TOKEN: &str = "v4.local.BwcHBwcHBwcHBwcHBwcHBwcHBwcHBwcHBwcHBwcHBweVG7VpixcJyBAclFk_Lfmrv7gbm2KMdTJ5o8gVDBRLTXabKfm11lYMmM6uSUjN5x27t9Zl8koQEw.a2V5LWlkOjE";
+
+  #[test]
+  #[should_panic(expected = "the 'seats' claim of the test token")]
+  fn test_assert_claims_eq_mismatch() {
+    assert_claims_eq(build_test_token(&json!({"seats": 4})), &json!({"seats": 5}));
+  }
+
+  #[test]
+  #[should_panic(expected = "failed to parse")]
+  fn test_assert_claims_eq_expired() {
+    assert_claims_eq(
+      build_test_token(&json!({"exp": "2019-01-01T00:00:00+00:00"})),
+      &json!({}),
+    );
+  }
+}
@@ -0,0 +1,63 @@
+//! Counts the allocations of creating and serializing borrowed claims, in a test binary of its own since it replaces
+//! the global allocator
+#![cfg(feature = "generic")]
+use rusty_paseto::generic::*;
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+
+struct CountingAllocator;
+
+thread_local! {
+  //per thread, so the allocations of the test harness running alongside aren't counted
+  static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+  unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+    let _ = ALLOCATIONS.try_with(|allocations| allocations.set(allocations.get() + 1));
+    System.alloc(layout)
+  }
+
+  unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+    System.dealloc(ptr, layout)
+  }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+fn allocations_of(f: impl FnOnce()) -> usize {
+  let before = ALLOCATIONS.with(Cell::get);
+  f();
+  ALLOCATIONS.with(Cell::get) - before
+}
+
+#[test]
+fn borrowed_claims_do_not_allocate() {
+  //values borrowed from an incoming request
+  let subject = String::from("loyal subjects");
+  let expiration = String::from("2030-01-01T00:00:00+00:00");
+  let key = String::from("tenant");
+  let mut buffer = Vec::with_capacity(1024);
+
+  let allocations = allocations_of(|| {
+    let subject = SubjectClaim::from(subject.as_str());
+    let expiration = ExpirationClaim::try_from(expiration.as_str()).unwrap();
+    let issued_at = IssuedAtClaim::try_from(expiration.as_ref().1.as_ref()).unwrap();
+    let tenant = CustomClaim::try_from((key.as_str(), "acme")).unwrap();
+
+    serde_json::to_writer(&mut buffer, &subject).unwrap();
+    serde_json::to_writer(&mut buffer, &expiration).unwrap();
+    serde_json::to_writer(&mut buffer, &issued_at).unwrap();
+    serde_json::to_writer(&mut buffer, &tenant).unwrap();
+  });
+
+  assert_eq!(allocations, 0);
+  assert!(buffer.ends_with(br#"{"tenant":"acme"}"#));
+
+  //owned values are still accepted, at the cost of the allocation made by the caller
+  let owned = allocations_of(|| {
+    ExpirationClaim::try_from(expiration.clone()).unwrap();
+  });
+  assert_eq!(owned, 1);
+}
@@ -0,0 +1,77 @@
+//! Counts the allocations of decrypting into a reused buffer, in a test binary of its own since it replaces the
+//! global allocator
+#![cfg(feature = "v4_local")]
+use rusty_paseto::core::*;
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+
+struct CountingAllocator;
+
+thread_local! {
+  //per thread, so the allocations of the test harness running alongside aren't counted
+  static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+  unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+    let _ = ALLOCATIONS.try_with(|allocations| allocations.set(allocations.get() + 1));
+    System.alloc(layout)
+  }
+
+  unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+    System.dealloc(ptr, layout)
+  }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+fn allocations_of(f: impl FnOnce()) -> usize {
+  let before = ALLOCATIONS.with(Cell::get);
+  f();
+  ALLOCATIONS.with(Cell::get) - before
+}
+
+//the most allocations left once the buffer has grown: the derived encryption key and counter nonce, the derived
+//authentication key and the tag to compare, all of fixed size whatever the payload
+const ALLOCATIONS_AFTER_WARMUP: usize = 4;
+
+#[test]
+fn decrypt_into_reused_buffer_allocates_less() -> anyhow::Result<()> {
+  let key = PasetoSymmetricKey::<V4, Local>::from(Key::<32>::from(*b"wubbalubbadubdubwubbalubbadubdub"));
+  let nonce = Key::<32>::try_new_random()?;
+  let nonce = PasetoNonce::<V4, Local>::from(&nonce);
+
+  for size in [4096, 65536] {
+    let message = format!(r#"{{"data":"{}"}}"#, "a".repeat(size));
+    let token = Paseto::<V4, Local>::builder()
+      .set_payload(Payload::from(message.as_str()))
+      .try_encrypt(&key, &nonce)?;
+
+    //warm up the buffer
+    let mut plaintext = Vec::new();
+    Paseto::<V4, Local>::try_decrypt_into(&token, &key, None, None, &mut plaintext)?;
+    let capacity = plaintext.capacity();
+
+    let owned = allocations_of(|| {
+      Paseto::<V4, Local>::try_decrypt(&token, &key, None, None).unwrap();
+    });
+    let reused = allocations_of(|| {
+      Paseto::<V4, Local>::try_decrypt_into(&token, &key, None, None, &mut plaintext).unwrap();
+    });
+
+    assert_eq!(plaintext, message.as_bytes());
+    assert_eq!(plaintext.capacity(), capacity);
+    //splitting the token, checking its header and packing the preauth allocate nothing, the payload sized
+    //allocations are left to the buffer
+    assert!(
+      reused <= ALLOCATIONS_AFTER_WARMUP,
+      "{} byte payload: {} allocations after warmup, {} without a reused buffer",
+      size,
+      reused,
+      owned
+    );
+    assert!(reused < owned);
+  }
+  Ok(())
+}
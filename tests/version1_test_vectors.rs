@@ -330,8 +330,8 @@ mod v1_test_vectors {
     //  //  //create a local v2 token
     //let token = Paseto::<V1, Public>::build_token(header, message, &key, None);
     let token = Paseto::<V1, Public>::default()
-      .set_payload(message.clone())
-      .set_footer(footer.clone())
+      .set_payload(message)
+      .set_footer(footer)
       .try_sign(&private_key)?;
 
     //  //validate the test vector
@@ -365,8 +365,8 @@ mod v1_test_vectors {
     //  //  //create a local v2 token
     //let token = Paseto::<V1, Public>::build_token(header, message, &key, None);
     let token = Paseto::<V1, Public>::default()
-      .set_payload(message.clone())
-      .set_footer(footer.clone())
+      .set_payload(message)
+      .set_footer(footer)
       .try_sign(&private_key)?;
 
     //now let's try to decrypt it
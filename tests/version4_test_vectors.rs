@@ -301,7 +301,7 @@ mod v4_test_vectors {
         //let nonce = PasetoNonce::<V4, Local>::from(&nonce);
         let private_key = Key::<64>::try_from("b4cbfb43df4ce210727d953e4a713307fa19bb7d9f85041438d9e11b942a37741eb9dbbbbc047c03fd70604e0071f0987e16b28b757225c11f00415d0e20b1a2")?;
         let pk: &[u8] = private_key.as_slice();
-        let private_key = PasetoAsymmetricPrivateKey::<V4, Public>::from(pk);
+        let private_key = PasetoAsymmetricPrivateKey::<V4, Public>::try_from(pk)?;
         let public_key = Key::<32>::try_from("1eb9dbbbbc047c03fd70604e0071f0987e16b28b757225c11f00415d0e20b1a2")?;
         let public_key = PasetoAsymmetricPublicKey::<V4, Public>::from(&public_key);
 
@@ -329,7 +329,7 @@ mod v4_test_vectors {
         //setup
         let private_key = Key::<64>::try_from("b4cbfb43df4ce210727d953e4a713307fa19bb7d9f85041438d9e11b942a37741eb9dbbbbc047c03fd70604e0071f0987e16b28b757225c11f00415d0e20b1a2")?;
         let pk: &[u8] = private_key.as_slice();
-        let private_key = PasetoAsymmetricPrivateKey::<V4, Public>::from(pk);
+        let private_key = PasetoAsymmetricPrivateKey::<V4, Public>::try_from(pk)?;
 
         let public_key = Key::<32>::try_from("1eb9dbbbbc047c03fd70604e0071f0987e16b28b757225c11f00415d0e20b1a2")?;
         let public_key = PasetoAsymmetricPublicKey::<V4, Public>::from(&public_key);
@@ -345,8 +345,8 @@ mod v4_test_vectors {
         //  //  //create a local v2 token
         //let token = Paseto::<V2, Public>::build_token(header, message, &key, None);
         let token = Paseto::<V4, Public>::default()
-            .set_payload(message.clone())
-            .set_footer(footer.clone())
+            .set_payload(message)
+            .set_footer(footer)
             .try_sign(&private_key);
         match token {
             Ok(token) => {
@@ -371,7 +371,7 @@ mod v4_test_vectors {
         //setup
         let private_key = Key::<64>::try_from("b4cbfb43df4ce210727d953e4a713307fa19bb7d9f85041438d9e11b942a37741eb9dbbbbc047c03fd70604e0071f0987e16b28b757225c11f00415d0e20b1a2")?;
         let pk: &[u8] = private_key.as_slice();
-        let private_key = PasetoAsymmetricPrivateKey::<V4, Public>::from(pk);
+        let private_key = PasetoAsymmetricPrivateKey::<V4, Public>::try_from(pk)?;
 
         let public_key = Key::<32>::try_from("1eb9dbbbbc047c03fd70604e0071f0987e16b28b757225c11f00415d0e20b1a2")?;
         let public_key = PasetoAsymmetricPublicKey::<V4, Public>::from(&public_key);
@@ -389,8 +389,8 @@ mod v4_test_vectors {
         //  //  //create a local v2 token
         //let token = Paseto::<V2, Public>::build_token(header, message, &key, None);
         let token = Paseto::<V4, Public>::default()
-            .set_payload(message.clone())
-            .set_footer(footer.clone())
+            .set_payload(message)
+            .set_footer(footer)
             .set_implicit_assertion(assertion)
             .try_sign(&private_key)?;
 